@@ -0,0 +1,252 @@
+// Import necessary crates and modules
+use crate::course::CourseInfo;
+use chrono::{DateTime, Datelike, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Start of an academic term within a calendar year.
+///
+/// Fields:
+/// - `label`: Value reported as the "semester" for dates inside this term (e.g. "1", "2", "Q3").
+/// - `start_month`: Month (1-12) in which the term begins.
+/// - `start_day`: Day of the month on which the term begins.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TermBoundary {
+    pub label: String,
+    pub start_month: u32,
+    pub start_day: u32,
+}
+
+/// Academic calendar used to derive the current year and semester.
+///
+/// A date belongs to the last term that started on or before it in the same calendar year; dates
+/// before the first boundary belong to the last term of the previous calendar year. The reported
+/// year is the calendar year, unless the date falls before `year_start`, in which case the previous
+/// year is reported (so that, for example, Spring 2025 can belong to academic year 2024).
+///
+/// Fields:
+/// - `terms`: Term boundaries, in any order.
+/// - `year_start`: `(month, day)` on which the academic year rolls over. Defaults to January 1st.
+/// - `enrollment_terms`: Optional mapping from Canvas enrollment term names to `(year, semester)`,
+///   used when the server-side term is known and should take precedence over the calendar heuristic.
+///
+/// Example:
+/// ```
+/// let calendar = AcademicCalendar::new(8, 15)
+///     .with_term("2", 1, 1)
+///     .with_term("1", 8, 15)
+///     .with_enrollment_term("Fall 2024", "2024", "1");
+/// let (year, semester) = calendar.current_year_and_semester();
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AcademicCalendar {
+    pub terms: Vec<TermBoundary>,
+    pub year_start: (u32, u32),
+    pub enrollment_terms: HashMap<String, (String, String)>,
+}
+
+impl Default for AcademicCalendar {
+    /// Two semesters split on July 15th, the behavior historically hardcoded in
+    /// `get_current_year_and_semester`.
+    fn default() -> Self {
+        Self::southern_semesters()
+    }
+}
+
+impl AcademicCalendar {
+    /// Creates an empty calendar whose academic year starts on the given month and day.
+    pub fn new(year_start_month: u32, year_start_day: u32) -> Self {
+        AcademicCalendar {
+            terms: Vec::new(),
+            year_start: (year_start_month, year_start_day),
+            enrollment_terms: HashMap::new(),
+        }
+    }
+
+    /// Adds a term boundary to the calendar.
+    pub fn with_term(mut self, label: &str, start_month: u32, start_day: u32) -> Self {
+        self.terms.push(TermBoundary {
+            label: label.to_string(),
+            start_month,
+            start_day,
+        });
+        self
+    }
+
+    /// Maps a Canvas enrollment term name to an explicit `(year, semester)` pair.
+    pub fn with_enrollment_term(mut self, term_name: &str, year: &str, semester: &str) -> Self {
+        self.enrollment_terms
+            .insert(term_name.to_string(), (year.to_string(), semester.to_string()));
+        self
+    }
+
+    /// Calendar-year semesters: "1" up to July 15th and "2" afterwards.
+    pub fn southern_semesters() -> Self {
+        Self::new(1, 1).with_term("1", 1, 1).with_term("2", 7, 16)
+    }
+
+    /// Academic year starting in August: "1" from August 15th (fall) and "2" from January 1st
+    /// (spring), with spring reported under the year in which the academic year began.
+    pub fn northern_semesters() -> Self {
+        Self::new(8, 15).with_term("2", 1, 1).with_term("1", 8, 15)
+    }
+
+    /// Calendar-year quarters labeled "1" to "4".
+    pub fn quarters() -> Self {
+        Self::new(1, 1)
+            .with_term("1", 1, 1)
+            .with_term("2", 4, 1)
+            .with_term("3", 7, 1)
+            .with_term("4", 10, 1)
+    }
+
+    /// Returns the `(year, semester)` pair for the given date.
+    pub fn year_and_semester(&self, date: NaiveDate) -> (String, String) {
+        let month_day = (date.month(), date.day());
+
+        let year = if month_day < self.year_start {
+            date.year() - 1
+        } else {
+            date.year()
+        };
+
+        let start = |term: &&TermBoundary| (term.start_month, term.start_day);
+        let semester = self
+            .terms
+            .iter()
+            .filter(|term| (term.start_month, term.start_day) <= month_day)
+            .max_by_key(start)
+            .or_else(|| self.terms.iter().max_by_key(start))
+            .map(|term| term.label.clone())
+            .unwrap_or_else(|| "1".to_string());
+
+        (year.to_string(), semester)
+    }
+
+    /// Returns the `(year, semester)` pair for today's date (UTC).
+    pub fn current_year_and_semester(&self) -> (String, String) {
+        self.year_and_semester(chrono::Utc::now().date_naive())
+    }
+
+    /// Resolves a Canvas enrollment term name through the configured mapping.
+    pub fn resolve_enrollment_term(&self, term_name: &str) -> Option<(String, String)> {
+        self.enrollment_terms.get(term_name).cloned()
+    }
+}
+
+/// Enrollment term of a course as known by Canvas (sent with `include[]=term`).
+///
+/// Fields:
+/// - `id`: ID of the term.
+/// - `name`: Name of the term (e.g. "2024/2"); Canvas calls the catch-all term "Default Term".
+/// - `start_at` / `end_at`: Dates of the term, when set by the institution.
+/// - `sis_term_id`: ID of the term in the student information system.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct CourseTerm {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default)]
+    pub start_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub end_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub sis_term_id: Option<String>,
+}
+
+impl CourseTerm {
+    /// `(year, semester)` of the term: the mapping of `calendar` for its name if any, otherwise
+    /// the period of `calendar` containing its start date. `None` for terms without dates, such
+    /// as the default term.
+    pub fn year_and_semester(&self, calendar: &AcademicCalendar) -> Option<(String, String)> {
+        calendar
+            .resolve_enrollment_term(&self.name)
+            .or_else(|| self.start_at.map(|start_at| calendar.year_and_semester(start_at.date_naive())))
+    }
+}
+
+/// Enrollment term of a course, when Canvas sent it with the course (see `Course::term`).
+pub fn course_term(course_info: &CourseInfo) -> Option<CourseTerm> {
+    course_info.extra.get("term").and_then(|term| serde_json::from_value(term.clone()).ok())
+}
+
+/// `(year, semester)` of a course, taken from its enrollment term when Canvas knows it and from
+/// today's date otherwise (see `CourseTerm::year_and_semester`).
+pub fn course_year_and_semester(course_info: &CourseInfo, calendar: &AcademicCalendar) -> (String, String) {
+    course_term(course_info)
+        .and_then(|term| term.year_and_semester(calendar))
+        .unwrap_or_else(|| calendar.current_year_and_semester())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn pair(year: &str, semester: &str) -> (String, String) {
+        (year.to_string(), semester.to_string())
+    }
+
+    #[test]
+    fn test_default_calendar_splits_on_july_15() {
+        let calendar = AcademicCalendar::default();
+        assert_eq!(calendar.year_and_semester(date(2024, 2, 1)), pair("2024", "1"));
+        assert_eq!(calendar.year_and_semester(date(2024, 7, 15)), pair("2024", "1"));
+        assert_eq!(calendar.year_and_semester(date(2024, 7, 16)), pair("2024", "2"));
+        assert_eq!(calendar.year_and_semester(date(2024, 12, 31)), pair("2024", "2"));
+    }
+
+    #[test]
+    fn test_northern_calendar_reports_academic_year() {
+        let calendar = AcademicCalendar::northern_semesters();
+        assert_eq!(calendar.year_and_semester(date(2024, 9, 1)), pair("2024", "1"));
+        assert_eq!(calendar.year_and_semester(date(2025, 3, 1)), pair("2024", "2"));
+        assert_eq!(calendar.year_and_semester(date(2025, 8, 15)), pair("2025", "1"));
+    }
+
+    #[test]
+    fn test_quarters_and_wraparound() {
+        let calendar = AcademicCalendar::quarters();
+        assert_eq!(calendar.year_and_semester(date(2024, 5, 10)), pair("2024", "2"));
+        assert_eq!(calendar.year_and_semester(date(2024, 11, 2)), pair("2024", "4"));
+
+        // Before the first boundary the last term of the previous year still applies.
+        let calendar = AcademicCalendar::new(1, 1).with_term("A", 3, 1).with_term("B", 9, 1);
+        assert_eq!(calendar.year_and_semester(date(2024, 1, 20)), pair("2024", "B"));
+    }
+
+    #[test]
+    fn test_enrollment_term_mapping() {
+        let calendar = AcademicCalendar::default().with_enrollment_term("2024/2", "2024", "2");
+        assert_eq!(calendar.resolve_enrollment_term("2024/2"), Some(pair("2024", "2")));
+        assert_eq!(calendar.resolve_enrollment_term("Default Term"), None);
+    }
+
+    #[test]
+    fn test_course_term_takes_precedence_over_the_clock() {
+        let mut course = CourseInfo::default();
+        course.extra.insert(
+            "term".to_string(),
+            serde_json::json!({"id": 12, "name": "Verão 2023", "start_at": "2023-01-09T03:00:00Z", "end_at": null}),
+        );
+        let term = course.term().unwrap();
+        assert_eq!(term.id, 12);
+        assert_eq!(term.end_at, None);
+        assert_eq!(course.year_and_semester(&AcademicCalendar::default()), pair("2023", "1"));
+
+        let calendar = AcademicCalendar::default().with_enrollment_term("Verão 2023", "2022", "3");
+        assert_eq!(course.year_and_semester(&calendar), pair("2022", "3"));
+
+        let default_term = CourseTerm {
+            id: 1,
+            name: "Default Term".to_string(),
+            start_at: None,
+            end_at: None,
+            sis_term_id: None,
+        };
+        assert_eq!(default_term.year_and_semester(&calendar), None);
+    }
+}
@@ -0,0 +1,428 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages, fetch_json};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::login::{self, Login, LoginUpdate};
+use crate::params::Params;
+use crate::user::{self, User, UserUpdate};
+use crate::{Canvas, CanvasCredentials, Course};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Information about a Canvas account (the institution or one of its sub-accounts).
+///
+/// Fields:
+/// - `id`: ID of the account.
+/// - `name`: Name of the account.
+/// - `parent_account_id`: Parent account (`None` for the root account).
+/// - `root_account_id`: Root account of the institution (`None` for the root account itself).
+/// - `sis_account_id`: SIS identifier, when visible to the user.
+/// - `canvas_info`: Credentials used to reach the account.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct AccountInfo {
+    pub id: u64,
+    pub name: String,
+    pub parent_account_id: Option<u64>,
+    pub root_account_id: Option<u64>,
+    pub sis_account_id: Option<String>,
+    #[serde(skip)]
+    pub canvas_info: Arc<CanvasCredentials>,
+}
+
+/// A Canvas account. Account-level operations (logins, users, courses) require admin rights.
+///
+/// Example:
+/// ```
+/// let account = Account::fetch(&client, &canvas_info, 1)?;
+/// for login in account.fetch_logins(&client)? {
+///     println!("{} -> {:?}", login.unique_id, login.sis_user_id);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Account {
+    pub info: Arc<AccountInfo>,
+}
+
+/// Workflow states used to filter the courses of an account.
+///
+/// - `Created` / `Claimed`: Unpublished courses (new, or with content).
+/// - `Available`: Published courses.
+/// - `Completed`: Concluded courses.
+/// - `Deleted`: Deleted courses.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CourseState {
+    Created,
+    Claimed,
+    Available,
+    Completed,
+    Deleted,
+}
+
+impl CourseState {
+    /// Value used by the Canvas API for this state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CourseState::Created => "created",
+            CourseState::Claimed => "claimed",
+            CourseState::Available => "available",
+            CourseState::Completed => "completed",
+            CourseState::Deleted => "deleted",
+        }
+    }
+}
+
+/// Filters applied when listing the courses of an account.
+///
+/// Example:
+/// ```
+/// let query = AccountCourseQuery::default()
+///     .search_term("Cálculo")
+///     .term_id(42)
+///     .state(CourseState::Available);
+/// let courses = account.fetch_courses(&client, &query)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccountCourseQuery {
+    pub search_term: Option<String>,
+    pub term_id: Option<u64>,
+    pub states: Vec<CourseState>,
+    pub by_teachers: Vec<u64>,
+    pub by_subaccounts: Vec<u64>,
+    pub published: Option<bool>,
+    pub with_enrollments: Option<bool>,
+}
+
+impl AccountCourseQuery {
+    /// Keeps only courses whose name, code or SIS ID contains `term` (at least 2 characters).
+    pub fn search_term(mut self, term: &str) -> Self {
+        self.search_term = Some(term.to_string());
+        self
+    }
+
+    /// Keeps only courses of the given enrollment term.
+    pub fn term_id(mut self, term_id: u64) -> Self {
+        self.term_id = Some(term_id);
+        self
+    }
+
+    /// Adds a workflow state to the accepted states (all states when none is given).
+    pub fn state(mut self, state: CourseState) -> Self {
+        self.states.push(state);
+        self
+    }
+
+    /// Keeps only courses taught by one of the given teachers.
+    pub fn by_teachers(mut self, teacher_ids: &[u64]) -> Self {
+        self.by_teachers.extend_from_slice(teacher_ids);
+        self
+    }
+
+    /// Keeps only courses of the given sub-accounts.
+    pub fn by_subaccounts(mut self, account_ids: &[u64]) -> Self {
+        self.by_subaccounts.extend_from_slice(account_ids);
+        self
+    }
+
+    /// Keeps only published (`true`) or unpublished (`false`) courses.
+    pub fn published(mut self, published: bool) -> Self {
+        self.published = Some(published);
+        self
+    }
+
+    /// Keeps only courses with (`true`) or without (`false`) enrollments.
+    pub fn with_enrollments(mut self, with_enrollments: bool) -> Self {
+        self.with_enrollments = Some(with_enrollments);
+        self
+    }
+
+    /// Converts the query into Canvas API query parameters.
+    pub fn to_params(&self) -> Vec<(String, String)> {
+        Params::new()
+            .param_opt("search_term", self.search_term.as_ref())
+            .param_opt("enrollment_term_id", self.term_id)
+            .array("state", self.states.iter().map(CourseState::as_str))
+            .array("by_teachers", &self.by_teachers)
+            .array("by_subaccounts", &self.by_subaccounts)
+            .param_opt("published", self.published)
+            .param_opt("with_enrollments", self.with_enrollments)
+            .into()
+    }
+}
+
+impl Account {
+    /// Retrieves one account by ID.
+    pub fn fetch(client: &Client, canvas_info: &CanvasCredentials, account_id: u64) -> Result<Account, Box<dyn Error>> {
+        let url = format!("{}/accounts/{}", canvas_info.url_canvas, account_id);
+        let json: Value = fetch_json(client, canvas_info, &url)?;
+        account_from_json(canvas_info, json).ok_or_else(|| "Invalid account returned by Canvas".into())
+    }
+
+    /// Retrieves the accounts the user administers.
+    pub fn fetch_all(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<Account>, Box<dyn Error>> {
+        let url = format!("{}/accounts", canvas_info.url_canvas);
+        Ok(fetch_all_pages(client, canvas_info, &url, Vec::new())?
+            .into_iter()
+            .filter_map(|json| account_from_json(canvas_info, json))
+            .collect())
+    }
+
+    /// Lists the courses of the account and its sub-accounts, following every page. Unlike
+    /// `Canvas::fetch_courses_with_credentials`, it isn't limited to the courses the user teaches.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `query`: Filters of the listing.
+    ///
+    /// Returns:
+    /// - `Result<Vec<Course>, Box<dyn Error>>`: The courses or an error.
+    pub fn fetch_courses(&self, client: &Client, query: &AccountCourseQuery) -> Result<Vec<Course>, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let url = format!("{}/accounts/{}/courses", canvas_info.url_canvas, self.info.id);
+        Ok(fetch_all_pages(client, canvas_info, &url, query.to_params())?
+            .iter()
+            .filter_map(|course| Canvas::convert_json_to_course(canvas_info, course))
+            .collect())
+    }
+
+    /// Lists the sub-accounts of this account.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `recursive`: `true` to include the whole tree below this account, `false` for the
+    ///   direct children only.
+    pub fn fetch_sub_accounts(&self, client: &Client, recursive: bool) -> Result<Vec<Account>, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let url = format!("{}/accounts/{}/sub_accounts", canvas_info.url_canvas, self.info.id);
+        let params = vec![("recursive".to_string(), recursive.to_string())];
+        Ok(fetch_all_pages(client, canvas_info, &url, params)?
+            .into_iter()
+            .filter_map(|json| account_from_json(canvas_info, json))
+            .collect())
+    }
+
+    /// Creates a sub-account below this account.
+    ///
+    /// Returns:
+    /// - `Result<Account, Box<dyn Error>>`: The new sub-account or an error.
+    pub fn create_sub_account(
+        &self,
+        client: &Client,
+        name: &str,
+        sis_account_id: Option<&str>,
+    ) -> Result<Account, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let url = format!("{}/accounts/{}/sub_accounts", canvas_info.url_canvas, self.info.id);
+        let body = sub_account_body(name, sis_account_id);
+        let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to create sub-account with status: {}", response.status()).into());
+        }
+        account_from_json(canvas_info, response.json()?).ok_or_else(|| "Invalid account returned by Canvas".into())
+    }
+
+    /// Moves a course into this account (e.g. when a department is reorganized).
+    ///
+    /// Example:
+    /// ```
+    /// let engineering = Account::fetch(&client, &canvas_info, 12)?;
+    /// for course in old_department.fetch_courses(&client, &AccountCourseQuery::default())? {
+    ///     engineering.move_course(&client, course.info.id)?;
+    /// }
+    /// ```
+    pub fn move_course(&self, client: &Client, course_id: u64) -> Result<(), Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let url = format!("{}/courses/{}", canvas_info.url_canvas, course_id);
+        let body = move_course_body(self.info.id);
+        let response = send_http_request(client, HttpMethod::Put(body), &url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to move course with status: {}", response.status()).into());
+        }
+        Ok(())
+    }
+
+    /// Lists the logins (pseudonyms) of every user of the account.
+    pub fn fetch_logins(&self, client: &Client) -> Result<Vec<Login>, Box<dyn Error>> {
+        login::fetch_account_logins(client, &self.info.canvas_info, self.info.id)
+    }
+
+    /// Changes a login of the account (unique ID, SIS ID, authentication provider...).
+    pub fn update_login(&self, client: &Client, login_id: u64, update: &LoginUpdate) -> Result<Login, Box<dyn Error>> {
+        login::update_login(client, &self.info.canvas_info, self.info.id, login_id, update)
+    }
+
+    /// Adds a login to an existing user. `update.unique_id` is required.
+    pub fn create_login(&self, client: &Client, user_id: u64, update: &LoginUpdate) -> Result<Login, Box<dyn Error>> {
+        login::create_login(client, &self.info.canvas_info, self.info.id, user_id, update)
+    }
+
+    /// Creates a user in the account, with a login and an e-mail address.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `name`: Full name of the user.
+    /// - `email`: E-mail address (communication channel).
+    /// - `login`: Unique ID used to sign in.
+    /// - `sis_user_id`: SIS identifier (registrar ID), if any.
+    /// - `send_confirmation`: Whether Canvas e-mails the user a registration confirmation; when
+    ///   `false` the registration and e-mail confirmation are skipped.
+    ///
+    /// Returns:
+    /// - `Result<User, Box<dyn Error>>`: The created user or an error (e.g. login already taken).
+    ///
+    /// Example:
+    /// ```
+    /// let user = account.create_user(&client, "Ana Lima", "ana@example.edu", "ana.lima", Some("2024001234"), false)?;
+    /// ```
+    pub fn create_user(
+        &self,
+        client: &Client,
+        name: &str,
+        email: &str,
+        login: &str,
+        sis_user_id: Option<&str>,
+        send_confirmation: bool,
+    ) -> Result<User, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let url = format!("{}/accounts/{}/users", canvas_info.url_canvas, self.info.id);
+        let body = new_user_body(name, email, login, sis_user_id, send_confirmation);
+        user::send_user_request(client, canvas_info, HttpMethod::Post(body), &url)
+    }
+
+    /// Changes the profile of a user of the account.
+    pub fn update_user(&self, client: &Client, user_id: u64, update: &UserUpdate) -> Result<User, Box<dyn Error>> {
+        user::update_user(client, &self.info.canvas_info, user_id, update)
+    }
+
+    /// Merges a duplicated user into another one (see the free function `merge_users`).
+    pub fn merge_users(&self, client: &Client, from_user_id: u64, into_user_id: u64) -> Result<User, Box<dyn Error>> {
+        user::merge_users(client, &self.info.canvas_info, from_user_id, into_user_id)
+    }
+}
+
+// Body of the sub-account creation request.
+fn sub_account_body(name: &str, sis_account_id: Option<&str>) -> Value {
+    let mut account = json!({ "name": name });
+    if let Some(sis_account_id) = sis_account_id {
+        account["sis_account_id"] = json!(sis_account_id);
+    }
+    json!({ "account": account })
+}
+
+// Body of the request moving a course into the account `account_id`.
+fn move_course_body(account_id: u64) -> Value {
+    json!({ "course": { "account_id": account_id } })
+}
+
+// Body of the user creation request.
+fn new_user_body(name: &str, email: &str, login: &str, sis_user_id: Option<&str>, send_confirmation: bool) -> Value {
+    let mut pseudonym = json!({
+        "unique_id": login,
+        "send_confirmation": send_confirmation
+    });
+    if let Some(sis_user_id) = sis_user_id {
+        pseudonym["sis_user_id"] = json!(sis_user_id);
+    }
+    json!({
+        "user": {
+            "name": name,
+            "skip_registration": !send_confirmation
+        },
+        "pseudonym": pseudonym,
+        "communication_channel": {
+            "type": "email",
+            "address": email,
+            "skip_confirmation": !send_confirmation
+        }
+    })
+}
+
+// Builds an `Account` bound to its credentials from the JSON returned by Canvas.
+pub(crate) fn account_from_json(canvas_info: &CanvasCredentials, json: Value) -> Option<Account> {
+    let mut info: AccountInfo = serde_json::from_value(json).ok()?;
+    info.canvas_info = Arc::new(canvas_info.clone());
+    Some(Account { info: Arc::new(info) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_course_query_params() {
+        let query = AccountCourseQuery::default()
+            .search_term("Cálculo")
+            .term_id(42)
+            .state(CourseState::Available)
+            .state(CourseState::Completed)
+            .by_teachers(&[7]);
+        assert_eq!(
+            query.to_params(),
+            vec![
+                ("search_term".to_string(), "Cálculo".to_string()),
+                ("enrollment_term_id".to_string(), "42".to_string()),
+                ("state[]".to_string(), "available".to_string()),
+                ("state[]".to_string(), "completed".to_string()),
+                ("by_teachers[]".to_string(), "7".to_string()),
+            ]
+        );
+        assert!(AccountCourseQuery::default().to_params().is_empty());
+    }
+
+    #[test]
+    fn test_new_user_body() {
+        let body = new_user_body("Ana Lima", "ana@example.edu", "ana.lima", Some("2024001234"), false);
+        assert_eq!(body["user"]["skip_registration"], json!(true));
+        assert_eq!(body["pseudonym"]["unique_id"], json!("ana.lima"));
+        assert_eq!(body["pseudonym"]["sis_user_id"], json!("2024001234"));
+        assert_eq!(body["communication_channel"]["address"], json!("ana@example.edu"));
+
+        let body = new_user_body("Bruno", "b@example.edu", "bruno", None, true);
+        assert!(body["pseudonym"].get("sis_user_id").is_none());
+        assert_eq!(body["communication_channel"]["skip_confirmation"], json!(false));
+    }
+
+    #[test]
+    fn test_sub_account_from_canvas_json() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: "token".to_string(),
+        };
+        let account = account_from_json(
+            &canvas_info,
+            json!({
+                "id": 12,
+                "name": "Engenharia",
+                "uuid": "WvAHhY5FINzq5IyRIJybGeiXyFkG3SqHUPb7jZY5",
+                "parent_account_id": 1,
+                "root_account_id": 1,
+                "sis_account_id": "ENG",
+                "workflow_state": "active",
+                "default_time_zone": "America/Sao_Paulo"
+            }),
+        )
+        .unwrap();
+        assert_eq!(account.info.parent_account_id, Some(1));
+        assert_eq!(account.info.sis_account_id.as_deref(), Some("ENG"));
+        assert_eq!(account.info.canvas_info.url_canvas, canvas_info.url_canvas);
+
+        // Root account: no parent, no root, SIS ID hidden from non-admins
+        let root = account_from_json(&canvas_info, json!({"id": 1, "name": "Universidade", "parent_account_id": null}))
+            .unwrap();
+        assert_eq!(root.info.parent_account_id, None);
+        assert_eq!(root.info.root_account_id, None);
+        assert!(account_from_json(&canvas_info, json!({"name": "Sem ID"})).is_none());
+    }
+
+    #[test]
+    fn test_sub_account_and_move_course_bodies() {
+        assert_eq!(
+            sub_account_body("Engenharia", Some("ENG")),
+            json!({"account": {"name": "Engenharia", "sis_account_id": "ENG"}})
+        );
+        assert_eq!(sub_account_body("Engenharia", None), json!({"account": {"name": "Engenharia"}}));
+        assert_eq!(move_course_body(12), json!({"course": {"account_id": 12}}));
+    }
+}
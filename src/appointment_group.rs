@@ -0,0 +1,271 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages_as, fetch_json};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::params::Params;
+use crate::CanvasCredentials;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::error::Error;
+
+/// The user holding a reservation.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ReservationUser {
+    pub id: u64,
+    pub name: String,
+}
+
+/// A reservation of a time slot by a student.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Reservation {
+    pub id: u64,
+    pub user: Option<ReservationUser>,
+    pub created_at: Option<String>,
+}
+
+/// A time slot of an appointment group.
+///
+/// Fields:
+/// - `id`: ID of the calendar event of the slot (used to reserve it).
+/// - `start_at` / `end_at`: Time span of the slot.
+/// - `available_slots`: Places left, when the group limits participants per slot.
+/// - `child_events`: Reservations of the slot.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AppointmentSlot {
+    pub id: u64,
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub available_slots: Option<u64>,
+    #[serde(default)]
+    pub child_events: Vec<Reservation>,
+}
+
+/// An appointment group (Scheduler signup sheet), e.g. office hours.
+///
+/// Fields:
+/// - `id`: ID of the group.
+/// - `title` / `description` / `location_name`: What students see.
+/// - `context_codes`: Courses offering the group (`course_123`).
+/// - `participants_per_appointment`: Students per slot (`None` for unlimited).
+/// - `workflow_state`: `pending` (unpublished), `active` or `deleted`.
+/// - `appointments`: Time slots, when requested with the group.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AppointmentGroup {
+    pub id: u64,
+    pub title: String,
+    pub description: Option<String>,
+    pub location_name: Option<String>,
+    #[serde(default)]
+    pub context_codes: Vec<String>,
+    pub participants_per_appointment: Option<u64>,
+    pub workflow_state: Option<String>,
+    #[serde(default)]
+    pub appointments: Vec<AppointmentSlot>,
+}
+
+/// One line of `AppointmentGroup::reservations`, for attendance exports.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ReservationRecord {
+    pub slot_id: u64,
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_at: Option<DateTime<Utc>>,
+    pub user_id: u64,
+    pub user_name: String,
+}
+
+/// Data of a new appointment group. Slots are `(start, end)` pairs.
+///
+/// Example:
+/// ```
+/// let new_group = NewAppointmentGroup {
+///     title: "Plantão de dúvidas".to_string(),
+///     context_codes: vec![format!("course_{}", course.info.id)],
+///     participants_per_appointment: Some(1),
+///     slots: vec![(start, start + chrono::Duration::minutes(15))],
+///     publish: true,
+///     ..Default::default()
+/// };
+/// let group = create_appointment_group(&client, &canvas_info, &new_group)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NewAppointmentGroup {
+    pub title: String,
+    pub description: Option<String>,
+    pub location_name: Option<String>,
+    pub context_codes: Vec<String>,
+    pub participants_per_appointment: Option<u64>,
+    pub slots: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+    pub publish: bool,
+}
+
+impl NewAppointmentGroup {
+    // Body of the creation request. Slots go in `new_appointments` keyed "0", "1"...
+    fn to_json(&self) -> Value {
+        let new_appointments: Map<String, Value> = self
+            .slots
+            .iter()
+            .enumerate()
+            .map(|(index, (start, end))| (index.to_string(), json!([start.to_rfc3339(), end.to_rfc3339()])))
+            .collect();
+        let mut group = json!({
+            "title": self.title,
+            "context_codes": self.context_codes,
+            "new_appointments": new_appointments,
+            "publish": self.publish,
+        });
+        if let Some(description) = &self.description {
+            group["description"] = json!(description);
+        }
+        if let Some(location) = &self.location_name {
+            group["location_name"] = json!(location);
+        }
+        if let Some(participants) = self.participants_per_appointment {
+            group["participants_per_appointment"] = json!(participants);
+        }
+        json!({ "appointment_group": group })
+    }
+}
+
+impl AppointmentGroup {
+    /// Lists every reservation of every slot, in slot order.
+    pub fn reservations(&self) -> Vec<ReservationRecord> {
+        self.appointments
+            .iter()
+            .flat_map(|slot| {
+                slot.child_events.iter().filter_map(move |reservation| {
+                    let user = reservation.user.as_ref()?;
+                    Some(ReservationRecord {
+                        slot_id: slot.id,
+                        start_at: slot.start_at,
+                        end_at: slot.end_at,
+                        user_id: user.id,
+                        user_name: user.name.clone(),
+                    })
+                })
+            })
+            .collect()
+    }
+}
+
+/// Lists the appointment groups of some courses, with their slots and reservations.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `context_codes`: Courses to include (`course_123`); all courses of the user when empty.
+///
+/// Returns:
+/// - `Result<Vec<AppointmentGroup>, Box<dyn Error>>`: The groups the user can manage, or an error.
+pub fn fetch_appointment_groups(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    context_codes: &[String],
+) -> Result<Vec<AppointmentGroup>, Box<dyn Error>> {
+    let url = format!("{}/appointment_groups", canvas_info.url_canvas);
+    let params = Params::new()
+        .param("scope", "manageable")
+        .include(["appointments", "child_events"])
+        .array("context_codes", context_codes);
+    fetch_all_pages_as(client, canvas_info, &url, params.into())
+}
+
+/// Retrieves one appointment group with its slots and reservations.
+pub fn fetch_appointment_group(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    group_id: u64,
+) -> Result<AppointmentGroup, Box<dyn Error>> {
+    let url = format!(
+        "{}/appointment_groups/{}?include[]=appointments&include[]=child_events",
+        canvas_info.url_canvas, group_id
+    );
+    fetch_json(client, canvas_info, &url)
+}
+
+/// Creates an appointment group.
+///
+/// Returns:
+/// - `Result<AppointmentGroup, Box<dyn Error>>`: The created group or an error.
+pub fn create_appointment_group(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    group: &NewAppointmentGroup,
+) -> Result<AppointmentGroup, Box<dyn Error>> {
+    let url = format!("{}/appointment_groups", canvas_info.url_canvas);
+    let response = send_http_request(client, HttpMethod::Post(group.to_json()), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to create appointment group with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+/// Reserves a time slot.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `slot_id`: ID of the slot (`AppointmentSlot::id`).
+/// - `participant_id`: Student to reserve for (teachers only), or `None` for the user itself.
+///
+/// Returns:
+/// - `Result<Reservation, Box<dyn Error>>`: The reservation or an error (e.g. slot full).
+pub fn reserve_slot(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    slot_id: u64,
+    participant_id: Option<u64>,
+) -> Result<Reservation, Box<dyn Error>> {
+    let url = match participant_id {
+        Some(participant_id) => format!(
+            "{}/calendar_events/{}/reservations/{}",
+            canvas_info.url_canvas, slot_id, participant_id
+        ),
+        None => format!("{}/calendar_events/{}/reservations", canvas_info.url_canvas, slot_id),
+    };
+    let response = send_http_request(client, HttpMethod::Post(json!({})), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to reserve slot with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new_group_body_and_reservations() {
+        let start = DateTime::parse_from_rfc3339("2024-04-02T14:00:00Z").unwrap().with_timezone(&Utc);
+        let end = DateTime::parse_from_rfc3339("2024-04-02T14:15:00Z").unwrap().with_timezone(&Utc);
+        let body = NewAppointmentGroup {
+            title: "Plantão".to_string(),
+            context_codes: vec!["course_7".to_string()],
+            participants_per_appointment: Some(1),
+            slots: vec![(start, end)],
+            publish: true,
+            ..Default::default()
+        }
+        .to_json();
+        assert_eq!(
+            body["appointment_group"]["new_appointments"]["0"],
+            json!(["2024-04-02T14:00:00+00:00", "2024-04-02T14:15:00+00:00"])
+        );
+        assert!(body["appointment_group"].get("location_name").is_none());
+
+        let group: AppointmentGroup = serde_json::from_value(json!({
+            "id": 1,
+            "title": "Plantão",
+            "appointments": [
+                {"id": 10, "start_at": "2024-04-02T14:00:00Z", "end_at": "2024-04-02T14:15:00Z",
+                 "child_events": [{"id": 100, "user": {"id": 5, "name": "Ana"}}]},
+                {"id": 11, "start_at": "2024-04-02T14:15:00Z", "end_at": "2024-04-02T14:30:00Z", "child_events": []}
+            ]
+        }))
+        .unwrap();
+        let reservations = group.reservations();
+        assert_eq!(reservations.len(), 1);
+        assert_eq!(reservations[0].user_name, "Ana");
+        assert_eq!(reservations[0].slot_id, 10);
+    }
+}
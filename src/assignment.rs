@@ -1,13 +1,17 @@
 // Import necessary crates and modules
+use crate::concurrency::parallel_map;
 use crate::rubric_downloaded::RubricDownloaded;
+use crate::rubric_results::RubricResults;
 use crate::submission::{Comment, Submission, SubmissionType};
 use crate::{canvas, CourseInfo, Student};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 use std::error::Error;
 use std::sync::Arc;
 use std::collections::HashMap;
+use std::path::Path;
+use reqwest::blocking::Client;
 
 /// Structure to hold detailed information about an assignment in the Canvas system.
 ///
@@ -31,10 +35,428 @@ pub struct AssignmentInfo {
     pub due_at: Option<DateTime<Utc>>, // Campo opcional para a data de vencimento
     pub rubric_id: Option<u64>,
     pub group_category_id: Option<u64>,
+    #[serde(default)]
+    pub points_possible: Option<f64>, // Pontuação máxima da atividade
+    #[serde(default)]
+    pub overrides: Vec<AssignmentOverride>, // Prorrogações por estudante, grupo ou turma
+    #[serde(default)]
+    pub all_dates: Vec<AssignmentDate>, // Todas as datas (padrão e sobrescritas)
+    #[serde(default)]
+    pub score_statistics: Option<AssignmentStatistics>, // Estatísticas calculadas pelo Canvas, se disponíveis
+    #[serde(default)]
+    pub submission_types: Vec<SubmissionType>, // Tipos de entrega aceitos (online_upload, on_paper...)
+    #[serde(default)]
+    pub allowed_extensions: Vec<String>, // Extensões aceitas no envio de arquivos (vazio: qualquer uma)
+    #[serde(default)]
+    pub allowed_attempts: Option<i64>, // Número máximo de tentativas (-1: ilimitado)
+    #[serde(default)]
+    pub annotatable_attachment_id: Option<u64>, // Documento das atividades de anotação (student_annotation)
+    #[serde(default)]
+    pub needs_grading_count: Option<u64>, // Submissões aguardando correção (visível para professores)
+    #[serde(default)]
+    pub needs_grading_count_by_section: Vec<SectionNeedsGrading>, // Mesma contagem, por turma
     #[serde(skip)]
     pub course_info: Arc<CourseInfo>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>, // Campos do Canvas não mapeados acima
 }
 
+/// A due date override (extension) of an assignment.
+///
+/// An override applies either to a list of students (`student_ids`), to a group (`group_id`) or to
+/// a course section (`course_section_id`). `due_at` is the effective due date for its targets:
+/// when the override doesn't change the due date, it holds the assignment's default due date;
+/// `None` means "no due date".
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AssignmentOverride {
+    pub id: u64,
+    pub title: Option<String>,
+    #[serde(default)]
+    pub student_ids: Vec<u64>,
+    pub group_id: Option<u64>,
+    pub course_section_id: Option<u64>,
+    pub due_at: Option<DateTime<Utc>>,
+}
+
+/// One entry of the `all_dates` list of an assignment.
+///
+/// The entry with `base == true` holds the default dates; the others correspond to overrides
+/// (`set_type` is `"ADHOC"`, `"Group"` or `"CourseSection"`, and `set_id` the group or section ID).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AssignmentDate {
+    pub id: Option<u64>,
+    #[serde(default)]
+    pub base: bool,
+    pub title: Option<String>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub unlock_at: Option<DateTime<Utc>>,
+    pub lock_at: Option<DateTime<Utc>>,
+    pub set_type: Option<String>,
+    pub set_id: Option<u64>,
+}
+
+/// Number of submissions waiting to be graded in one section of the course.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SectionNeedsGrading {
+    pub section_id: u64,
+    pub needs_grading_count: u64,
+}
+
+/// Grading state of the submissions of an assignment. See `Assignment::grading_progress`.
+///
+/// Fields:
+/// - `total`: Number of submissions (one per student, or per group for group assignments).
+/// - `submitted`: Submissions turned in by the students.
+/// - `graded`: Submissions with a grade (or excused).
+/// - `needs_grading`: Submissions turned in and not graded yet (or resubmitted after grading).
+/// - `missing`: Submissions Canvas flags as missing (past due and not turned in).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GradingProgress {
+    pub total: usize,
+    pub submitted: usize,
+    pub graded: usize,
+    pub needs_grading: usize,
+    pub missing: usize,
+}
+
+impl GradingProgress {
+    /// Counts the grading state of `submissions`.
+    pub fn from_submissions(submissions: &[Submission]) -> GradingProgress {
+        let mut progress = GradingProgress {
+            total: submissions.len(),
+            ..Default::default()
+        };
+        for submission in submissions {
+            let flag = |key: &str| submission.extra.get(key).and_then(Value::as_bool) == Some(true);
+            let state = submission.extra.get("workflow_state").and_then(Value::as_str);
+            let graded = submission.score.is_some() || flag("excused");
+            if submission.submitted_at.is_some() {
+                progress.submitted += 1;
+            }
+            if graded {
+                progress.graded += 1;
+            }
+            if matches!(state, Some("submitted") | Some("pending_review"))
+                || (state.is_none() && submission.submitted_at.is_some() && !graded)
+            {
+                progress.needs_grading += 1;
+            }
+            if flag("missing") {
+                progress.missing += 1;
+            }
+        }
+        progress
+    }
+
+    /// Share of the turned-in submissions already graded, from 0.0 to 1.0 (1.0 when nothing was
+    /// turned in).
+    pub fn completion(&self) -> f64 {
+        if self.submitted == 0 {
+            return 1.0;
+        }
+        1.0 - self.needs_grading as f64 / self.submitted as f64
+    }
+}
+
+/// Summary statistics of the scores of an assignment.
+///
+/// Fields:
+/// - `count`: Number of graded submissions.
+/// - `min`, `max`, `mean`: Lowest, highest and average score.
+/// - `lower_q`, `median`, `upper_q`: Quartiles (linear interpolation between closest ranks).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AssignmentStatistics {
+    #[serde(default)]
+    pub count: usize,
+    pub min: f64,
+    pub max: f64,
+    pub mean: f64,
+    pub lower_q: Option<f64>,
+    pub median: Option<f64>,
+    pub upper_q: Option<f64>,
+}
+
+impl AssignmentStatistics {
+    /// Computes the statistics of a list of scores, or `None` if the list is empty.
+    pub fn from_scores(scores: &[f64]) -> Option<AssignmentStatistics> {
+        if scores.is_empty() {
+            return None;
+        }
+        let mut sorted = scores.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let quantile = |q: f64| {
+            let pos = q * (sorted.len() - 1) as f64;
+            let (low, high) = (pos.floor() as usize, pos.ceil() as usize);
+            sorted[low] + (sorted[high] - sorted[low]) * (pos - low as f64)
+        };
+        Some(AssignmentStatistics {
+            count: sorted.len(),
+            min: sorted[0],
+            max: sorted[sorted.len() - 1],
+            mean: sorted.iter().sum::<f64>() / sorted.len() as f64,
+            lower_q: Some(quantile(0.25)),
+            median: Some(quantile(0.5)),
+            upper_q: Some(quantile(0.75)),
+        })
+    }
+}
+
+impl AssignmentInfo {
+    /// Returns `true` when students can turn in a submission of type `submission_type`. When the
+    /// accepted types are unknown (not sent by Canvas), every type is accepted.
+    pub fn accepts(&self, submission_type: &SubmissionType) -> bool {
+        self.submission_types.is_empty() || self.submission_types.contains(submission_type)
+    }
+
+    /// Returns `true` when a file named `file_name` can be uploaded to this assignment, according
+    /// to the extensions it allows (any file when it doesn't restrict them). The comparison
+    /// ignores case.
+    pub fn accepts_file(&self, file_name: &str) -> bool {
+        if self.allowed_extensions.is_empty() {
+            return true;
+        }
+        let extension = Path::new(file_name).extension().and_then(|e| e.to_str()).unwrap_or("");
+        self.allowed_extensions.iter().any(|allowed| allowed.trim_start_matches('.').eq_ignore_ascii_case(extension))
+    }
+
+    /// Maximum number of attempts of a student, or `None` when unlimited.
+    pub fn attempts_limit(&self) -> Option<u32> {
+        self.allowed_attempts.filter(|attempts| *attempts > 0).map(|attempts| attempts as u32)
+    }
+
+    /// Checks that `submission` matches what the assignment accepts: its type, and the extension
+    /// of each attached file.
+    ///
+    /// Returns:
+    /// - `Result<(), Box<dyn Error>>`: Success, or an error describing the first mismatch.
+    pub fn check_submission(&self, submission: &Submission) -> Result<(), Box<dyn Error>> {
+        if let Some(submission_type) = &submission.submission_type {
+            if !self.accepts(submission_type) {
+                return Err(format!(
+                    "Assignment {} doesn't accept {} submissions",
+                    self.id,
+                    submission_type.as_str()
+                )
+                .into());
+            }
+        }
+        if let Some(file_name) = submission.extension_violations().first() {
+            return Err(format!("Assignment {} doesn't accept the file {}", self.id, file_name).into());
+        }
+        Ok(())
+    }
+
+    /// Returns the effective due date of this assignment for a student, taking overrides into account.
+    ///
+    /// Like Canvas, every override that applies to the student (one naming the student, one for the
+    /// student's group and those of the student's sections) is considered and the most lenient date
+    /// wins: the latest one, or no due date at all if one of them has none. Without any matching
+    /// override, the default `due_at` is returned.
+    ///
+    /// Arguments:
+    /// - `student_id`: ID of the student.
+    /// - `section_ids`: Sections the student is enrolled in (may be empty if unknown).
+    /// - `group_id`: Group of the student for group assignments, if any.
+    ///
+    /// Returns:
+    /// - `Option<DateTime<Utc>>`: The due date, or `None` if the student has no due date.
+    pub fn due_at_for_student(
+        &self,
+        student_id: u64,
+        section_ids: &[u64],
+        group_id: Option<u64>,
+    ) -> Option<DateTime<Utc>> {
+        let dates: Vec<Option<DateTime<Utc>>> = self
+            .overrides
+            .iter()
+            .filter(|o| {
+                o.student_ids.contains(&student_id)
+                    || (group_id.is_some() && o.group_id == group_id)
+                    || o.course_section_id.is_some_and(|s| section_ids.contains(&s))
+            })
+            .map(|o| o.due_at)
+            .collect();
+        if dates.is_empty() {
+            return self.due_at;
+        }
+        // Having no due date in one of the overrides is the most lenient option
+        if dates.iter().any(|d| d.is_none()) {
+            None
+        } else {
+            dates.into_iter().flatten().max()
+        }
+    }
+
+    /// Returns the effective due date of this assignment for a course section.
+    pub fn due_at_for_section(&self, section_id: u64) -> Option<DateTime<Utc>> {
+        match self
+            .overrides
+            .iter()
+            .find(|o| o.course_section_id == Some(section_id))
+        {
+            Some(o) => o.due_at,
+            None => self.due_at,
+        }
+    }
+}
+
+/// Time-based bucket used to filter the assignments of a course.
+///
+/// - `Past`: Due date already passed.
+/// - `Overdue`: Past due and not yet submitted.
+/// - `Undated`: No due date.
+/// - `Upcoming`: Due within the next weeks.
+/// - `Future`: Due date in the future.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentBucket {
+    Past,
+    Overdue,
+    Undated,
+    Upcoming,
+    Future,
+}
+
+impl AssignmentBucket {
+    /// Value used by the Canvas API for this bucket.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssignmentBucket::Past => "past",
+            AssignmentBucket::Overdue => "overdue",
+            AssignmentBucket::Undated => "undated",
+            AssignmentBucket::Upcoming => "upcoming",
+            AssignmentBucket::Future => "future",
+        }
+    }
+}
+
+/// Sort order of an assignment listing.
+///
+/// - `Position`: Order shown in the Canvas assignments page (default).
+/// - `Name`: Alphabetical order.
+/// - `DueAt`: By due date.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AssignmentOrder {
+    Position,
+    Name,
+    DueAt,
+}
+
+impl AssignmentOrder {
+    /// Value used by the Canvas API for this order.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AssignmentOrder::Position => "position",
+            AssignmentOrder::Name => "name",
+            AssignmentOrder::DueAt => "due_at",
+        }
+    }
+}
+
+/// Filters applied when listing the assignments of a course.
+///
+/// Example:
+/// ```
+/// let query = AssignmentQuery::default()
+///     .bucket(AssignmentBucket::Upcoming)
+///     .order_by(AssignmentOrder::DueAt)
+///     .search_term("lista");
+/// let assignments = course.fetch_assignments_with_query(&query)?;
+/// course.choose_assignment(None, Some(assignments));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AssignmentQuery {
+    pub bucket: Option<AssignmentBucket>,
+    pub order_by: Option<AssignmentOrder>,
+    pub search_term: Option<String>,
+}
+
+impl AssignmentQuery {
+    /// Restricts the listing to the given bucket.
+    pub fn bucket(mut self, bucket: AssignmentBucket) -> Self {
+        self.bucket = Some(bucket);
+        self
+    }
+
+    /// Sets the sort order of the listing.
+    pub fn order_by(mut self, order: AssignmentOrder) -> Self {
+        self.order_by = Some(order);
+        self
+    }
+
+    /// Keeps only assignments whose name contains `term`.
+    pub fn search_term(mut self, term: &str) -> Self {
+        self.search_term = Some(term.to_string());
+        self
+    }
+
+    /// Returns `true` when no filter is set.
+    pub fn is_empty(&self) -> bool {
+        self == &AssignmentQuery::default()
+    }
+
+    /// Converts the query into Canvas API query parameters.
+    pub fn to_params(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        if let Some(bucket) = self.bucket {
+            params.push(("bucket".to_string(), bucket.as_str().to_string()));
+        }
+        if let Some(order) = self.order_by {
+            params.push(("order_by".to_string(), order.as_str().to_string()));
+        }
+        if let Some(term) = &self.search_term {
+            params.push(("search_term".to_string(), term.clone()));
+        }
+        params
+    }
+}
+
+/// Which students receive a message sent with `Assignment::message_students_who`.
+///
+/// - `NotSubmitted`: Students who haven't submitted the assignment.
+/// - `NotGraded`: Students whose submission has no score yet.
+/// - `ScoredBelow(x)`: Students with a score lower than `x`.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum MessageCriterion {
+    NotSubmitted,
+    NotGraded,
+    ScoredBelow(f64),
+}
+
+impl MessageCriterion {
+    /// Returns the IDs of the `students` matching the criterion, given their `submissions`.
+    ///
+    /// Students without any submission record count as not submitted and not graded.
+    pub fn select(&self, students: &[Student], submissions: &[Submission]) -> Vec<u64> {
+        students
+            .iter()
+            .filter(|student| {
+                let submission = submissions
+                    .iter()
+                    .find(|s| s.students_info.iter().any(|info| info.id == student.info.id));
+                match (self, submission) {
+                    (MessageCriterion::NotSubmitted, None) | (MessageCriterion::NotGraded, None) => true,
+                    (MessageCriterion::NotSubmitted, Some(s)) => s.submitted_at.is_none(),
+                    (MessageCriterion::NotGraded, Some(s)) => s.score.is_none(),
+                    (MessageCriterion::ScoredBelow(limit), Some(s)) => s.score.is_some_and(|score| score < *limit),
+                    (MessageCriterion::ScoredBelow(_), None) => false,
+                }
+            })
+            .map(|student| student.info.id)
+            .collect()
+    }
+}
+
+/// Maximum number of worker threads used by `Assignment::comment_many`.
+pub const COMMENT_MANY_WORKERS: usize = 8;
+
+/// A comment to post with `Assignment::comment_many`: student ID, text and optional file path.
+pub type CommentRequest = (u64, String, Option<String>);
+
+/// Outcome of one comment posted by `Assignment::comment_many`.
+pub type CommentResult = Result<(), Box<dyn Error>>;
+
 /// High-level structure representing an assignment within the Canvas Learning Management System.
 ///
 /// This struct serves as a wrapper around the `AssignmentInfo` struct, providing a more abstracted representation
@@ -54,9 +476,43 @@ pub struct Assignment {
 }
 
 impl Assignment {
+    /// Returns `true` when students can turn in a submission of type `submission_type`.
+    /// See `AssignmentInfo::accepts`.
+    pub fn accepts(&self, submission_type: &SubmissionType) -> bool {
+        self.info.accepts(submission_type)
+    }
+
     pub fn fetch_submissions(
         &self,
         students: &Vec<Student>,
+    ) -> Result<Vec<Submission>, Box<dyn std::error::Error>> {
+        self.fetch_submissions_filtered(students, None)
+    }
+
+    /// Retrieves the submissions of this assignment made by the students of a single section.
+    ///
+    /// Only the section's submissions and roster are downloaded, which makes this much faster
+    /// than `fetch_submissions` in large multi-section courses. As in `fetch_submissions`, only
+    /// submissions related to `students` are returned. For group submissions, group members
+    /// enrolled in other sections are not listed in `students_info`.
+    ///
+    /// Example:
+    /// ```
+    /// let students = course.fetch_students_in_section(section_id)?;
+    /// let submissions = assignment.fetch_submissions_in_section(section_id, &students)?;
+    /// ```
+    pub fn fetch_submissions_in_section(
+        &self,
+        section_id: u64,
+        students: &Vec<Student>,
+    ) -> Result<Vec<Submission>, Box<dyn std::error::Error>> {
+        self.fetch_submissions_filtered(students, Some(section_id))
+    }
+
+    fn fetch_submissions_filtered(
+        &self,
+        students: &Vec<Student>,
+        section_id: Option<u64>,
     ) -> Result<Vec<Submission>, Box<dyn std::error::Error>> {
         let client = &reqwest::blocking::Client::new();
 
@@ -77,16 +533,37 @@ impl Assignment {
             }
         };
 
-        match canvas::get_all_submissions(
-            client,
-            self.info.course_info.canvas_info.as_ref(),
-            self.info.course_info.id,
-            self.info.id,
-            groups.is_some(),
-        ) {
+        let submissions_result = match section_id {
+            Some(section_id) => canvas::get_all_submissions_in_section(
+                client,
+                self.info.course_info.canvas_info.as_ref(),
+                section_id,
+                self.info.id,
+                groups.is_some(),
+            ),
+            None => canvas::get_all_submissions(
+                client,
+                self.info.course_info.canvas_info.as_ref(),
+                self.info.course_info.id,
+                self.info.id,
+                groups.is_some(),
+            ),
+        };
+
+        match submissions_result {
             Ok(submissions_value) => {
-                // Recupera todos os estudantes do curso
-                let all_course_students = self.info.course_info.fetch_students()?;
+                // Recupera todos os estudantes do curso (ou apenas os da turma)
+                let all_course_students = match section_id {
+                    Some(section_id) => self.info.course_info.fetch_students_in_section(section_id)?,
+                    None => self.info.course_info.fetch_students()?,
+                };
+                // Estudantes informados pelo chamador (ex.: matrículas inativas) também são associados
+                let mut all_course_students = all_course_students;
+                for student in students {
+                    if !all_course_students.iter().any(|s| s.info.id == student.info.id) {
+                        all_course_students.push(student.clone());
+                    }
+                }
 
                 let submissions = submissions_value
                     .iter()
@@ -124,8 +601,51 @@ impl Assignment {
         }
     }
 
+    /// Counts the submissions of `students` that are turned in, graded, waiting to be graded and
+    /// missing, e.g. for TA workload dashboards.
+    ///
+    /// Example:
+    /// ```
+    /// let progress = assignment.grading_progress(&students)?;
+    /// println!("{}: {} to grade ({:.0}% done)", assignment.info.name, progress.needs_grading, progress.completion() * 100.0);
+    /// ```
+    pub fn grading_progress(&self, students: &Vec<Student>) -> Result<GradingProgress, Box<dyn Error>> {
+        Ok(GradingProgress::from_submissions(&self.fetch_submissions(students)?))
+    }
+
+    /// Computes the score statistics of this assignment (min, max, mean and quartiles).
+    ///
+    /// Statistics are computed from the graded submissions of `students`; ungraded submissions are
+    /// ignored. Canvas only exposes its own `score_statistics` to students, so teachers need this
+    /// computation to build after-assessment reports. For group submissions, each member counts
+    /// once.
+    ///
+    /// Returns:
+    /// - `Result<Option<AssignmentStatistics>, Box<dyn Error>>`: The statistics, `None` if nothing
+    ///   is graded yet, or an error if the submissions couldn't be fetched.
+    ///
+    /// Example:
+    /// ```
+    /// if let Some(stats) = assignment.statistics(&students)? {
+    ///     println!("mean {:.2}, median {:?}", stats.mean, stats.median);
+    /// }
+    /// ```
+    pub fn statistics(&self, students: &Vec<Student>) -> Result<Option<AssignmentStatistics>, Box<dyn Error>> {
+        let scores: Vec<f64> = self
+            .fetch_submissions(students)?
+            .iter()
+            .filter_map(|submission| {
+                submission
+                    .score
+                    .map(|score| (score, submission.students_info.len().max(1)))
+            })
+            .flat_map(|(score, members)| std::iter::repeat_n(score, members))
+            .collect();
+        Ok(AssignmentStatistics::from_scores(&scores))
+    }
+
     /// Função que converte o JSON de submissões em uma estrutura `Submission`.
-    fn convert_json_to_submission(
+    pub(crate) fn convert_json_to_submission(
         all_course_students: &Vec<Student>,
         j: &Value,
         assignment_info: Arc<AssignmentInfo>,
@@ -144,26 +664,11 @@ impl Assignment {
                         });
 
                     // Processa os comentários da submissão
-                    let comments =
-                        j["submission_comments"]
-                            .as_array()
-                            .map_or(Vec::new(), |comments_array| {
-                                comments_array
-                                    .iter()
-                                    .filter_map(|comment| {
-                                        // Captura o ID e o conteúdo do comentário
-                                        let id = comment["id"].as_u64();
-                                        let content = comment["comment"].as_str().map(String::from);
-
-                                        // Se ambos o ID e o conteúdo do comentário existirem
-                                        if let (Some(id), Some(content)) = (id, content) {
-                                            Some(Comment { id, content })
-                                        } else {
-                                            None
-                                        }
-                                    })
-                                    .collect()
-                            });
+                    let comments = j["submission_comments"]
+                        .as_array()
+                        .map_or(Vec::new(), |comments_array| {
+                            comments_array.iter().filter_map(Comment::from_json).collect()
+                        });
 
                     // Localiza o grupo do estudante
                     let group_id = groups.as_ref().and_then(|groups| {
@@ -185,40 +690,168 @@ impl Assignment {
 
                     if students_info.is_empty() {
                         // Se está vazio significa que não é por grupo. Inclui o estudante com user_id
-                        if let Some(student) = all_course_students.iter().find(|student| student.info.id == user_id) {
-                            students_info.push(student.info.clone());
-                        } else {
-                            panic!("Falha ao associar um estudante a uma submissão.");
-                        }
+                        students_info.push(student.info.clone());
                     }
 
                     return Some(Submission {
-                        id: j["id"].as_u64().unwrap(),
-                        assignment_id: j["assignment_id"].as_u64().unwrap(),
+                        id: j["id"].as_u64()?,
+                        assignment_id: j["assignment_id"].as_u64()?,
                         score: j["score"].as_f64(),
                         submitted_at: j["submitted_at"]
                             .as_str()
-                            .map(|s| DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)),
-                        submission_type: j["submission_type"].as_str().map(|st| match st {
-                            "online_upload" => SubmissionType::OnlineUpload,
-                            "online_text_entry" => SubmissionType::OnlineTextEntry,
-                            "online_url" => SubmissionType::OnlineUrl,
-                            "media_recording" => SubmissionType::MediaRecording,
-                            "none" => SubmissionType::None,
-                            _ => SubmissionType::Other,
-                        }),
+                            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+                            .map(|dt| dt.with_timezone(&Utc)),
+                        submission_type: j["submission_type"].as_str().map(SubmissionType::from),
                         // student_info: student.info.clone(),
                         students_info,
                         file_ids,
                         assignment_info,
                         comments,
+                        extra: canvas::unknown_fields(
+                            j,
+                            &["id", "assignment_id", "score", "submitted_at", "submission_type", "submission_comments"],
+                        ),
                     });
                 }
             }
         }
+        // Usuário fora da lista de estudantes (aluno de teste, aluno removido, monitor...): a
+        // submissão é ignorada
+        log::warn!(
+            "Skipping submission {} of user {}: user is not among the course students",
+            j["id"],
+            j["user_id"]
+        );
         None
     }
 
+    /// Posts many submission comments (optionally with a file) in parallel.
+    ///
+    /// Comments are spread over up to `COMMENT_MANY_WORKERS` threads; every request still goes
+    /// through the global concurrency limit and retry policy of the connector, so large batches
+    /// don't trip the Canvas rate limiter. A failure for one student doesn't stop the others.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `comments`: `(student_id, text, optional file path)` for each comment.
+    ///
+    /// Returns:
+    /// - `Vec<(u64, CommentResult)>`: The outcome for each student, in the order of `comments`.
+    ///
+    /// Example:
+    /// ```
+    /// let comments = vec![
+    ///     (101, "Veja o feedback em anexo".to_string(), Some("feedback/101.pdf".to_string())),
+    ///     (102, "Ótimo trabalho!".to_string(), None),
+    /// ];
+    /// for (student_id, result) in assignment.comment_many(&client, comments) {
+    ///     if let Err(e) = result {
+    ///         eprintln!("{}: {}", student_id, e);
+    ///     }
+    /// }
+    /// ```
+    pub fn comment_many(
+        &self,
+        client: &Client,
+        comments: Vec<CommentRequest>,
+    ) -> Vec<(u64, CommentResult)> {
+        let results = parallel_map(&comments, COMMENT_MANY_WORKERS, |(student_id, text, file_path)| {
+            canvas::comment_with_file(
+                client,
+                self.info.course_info.canvas_info.as_ref(),
+                self.info.course_info.id,
+                self.info.id,
+                *student_id,
+                file_path.as_deref(),
+                text,
+            )
+            .map_err(|e| e.to_string())
+        });
+
+        comments
+            .iter()
+            .zip(results)
+            .map(|((student_id, _, _), result)| (*student_id, result.map_err(|e| e.into())))
+            .collect()
+    }
+
+    /// Sends a message to the students matching `criterion`, like the "Message students who..."
+    /// option of the Canvas gradebook.
+    ///
+    /// Each recipient gets a separate conversation in the Canvas Inbox. Nothing is sent when no
+    /// student matches.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `students`: Students to consider (usually the course roster).
+    /// - `criterion`: Which students should receive the message.
+    /// - `subject`: Subject of the message.
+    /// - `body`: Text of the message.
+    ///
+    /// Returns:
+    /// - `Result<Vec<u64>, Box<dyn Error>>`: IDs of the students messaged, or an error.
+    ///
+    /// Example:
+    /// ```
+    /// let sent_to = assignment.message_students_who(
+    ///     &client,
+    ///     &students,
+    ///     MessageCriterion::NotSubmitted,
+    ///     "Entrega pendente",
+    ///     "A lista 3 vence amanhã.",
+    /// )?;
+    /// println!("{} students messaged", sent_to.len());
+    /// ```
+    pub fn message_students_who(
+        &self,
+        client: &Client,
+        students: &Vec<Student>,
+        criterion: MessageCriterion,
+        subject: &str,
+        body: &str,
+    ) -> Result<Vec<u64>, Box<dyn Error>> {
+        let submissions = self.fetch_submissions(students)?;
+        let recipients = criterion.select(students, &submissions);
+        if !recipients.is_empty() {
+            canvas::send_conversation(
+                client,
+                self.info.course_info.canvas_info.as_ref(),
+                self.info.course_info.id,
+                &recipients,
+                subject,
+                body,
+            )?;
+        }
+        Ok(recipients)
+    }
+
+    /// Exports the rubric assessments of this assignment as a students × criteria table.
+    ///
+    /// Each row holds, for one of `students`, the points and comments given on every rubric
+    /// criterion. The table can be saved as CSV with `RubricResults::save_csv`.
+    ///
+    /// Returns:
+    /// - `Result<RubricResults, Box<dyn Error>>`: The table, or an error if the assignment has no
+    ///   rubric or the requests failed.
+    ///
+    /// Example:
+    /// ```
+    /// let students = course.fetch_students()?;
+    /// assignment.export_rubric_results(&students)?.save_csv("rubric.csv")?;
+    /// ```
+    pub fn export_rubric_results(&self, students: &[Student]) -> Result<RubricResults, Box<dyn Error>> {
+        let rubric = self
+            .download_rubric()
+            .ok_or_else(|| format!("Assignment {} has no rubric", self.info.id))?;
+        let submissions = canvas::get_rubric_assessments(
+            &reqwest::blocking::Client::new(),
+            self.info.course_info.canvas_info.as_ref(),
+            self.info.course_info.id,
+            self.info.id,
+        )?;
+        Ok(RubricResults::build(self.info.id, &rubric, &submissions, students))
+    }
+
     pub fn download_rubric(&self) -> Option<RubricDownloaded> {
         let client = &reqwest::blocking::Client::new();
 
@@ -410,4 +1043,312 @@ impl GetSubmissionFromSubmissionIdCache {
             submission: None,
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::convert_json_to_student;
+    use serde_json::json;
+
+    #[test]
+    fn test_submission_matches_student_with_hidden_email() {
+        let roster: Vec<Student> = [
+            json!({"id": 10, "name": "Visible", "email": "visible@example.com"}),
+            json!({"id": 11, "name": "Hidden"}),
+        ]
+        .iter()
+        .filter_map(|j| convert_json_to_student(CourseInfo::default(), j))
+        .collect();
+        assert_eq!(roster.len(), 2);
+
+        let submission_json = json!({
+            "id": 500,
+            "assignment_id": 7,
+            "user_id": 11,
+            "score": 8.5,
+            "submitted_at": "2024-03-01T12:00:00Z",
+            "submission_type": "online_upload"
+        });
+
+        let submission = Assignment::convert_json_to_submission(
+            &roster,
+            &submission_json,
+            Arc::new(AssignmentInfo::default()),
+            &None,
+        )
+        .unwrap();
+
+        assert_eq!(submission.students_info.len(), 1);
+        assert_eq!(submission.students_info[0].id, 11);
+        assert!(submission.students_info[0].email.is_none());
+    }
+
+    #[test]
+    fn test_grading_progress_and_needs_grading_counts() {
+        let roster: Vec<Student> = (10..14)
+            .filter_map(|id| convert_json_to_student(CourseInfo::default(), &json!({"id": id, "name": "Aluno"})))
+            .collect();
+        let submissions: Vec<Submission> = [
+            json!({"id": 1, "assignment_id": 7, "user_id": 10, "score": 9.0, "submitted_at": "2024-03-01T12:00:00Z", "workflow_state": "graded"}),
+            json!({"id": 2, "assignment_id": 7, "user_id": 11, "submitted_at": "2024-03-01T12:00:00Z", "workflow_state": "submitted"}),
+            json!({"id": 3, "assignment_id": 7, "user_id": 12, "workflow_state": "unsubmitted", "missing": true}),
+            json!({"id": 4, "assignment_id": 7, "user_id": 13, "workflow_state": "graded", "excused": true}),
+        ]
+        .iter()
+        .filter_map(|j| Assignment::convert_json_to_submission(&roster, j, Arc::new(AssignmentInfo::default()), &None))
+        .collect();
+
+        let progress = GradingProgress::from_submissions(&submissions);
+        assert_eq!(
+            progress,
+            GradingProgress {
+                total: 4,
+                submitted: 2,
+                graded: 2,
+                needs_grading: 1,
+                missing: 1
+            }
+        );
+        assert_eq!(progress.completion(), 0.5);
+
+        let assignment = crate::canvas::convert_json_to_assignment(
+            &Arc::new(CourseInfo::default()),
+            &json!({
+                "id": 7,
+                "name": "Lista 1",
+                "needs_grading_count": 5,
+                "needs_grading_count_by_section": [{"section_id": "300", "needs_grading_count": 2}]
+            }),
+        )
+        .unwrap();
+        assert_eq!(assignment.info.needs_grading_count, Some(5));
+        assert_eq!(
+            assignment.info.needs_grading_count_by_section,
+            vec![SectionNeedsGrading {
+                section_id: 300,
+                needs_grading_count: 2
+            }]
+        );
+    }
+
+    #[test]
+    fn test_accepted_submission_types_and_extensions() {
+        let assignment = crate::canvas::convert_json_to_assignment(
+            &Arc::new(CourseInfo::default()),
+            &json!({
+                "id": 7,
+                "name": "Relatório",
+                "submission_types": ["online_upload", "online_text_entry"],
+                "allowed_extensions": ["pdf", "DOCX"],
+                "allowed_attempts": 2
+            }),
+        )
+        .unwrap();
+        assert!(assignment.accepts(&SubmissionType::OnlineUpload));
+        assert!(!assignment.accepts(&SubmissionType::OnlineUrl));
+        assert!(assignment.info.accepts_file("relatorio.PDF"));
+        assert!(assignment.info.accepts_file("relatorio.docx"));
+        assert!(!assignment.info.accepts_file("relatorio.zip"));
+        assert_eq!(assignment.info.allowed_extensions, vec!["pdf", "DOCX"]);
+        assert_eq!(assignment.info.attempts_limit(), Some(2));
+        assert_eq!(AssignmentInfo { allowed_attempts: Some(-1), ..Default::default() }.attempts_limit(), None);
+        assert!(AssignmentInfo::default().accepts(&SubmissionType::OnlineUrl));
+
+        let roster: Vec<Student> = [json!({"id": 10, "name": "Ana"})]
+            .iter()
+            .filter_map(|j| convert_json_to_student(CourseInfo::default(), j))
+            .collect();
+        let submission_json = json!({
+            "id": 1,
+            "assignment_id": 7,
+            "user_id": 10,
+            "submission_type": "online_upload",
+            "attachments": [{"id": 5000, "filename": "relatorio.zip"}]
+        });
+        let submission =
+            Assignment::convert_json_to_submission(&roster, &submission_json, assignment.info.clone(), &None).unwrap();
+        assert_eq!(submission.extension_violations(), vec!["relatorio.zip"]);
+        assert!(assignment.info.check_submission(&submission).is_err());
+    }
+
+    #[test]
+    fn test_submission_of_user_outside_roster_is_skipped() {
+        let roster: Vec<Student> = [json!({"id": 10, "name": "Ana"})]
+            .iter()
+            .filter_map(|j| convert_json_to_student(CourseInfo::default(), j))
+            .collect();
+
+        // Aluno de teste (Test Student) não aparece na lista de estudantes
+        let test_student = json!({"id": 501, "assignment_id": 7, "user_id": 99, "score": 10.0});
+        let submission = Assignment::convert_json_to_submission(
+            &roster,
+            &test_student,
+            Arc::new(AssignmentInfo::default()),
+            &None,
+        );
+        assert!(submission.is_none());
+
+        // Membros do grupo fora da lista são deixados de fora da submissão
+        let groups = Some(HashMap::from([(1, vec![10, 99])]));
+        let group_submission = json!({"id": 502, "assignment_id": 7, "user_id": 10});
+        let submission = Assignment::convert_json_to_submission(
+            &roster,
+            &group_submission,
+            Arc::new(AssignmentInfo::default()),
+            &groups,
+        )
+        .unwrap();
+        assert_eq!(submission.students_info.len(), 1);
+        assert_eq!(submission.students_info[0].id, 10);
+    }
+
+    #[test]
+    fn test_due_at_for_student_and_section() {
+        let date = |d: &str| Some(DateTime::parse_from_rfc3339(d).unwrap().with_timezone(&Utc));
+        let info = AssignmentInfo {
+            due_at: date("2024-03-01T23:59:00Z"),
+            overrides: vec![
+                AssignmentOverride {
+                    id: 1,
+                    student_ids: vec![10],
+                    due_at: date("2024-03-05T23:59:00Z"),
+                    ..Default::default()
+                },
+                AssignmentOverride {
+                    id: 2,
+                    course_section_id: Some(100),
+                    due_at: date("2024-03-02T23:59:00Z"),
+                    ..Default::default()
+                },
+                AssignmentOverride {
+                    id: 3,
+                    course_section_id: Some(200),
+                    due_at: date("2024-03-03T23:59:00Z"),
+                    ..Default::default()
+                },
+                AssignmentOverride {
+                    id: 4,
+                    student_ids: vec![13],
+                    due_at: date("2024-03-02T12:00:00Z"),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+
+        // Most lenient of the student and section overrides applies
+        assert_eq!(info.due_at_for_student(10, &[100], None), date("2024-03-05T23:59:00Z"));
+        assert_eq!(info.due_at_for_student(13, &[200], None), date("2024-03-03T23:59:00Z"));
+        // Latest section date applies
+        assert_eq!(info.due_at_for_student(11, &[100, 200], None), date("2024-03-03T23:59:00Z"));
+        // No override: default due date
+        assert_eq!(info.due_at_for_student(12, &[], None), info.due_at);
+        assert_eq!(info.due_at_for_section(100), date("2024-03-02T23:59:00Z"));
+        assert_eq!(info.due_at_for_section(300), info.due_at);
+    }
+
+    #[test]
+    fn test_section_extension_is_not_late() {
+        let date = |d: &str| Some(DateTime::parse_from_rfc3339(d).unwrap().with_timezone(&Utc));
+        let info = Arc::new(AssignmentInfo {
+            due_at: date("2024-03-01T23:59:00Z"),
+            overrides: vec![AssignmentOverride {
+                id: 1,
+                course_section_id: Some(100),
+                due_at: date("2024-03-08T23:59:00Z"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        });
+        let roster: Vec<Student> = [json!({"id": 10, "name": "Ana", "enrollments": [{"course_section_id": 100}]})]
+            .iter()
+            .filter_map(|j| convert_json_to_student(CourseInfo::default(), j))
+            .collect();
+        let submitted = json!({"id": 1, "assignment_id": 7, "user_id": 10, "submitted_at": "2024-03-05T10:00:00Z"});
+
+        // Date resolved by Canvas
+        let mut with_cached = submitted.clone();
+        with_cached["cached_due_date"] = json!("2024-03-08T23:59:00Z");
+        let submission = Assignment::convert_json_to_submission(&roster, &with_cached, info.clone(), &None).unwrap();
+        assert_eq!(submission.effective_due_at(), date("2024-03-08T23:59:00Z"));
+        assert_eq!(submission.is_late(), None);
+
+        // Without it, the section override of the student's enrollment applies
+        let submission = Assignment::convert_json_to_submission(&roster, &submitted, info.clone(), &None).unwrap();
+        assert_eq!(submission.effective_due_at(), date("2024-03-08T23:59:00Z"));
+        assert_eq!(submission.is_late(), None);
+
+        // Default due date for students of other sections
+        let roster: Vec<Student> = [json!({"id": 10, "name": "Ana"})]
+            .iter()
+            .filter_map(|j| convert_json_to_student(CourseInfo::default(), j))
+            .collect();
+        let submission = Assignment::convert_json_to_submission(&roster, &submitted, info, &None).unwrap();
+        assert!(submission.is_late().is_some());
+    }
+
+    #[test]
+    fn test_message_criterion_selects_students() {
+        let roster: Vec<Student> = (1..=4)
+            .filter_map(|id| convert_json_to_student(CourseInfo::default(), &json!({"id": id, "name": "S"})))
+            .collect();
+        let submission = |user_id: u64, submitted: bool, score: Option<f64>| Submission {
+            id: user_id * 100,
+            assignment_id: 7,
+            score,
+            submitted_at: if submitted { Some(Utc::now()) } else { None },
+            submission_type: None,
+            students_info: vec![roster[(user_id - 1) as usize].info.clone()],
+            assignment_info: Arc::new(AssignmentInfo::default()),
+            file_ids: Vec::new(),
+            comments: Vec::new(),
+            extra: Default::default(),
+        };
+        // 1: submitted and graded 4.0; 2: submitted, not graded; 3: not submitted; 4: no record
+        let submissions = vec![
+            submission(1, true, Some(4.0)),
+            submission(2, true, None),
+            submission(3, false, None),
+        ];
+
+        assert_eq!(MessageCriterion::NotSubmitted.select(&roster, &submissions), vec![3, 4]);
+        assert_eq!(MessageCriterion::NotGraded.select(&roster, &submissions), vec![2, 3, 4]);
+        assert_eq!(MessageCriterion::ScoredBelow(5.0).select(&roster, &submissions), vec![1]);
+    }
+
+    #[test]
+    fn test_statistics_from_scores() {
+        assert!(AssignmentStatistics::from_scores(&[]).is_none());
+
+        let stats = AssignmentStatistics::from_scores(&[7.0, 3.0, 10.0, 5.0, 9.0]).unwrap();
+        assert_eq!(stats.count, 5);
+        assert_eq!(stats.min, 3.0);
+        assert_eq!(stats.max, 10.0);
+        assert!((stats.mean - 6.8).abs() < 1e-9);
+        assert_eq!(stats.lower_q, Some(5.0));
+        assert_eq!(stats.median, Some(7.0));
+        assert_eq!(stats.upper_q, Some(9.0));
+
+        let stats = AssignmentStatistics::from_scores(&[1.0, 2.0]).unwrap();
+        assert_eq!(stats.median, Some(1.5));
+    }
+
+    #[test]
+    fn test_assignment_query_params() {
+        assert!(AssignmentQuery::default().is_empty());
+        assert!(AssignmentQuery::default().to_params().is_empty());
+
+        let query = AssignmentQuery::default()
+            .bucket(AssignmentBucket::Overdue)
+            .order_by(AssignmentOrder::DueAt)
+            .search_term("prova");
+        assert_eq!(
+            query.to_params(),
+            vec![
+                ("bucket".to_string(), "overdue".to_string()),
+                ("order_by".to_string(), "due_at".to_string()),
+                ("search_term".to_string(), "prova".to_string()),
+            ]
+        );
+    }
+}
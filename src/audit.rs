@@ -0,0 +1,220 @@
+// Import necessary crates and modules
+use crate::connection::HttpMethod;
+use crate::CanvasCredentials;
+use chrono::{DateTime, Utc};
+use lazy_static::lazy_static;
+use serde::{Deserialize, Serialize};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::sync::{Arc, Mutex, RwLock};
+
+/// Maximum number of characters of a request body kept in `AuditEntry::payload_summary`.
+pub const PAYLOAD_SUMMARY_LIMIT: usize = 500;
+
+/// Record of a single mutating request (PUT, POST or DELETE) sent to Canvas.
+///
+/// Fields:
+/// - `timestamp`: When the request finished (after retries).
+/// - `actor`: Free-form label set with `AuditLog::with_actor` (e.g. the grading script name).
+/// - `token_hint`: Last four characters of the API token, identifying which credentials were used
+///   without leaking them.
+/// - `method`: HTTP verb.
+/// - `url`: Endpoint called.
+/// - `payload_summary`: Request body as JSON, truncated to `PAYLOAD_SUMMARY_LIMIT` characters.
+/// - `status`: HTTP status of the final response, if one was received.
+/// - `success`: Whether the call succeeded.
+/// - `error`: Error message when the call failed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct AuditEntry {
+    pub timestamp: DateTime<Utc>,
+    pub actor: Option<String>,
+    pub token_hint: String,
+    pub method: String,
+    pub url: String,
+    pub payload_summary: Option<String>,
+    pub status: Option<u16>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+// Destination of the audit entries.
+enum AuditTarget {
+    Jsonl(Mutex<File>),
+    Callback(Box<dyn Fn(&AuditEntry) + Send + Sync>),
+}
+
+/// Sink recording every mutating call made by the connector.
+///
+/// Entries can be appended to a JSON Lines file (one `AuditEntry` per line) or handed to a
+/// callback. Register the log with `set_audit_log`; GET requests are never recorded.
+///
+/// Example:
+/// ```
+/// let log = AuditLog::to_file("grading-audit.jsonl")?.with_actor("autograder v2");
+/// set_audit_log(Some(log));
+/// ```
+pub struct AuditLog {
+    target: AuditTarget,
+    actor: Option<String>,
+}
+
+impl AuditLog {
+    /// Creates a log appending JSON lines to the file at `path` (created if missing).
+    pub fn to_file(path: &str) -> Result<AuditLog, std::io::Error> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(AuditLog {
+            target: AuditTarget::Jsonl(Mutex::new(file)),
+            actor: None,
+        })
+    }
+
+    /// Creates a log forwarding each entry to `callback`.
+    pub fn with_callback<F>(callback: F) -> AuditLog
+    where
+        F: Fn(&AuditEntry) + Send + Sync + 'static,
+    {
+        AuditLog {
+            target: AuditTarget::Callback(Box::new(callback)),
+            actor: None,
+        }
+    }
+
+    /// Sets the actor label stored in every entry.
+    pub fn with_actor(mut self, actor: &str) -> AuditLog {
+        self.actor = Some(actor.to_string());
+        self
+    }
+
+    /// Writes an entry to the log. Failures to write the file are reported on stderr and never
+    /// interrupt the request that is being audited.
+    pub fn record(&self, entry: &AuditEntry) {
+        match &self.target {
+            AuditTarget::Jsonl(file) => {
+                let line = match serde_json::to_string(entry) {
+                    Ok(line) => line,
+                    Err(e) => {
+                        eprintln!("Failed to serialize audit entry: {}", e);
+                        return;
+                    }
+                };
+                let mut file = file.lock().unwrap();
+                if let Err(e) = writeln!(file, "{}", line) {
+                    eprintln!("Failed to write audit entry: {}", e);
+                }
+            }
+            AuditTarget::Callback(callback) => callback(entry),
+        }
+    }
+}
+
+// Global audit log. `None` disables auditing (the default).
+lazy_static! {
+    static ref AUDIT_LOG: RwLock<Option<Arc<AuditLog>>> = RwLock::new(None);
+}
+
+/// Registers the audit log used for every subsequent mutating request, or disables it with `None`.
+pub fn set_audit_log(log: Option<AuditLog>) {
+    *AUDIT_LOG.write().unwrap() = log.map(Arc::new);
+}
+
+/// Records the outcome of a request in the global audit log, if one is registered and the
+/// request is mutating.
+pub(crate) fn record_request(
+    method: &HttpMethod,
+    url: &str,
+    canvas_info: &CanvasCredentials,
+    status: Option<u16>,
+    error: Option<String>,
+) {
+    if let HttpMethod::Get = method {
+        return;
+    }
+    let log = match AUDIT_LOG.read().unwrap().clone() {
+        Some(log) => log,
+        None => return,
+    };
+
+    let payload_summary = match method {
+        HttpMethod::Put(body) | HttpMethod::Post(body) => Some(summarize_payload(body)),
+        _ => None,
+    };
+
+    log.record(&AuditEntry {
+        timestamp: Utc::now(),
+        actor: log.actor.clone(),
+        token_hint: token_hint(&canvas_info.token_canvas),
+        method: method.as_str().to_string(),
+        url: url.to_string(),
+        payload_summary,
+        status,
+        success: error.is_none(),
+        error,
+    });
+}
+
+// Serializes a body and truncates it to PAYLOAD_SUMMARY_LIMIT characters.
+fn summarize_payload(body: &serde_json::Value) -> String {
+    let text = body.to_string();
+    if text.chars().count() > PAYLOAD_SUMMARY_LIMIT {
+        let truncated: String = text.chars().take(PAYLOAD_SUMMARY_LIMIT).collect();
+        format!("{}...", truncated)
+    } else {
+        text
+    }
+}
+
+// Keeps only the last four characters of a token.
+fn token_hint(token: &str) -> String {
+    let chars: Vec<char> = token.chars().collect();
+    let start = chars.len().saturating_sub(4);
+    format!("...{}", chars[start..].iter().collect::<String>())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_summary_and_token_hint() {
+        let small = json!({"submission": {"posted_grade": 9.5}});
+        assert_eq!(summarize_payload(&small), small.to_string());
+
+        let large = json!({"comment": {"text_comment": "x".repeat(2000)}});
+        let summary = summarize_payload(&large);
+        assert!(summary.ends_with("..."));
+        assert_eq!(summary.chars().count(), PAYLOAD_SUMMARY_LIMIT + 3);
+
+        assert_eq!(token_hint("20746~abcdefgh"), "...efgh");
+        assert_eq!(token_hint("ab"), "...ab");
+    }
+
+    #[test]
+    fn test_jsonl_audit_log_appends_entries() {
+        let path = std::env::temp_dir().join(format!("canvas_audit_{}.jsonl", std::process::id()));
+        let path_str = path.to_str().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let log = AuditLog::to_file(path_str).unwrap().with_actor("tests");
+        let entry = AuditEntry {
+            timestamp: Utc::now(),
+            actor: log.actor.clone(),
+            token_hint: "...abcd".to_string(),
+            method: "PUT".to_string(),
+            url: "https://canvas.example.com/api/v1/courses/1".to_string(),
+            payload_summary: Some("{}".to_string()),
+            status: Some(200),
+            success: true,
+            error: None,
+        };
+        log.record(&entry);
+        log.record(&entry);
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let parsed: AuditEntry = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(parsed, entry);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_pages_following_links;
+use crate::CanvasCredentials;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Whose authentication events are listed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthenticationLogTarget {
+    User(u64),
+    Account(u64),
+    Login(u64),
+}
+
+impl AuthenticationLogTarget {
+    // Path of the audit endpoint of the target.
+    fn path(&self) -> String {
+        match self {
+            AuthenticationLogTarget::User(id) => format!("users/{}", id),
+            AuthenticationLogTarget::Account(id) => format!("accounts/{}", id),
+            AuthenticationLogTarget::Login(id) => format!("logins/{}", id),
+        }
+    }
+}
+
+/// Objects an authentication event refers to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AuthenticationLinks {
+    pub login: Option<u64>,
+    pub account: Option<u64>,
+    pub user: Option<u64>,
+    pub page_view: Option<String>,
+}
+
+/// An entry of the authentication audit log.
+///
+/// Fields:
+/// - `id`: ID of the event.
+/// - `created_at`: When the user logged in or out.
+/// - `event_type`: `login`, `logout` or `corrupted`.
+/// - `links`: Login, account, user and page view of the event.
+/// - `remote_ip`: IP address the request came from, taken from the linked page view.
+/// - `user_agent`: Browser of the request, taken from the linked page view.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AuthenticationEvent {
+    pub id: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub event_type: String,
+    #[serde(default)]
+    pub links: AuthenticationLinks,
+    #[serde(default)]
+    pub remote_ip: Option<String>,
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+// Reads the events of one page of the log, completing them with the linked page views.
+fn events_from_page(page: &Value) -> Vec<AuthenticationEvent> {
+    let page_views: HashMap<String, &Value> = page["linked"]["page_views"]
+        .as_array()
+        .map(|views| {
+            views
+                .iter()
+                .filter_map(|view| Some((view["id"].as_str()?.to_string(), view)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    page["events"]
+        .as_array()
+        .map(|events| {
+            events
+                .iter()
+                .filter_map(|json| serde_json::from_value::<AuthenticationEvent>(json.clone()).ok())
+                .map(|mut event| {
+                    if let Some(view) = event.links.page_view.as_ref().and_then(|id| page_views.get(id)) {
+                        event.remote_ip = view["remote_ip"].as_str().map(str::to_string);
+                        event.user_agent = view["user_agent"].as_str().map(str::to_string);
+                    }
+                    event
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Lists the logins and logouts of a user, account or login.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials (the user needs the "view statistics" permission).
+/// - `target`: Whose events are listed.
+/// - `start_time` / `end_time`: Optional time window.
+///
+/// Returns:
+/// - `Result<Vec<AuthenticationEvent>, Box<dyn Error>>`: The events, newest first, or an error.
+///
+/// Example:
+/// ```
+/// // Where did the student log in from during the exam?
+/// let events = fetch_authentication_events(
+///     &client, &canvas_info, AuthenticationLogTarget::User(student.info.id), Some(start), Some(end),
+/// )?;
+/// for event in events.iter().filter(|e| e.event_type == "login") {
+///     println!("{} {}", event.created_at, event.remote_ip.as_deref().unwrap_or("?"));
+/// }
+/// ```
+pub fn fetch_authentication_events(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    target: AuthenticationLogTarget,
+    start_time: Option<DateTime<Utc>>,
+    end_time: Option<DateTime<Utc>>,
+) -> Result<Vec<AuthenticationEvent>, Box<dyn Error>> {
+    let url = format!("{}/audit/authentication/{}", canvas_info.url_canvas, target.path());
+    let mut params = Vec::new();
+    if let Some(start) = start_time {
+        params.push(("start_time".to_string(), start.to_rfc3339()));
+    }
+    if let Some(end) = end_time {
+        params.push(("end_time".to_string(), end.to_rfc3339()));
+    }
+    Ok(fetch_pages_following_links(client, canvas_info, &url, params)?
+        .iter()
+        .flat_map(events_from_page)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_events_from_page_with_linked_page_views() {
+        let page = json!({
+            "events": [
+                {"id": "a1", "created_at": "2024-06-03T12:00:00Z", "event_type": "login",
+                 "links": {"login": 5, "account": 1, "user": 100, "page_view": "pv-1"}},
+                {"id": "a2", "created_at": "2024-06-03T13:00:00Z", "event_type": "logout",
+                 "links": {"login": 5, "account": 1, "user": 100, "page_view": null}}
+            ],
+            "linked": {
+                "page_views": [{"id": "pv-1", "remote_ip": "10.0.0.7", "user_agent": "Firefox"}]
+            }
+        });
+        let events = events_from_page(&page);
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0].remote_ip.as_deref(), Some("10.0.0.7"));
+        assert!(events[1].remote_ip.is_none());
+        assert_eq!(AuthenticationLogTarget::Login(5).path(), "logins/5");
+    }
+}
@@ -0,0 +1,199 @@
+//! `canvas-connector`: command-line companion of the library.
+//!
+//! Build with `cargo install canvas_lms_connector --features cli`. Credentials are obtained the
+//! same way as in the library (environment variables or system keyring, prompting if missing).
+//! When a course or assignment ID is omitted, it is chosen from an interactive menu.
+
+// Import necessary crates and modules
+use canvas_lms_connector::sync::{self, ConflictPolicy, Gradebook};
+use canvas_lms_connector::{Assignment, Canvas, CanvasCredentials, CanvasResultCourses, CanvasResultSingleCourse, Course};
+use reqwest::blocking::Client;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::exit;
+
+const USAGE: &str = "Usage: canvas-connector <command> [arguments]
+
+Commands:
+  courses                                          List the courses you teach
+  gradebook [course_id] [output.csv]               Export the scores of every assignment
+  download [course_id] [assignment_id] [dir]       Download the files of every submission
+  post-grades <grades.csv> [course_id] [--dry-run] Post grades from a student_id,assignment_id,score CSV";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("Error: {}", e);
+        exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let dry_run = args.iter().any(|arg| arg == "--dry-run");
+    let args: Vec<&str> = args.iter().map(String::as_str).filter(|arg| *arg != "--dry-run").collect();
+    match args.as_slice() {
+        ["courses"] => list_courses(),
+        ["gradebook", rest @ ..] => {
+            let course = course(rest.first())?;
+            export_gradebook(&course, rest.get(1).copied())
+        }
+        ["download", rest @ ..] => {
+            let course = course(rest.first())?;
+            let assignment = assignment(&course, rest.get(1))?;
+            download_submissions(&assignment, rest.get(2).copied().unwrap_or("submissions"))
+        }
+        ["post-grades", csv_path, rest @ ..] => {
+            let course = course(rest.first())?;
+            post_grades(&course, csv_path, dry_run)
+        }
+        _ => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+    }
+}
+
+// Fetches the course with the given ID, or lets the user choose one.
+fn course(id: Option<&&str>) -> Result<Course, Box<dyn Error>> {
+    let Some(id) = id else {
+        return Canvas::choose_course().ok_or_else(|| "No course selected".into());
+    };
+    let id: u64 = id.parse().map_err(|_| format!("Invalid course ID: {}", id))?;
+    match Canvas::fetch_single_course_with_credentials(&CanvasCredentials::credentials(), id) {
+        CanvasResultSingleCourse::Ok(course) => Ok(course),
+        CanvasResultSingleCourse::ErrConnection(e) | CanvasResultSingleCourse::ErrCredentials(e) => Err(e.into()),
+        CanvasResultSingleCourse::ErrAccessRestricted(id) => Err(format!("Course {} is restricted by date", id).into()),
+    }
+}
+
+// Fetches the assignment with the given ID, or lets the user choose one.
+fn assignment(course: &Course, id: Option<&&str>) -> Result<Assignment, Box<dyn Error>> {
+    let Some(id) = id else {
+        let (mut assignments, index) = course.choose_assignment(None, None).ok_or("No assignment selected")?;
+        return Ok(assignments.swap_remove(index));
+    };
+    let id: u64 = id.parse().map_err(|_| format!("Invalid assignment ID: {}", id))?;
+    course.get_assignment_from_assignment_id(id)
+}
+
+fn list_courses() -> Result<(), Box<dyn Error>> {
+    match Canvas::fetch_courses_with_credentials(&CanvasCredentials::credentials()) {
+        CanvasResultCourses::Ok(courses) => {
+            for course in courses {
+                println!("{}\t{}\t{}", course.info.id, course.info.course_code, course.info.name);
+            }
+            Ok(())
+        }
+        CanvasResultCourses::ErrConnection(e) | CanvasResultCourses::ErrCredentials(e) => Err(e.into()),
+    }
+}
+
+// Writes one row per student and one column per assignment, to a file or to stdout.
+fn export_gradebook(course: &Course, output: Option<&str>) -> Result<(), Box<dyn Error>> {
+    let students = course.fetch_students()?;
+    let assignments = course.fetch_assignments()?;
+    let ids: Vec<u64> = assignments.iter().map(|assignment| assignment.info.id).collect();
+    let gradebook = sync::fetch_remote_gradebook(course, &ids)?;
+
+    let mut csv = String::from("student_id,name");
+    for assignment in &assignments {
+        csv.push(',');
+        csv.push_str(&csv_field(&assignment.info.name));
+    }
+    csv.push('\n');
+    for student in &students {
+        csv.push_str(&format!("{},{}", student.info.id, csv_field(&student.info.name)));
+        let scores = gradebook.get(&student.info.id);
+        for id in &ids {
+            csv.push(',');
+            if let Some(score) = scores.and_then(|scores| scores.get(id)) {
+                csv.push_str(&score.to_string());
+            }
+        }
+        csv.push('\n');
+    }
+
+    match output {
+        Some(path) => fs::write(path, csv)?,
+        None => std::io::stdout().write_all(csv.as_bytes())?,
+    }
+    Ok(())
+}
+
+// Saves the files of each submission in a folder named after the student.
+fn download_submissions(assignment: &Assignment, output_dir: &str) -> Result<(), Box<dyn Error>> {
+    let client = Client::new();
+    let students = assignment.info.course_info.fetch_students()?;
+    for submission in assignment.fetch_submissions(&students)? {
+        let Some(student) = submission.students_info.first() else {
+            continue;
+        };
+        let folder = Path::new(output_dir).join(format!("{} - {}", student.id, student.name));
+        match submission.download_submission_files(&client, &folder.to_string_lossy()) {
+            Ok(Some(files)) => println!("{}: {} file(s)", student.name, files.len()),
+            Ok(None) => {}
+            Err(e) => eprintln!("{}: {}", student.name, e),
+        }
+    }
+    Ok(())
+}
+
+// Posts the grades of a CSV file, writing only those that differ from Canvas.
+fn post_grades(course: &Course, csv_path: &str, dry_run: bool) -> Result<(), Box<dyn Error>> {
+    let local = parse_grades(&fs::read_to_string(csv_path)?)?;
+    let changes = sync::plan(course, &local, None)?;
+    let report = sync::apply(course, &Client::new(), &changes, ConflictPolicy::PreferLocal, dry_run)?;
+    let verb = if dry_run { "would be posted" } else { "posted" };
+    println!("{} grade(s) {}", report.applied.len(), verb);
+    for (change, e) in &report.failed {
+        eprintln!("Student {} / assignment {}: {}", change.student_id, change.assignment_id, e);
+    }
+    Ok(())
+}
+
+// Reads a `student_id,assignment_id,score` CSV (with or without header).
+fn parse_grades(csv: &str) -> Result<Gradebook, Box<dyn Error>> {
+    let mut gradebook = Gradebook::new();
+    for (index, line) in csv.lines().enumerate() {
+        let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+        if line.trim().is_empty() || (index == 0 && fields[0].parse::<u64>().is_err()) {
+            continue;
+        }
+        let [student_id, assignment_id, score] = fields[..] else {
+            return Err(format!("Line {}: expected student_id,assignment_id,score", index + 1).into());
+        };
+        let invalid = || format!("Line {}: invalid number", index + 1);
+        gradebook
+            .entry(student_id.parse().map_err(|_| invalid())?)
+            .or_default()
+            .insert(assignment_id.parse().map_err(|_| invalid())?, score.parse().map_err(|_| invalid())?);
+    }
+    Ok(gradebook)
+}
+
+// Quotes a CSV field when needed.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_grades_csv() {
+        let gradebook = parse_grades("student_id,assignment_id,score\n10, 100, 8.5\n11,100,7\n\n10,101,10\n").unwrap();
+        assert_eq!(gradebook[&10][&100], 8.5);
+        assert_eq!(gradebook[&10][&101], 10.0);
+        assert_eq!(gradebook[&11][&100], 7.0);
+        assert!(parse_grades("10,100").is_err());
+        assert!(parse_grades("10,100,abc").is_err());
+        assert_eq!(csv_field("Silva, Ana"), "\"Silva, Ana\"");
+    }
+}
@@ -0,0 +1,292 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages_as, fetch_json};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// Blueprint template of a blueprint (parent) course.
+///
+/// Fields:
+/// - `id`: ID of the template.
+/// - `course_id`: The blueprint course.
+/// - `last_export_completed_at`: When the last sync finished.
+/// - `associated_course_count`: Number of child courses.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlueprintTemplate {
+    pub id: u64,
+    pub course_id: u64,
+    pub last_export_completed_at: Option<String>,
+    #[serde(default)]
+    pub associated_course_count: u64,
+}
+
+/// A child course associated with a blueprint.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlueprintAssociatedCourse {
+    pub id: u64,
+    pub name: String,
+    pub course_code: Option<String>,
+    pub term_name: Option<String>,
+    pub sis_course_id: Option<String>,
+}
+
+/// A child course where a synced change could not be applied.
+///
+/// Fields:
+/// - `course_id`: The child course.
+/// - `conflicting_changes`: What was changed locally and blocked the sync (`content`,
+///   `points`, `due_dates`, `availability_dates`...).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlueprintException {
+    pub course_id: u64,
+    #[serde(default)]
+    pub conflicting_changes: Vec<String>,
+}
+
+/// A change of blueprint content, either waiting to be synced or part of a sync.
+///
+/// Fields:
+/// - `asset_id` / `asset_type` / `asset_name`: The changed content.
+/// - `change_type`: `created`, `updated` or `deleted`.
+/// - `html_url`: Link to the content in the blueprint course.
+/// - `locked`: Whether the content is locked in child courses.
+/// - `exceptions`: Child courses where the change wasn't applied (sync details only).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlueprintChange {
+    pub asset_id: u64,
+    pub asset_type: String,
+    pub asset_name: Option<String>,
+    pub change_type: String,
+    pub html_url: Option<String>,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub exceptions: Vec<BlueprintException>,
+}
+
+/// A sync (migration) of a blueprint to its child courses.
+///
+/// Fields:
+/// - `id`: ID of the migration.
+/// - `workflow_state`: `queued`, `exporting`, `imports_queued`, `completed`, `exports_failed` or
+///   `imports_failed`.
+/// - `created_at` / `imports_completed_at`: When the sync started and finished.
+/// - `comment`: Note written by the user who started the sync.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlueprintMigration {
+    pub id: u64,
+    pub workflow_state: String,
+    pub created_at: Option<String>,
+    pub imports_completed_at: Option<String>,
+    pub comment: Option<String>,
+}
+
+/// Sync status of one child course. See `BlueprintStatus`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AssociatedCourseStatus {
+    pub course: BlueprintAssociatedCourse,
+    /// Changes of the last sync that were not applied to this course.
+    pub exceptions: Vec<BlueprintChange>,
+    /// `true` when nothing is waiting to be synced and the last sync applied every change.
+    pub up_to_date: bool,
+}
+
+/// Overview of a blueprint course for a dashboard: pending changes, last sync and the status of
+/// every child course.
+///
+/// Example:
+/// ```
+/// let status = course.fetch_blueprint_status(&client)?;
+/// for child in status.courses.iter().filter(|c| !c.up_to_date) {
+///     println!("{} is out of date", child.course.name);
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BlueprintStatus {
+    pub template: BlueprintTemplate,
+    pub unsynced_changes: Vec<BlueprintChange>,
+    pub last_migration: Option<BlueprintMigration>,
+    pub courses: Vec<AssociatedCourseStatus>,
+}
+
+impl BlueprintStatus {
+    /// Combines the pieces of the status. `last_changes` are the details of `last_migration`.
+    pub fn build(
+        template: BlueprintTemplate,
+        unsynced_changes: Vec<BlueprintChange>,
+        last_migration: Option<BlueprintMigration>,
+        last_changes: &[BlueprintChange],
+        associated_courses: Vec<BlueprintAssociatedCourse>,
+    ) -> BlueprintStatus {
+        let last_sync_ok = last_migration
+            .as_ref()
+            .is_none_or(|migration| migration.workflow_state == "completed");
+        let courses = associated_courses
+            .into_iter()
+            .map(|course| {
+                let exceptions: Vec<BlueprintChange> = last_changes
+                    .iter()
+                    .filter(|change| change.exceptions.iter().any(|e| e.course_id == course.id))
+                    .cloned()
+                    .collect();
+                let up_to_date = unsynced_changes.is_empty() && last_sync_ok && exceptions.is_empty();
+                AssociatedCourseStatus {
+                    course,
+                    exceptions,
+                    up_to_date,
+                }
+            })
+            .collect();
+        BlueprintStatus {
+            template,
+            unsynced_changes,
+            last_migration,
+            courses,
+        }
+    }
+}
+
+// Base URL of the default template of a blueprint course.
+fn template_url(canvas_info: &CanvasCredentials, course_id: u64) -> String {
+    format!(
+        "{}/courses/{}/blueprint_templates/default",
+        canvas_info.url_canvas, course_id
+    )
+}
+
+/// Retrieves the blueprint template of a blueprint course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the blueprint course.
+///
+/// Returns:
+/// - `Result<BlueprintTemplate, Box<dyn Error>>`: The template or an error (e.g. the course is
+///   not a blueprint).
+pub fn fetch_blueprint_template(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<BlueprintTemplate, Box<dyn Error>> {
+    fetch_json(client, canvas_info, &template_url(canvas_info, course_id))
+}
+
+/// Lists the changes made in a blueprint course since its last sync.
+pub fn fetch_unsynced_changes(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<Vec<BlueprintChange>, Box<dyn Error>> {
+    let url = format!("{}/unsynced_changes", template_url(canvas_info, course_id));
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Lists the child courses of a blueprint course.
+pub fn fetch_associated_courses(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<Vec<BlueprintAssociatedCourse>, Box<dyn Error>> {
+    let url = format!("{}/associated_courses", template_url(canvas_info, course_id));
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Lists the syncs of a blueprint course, newest first.
+pub fn fetch_blueprint_migrations(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<Vec<BlueprintMigration>, Box<dyn Error>> {
+    let url = format!("{}/migrations", template_url(canvas_info, course_id));
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Lists the changes of one sync, with the child courses where each change failed.
+pub fn fetch_migration_details(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    migration_id: u64,
+) -> Result<Vec<BlueprintChange>, Box<dyn Error>> {
+    let url = format!(
+        "{}/migrations/{}/details",
+        template_url(canvas_info, course_id),
+        migration_id
+    );
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Collects the template, pending changes, last sync and child courses of a blueprint course
+/// into a `BlueprintStatus`.
+pub fn fetch_blueprint_status(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<BlueprintStatus, Box<dyn Error>> {
+    let template = fetch_blueprint_template(client, canvas_info, course_id)?;
+    let unsynced_changes = fetch_unsynced_changes(client, canvas_info, course_id)?;
+    let last_migration = fetch_blueprint_migrations(client, canvas_info, course_id)?
+        .into_iter()
+        .next();
+    let last_changes = match &last_migration {
+        Some(migration) => fetch_migration_details(client, canvas_info, course_id, migration.id)?,
+        None => Vec::new(),
+    };
+    let associated_courses = fetch_associated_courses(client, canvas_info, course_id)?;
+    Ok(BlueprintStatus::build(
+        template,
+        unsynced_changes,
+        last_migration,
+        &last_changes,
+        associated_courses,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_blueprint_status() {
+        let course = |id: u64, name: &str| BlueprintAssociatedCourse {
+            id,
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let last_changes: Vec<BlueprintChange> = serde_json::from_value(json!([
+            {"asset_id": 1, "asset_type": "assignment", "asset_name": "Lista 1", "change_type": "updated",
+             "locked": false, "exceptions": [{"course_id": 20, "conflicting_changes": ["points"]}]},
+            {"asset_id": 2, "asset_type": "wiki_page", "asset_name": "Plano", "change_type": "created", "locked": true}
+        ]))
+        .unwrap();
+        let migration = BlueprintMigration {
+            id: 3,
+            workflow_state: "completed".to_string(),
+            ..Default::default()
+        };
+
+        let status = BlueprintStatus::build(
+            BlueprintTemplate::default(),
+            Vec::new(),
+            Some(migration),
+            &last_changes,
+            vec![course(10, "Turma A"), course(20, "Turma B")],
+        );
+        assert!(status.courses[0].up_to_date);
+        assert!(!status.courses[1].up_to_date);
+        assert_eq!(status.courses[1].exceptions[0].exceptions[0].conflicting_changes, vec!["points"]);
+
+        let pending = vec![last_changes[1].clone()];
+        let status = BlueprintStatus::build(
+            BlueprintTemplate::default(),
+            pending,
+            None,
+            &[],
+            vec![course(10, "Turma A")],
+        );
+        assert!(!status.courses[0].up_to_date);
+    }
+}
@@ -0,0 +1,138 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages_as, fetch_json};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+/// A bookmark of the authenticated user.
+///
+/// Fields:
+/// - `id`: ID of the bookmark.
+/// - `name`: Label shown to the user.
+/// - `url`: Target of the bookmark (any Canvas page, e.g. a SpeedGrader link).
+/// - `position`: Order of the bookmark in the user's list.
+/// - `data`: Free-form JSON stored with the bookmark.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Bookmark {
+    pub id: u64,
+    pub name: String,
+    pub url: String,
+    pub position: Option<i64>,
+    pub data: Option<Value>,
+}
+
+/// Fields of a bookmark to create or change. Only the fields set to `Some` are sent.
+///
+/// Example:
+/// ```
+/// let bookmark = BookmarkUpdate {
+///     name: Some("Lista 3 - correção".to_string()),
+///     url: Some(format!("{}/courses/{}/gradebook/speed_grader?assignment_id={}", host, course_id, assignment_id)),
+///     ..Default::default()
+/// };
+/// create_bookmark(&client, &canvas_info, &bookmark)?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct BookmarkUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+// Base URL of the bookmarks of the authenticated user.
+fn bookmarks_url(canvas_info: &CanvasCredentials) -> String {
+    format!("{}/users/self/bookmarks", canvas_info.url_canvas)
+}
+
+/// Retrieves the bookmarks of the authenticated user.
+pub fn fetch_bookmarks(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<Bookmark>, Box<dyn Error>> {
+    fetch_all_pages_as(client, canvas_info, &bookmarks_url(canvas_info), Vec::new())
+}
+
+/// Retrieves one bookmark of the authenticated user.
+pub fn fetch_bookmark(client: &Client, canvas_info: &CanvasCredentials, bookmark_id: u64) -> Result<Bookmark, Box<dyn Error>> {
+    let url = format!("{}/{}", bookmarks_url(canvas_info), bookmark_id);
+    fetch_json(client, canvas_info, &url)
+}
+
+/// Creates a bookmark. `name` and `url` are required by Canvas.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `bookmark`: Fields of the new bookmark.
+///
+/// Returns:
+/// - `Result<Bookmark, Box<dyn Error>>`: The created bookmark or an error.
+pub fn create_bookmark(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    bookmark: &BookmarkUpdate,
+) -> Result<Bookmark, Box<dyn Error>> {
+    if bookmark.name.is_none() || bookmark.url.is_none() {
+        return Err("A bookmark needs a name and a url".into());
+    }
+    let body = serde_json::to_value(bookmark)?;
+    send_bookmark_request(client, canvas_info, HttpMethod::Post(body), &bookmarks_url(canvas_info))
+}
+
+/// Changes the fields of a bookmark set in `update`.
+pub fn update_bookmark(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    bookmark_id: u64,
+    update: &BookmarkUpdate,
+) -> Result<Bookmark, Box<dyn Error>> {
+    let url = format!("{}/{}", bookmarks_url(canvas_info), bookmark_id);
+    let body = serde_json::to_value(update)?;
+    send_bookmark_request(client, canvas_info, HttpMethod::Put(body), &url)
+}
+
+/// Deletes a bookmark.
+pub fn delete_bookmark(client: &Client, canvas_info: &CanvasCredentials, bookmark_id: u64) -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/{}", bookmarks_url(canvas_info), bookmark_id);
+    send_bookmark_request::<Value>(client, canvas_info, HttpMethod::Delete, &url)?;
+    Ok(())
+}
+
+// Sends a mutating request to a bookmarks endpoint and decodes the answer.
+fn send_bookmark_request<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    method: HttpMethod,
+    url: &str,
+) -> Result<T, Box<dyn Error>> {
+    let response = send_http_request(client, method, url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update bookmark with status: {}", response.status()).into());
+    }
+    let text = response.text()?;
+    Ok(serde_json::from_str(if text.trim().is_empty() { "{}" } else { &text })?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_bookmark_update_sends_only_set_fields() {
+        let update = BookmarkUpdate {
+            position: Some(1),
+            data: Some(json!({ "student_id": 42 })),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&update).unwrap(),
+            json!({ "position": 1, "data": { "student_id": 42 } })
+        );
+    }
+}
@@ -0,0 +1,130 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::{Canvas, CanvasCredentials};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+/// Assignment behind an `assignment` calendar event.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CalendarAssignment {
+    pub id: u64,
+    pub name: String,
+    pub due_at: Option<DateTime<Utc>>,
+    pub course_id: Option<u64>,
+    pub points_possible: Option<f64>,
+}
+
+/// An upcoming calendar event or assignment of the authenticated user.
+///
+/// Fields:
+/// - `id`: ID of the event (`assignment_123` for assignments).
+/// - `title`: Title shown in the calendar.
+/// - `event_type`: `event` or `assignment`.
+/// - `start_at` / `end_at`: Time span (the due date for assignments).
+/// - `all_day`: Whether the event lasts the whole day.
+/// - `context_code`: Owner of the event (`course_123`, `user_45`...).
+/// - `html_url`: Link to the event in Canvas.
+/// - `assignment`: The assignment, for `assignment` events.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CalendarEvent {
+    #[serde(deserialize_with = "id_as_string")]
+    pub id: String,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub start_at: Option<DateTime<Utc>>,
+    pub end_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub all_day: bool,
+    pub context_code: Option<String>,
+    pub html_url: Option<String>,
+    pub assignment: Option<CalendarAssignment>,
+}
+
+impl CalendarEvent {
+    /// Returns `true` for assignment due dates.
+    pub fn is_assignment(&self) -> bool {
+        self.event_type == "assignment"
+    }
+}
+
+// Canvas returns numeric IDs for events and `assignment_<id>` for assignments.
+fn id_as_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::String(id) => id,
+        other => other.to_string(),
+    })
+}
+
+/// Retrieves the upcoming events and assignments of the authenticated user (the next week, as
+/// computed by Canvas).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+///
+/// Returns:
+/// - `Result<Vec<CalendarEvent>, Box<dyn Error>>`: The events in chronological order, or an error.
+pub fn fetch_upcoming_events(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+    let url = format!("{}/users/self/upcoming_events", canvas_info.url_canvas);
+    fetch_json(client, canvas_info, &url)
+}
+
+/// Retrieves the URL of the ICS calendar feed of the authenticated user, to subscribe from
+/// calendar applications. The URL embeds a secret: it doesn't require the API token.
+pub fn fetch_calendar_feed_url(client: &Client, canvas_info: &CanvasCredentials) -> Result<String, Box<dyn Error>> {
+    let url = format!("{}/users/self/profile", canvas_info.url_canvas);
+    let profile: Value = fetch_json(client, canvas_info, &url)?;
+    ics_url(&profile).ok_or_else(|| "Canvas did not return a calendar feed".into())
+}
+
+// Extracts the feed URL of a user profile or course (`{"calendar": {"ics": "..."}}`).
+fn ics_url(json: &Value) -> Option<String> {
+    json["calendar"]["ics"].as_str().map(str::to_string)
+}
+
+impl Canvas {
+    /// Retrieves the upcoming events and assignments of the authenticated user.
+    ///
+    /// Example:
+    /// ```
+    /// for event in Canvas::upcoming_events(&credentials)?.iter().filter(|e| e.is_assignment()) {
+    ///     println!("{} is due at {:?}", event.title, event.start_at);
+    /// }
+    /// ```
+    pub fn upcoming_events(info: &CanvasCredentials) -> Result<Vec<CalendarEvent>, Box<dyn Error>> {
+        fetch_upcoming_events(&Client::new(), info)
+    }
+
+    /// Retrieves the URL of the ICS calendar feed of the authenticated user.
+    pub fn calendar_feed_url(info: &CanvasCredentials) -> Result<String, Box<dyn Error>> {
+        fetch_calendar_feed_url(&Client::new(), info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_upcoming_events_and_feed() {
+        let events: Vec<CalendarEvent> = serde_json::from_value(json!([
+            {"id": 901, "title": "Plantão", "type": "event", "start_at": "2024-06-10T17:00:00Z",
+             "end_at": "2024-06-10T18:00:00Z", "context_code": "course_7"},
+            {"id": "assignment_55", "title": "Lista 4", "type": "assignment",
+             "start_at": "2024-06-11T02:59:00Z", "all_day": false,
+             "assignment": {"id": 55, "name": "Lista 4", "due_at": "2024-06-11T02:59:00Z", "course_id": 7}}
+        ]))
+        .unwrap();
+        assert_eq!(events[0].id, "901");
+        assert!(events[1].is_assignment());
+        assert_eq!(events[1].assignment.as_ref().unwrap().id, 55);
+
+        let profile = json!({"id": 1, "calendar": {"ics": "https://canvas.example/feeds/calendars/user_abc.ics"}});
+        assert_eq!(ics_url(&profile).as_deref(), Some("https://canvas.example/feeds/calendars/user_abc.ics"));
+    }
+}
@@ -1,9 +1,15 @@
-use crate::connection::{send_http_request, HttpMethod, SYNC_ATTEMPT};
+use crate::academic_calendar::AcademicCalendar;
+use crate::connection::{
+    retry_policy, retry_with_backoff, send_http_request, send_http_request_uncached, send_multipart_upload, HttpMethod,
+};
+use crate::assignment::SectionNeedsGrading;
+use crate::group;
+use crate::params::Params;
 use crate::{
     course, Assignment, AssignmentInfo, CanvasCredentials, Course, CourseInfo, Student,
     StudentInfo, Submission,
 };
-use course::parse_course_name;
+use course::{parse_course_name, CourseAccess};
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Select;
 use reqwest::blocking::multipart::{Form, Part};
@@ -13,8 +19,7 @@ use serde_json::Value;
 use std::collections::HashMap;
 use std::error::Error;
 use std::path::Path;
-use std::sync::{Arc, Mutex};
-use std::thread::sleep;
+use std::sync::Arc;
 use urlencoding::decode;
 
 /// Enum to represent the result of fetching multiple courses.
@@ -30,11 +35,13 @@ pub enum CanvasResultCourses {
 /// Enum to represent the result of fetching a single course.
 ///
 /// Similar to `CanvasResultCourses`, but tailored for scenarios where only a single course is being fetched.
-/// Distinguishes between success, connection errors, and credential errors.
+/// Distinguishes between success, connection errors, credential errors, and courses whose access is
+/// restricted by date (which Canvas returns without name or course code).
 pub enum CanvasResultSingleCourse {
-    Ok(Course),             // Success case with a single Course object.
-    ErrConnection(String),  // Connection error with a descriptive message.
-    ErrCredentials(String), // Credential error with a descriptive message.
+    Ok(Course),               // Success case with a single Course object.
+    ErrConnection(String),    // Connection error with a descriptive message.
+    ErrCredentials(String),   // Credential error with a descriptive message.
+    ErrAccessRestricted(u64), // The course exists but is outside its participation dates.
 }
 
 /// Main interface for interacting with the Canvas LMS.
@@ -103,6 +110,28 @@ impl Canvas {
     /// }
     /// ```
     pub fn fetch_courses_with_credentials(info: &CanvasCredentials) -> CanvasResultCourses {
+        Canvas::fetch_courses_with_role(info, "TeacherEnrollment")
+    }
+
+    /// Fetches the courses in which the owner of the credentials is enrolled as a student.
+    ///
+    /// Same as `fetch_courses_with_credentials` for tools run with the token of a student
+    /// (see the `student_mode` module).
+    ///
+    /// Example:
+    /// ```
+    /// if let CanvasResultCourses::Ok(courses) = Canvas::fetch_student_courses_with_credentials(&canvas_info) {
+    ///     for course in courses {
+    ///         println!("{}: {:?}", course.info.name, course.fetch_own_grade(&client)?.current_score);
+    ///     }
+    /// }
+    /// ```
+    pub fn fetch_student_courses_with_credentials(info: &CanvasCredentials) -> CanvasResultCourses {
+        Canvas::fetch_courses_with_role(info, "StudentEnrollment")
+    }
+
+    // Lists the courses in which the owner of the credentials has the enrollment `role`.
+    fn fetch_courses_with_role(info: &CanvasCredentials, role: &str) -> CanvasResultCourses {
         let canvas_info_arc = Arc::new((*info).clone());
 
         let url = format!("{}/courses", info.url_canvas);
@@ -111,15 +140,12 @@ impl Canvas {
         let client = &Client::new();
 
         loop {
-            let params = vec![
-                (
-                    "enrollment_role".to_string(),
-                    "TeacherEnrollment".to_string(),
-                ),
-                ("page".to_string(), page.to_string()),
-                ("per_page".to_string(), "100".to_string()),
-            ];
-            match send_http_request(&client, HttpMethod::Get, &url, &info, params) {
+            let params = Params::new()
+                .param("enrollment_role", role)
+                .include(["term"])
+                .page(page)
+                .per_page(100);
+            match send_http_request(&client, HttpMethod::Get, &url, &info, params.into()) {
                 Ok(response) => {
                     if response.status().is_success() {
                         match response.text() {
@@ -207,13 +233,16 @@ impl Canvas {
             HttpMethod::Get,
             &url,
             info,
-            Vec::new(), // No additional parameters for this request
+            Params::new().include(["term"]).into(), // Enrollment term of the course (see `Course::term`)
         ) {
             Ok(response) => {
                 if response.status().is_success() {
                     let course: serde_json::Value = response.json().unwrap();
                     if let Some(course) = Canvas::convert_json_to_course(&canvas_info_arc, &course)
                     {
+                        if course.info.access == CourseAccess::RestrictedByDate {
+                            return CanvasResultSingleCourse::ErrAccessRestricted(course_id);
+                        }
                         return CanvasResultSingleCourse::Ok(course);
                     } else {
                         return CanvasResultSingleCourse::ErrConnection(
@@ -242,7 +271,8 @@ impl Canvas {
     /// - `course`: JSON object representing a course.
     ///
     /// Returns:
-    /// - `Option<Course>`: A course if successful, or `None` if conversion fails.
+    /// - `Option<Course>`: A course if successful, or `None` if conversion fails. Courses restricted
+    ///   by date are returned with `CourseAccess::RestrictedByDate` and empty name/code.
     ///
     /// Example:
     /// ```
@@ -250,22 +280,44 @@ impl Canvas {
     /// let course_json = serde_json::json!({ /* JSON data */ });
     /// let course = Canvas::convert_json_to_course(&canvas_info, &course_json);
     /// ```
-    fn convert_json_to_course(
+    pub(crate) fn convert_json_to_course(
         canvas_info: &Arc<CanvasCredentials>,
         course: &serde_json::Value,
     ) -> Option<Course> {
         let id = course["id"].as_u64()?;
+
+        // Courses outside their participation dates only carry the id and this flag
+        if course["access_restricted_by_date"].as_bool() == Some(true) {
+            return Some(Course {
+                info: Arc::new(CourseInfo {
+                    id,
+                    access: CourseAccess::RestrictedByDate,
+                    canvas_info: Arc::clone(canvas_info),
+                    ..Default::default()
+                }),
+            });
+        }
+
         let name = course["name"].as_str().map(String::from)?;
         let course_code = course["course_code"].as_str().map(String::from)?;
+        let access = match course["workflow_state"].as_str() {
+            Some("unpublished") => CourseAccess::Unpublished,
+            _ => CourseAccess::Available,
+        };
         Some(Course {
             info: Arc::new(CourseInfo {
                 id,
                 name: name.clone(),
                 course_code: course_code.clone(),
+                access,
+                time_zone: course["time_zone"].as_str().map(String::from),
                 canvas_info: Arc::clone(canvas_info),
                 abbreviated_name: parse_course_name(name.as_str(), course_code.as_str()), // Parse the course name
-                students_cache: Mutex::new(Vec::new()),
-                assignments_cache: Mutex::new(Vec::new()),
+                extra: unknown_fields(
+                    course,
+                    &["id", "name", "course_code", "time_zone"],
+                ),
+                ..Default::default()
             }),
         })
     }
@@ -278,7 +330,8 @@ impl Canvas {
         println!("Fetching courses...");
         match Canvas::fetch_courses_with_credentials(&credentials) {
             CanvasResultCourses::Ok(courses) => {
-                for course in courses {
+                // Courses restricted by date can't be opened by the teacher: only the raw listing keeps them
+                for course in courses.into_iter().filter(|course| course.info.access != CourseAccess::RestrictedByDate) {
                     if let Some(course_details_name) = parse_course_name(
                         course.info.name.as_str(),
                         course.info.course_code.as_str(),
@@ -322,6 +375,13 @@ impl Canvas {
 /// is determined based on the current month and day: "1" for dates on or before
 /// July 15 (first semester), and "2" for dates after July 15 (second semester).
 ///
+/// This uses `AcademicCalendar::default()`; institutions with a different calendar
+/// should call `get_current_year_and_semester_with` instead.
+///
+/// Deprecated: the date alone guesses wrong for courses outside the regular calendar (summer
+/// courses, late terms...). `Course::year_and_semester` uses the enrollment term of the course
+/// when Canvas knows it.
+///
 /// # Examples
 ///
 /// ```
@@ -333,19 +393,21 @@ impl Canvas {
 ///
 /// This function does not return any errors. It will always provide the current year and
 /// the calculated semester based on the current date.
+#[deprecated(since = "0.1.8", note = "use `Course::year_and_semester`, based on the term of the course")]
 pub fn get_current_year_and_semester() -> (String, String) {
-    use chrono::{Datelike, Utc};
-
-    let current_date = Utc::now();
-    let year = current_date.year().to_string();
-    let semester =
-        if current_date.month() < 7 || (current_date.month() == 7 && current_date.day() <= 15) {
-            "1".to_string()
-        } else {
-            "2".to_string()
-        };
+    get_current_year_and_semester_with(&AcademicCalendar::default())
+}
 
-    (year, semester)
+/// Returns the current year and semester according to the given academic calendar.
+///
+/// # Examples
+///
+/// ```
+/// let calendar = AcademicCalendar::northern_semesters();
+/// let (year, semester) = get_current_year_and_semester_with(&calendar);
+/// ```
+pub fn get_current_year_and_semester_with(calendar: &AcademicCalendar) -> (String, String) {
+    calendar.current_year_and_semester()
 }
 
 /// Adds a comment to a student's assignment submission.
@@ -559,10 +621,8 @@ fn upload_file(
             }
             form = form.file("file", file_path)?;
 
-            let response = client
-                .post(&upload_url)
-                .multipart(form)
-                .send()
+            let summary = json!({ "name": file_name, "size": file_size });
+            let response = send_multipart_upload(client, canvas_info, &upload_url, form, summary)
                 .map_err(|e| format!("Failed to upload file: {}", e))?;
 
             let json: Value = response
@@ -687,30 +747,195 @@ pub fn get_all_submissions(
         "{}/courses/{}/assignments/{}/submissions",
         canvas_info.url_canvas, course_id, assignment_id
     );
+    fetch_submission_pages(client, canvas_info, &url, group_submissions, &["submission_comments"])
+}
 
-    let mut all_submissions = Vec::new();
+/// Retrieves the submissions of an assignment made by the students of a single section.
+///
+/// Same as `get_all_submissions`, but uses the section-scoped endpoint so that, in large
+/// multi-section courses, only the submissions of the given section are transferred.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `section_id`: ID of the course section.
+/// - `assignment_id`: ID of the assignment.
+/// - `group_submissions`: Whether submissions should be grouped (group assignments).
+///
+/// Returns:
+/// - `Result<Vec<Value>, Box<dyn Error>>`: The raw submissions or an error.
+pub fn get_all_submissions_in_section(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    section_id: u64,
+    assignment_id: u64,
+    group_submissions: bool,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let url = format!(
+        "{}/sections/{}/assignments/{}/submissions",
+        canvas_info.url_canvas, section_id, assignment_id
+    );
+    fetch_submission_pages(client, canvas_info, &url, group_submissions, &["submission_comments"])
+}
+
+/// Retrieves the submissions of an assignment together with their rubric assessments.
+///
+/// Each submission carries a `rubric_assessment` object mapping criterion IDs to the points,
+/// rating and comments given by the grader (absent when the submission wasn't assessed).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `assignment_id`: ID of the assignment.
+///
+/// Returns:
+/// - `Result<Vec<Value>, Box<dyn Error>>`: The raw submissions or an error.
+pub fn get_rubric_assessments(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    assignment_id: u64,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let url = format!(
+        "{}/courses/{}/assignments/{}/submissions",
+        canvas_info.url_canvas, course_id, assignment_id
+    );
+    fetch_submission_pages(client, canvas_info, &url, false, &["rubric_assessment"])
+}
+
+/// Downloads every page of a Canvas listing endpoint returning a JSON array.
+///
+/// `params` are sent with every page, in addition to `page` and `per_page`.
+pub(crate) fn fetch_all_pages(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    params: Vec<(String, String)>,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    fetch_all_pages_with_key(client, canvas_info, url, params, None)
+}
+
+/// Same as `fetch_all_pages`, decoding each item into `T` (items that don't match are skipped).
+pub(crate) fn fetch_all_pages_as<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    params: Vec<(String, String)>,
+) -> Result<Vec<T>, Box<dyn Error>> {
+    Ok(fetch_all_pages(client, canvas_info, url, params)?
+        .into_iter()
+        .filter_map(|item| serde_json::from_value(item).ok())
+        .collect())
+}
+
+/// Same as `fetch_all_pages`, for endpoints that wrap the array in an object under `key`
+/// (e.g. `{"quiz_submissions": [...]}`).
+pub(crate) fn fetch_all_pages_with_key(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    params: Vec<(String, String)>,
+    key: Option<&str>,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut all_items = Vec::new();
     let mut page = 1;
     loop {
-        let mut params = vec![("page", page.to_string()), ("per_page", "100".to_string())];
+        let page_params = Params::from(params.clone()).page(page).per_page(100);
+
+        let response = send_http_request(client, HttpMethod::Get, url, canvas_info, page_params.into())?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch {} with status: {}", url, response.status()).into());
+        }
+        let body: Value = response.json()?;
+        let items = match key {
+            Some(key) => body[key].as_array().cloned().unwrap_or_default(),
+            None => body.as_array().cloned().unwrap_or_default(),
+        };
+        if items.is_empty() {
+            break; // Sai do loop se não há mais itens
+        }
+        all_items.extend(items);
+        page += 1;
+    }
+    Ok(all_items)
+}
+
+/// Downloads every page of an endpoint paginated by `Link` headers only (e.g. the audit logs,
+/// which ignore the `page` parameter), returning the body of each page.
+pub(crate) fn fetch_pages_following_links(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    params: Vec<(String, String)>,
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut bodies = Vec::new();
+    let mut page_params = Params::from(params).per_page(100).into_vec();
+    let mut next_url = Some(url.to_string());
+    while let Some(page_url) = next_url {
+        let response = send_http_request(client, HttpMethod::Get, &page_url, canvas_info, page_params)?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch {} with status: {}", page_url, response.status()).into());
+        }
+        next_url = response
+            .headers()
+            .get("Link")
+            .and_then(|link| link.to_str().ok())
+            .and_then(next_page_url);
+        bodies.push(response.json()?);
+        // The next URL already carries the query
+        page_params = Vec::new();
+    }
+    Ok(bodies)
+}
 
-        if group_submissions {
-            params.push(("grouped", "true".to_string()));
+// Extracts the `rel="next"` URL of a `Link` header.
+fn next_page_url(link_header: &str) -> Option<String> {
+    link_header.split(',').find_map(|part| {
+        let (target, rel) = part.split_once(';')?;
+        if !rel.contains("rel=\"next\"") {
+            return None;
         }
+        Some(target.trim().trim_start_matches('<').trim_end_matches('>').to_string())
+    })
+}
 
-        // Convertendo (&str, String) para (String, String)
-        let mut converted_params: Vec<(String, String)> = params
-            .into_iter()
-            .map(|(key, value)| (key.to_string(), value))
-            .collect();
+/// Sends a GET request to `url` and decodes the JSON answer into `T`.
+pub(crate) fn fetch_json<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+) -> Result<T, Box<dyn Error>> {
+    let response = send_http_request(client, HttpMethod::Get, url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to fetch {} with status: {}", url, response.status()).into());
+    }
+    Ok(response.json()?)
+}
 
-        converted_params.push(("include[]".to_string(), "submission_comments".to_string()));
+// Downloads every page of a submissions listing endpoint.
+fn fetch_submission_pages(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    group_submissions: bool,
+    includes: &[&str],
+) -> Result<Vec<Value>, Box<dyn Error>> {
+    let mut all_submissions = Vec::new();
+    let mut page = 1;
+    loop {
+        let params = Params::new()
+            .page(page)
+            .per_page(100)
+            .param_opt("grouped", group_submissions.then_some(true))
+            .include(includes);
 
         match send_http_request(
             client,
             HttpMethod::Get,
-            &url,
+            url,
             canvas_info,
-            converted_params, // Passando o Vec<(String, String)> diretamente
+            params.into(),
         ) {
             Ok(response) => {
                 if response.status().is_success() {
@@ -765,108 +990,177 @@ where
 
         interaction();
 
-        for attempt in 0..SYNC_ATTEMPT {
-            let response =
-                send_http_request(client, HttpMethod::Get, &url, canvas_info, params.clone());
-
-            match response {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        // Deserializar o JSON da resposta uma vez
-                        let response_json: Value = response.json()?; // Armazenando o JSON da resposta
-
-                        // Deserializar a submissão do JSON
-                        let mut submission: Submission =
-                            serde_json::from_value(response_json.clone())?; // Usando clone para reutilizar o JSON
-
-                        // Extrair os file_ids dos anexos (se houver)
-                        let file_ids =
-                            if let Some(attachments) = response_json["attachments"].as_array() {
-                                attachments
-                                    .iter()
-                                    .filter_map(|file| file["id"].as_u64()) // Extrai os file_ids
-                                    .collect()
-                            } else {
-                                Vec::new() // Caso não haja arquivos, retorna um vetor vazio
-                            };
-
-                        // Atribuir os file_ids extraídos à submissão
-                        submission.file_ids = file_ids;
-
-                        submissions.push(submission);
-                    } else {
-                        let error_message = response.text()?;
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!(
-                                "Failed to fetch submissions with error: {} (a)",
-                                error_message
-                            ),
-                        )));
-                    }
-                    break;
-                }
-                Err(e) => {
-                    if attempt == SYNC_ATTEMPT - 1 {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!("Failed to fetch submissions with error: {} (b)", e),
-                        )));
-                    } else {
-                        sleep(Duration::from_millis(100));
-                    }
+        // Transient failures are already retried with backoff by send_http_request
+        match send_http_request(client, HttpMethod::Get, &url, canvas_info, params) {
+            Ok(response) => {
+                if response.status().is_success() {
+                    // Deserializar o JSON da resposta uma vez
+                    let response_json: Value = response.json()?; // Armazenando o JSON da resposta
+
+                    // Deserializar a submissão do JSON
+                    let mut submission: Submission =
+                        serde_json::from_value(response_json.clone())?; // Usando clone para reutilizar o JSON
+
+                    // Extrair os file_ids dos anexos (se houver)
+                    let file_ids =
+                        if let Some(attachments) = response_json["attachments"].as_array() {
+                            attachments
+                                .iter()
+                                .filter_map(|file| file["id"].as_u64()) // Extrai os file_ids
+                                .collect()
+                        } else {
+                            Vec::new() // Caso não haja arquivos, retorna um vetor vazio
+                        };
+
+                    // Atribuir os file_ids extraídos à submissão
+                    submission.file_ids = file_ids;
+
+                    submissions.push(submission);
+                } else {
+                    let error_message = response.text()?;
+                    return Err(Box::new(std::io::Error::new(
+                        std::io::ErrorKind::Other,
+                        format!(
+                            "Failed to fetch submissions with error: {} (a)",
+                            error_message
+                        ),
+                    )));
                 }
             }
+            Err(e) => {
+                return Err(Box::new(std::io::Error::new(
+                    std::io::ErrorKind::Other,
+                    format!("Failed to fetch submissions with error: {} (b)", e),
+                )));
+            }
         }
     }
 
     Ok(submissions)
 }
 
+/// Returns the members of a Canvas JSON object that are not in `known`, to keep the data the
+/// typed models don't map (the `extra` field of `Course`, `Assignment`, `Student` and `Submission`).
+pub(crate) fn unknown_fields(json: &Value, known: &[&str]) -> serde_json::Map<String, Value> {
+    json.as_object()
+        .map(|object| {
+            object
+                .iter()
+                .filter(|(key, _)| !known.contains(&key.as_str()))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Converts a JSON object to a `Student` structure.
+///
+/// Parses a JSON representation of a student from the Canvas API into a `Student` object.
+/// Extracts student ID, name, and email and associates it with course information.
+///
+/// The email is optional: institutions can restrict its visibility, in which case Canvas omits
+/// the field (or returns `null`). Such students are still returned, with `email` set to `None`,
+/// so that their submissions can be matched.
+pub(crate) fn convert_json_to_student(
+    course_info: CourseInfo,
+    student: &serde_json::Value,
+) -> Option<Student> {
+    let id = student["id"].as_u64()?;
+    let name = student["name"].as_str().map(String::from)?;
+    let email = student["email"].as_str().map(String::from);
+    Some(Student {
+        info: Arc::new(StudentInfo {
+            id,
+            name,
+            email,
+            course_info: Arc::new(course_info),
+            extra: unknown_fields(student, &["id", "name", "email"]),
+        }),
+    })
+}
+
 pub fn fetch_students(course_info: &CourseInfo) -> Result<Vec<Student>, Box<dyn Error>> {
+    fetch_students_with_states(course_info, &[])
+}
+
+/// Retrieves the students of a course whose enrollment is in one of the given states.
+///
+/// An empty `states` slice keeps the Canvas default (active and invited enrollments).
+///
+/// Arguments:
+/// - `course_info`: Course whose roster is listed.
+/// - `states`: Enrollment states to include.
+///
+/// Returns:
+/// - `Result<Vec<Student>, Box<dyn Error>>`: The matching students or an error.
+pub fn fetch_students_with_states(
+    course_info: &CourseInfo,
+    states: &[EnrollmentState],
+) -> Result<Vec<Student>, Box<dyn Error>> {
+    let filters = Params::new().array("enrollment_state", states.iter().map(EnrollmentState::as_str));
+    Ok(fetch_course_users_json(course_info, EnrollmentRole::Student, filters.into())?
+        .iter()
+        .filter_map(|student| convert_json_to_student(course_info.clone(), student))
+        .collect())
+}
+
+/// Retrieves the users of a course enrolled with the given role (teachers, TAs, designers...).
+///
+/// Arguments:
+/// - `course_info`: Course whose users are listed.
+/// - `role`: Enrollment role to filter by.
+///
+/// Returns:
+/// - `Result<Vec<User>, Box<dyn Error>>`: The matching users or an error.
+pub fn fetch_users(course_info: &CourseInfo, role: EnrollmentRole) -> Result<Vec<User>, Box<dyn Error>> {
+    Ok(fetch_course_users_json(course_info, role, Vec::new())?
+        .iter()
+        .filter_map(|user| serde_json::from_value(user.clone()).ok())
+        .collect())
+}
+
+/// Searches the students of a course by partial name, email, login or SIS ID.
+///
+/// The match is done by Canvas (`search_term` parameter), so the roster doesn't need to be
+/// downloaded first.
+///
+/// Arguments:
+/// - `course_info`: Course whose students are searched.
+/// - `query`: Partial name, email or ID to look for.
+///
+/// Returns:
+/// - `Result<Vec<Student>, Box<dyn Error>>`: The matching students or an error.
+pub fn search_users(course_info: &CourseInfo, query: &str) -> Result<Vec<Student>, Box<dyn Error>> {
+    let filters = vec![("search_term".to_string(), query.to_string())];
+    Ok(fetch_course_users_json(course_info, EnrollmentRole::Student, filters)?
+        .iter()
+        .filter_map(|student| convert_json_to_student(course_info.clone(), student))
+        .collect())
+}
+
+// Downloads every page of the course users listing for one enrollment role, with extra filters.
+fn fetch_course_users_json(
+    course_info: &CourseInfo,
+    role: EnrollmentRole,
+    filters: Vec<(String, String)>,
+) -> Result<Vec<Value>, Box<dyn Error>> {
     let url = format!(
         "{}/courses/{}/users",
         course_info.canvas_info.url_canvas, course_info.id
     );
 
-    /// Converts a JSON object to a `Student` structure.
-    ///
-    /// Parses a JSON representation of a student from the Canvas API into a `Student` object.
-    /// Extracts student ID, name, and email and associates it with course information.
-    pub fn convert_json_to_student(
-        course_info: CourseInfo,
-        student: &serde_json::Value,
-    ) -> Option<Student> {
-        let id = student["id"].as_u64()?;
-        let name = student["name"].as_str().map(String::from)?;
-        let email = student["email"].as_str().map(String::from)?;
-        Some(Student {
-            info: Arc::new(StudentInfo {
-                id,
-                name,
-                email,
-                course_info: Arc::new(course_info),
-            }),
-        })
-    }
-
-    let mut all_students = Vec::new();
+    let mut all_users = Vec::new();
     let mut page = 1;
     let client = &Client::new();
 
     loop {
-        let params = vec![
-            ("enrollment_type[]", "student".to_string()),
-            ("include[]", "email".to_string()),
-            ("per_page", "150".to_string()),
-            ("page", page.to_string()),
-        ];
-
-        // Convertendo (&str, String) para (String, String)
-        let converted_params: Vec<(String, String)> = params
-            .into_iter()
-            .map(|(key, value)| (key.to_string(), value))
-            .collect();
+        let mut params = Params::new()
+            .array("enrollment_type", [role.as_str()])
+            .include(["email", "avatar_url"])
+            .per_page(150)
+            .page(page)
+            .into_vec();
+        params.extend(filters.iter().cloned());
 
         // Passando HttpMethod::Get ao invés de "GET"
         match send_http_request(
@@ -874,25 +1168,21 @@ pub fn fetch_students(course_info: &CourseInfo) -> Result<Vec<Student>, Box<dyn
             HttpMethod::Get, // Supondo que HttpMethod::Get é um enum definido em algum lugar
             &url,
             &course_info.canvas_info,
-            converted_params, // Passando o Vec<(String, String)> diretamente
+            params,
         ) {
             Ok(response) => {
                 if response.status().is_success() {
-                    let students_page: Vec<serde_json::Value> = response.json()?;
-                    if students_page.is_empty() {
-                        break; // Sai do loop se não há mais estudantes
+                    let users_page: Vec<serde_json::Value> = response.json()?;
+                    if users_page.is_empty() {
+                        break; // Sai do loop se não há mais usuários
                     }
-                    all_students.extend(
-                        students_page
-                            .into_iter()
-                            .filter_map(|student| convert_json_to_student(course_info.clone(), &student)),
-                    );
+                    all_users.extend(users_page);
                     page += 1; // Incrementa o número da página para a próxima iteração
                 } else {
                     return Err(Box::new(std::io::Error::new(
                         std::io::ErrorKind::Other,
                         format!(
-                            "Failed to fetch students with status: {}",
+                            "Failed to fetch users with status: {}",
                             response.status()
                         ),
                     )));
@@ -901,7 +1191,69 @@ pub fn fetch_students(course_info: &CourseInfo) -> Result<Vec<Student>, Box<dyn
             Err(e) => {
                 return Err(Box::new(std::io::Error::new(
                     std::io::ErrorKind::Other,
-                    format!("Failed to fetch students with error: {}", e),
+                    format!("Failed to fetch users with error: {}", e),
+                )));
+            }
+        }
+    }
+    Ok(all_users)
+}
+
+/// Retrieves the students enrolled in a single section of a course.
+///
+/// Uses the section enrollments endpoint, which is much faster than listing the whole course
+/// roster in large multi-section courses. Only active student enrollments are returned. The
+/// enrollment payload doesn't always carry the email, in which case `email` is `None`.
+///
+/// Arguments:
+/// - `course_info`: Course the section belongs to (credentials and course data).
+/// - `section_id`: ID of the course section.
+///
+/// Returns:
+/// - `Result<Vec<Student>, Box<dyn Error>>`: The students of the section or an error.
+pub fn fetch_students_in_section(
+    course_info: &CourseInfo,
+    section_id: u64,
+) -> Result<Vec<Student>, Box<dyn Error>> {
+    let url = format!(
+        "{}/sections/{}/enrollments",
+        course_info.canvas_info.url_canvas, section_id
+    );
+
+    let mut all_students = Vec::new();
+    let mut page = 1;
+    let client = &Client::new();
+
+    loop {
+        let params = Params::new()
+            .array("type", ["StudentEnrollment"])
+            .array("state", ["active"])
+            .per_page(100)
+            .page(page);
+
+        match send_http_request(client, HttpMethod::Get, &url, &course_info.canvas_info, params.into()) {
+            Ok(response) => {
+                if response.status().is_success() {
+                    let enrollments_page: Vec<Value> = response.json()?;
+                    if enrollments_page.is_empty() {
+                        break;
+                    }
+                    all_students.extend(enrollments_page.iter().filter_map(|enrollment| {
+                        convert_json_to_student(course_info.clone(), &enrollment["user"])
+                    }));
+                    page += 1;
+                } else {
+                    return Err(Box::new(std::io::Error::other(
+                        format!(
+                            "Failed to fetch section students with status: {}",
+                            response.status()
+                        ),
+                    )));
+                }
+            }
+            Err(e) => {
+                return Err(Box::new(std::io::Error::other(
+                    format!("Failed to fetch section students with error: {}", e),
                 )));
             }
         }
@@ -909,6 +1261,53 @@ pub fn fetch_students(course_info: &CourseInfo) -> Result<Vec<Student>, Box<dyn
     Ok(all_students)
 }
 
+/// Retrieves the course grade of a student from the enrollments endpoint.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `student_id`: ID of the student.
+///
+/// Returns:
+/// - `Result<CourseGrade, Box<dyn Error>>`: The grades of the student's enrollment or an error.
+pub fn fetch_course_grade(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    student_id: u64,
+) -> Result<CourseGrade, Box<dyn Error>> {
+    fetch_course_grade_of(client, canvas_info, course_id, &student_id.to_string())
+}
+
+// Same as `fetch_course_grade` for a user ID as accepted by Canvas, including `self`.
+pub(crate) fn fetch_course_grade_of(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    student_id: &str,
+) -> Result<CourseGrade, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/enrollments", canvas_info.url_canvas, course_id);
+    let params = Params::new().param("user_id", student_id).array("type", ["StudentEnrollment"]);
+
+    let response = send_http_request(client, HttpMethod::Get, &url, canvas_info, params.into())?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch course grade with status: {}",
+            response.status()
+        )
+        .into());
+    }
+    let enrollments: Vec<Value> = response.json()?;
+    // Um estudante pode ter mais de uma matrícula (várias turmas); usa a primeira com notas
+    let grades = enrollments
+        .iter()
+        .map(|enrollment| &enrollment["grades"])
+        .find(|grades| grades.is_object())
+        .ok_or_else(|| format!("No enrollment with grades found for user {}", student_id))?;
+    Ok(serde_json::from_value(grades.clone())?)
+}
+
 pub fn convert_json_to_assignment(
     course_info: &Arc<CourseInfo>,
     assignment: &serde_json::Value,
@@ -929,6 +1328,70 @@ pub fn convert_json_to_assignment(
     // Verifica se o assignment está configurado para submissões em grupo e extrai o group_category_id
     let group_category_id = assignment["group_category_id"].as_u64();
 
+    // Pontuação máxima da atividade
+    let points_possible = assignment["points_possible"].as_f64();
+
+    // Prorrogações (presentes com include[]=overrides). Sem due_at próprio, vale a data padrão.
+    let overrides = assignment["overrides"]
+        .as_array()
+        .map_or(Vec::new(), |overrides| {
+            overrides
+                .iter()
+                .filter_map(|o| {
+                    Some(AssignmentOverride {
+                        id: o["id"].as_u64()?,
+                        title: o["title"].as_str().map(String::from),
+                        student_ids: o["student_ids"]
+                            .as_array()
+                            .map_or(Vec::new(), |ids| ids.iter().filter_map(|id| id.as_u64()).collect()),
+                        group_id: o["group_id"].as_u64(),
+                        course_section_id: o["course_section_id"].as_u64(),
+                        due_at: if o.get("due_at").is_some() {
+                            o["due_at"]
+                                .as_str()
+                                .and_then(|d| DateTime::parse_from_rfc3339(d).ok())
+                                .map(|dt| dt.with_timezone(&Utc))
+                        } else {
+                            due_at
+                        },
+                    })
+                })
+                .collect()
+        });
+
+    // Estatísticas de notas (presentes com include[]=score_statistics, visíveis apenas a estudantes)
+    let score_statistics = serde_json::from_value::<AssignmentStatistics>(
+        assignment["score_statistics"].clone(),
+    )
+    .ok();
+
+    // Todas as datas (presentes com include[]=all_dates)
+    let all_dates = assignment["all_dates"]
+        .as_array()
+        .map_or(Vec::new(), |dates| {
+            dates
+                .iter()
+                .filter_map(|d| serde_json::from_value::<AssignmentDate>(d.clone()).ok())
+                .collect()
+        });
+
+    // Contagem por turma (com include[]=needs_grading_count_by_section); o Canvas envia o ID da
+    // turma como texto
+    let needs_grading_count_by_section = assignment["needs_grading_count_by_section"]
+        .as_array()
+        .map_or(Vec::new(), |sections| {
+            sections
+                .iter()
+                .filter_map(|section| {
+                    let section_id = &section["section_id"];
+                    Some(SectionNeedsGrading {
+                        section_id: section_id.as_u64().or_else(|| section_id.as_str()?.parse().ok())?,
+                        needs_grading_count: section["needs_grading_count"].as_u64()?,
+                    })
+                })
+                .collect()
+        });
+
     Some(Assignment {
         info: Arc::new(AssignmentInfo {
             id,
@@ -937,13 +1400,51 @@ pub fn convert_json_to_assignment(
             rubric_id,                            // Armazena o ID da rubrica
             due_at,                               // Adiciona o campo due_at (opcional)
             group_category_id,
+            points_possible,
+            overrides,
+            all_dates,
+            score_statistics,
+            submission_types: assignment["submission_types"]
+                .as_array()
+                .map_or(Vec::new(), |types| types.iter().filter_map(Value::as_str).map(SubmissionType::from).collect()),
+            allowed_extensions: assignment["allowed_extensions"]
+                .as_array()
+                .map_or(Vec::new(), |extensions| extensions.iter().filter_map(Value::as_str).map(String::from).collect()),
+            allowed_attempts: assignment["allowed_attempts"].as_i64(),
+            annotatable_attachment_id: assignment["annotatable_attachment_id"].as_u64(),
+            needs_grading_count: assignment["needs_grading_count"].as_u64(),
+            needs_grading_count_by_section,
             course_info: Arc::clone(course_info), // Mantém a referência ao CourseInfo
+            extra: unknown_fields(
+                assignment,
+                &[
+                    "id", "name", "description", "due_at", "group_category_id", "points_possible",
+                    "overrides", "all_dates", "score_statistics", "submission_types", "allowed_extensions", "allowed_attempts",
+                    "annotatable_attachment_id", "needs_grading_count",
+                    "needs_grading_count_by_section",
+                ],
+            ),
         }),
     })
 }
 
 
 pub fn fetch_assignments(course: &Course) -> Result<Vec<Assignment>, Box<dyn Error>> {
+    fetch_assignments_with_query(course, &AssignmentQuery::default())
+}
+
+/// Retrieves the assignments of a course matching `query` (bucket, order and search term).
+///
+/// Arguments:
+/// - `course`: Course whose assignments are listed.
+/// - `query`: Filters sent to the Canvas API.
+///
+/// Returns:
+/// - `Result<Vec<Assignment>, Box<dyn Error>>`: The matching assignments or an error.
+pub fn fetch_assignments_with_query(
+    course: &Course,
+    query: &AssignmentQuery,
+) -> Result<Vec<Assignment>, Box<dyn Error>> {
     let url = format!(
         "{}/courses/{}/assignments",
         course.info.canvas_info.url_canvas, course.info.id
@@ -953,19 +1454,19 @@ pub fn fetch_assignments(course: &Course) -> Result<Vec<Assignment>, Box<dyn Err
     let mut page = 1;
     let client = &reqwest::blocking::Client::new();
     loop {
-        let params = vec![("page", page.to_string()), ("per_page", "100".to_string())];
-
-        let converted_params: Vec<(String, String)> = params
-            .into_iter()
-            .map(|(key, value)| (key.to_string(), value))
-            .collect();
+        let mut params = Params::new()
+            .page(page)
+            .per_page(100)
+            .include(["all_dates", "overrides", "score_statistics", "needs_grading_count_by_section"])
+            .into_vec();
+        params.extend(query.to_params());
 
         match send_http_request(
             client,
             HttpMethod::Get,
             &url,
             &course.info.canvas_info,
-            converted_params,
+            params,
         ) {
             Ok(response) => {
                 if response.status().is_success() {
@@ -1034,39 +1535,101 @@ pub fn update_assignment_score(
         });
     }
 
-    // Try to send the HTTP request SYNC_ATTEMPT times
-    let mut attempt = SYNC_ATTEMPT;
-    loop {
-        match send_http_request(
-            client,
-            HttpMethod::Put(body.clone()), // Use HttpMethod::Put enum variant
-            &url,
-            &canvas_info,
-            Vec::new(), // PUT request does not need params
-        ) {
-            Ok(response) => match response.status().is_success() {
-                true => return Ok(()),
-                false => {
-                    if attempt == 0 {
-                        return Err(Box::new(std::io::Error::new(
-                            std::io::ErrorKind::Other,
-                            format!("Failed to update score with status: {}", response.status()),
-                        )));
-                    }
-                }
-            },
-            Err(e) => {
-                if attempt == 0 {
-                    return Err(Box::new(std::io::Error::new(
-                        std::io::ErrorKind::Other,
-                        format!("Failed to update score with error: {}", e),
-                    )));
-                }
-            }
-        };
-        attempt -= 1;
-        sleep(std::time::Duration::from_millis(100));
+    // Transient failures are retried with backoff by send_http_request
+    match send_http_request(
+        client,
+        HttpMethod::Put(body), // Use HttpMethod::Put enum variant
+        &url,
+        canvas_info,
+        Vec::new(), // PUT request does not need params
+    ) {
+        Ok(response) if response.status().is_success() => Ok(()),
+        Ok(response) => Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("Failed to update score with status: {}", response.status()),
+        ))),
+        Err(e) => Err(Box::new(std::io::Error::new(
+            std::io::ErrorKind::Other,
+            format!("Failed to update score with error: {}", e),
+        ))),
+    }
+}
+
+/// Updates a student's score only if the remote state allows it.
+///
+/// When `options` require it, the current submission is read first and the write is skipped if
+/// the score is unchanged or if the submission was graded after `options.baseline`.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Reference to Canvas credentials and configuration.
+/// - `course_id`, `assignment_id`, `student_id`: Identify the submission to update.
+/// - `new_score`: New score to be set, or `None` to clear the existing score.
+/// - `options`: Idempotency rules for the write.
+///
+/// Returns:
+/// - `Result<GradeWriteOutcome, Box<dyn Error>>`: Whether the score was written or why it was skipped.
+pub fn update_assignment_score_with_options(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    assignment_id: u64,
+    student_id: u64,
+    new_score: Option<f64>,
+    options: &GradeWriteOptions,
+) -> Result<GradeWriteOutcome, Box<dyn Error>> {
+    if options.requires_read() {
+        let url = format!(
+            "{}/courses/{}/assignments/{}/submissions/{}",
+            canvas_info.url_canvas, course_id, assignment_id, student_id,
+        );
+        // The guard must see the current state, never a cached one
+        let current: Value =
+            send_http_request_uncached(client, HttpMethod::Get, &url, canvas_info, Vec::new())?.json()?;
+        let graded_at = current["graded_at"]
+            .as_str()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        if let Some(skipped) = options.check(current["score"].as_f64(), graded_at, new_score) {
+            return Ok(skipped);
+        }
     }
+
+    update_assignment_score(client, canvas_info, course_id, assignment_id, student_id, new_score)?;
+    Ok(GradeWriteOutcome::Written)
+}
+
+/// Applies a `SubmissionUpdate` (grade, rubric assessment, comment...) to a student's submission
+/// in a single PUT.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Reference to Canvas credentials and configuration.
+/// - `course_id`, `assignment_id`, `student_id`: Identify the submission to update.
+/// - `update`: Changes to apply.
+///
+/// Returns:
+/// - `Result<Value, Box<dyn Error>>`: The updated submission as returned by Canvas, or an error.
+pub fn update_submission(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    assignment_id: u64,
+    student_id: u64,
+    update: &SubmissionUpdate,
+) -> Result<Value, Box<dyn Error>> {
+    if update.is_empty() {
+        return Err("Nothing to update in the submission".into());
+    }
+    let url = format!(
+        "{}/courses/{}/assignments/{}/submissions/{}",
+        canvas_info.url_canvas, course_id, assignment_id, student_id,
+    );
+    let response = send_http_request(client, HttpMethod::Put(update.to_json()), &url, canvas_info, Vec::new())?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update submission with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
 }
 
 pub fn comment_with_binary_file(
@@ -1083,10 +1646,10 @@ pub fn comment_with_binary_file(
     let assignment_id_str = assignment_id.to_string();
 
     let file_ids = if let (Some(name), Some(content)) = (file_name, file_content) {
-        let mut attempts = 0;
-        let max_attempts = 3;
-        loop {
-            match upload_binary_file(
+        // The upload goes to a pre-signed URL outside send_http_request, so retry it here
+        let rule = retry_policy().network;
+        let file_id = retry_with_backoff(&rule, || {
+            upload_binary_file(
                 client,
                 canvas_info,
                 course_id,
@@ -1094,21 +1657,15 @@ pub fn comment_with_binary_file(
                 &user_id,
                 name,
                 content,
-            ) {
-                Ok(file_id) => break Some(vec![file_id]),
-                Err(e) => {
-                    attempts += 1;
-                    if attempts >= max_attempts {
-                        return Err(format!(
-                            "Error in upload_binary_file after {} attempts: {}",
-                            attempts, e
-                        )
-                        .into());
-                    }
-                    sleep(std::time::Duration::from_secs(1)); // Espera 1 segundo antes de tentar novamente
-                }
-            }
-        }
+            )
+        })
+        .map_err(|e| {
+            format!(
+                "Error in upload_binary_file after {} attempts: {}",
+                rule.max_attempts, e
+            )
+        })?;
+        Some(vec![file_id])
     } else {
         None
     };
@@ -1157,10 +1714,8 @@ fn upload_binary_file(
                 Part::bytes(file_content.clone()).file_name(file_name.to_string()),
             );
 
-            let response = client
-                .post(&upload_url)
-                .multipart(form)
-                .send()
+            let summary = json!({ "name": file_name, "size": file_size });
+            let response = send_multipart_upload(client, canvas_info, &upload_url, form, summary)
                 .map_err(|e| format!("Failed to upload file: {}", e))?;
 
             let json: Value = response
@@ -1281,11 +1836,56 @@ pub fn create_announcement(
     }
 }
 
+/// Sends a message through the Canvas conversations (Inbox) API.
+///
+/// With several recipients, a separate conversation is created for each of them (bulk message),
+/// so students don't see each other, as in the "Message students who..." gradebook feature.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course used as the conversation context.
+/// - `recipient_ids`: IDs of the users receiving the message.
+/// - `subject`: Subject of the message.
+/// - `body`: Text of the message.
+///
+/// Returns:
+/// - `Result<(), Box<dyn Error>>`: Success or an error.
+pub fn send_conversation(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    recipient_ids: &[u64],
+    subject: &str,
+    body: &str,
+) -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/conversations", canvas_info.url_canvas);
+    let recipients: Vec<String> = recipient_ids.iter().map(|id| id.to_string()).collect();
+    let payload = json!({
+        "recipients": recipients,
+        "subject": subject,
+        "body": body,
+        "context_code": format!("course_{}", course_id),
+        "group_conversation": true,
+        "bulk_message": true
+    });
+
+    let response = send_http_request(client, HttpMethod::Post(payload), &url, canvas_info, vec![])?;
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to send conversation with status: {}", response.status()).into())
+    }
+}
+
 use std::fs::File;
 use std::io::Write;
-use std::time::Duration;
 use chrono::{DateTime, Utc};
 use crate::rubric_submission::CanvasRubricSubmission;
+use crate::submission::{GradeWriteOptions, GradeWriteOutcome, SubmissionType, SubmissionUpdate};
+use crate::student::{CourseGrade, EnrollmentState};
+use crate::user::{EnrollmentRole, User};
+use crate::assignment::{AssignmentDate, AssignmentOverride, AssignmentQuery, AssignmentStatistics};
 
 /// Downloads a file from the Canvas LMS.
 ///
@@ -1382,11 +1982,11 @@ pub fn download_rubric(
         canvas_info.url_canvas, course_id, rubric_id
     );
 
-    // Parâmetros adicionais, se necessário (neste caso, nenhum parâmetro extra)
-    let params = Vec::new();
+    // Inclui as associações da rubrica (atividades e curso onde é usada)
+    let params = Params::new().include(["associations"]);
 
     // Realiza a requisição HTTP
-    match send_http_request(client, HttpMethod::Get, &url, canvas_info, params) {
+    match send_http_request(client, HttpMethod::Get, &url, canvas_info, params.into()) {
         Ok(response) => {
             if response.status().is_success() {
                 // Parseia o JSON retornado pela resposta
@@ -1416,6 +2016,17 @@ pub fn create_rubric(
     course_id: u64,
     rubric: &CanvasRubricSubmission,  // Using CanvasRubricSubmission instead of Rubric
 ) -> Result<(), Box<dyn Error>> {
+    create_rubric_with_response(client, canvas_info, course_id, rubric).map(|_| ())
+}
+
+/// Same as `create_rubric`, returning the JSON answered by Canvas (`{"rubric": {...},
+/// "rubric_association": {...}}`), which holds the ID of the new rubric.
+pub fn create_rubric_with_response(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    rubric: &CanvasRubricSubmission,
+) -> Result<Value, Box<dyn Error>> {
     // URL for the API to create the rubric
     let url = format!("{}/courses/{}/rubrics", canvas_info.url_canvas, course_id);
 
@@ -1460,9 +2071,9 @@ pub fn create_rubric(
 
     // Checking if the response was successful
     if response.status().is_success() {
-        // let resp_json: serde_json::Value = response.json()?;
+        let resp_json: serde_json::Value = response.json()?;
         // println!("Rubric created successfully: {:?}", resp_json);
-        Ok(())
+        Ok(resp_json)
     } else {
         // let error_text = response.text()?;
         // println!("Failed to create rubric: {}", error_text);
@@ -1524,55 +2135,88 @@ pub fn delete_comment(
     }
 }
 
-fn fetch_groups_for_category(
-    client: &reqwest::blocking::Client,
-    group_category_id: u64,
-    canvas_info: &CanvasCredentials,
-) -> Result<Vec<serde_json::Value>, Box<dyn std::error::Error>> {
-    let url = format!("{}/group_categories/{}/groups", canvas_info.url_canvas, group_category_id);
-    let response = send_http_request(client, HttpMethod::Get, &url, canvas_info, vec![])?;
-    let groups: Vec<serde_json::Value> = response.json()?;
-    Ok(groups)
-}
-
 pub fn fetch_groups_for_assignment(
     client: &reqwest::blocking::Client,
     assignment_info: &AssignmentInfo,
     canvas_info: &CanvasCredentials,
 ) -> Result<HashMap<u64, Vec<u64>>, Box<dyn std::error::Error>> {
-    let mut group_student_map = HashMap::new();
-
     // Verificar se o assignment possui um `group_category_id`
-    if let Some(group_category_id) = assignment_info.group_category_id {
-        // Obter os grupos da categoria de grupo
-        let groups = fetch_groups_for_category(client, group_category_id, canvas_info)?;
-
-        // Itera sobre os grupos e busca os estudantes de cada grupo
-        for group in groups {
-            if let Some(group_id) = group["id"].as_u64() {
-                let group_url = format!("{}/groups/{}/users", canvas_info.url_canvas, group_id);
-                let group_response = send_http_request(client, HttpMethod::Get, &group_url, canvas_info, vec![])?;
-                let users: Vec<serde_json::Value> = group_response.json()?;
-
-                // Coleta os IDs dos estudantes para o grupo
-                let mut student_ids = Vec::new();
-                for user in users {
-                    if let Some(student_id) = user["id"].as_u64() {
-                        student_ids.push(student_id);
-                    }
-                }
-
-                // Adiciona o grupo e os estudantes ao mapa
-                group_student_map.insert(group_id, student_ids);
-            }
-        }
-    } else {
+    let Some(group_category_id) = assignment_info.group_category_id else {
         return Err(Box::new(std::io::Error::new(
             std::io::ErrorKind::Other,
             "Assignment is not configured for group submissions",
         )));
-    }
+    };
 
-    Ok(group_student_map)
+    // Grupos da categoria com os IDs dos estudantes de cada um
+    Ok(group::fetch_groups_with_members(client, canvas_info, group_category_id)?
+        .into_iter()
+        .map(|(group, student_ids)| (group.id, student_ids))
+        .collect())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_json_to_student_with_email() {
+        let json = json!({"id": 1, "name": "Ana Souza", "email": "ana@example.com"});
+        let student = convert_json_to_student(CourseInfo::default(), &json).unwrap();
+        assert_eq!(student.info.id, 1);
+        assert_eq!(student.info.email.as_deref(), Some("ana@example.com"));
+    }
+
+    #[test]
+    fn test_convert_json_to_student_with_hidden_email() {
+        let missing = json!({"id": 2, "name": "Bruno Lima"});
+        let null = json!({"id": 3, "name": "Carla Dias", "email": null});
+
+        let missing = convert_json_to_student(CourseInfo::default(), &missing).unwrap();
+        let null = convert_json_to_student(CourseInfo::default(), &null).unwrap();
+
+        assert_eq!(missing.info.name, "Bruno Lima");
+        assert!(missing.info.email.is_none());
+        assert!(null.info.email.is_none());
+    }
+
+    #[test]
+    fn test_convert_json_to_course_access_states() {
+        let credentials = Arc::new(CanvasCredentials::default());
+
+        let restricted = json!({"id": 42, "access_restricted_by_date": true});
+        let course = Canvas::convert_json_to_course(&credentials, &restricted).unwrap();
+        assert_eq!(course.info.id, 42);
+        assert_eq!(course.info.access, CourseAccess::RestrictedByDate);
+        assert!(!course.is_accessible());
+
+        let unpublished = json!({
+            "id": 43, "name": "Algoritmos", "course_code": "ALG", "workflow_state": "unpublished"
+        });
+        let course = Canvas::convert_json_to_course(&credentials, &unpublished).unwrap();
+        assert_eq!(course.info.access, CourseAccess::Unpublished);
+
+        let available = json!({
+            "id": 44, "name": "Algoritmos", "course_code": "ALG", "workflow_state": "available"
+        });
+        let course = Canvas::convert_json_to_course(&credentials, &available).unwrap();
+        assert!(course.is_accessible());
+    }
+
+    #[test]
+    fn test_convert_json_to_student_requires_id_and_name() {
+        let json = json!({"name": "No Id", "email": "x@example.com"});
+        assert!(convert_json_to_student(CourseInfo::default(), &json).is_none());
+    }
+
+    #[test]
+    fn test_next_page_url() {
+        let header = "<https://canvas.example/api/v1/audit/grade_change?page=first&per_page=100>; rel=\"current\",\
+                      <https://canvas.example/api/v1/audit/grade_change?page=bookmark:WzEsMl0&per_page=100>; rel=\"next\"";
+        assert_eq!(
+            next_page_url(header).as_deref(),
+            Some("https://canvas.example/api/v1/audit/grade_change?page=bookmark:WzEsMl0&per_page=100")
+        );
+        assert!(next_page_url("<https://canvas.example/api/v1/x?page=1>; rel=\"last\"").is_none());
+    }
+}
@@ -0,0 +1,257 @@
+// Import necessary crates and modules
+use crate::connection::HttpMethod;
+use crate::CanvasCredentials;
+use lazy_static::lazy_static;
+use reqwest::blocking::Response;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Placeholder written in cassettes instead of the API token.
+pub const SCRUBBED_TOKEN: &str = "<CANVAS_TOKEN>";
+
+/// What `insert_cassette` does with the requests sent through the connector.
+///
+/// - `Record`: Sends them to Canvas and captures every request/response pair. The cassette file is
+///   written by `eject_cassette`.
+/// - `Replay`: Answers them from the cassette file without touching the network. A request without
+///   a recorded counterpart fails.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum CassetteMode {
+    Record,
+    Replay,
+}
+
+/// A request/response pair stored in a cassette.
+///
+/// Fields:
+/// - `method` / `url`: The request, with its query string and without the token.
+/// - `request_body`: JSON body of PUT and POST requests.
+/// - `status` / `headers`: Status and headers of the response (`Link`, rate limit...).
+/// - `body`: Response body, kept as JSON when it parses as such to stay readable and editable.
+/// - `error`: Error returned by the connector instead of a response (e.g. after a 404).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Interaction {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_body: Option<Value>,
+    #[serde(default)]
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+// Cassette in use and the interactions recorded or left to replay.
+struct Cassette {
+    path: PathBuf,
+    mode: CassetteMode,
+    interactions: Vec<Interaction>,
+}
+
+lazy_static! {
+    static ref CASSETTE: Mutex<Option<Cassette>> = Mutex::new(None);
+}
+
+/// Starts recording to, or replaying from, the cassette file at `path`.
+///
+/// Every request sent through the connector is concerned, from every thread. Direct transfers
+/// outside the Canvas API (file uploads to pre-signed URLs, file downloads) are not captured.
+///
+/// Example:
+/// ```
+/// let mode = if Path::new("tests/cassettes/grading.json").exists() { CassetteMode::Replay } else { CassetteMode::Record };
+/// insert_cassette("tests/cassettes/grading.json", mode)?;
+/// let submissions = assignment.fetch_submissions(&students)?;
+/// eject_cassette()?;
+/// ```
+pub fn insert_cassette(path: impl AsRef<Path>, mode: CassetteMode) -> Result<(), Box<dyn Error>> {
+    let path = path.as_ref().to_path_buf();
+    let interactions = match mode {
+        CassetteMode::Record => Vec::new(),
+        CassetteMode::Replay => serde_json::from_str(&std::fs::read_to_string(&path)?)?,
+    };
+    *CASSETTE.lock().unwrap() = Some(Cassette { path, mode, interactions });
+    Ok(())
+}
+
+/// Stops using the cassette, writing the recorded interactions when recording.
+pub fn eject_cassette() -> Result<(), Box<dyn Error>> {
+    let Some(cassette) = CASSETTE.lock().unwrap().take() else {
+        return Ok(());
+    };
+    if cassette.mode == CassetteMode::Record {
+        if let Some(dir) = cassette.path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&cassette.path, serde_json::to_string_pretty(&cassette.interactions)?)?;
+    }
+    Ok(())
+}
+
+// Builds the URL stored in the cassette: query parameters included, token scrubbed.
+fn request_key(method: &HttpMethod, url: &str, params: &[(String, String)], canvas_info: &CanvasCredentials) -> (String, Option<Value>) {
+    let url = reqwest::Url::parse_with_params(url, params).map_or_else(|_| url.to_string(), |url| url.to_string());
+    let body = match method {
+        HttpMethod::Put(body) | HttpMethod::Post(body) => Some(scrub_json(body, &canvas_info.token_canvas)),
+        HttpMethod::Get | HttpMethod::Delete => None,
+    };
+    (scrub(&url, &canvas_info.token_canvas), body)
+}
+
+fn scrub(text: &str, token: &str) -> String {
+    if token.is_empty() {
+        text.to_string()
+    } else {
+        text.replace(token, SCRUBBED_TOKEN)
+    }
+}
+
+fn scrub_json(value: &Value, token: &str) -> Value {
+    match value {
+        Value::String(text) => Value::String(scrub(text, token)),
+        Value::Array(items) => Value::Array(items.iter().map(|item| scrub_json(item, token)).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(key, item)| (key.clone(), scrub_json(item, token))).collect()),
+        other => other.clone(),
+    }
+}
+
+// Rebuilds a response from a recorded interaction.
+fn to_response(interaction: &Interaction) -> Result<Response, Box<dyn Error>> {
+    let mut builder = http::Response::builder().status(interaction.status);
+    for (name, value) in &interaction.headers {
+        builder = builder.header(name, value);
+    }
+    let body = match &interaction.body {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    };
+    Ok(Response::from(builder.body(body)?))
+}
+
+/// Returns `true` while a cassette is inserted, recording or replaying.
+pub(crate) fn is_inserted() -> bool {
+    CASSETTE.lock().unwrap().is_some()
+}
+
+/// Answers a request from the cassette when replaying. Returns `None` when no cassette is
+/// replaying, so the request must really be sent.
+pub(crate) fn replay(
+    method: &HttpMethod,
+    url: &str,
+    params: &[(String, String)],
+    canvas_info: &CanvasCredentials,
+) -> Option<Result<Response, Box<dyn Error>>> {
+    let mut guard = CASSETTE.lock().unwrap();
+    let cassette = guard.as_mut().filter(|cassette| cassette.mode == CassetteMode::Replay)?;
+    let (url, body) = request_key(method, url, params, canvas_info);
+
+    // Identical requests are answered in the order they were recorded
+    let Some(index) = cassette
+        .interactions
+        .iter()
+        .position(|i| i.method == method.as_str() && i.url == url && i.request_body == body)
+    else {
+        return Some(Err(format!("No recorded interaction in the cassette for {} {}", method.as_str(), url).into()));
+    };
+    let interaction = cassette.interactions.remove(index);
+    Some(match &interaction.error {
+        Some(error) => Err(error.clone().into()),
+        None => to_response(&interaction),
+    })
+}
+
+/// Stores the outcome of a request when recording, and hands it back to the caller (the body of
+/// the response has to be read, so the response is rebuilt from the recorded copy).
+pub(crate) fn record(
+    method: &HttpMethod,
+    url: &str,
+    params: &[(String, String)],
+    canvas_info: &CanvasCredentials,
+    result: Result<Response, Box<dyn Error>>,
+) -> Result<Response, Box<dyn Error>> {
+    let mut guard = CASSETTE.lock().unwrap();
+    let Some(cassette) = guard.as_mut().filter(|cassette| cassette.mode == CassetteMode::Record) else {
+        return result;
+    };
+    let token = &canvas_info.token_canvas;
+    let (url, request_body) = request_key(method, url, params, canvas_info);
+    let mut interaction = Interaction {
+        method: method.as_str().to_string(),
+        url,
+        request_body,
+        status: 0,
+        headers: Vec::new(),
+        body: Value::Null,
+        error: None,
+    };
+    match result {
+        Ok(response) => {
+            interaction.status = response.status().as_u16();
+            interaction.headers = response
+                .headers()
+                .iter()
+                .filter(|(name, _)| !matches!(name.as_str(), "set-cookie" | "authorization"))
+                .filter_map(|(name, value)| Some((name.to_string(), scrub(value.to_str().ok()?, token))))
+                .collect();
+            let text = scrub(&response.text()?, token);
+            interaction.body = serde_json::from_str(&text).unwrap_or(Value::String(text));
+            let response = to_response(&interaction);
+            cassette.interactions.push(interaction);
+            response
+        }
+        Err(e) => {
+            interaction.error = Some(scrub(&e.to_string(), token));
+            cassette.interactions.push(interaction);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_record_then_replay_with_scrubbed_token() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: "secret-token-123".to_string(),
+        };
+        let path = std::env::temp_dir().join(format!("canvas_cassette_{}.json", std::process::id()));
+        let url = "https://canvas.example/api/v1/courses/7/users";
+        let page = |n: u32| vec![("page".to_string(), n.to_string())];
+        let recorded = |body: Value| {
+            Ok(Response::from(http::Response::builder().status(200).header("Link", "<x>; rel=\"next\"").body(body.to_string()).unwrap()))
+        };
+
+        insert_cassette(&path, CassetteMode::Record).unwrap();
+        let response = record(&HttpMethod::Get, url, &page(1), &canvas_info, recorded(json!([{"id": 10, "name": "Ana"}]))).unwrap();
+        assert_eq!(response.json::<Value>().unwrap()[0]["id"], 10);
+        record(&HttpMethod::Get, url, &page(2), &canvas_info, recorded(json!([]))).unwrap();
+        let put = HttpMethod::Put(json!({"comment": {"text_comment": "ok secret-token-123"}}));
+        let _ = record(&put, url, &[], &canvas_info, Err("HTTP request failed with status code: 404".into()));
+        eject_cassette().unwrap();
+
+        let saved = std::fs::read_to_string(&path).unwrap();
+        assert!(!saved.contains("secret-token-123"));
+
+        insert_cassette(&path, CassetteMode::Replay).unwrap();
+        let page_two = replay(&HttpMethod::Get, url, &page(2), &canvas_info).unwrap().unwrap();
+        assert_eq!(page_two.json::<Value>().unwrap(), json!([]));
+        let page_one = replay(&HttpMethod::Get, url, &page(1), &canvas_info).unwrap().unwrap();
+        assert_eq!(page_one.headers()["link"], "<x>; rel=\"next\"");
+        assert!(replay(&put, url, &[], &canvas_info).unwrap().is_err());
+        assert!(replay(&HttpMethod::Get, url, &page(3), &canvas_info).unwrap().is_err());
+        eject_cassette().unwrap();
+        assert!(replay(&HttpMethod::Get, url, &page(1), &canvas_info).is_none());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,157 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_pages_following_links;
+use crate::concurrency::parallel_map;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::params::Params;
+use crate::{CanvasCredentials, CanvasError};
+use reqwest::blocking::Client;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Connection to a Canvas instance: credentials plus a reusable HTTP client.
+///
+/// Besides the typed API of the crate, `CanvasClient` gives access to endpoints the crate doesn't
+/// model yet through the `raw_*` methods, which share the authentication, retries, rate limiting
+/// and pagination of every other request.
+///
+/// Example:
+/// ```
+/// let client = CanvasClient::new(CanvasCredentials::credentials());
+/// let history = client.raw_get("/courses/1/gradebook_history/feed", Params::new().param("assignment_id", 10))?;
+/// client.raw_put("/courses/1/assignments/10", Params::new(), json!({"assignment": {"published": true}}))?;
+/// ```
+#[derive(Clone, Debug)]
+pub struct CanvasClient {
+    credentials: Arc<CanvasCredentials>,
+    http: Client,
+}
+
+impl CanvasClient {
+    /// Creates a client for the given credentials.
+    pub fn new(credentials: CanvasCredentials) -> Self {
+        CanvasClient {
+            credentials: Arc::new(credentials),
+            http: Client::new(),
+        }
+    }
+
+    /// Credentials used by the client.
+    pub fn credentials(&self) -> &CanvasCredentials {
+        &self.credentials
+    }
+
+    /// Underlying HTTP client, for the functions of the crate that take a `reqwest` client.
+    pub fn http(&self) -> &Client {
+        &self.http
+    }
+
+    /// Absolute URL of an API path (`/courses/1` or `courses/1`). Absolute URLs are kept as is.
+    pub fn url(&self, path: &str) -> String {
+        if path.starts_with("http://") || path.starts_with("https://") {
+            path.to_string()
+        } else {
+            format!("{}/{}", self.credentials.url_canvas.trim_end_matches('/'), path.trim_start_matches('/'))
+        }
+    }
+
+    /// Sends a GET request. Paginated listings are followed to the end and returned as a single
+    /// array.
+    ///
+    /// `params` is a `Params` or a `Vec<(String, String)>`, as for the other `raw_*` methods.
+    pub fn raw_get(&self, path: &str, params: impl Into<Params>) -> Result<Value, CanvasError> {
+        let params = params.into().into_vec();
+        let mut pages = fetch_pages_following_links(&self.http, &self.credentials, &self.url(path), params)?;
+        if pages.len() == 1 {
+            return Ok(pages.remove(0));
+        }
+        Ok(Value::Array(merge_pages(pages)))
+    }
+
+    /// Sends a POST request with a JSON body.
+    pub fn raw_post(&self, path: &str, params: impl Into<Params>, body: Value) -> Result<Value, CanvasError> {
+        self.send(HttpMethod::Post(body), path, params)
+    }
+
+    /// Sends a PUT request with a JSON body.
+    pub fn raw_put(&self, path: &str, params: impl Into<Params>, body: Value) -> Result<Value, CanvasError> {
+        self.send(HttpMethod::Put(body), path, params)
+    }
+
+    /// Sends a DELETE request.
+    pub fn raw_delete(&self, path: &str, params: impl Into<Params>) -> Result<Value, CanvasError> {
+        self.send(HttpMethod::Delete, path, params)
+    }
+
+    /// Applies `f` to every item on up to `max_concurrency` threads and returns the results in
+    /// the order of `items`, for batches of custom requests (e.g. with the `raw_*` methods).
+    ///
+    /// Requests sent through the client still share the global throttling of the connector: the
+    /// number in flight adapts to the rate limit feedback of Canvas, so it may stay below
+    /// `max_concurrency`, and throttled requests are retried.
+    ///
+    /// Example:
+    /// ```
+    /// let histories = client.parallel_map(&assignment_ids, |client, id| {
+    ///     client.raw_get("/courses/1/gradebook_history/feed", Params::new().param("assignment_id", id))
+    /// }, 8);
+    /// ```
+    pub fn parallel_map<T, R, F>(&self, items: &[T], f: F, max_concurrency: usize) -> Vec<R>
+    where
+        T: Sync,
+        R: Send,
+        F: Fn(&CanvasClient, &T) -> R + Sync,
+    {
+        parallel_map(items, max_concurrency, |item| f(self, item))
+    }
+
+    // Sends a single request; the answer is `null` when Canvas returns an empty body.
+    fn send(&self, method: HttpMethod, path: &str, params: impl Into<Params>) -> Result<Value, CanvasError> {
+        let params = params.into().into_vec();
+        let mut url = self.url(path);
+        // Only GET and DELETE requests get `params` as query string, so add them to the URL
+        let params = match method {
+            HttpMethod::Post(_) | HttpMethod::Put(_) if !params.is_empty() => {
+                url = reqwest::Url::parse_with_params(&url, &params)
+                    .map_err(|e| CanvasError::Other(format!("Invalid URL {}: {}", url, e)))?
+                    .to_string();
+                Vec::new()
+            }
+            _ => params,
+        };
+        let response = send_http_request(&self.http, method, &url, &self.credentials, params)?;
+        let text = response.text().map_err(|e| CanvasError::InvalidResponse(e.to_string()))?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        Ok(serde_json::from_str(&text)?)
+    }
+}
+
+// Concatenates the pages of a listing (non-array pages are kept as items).
+fn merge_pages(pages: Vec<Value>) -> Vec<Value> {
+    pages
+        .into_iter()
+        .flat_map(|page| match page {
+            Value::Array(items) => items,
+            other => vec![other],
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_paths_and_page_merging() {
+        let client = CanvasClient::new(CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1/".to_string(),
+            token_canvas: String::new(),
+        });
+        assert_eq!(client.url("/courses/1/settings"), "https://canvas.example/api/v1/courses/1/settings");
+        assert_eq!(client.url("courses/1"), "https://canvas.example/api/v1/courses/1");
+        assert_eq!(client.url("https://other.example/x"), "https://other.example/x");
+        assert_eq!(merge_pages(vec![json!([1, 2]), json!([3])]), vec![json!(1), json!(2), json!(3)]);
+    }
+}
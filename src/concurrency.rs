@@ -0,0 +1,290 @@
+// Import necessary crates and modules
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Condvar, Mutex};
+
+/// Below this `X-Rate-Limit-Remaining` value the bucket is nearly empty and the limit is halved.
+pub const CRITICAL_RATE_LIMIT_REMAINING: f64 = 50.0;
+
+/// Below this `X-Rate-Limit-Remaining` value the limit is lowered by one request per response.
+pub const LOW_RATE_LIMIT_REMAINING: f64 = 200.0;
+
+/// Saved throttling states older than this are ignored: the Canvas bucket has refilled by then.
+pub const RATE_LIMIT_STATE_MAX_AGE_SECS: i64 = 600;
+
+/// Throttling state saved to disk, so that a job restarted right after being stopped resumes at
+/// the pace the previous run had reached instead of hitting the rate limit again at full speed.
+/// See `persist_rate_limit_state`.
+///
+/// Fields:
+/// - `limit`: Requests allowed in flight when the state was saved.
+/// - `rate_limit_remaining`: Last `X-Rate-Limit-Remaining` received.
+/// - `last_request_cost`: Last `X-Request-Cost` received.
+/// - `saved_at`: When the state was saved.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RateLimitState {
+    pub limit: usize,
+    pub rate_limit_remaining: Option<f64>,
+    pub last_request_cost: Option<f64>,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl RateLimitState {
+    /// Reads a state saved with `save`. Returns `Ok(None)` when the file does not exist.
+    pub fn load(path: &Path) -> Result<Option<RateLimitState>, Box<dyn std::error::Error>> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => Ok(Some(serde_json::from_str(&text)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Writes the state to `path`, creating its folder if needed. The file is replaced atomically
+    /// so that a process stopped while saving never leaves half a file behind.
+    pub fn save(&self, path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp = path.with_extension("tmp");
+        std::fs::write(&temp, serde_json::to_string(self)?)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+
+    /// Limit to start with at `now`: the saved one, halved when the bucket was almost empty, or
+    /// `None` when the state is too old to matter.
+    pub fn resume_limit(&self, now: DateTime<Utc>) -> Option<usize> {
+        if (now - self.saved_at).num_seconds() > RATE_LIMIT_STATE_MAX_AGE_SECS {
+            return None;
+        }
+        let remaining = self.rate_limit_remaining.unwrap_or(f64::INFINITY);
+        if remaining < CRITICAL_RATE_LIMIT_REMAINING {
+            Some((self.limit / 2).max(1))
+        } else {
+            Some(self.limit.max(1))
+        }
+    }
+}
+
+// Current limit and number of requests in flight.
+#[derive(Debug, Clone, PartialEq)]
+struct LimiterState {
+    limit: usize,
+    in_flight: usize,
+    // Healthy responses since the last change of the limit
+    successes: usize,
+}
+
+impl LimiterState {
+    // Adjusts the limit after a response: halves it when Canvas throttles (or the bucket is almost
+    // empty), lowers it by one when the bucket runs low, and raises it by one after `limit`
+    // healthy responses in a row. Returns `true` when the limit grew.
+    fn record(&mut self, throttled: bool, remaining: Option<f64>, min: usize, max: usize) -> bool {
+        let remaining = remaining.unwrap_or(f64::INFINITY);
+        if throttled || remaining < CRITICAL_RATE_LIMIT_REMAINING {
+            self.limit = (self.limit / 2).max(min);
+            self.successes = 0;
+        } else if remaining < LOW_RATE_LIMIT_REMAINING {
+            self.limit = self.limit.saturating_sub(1).max(min);
+            self.successes = 0;
+        } else {
+            self.successes += 1;
+            if self.successes >= self.limit && self.limit < max {
+                self.limit += 1;
+                self.successes = 0;
+                return true;
+            }
+        }
+        false
+    }
+}
+
+/// Limits the number of requests in flight, adapting the limit to the throttling feedback of
+/// Canvas (additive increase, multiplicative decrease).
+pub(crate) struct AdaptiveLimiter {
+    state: Mutex<LimiterState>,
+    available: Condvar,
+    min: usize,
+    max: usize,
+}
+
+/// Slot of a request in flight, released when dropped.
+pub(crate) struct LimiterPermit<'a> {
+    limiter: &'a AdaptiveLimiter,
+}
+
+impl Drop for LimiterPermit<'_> {
+    fn drop(&mut self) {
+        let mut state = self.limiter.state.lock().unwrap();
+        state.in_flight -= 1;
+        self.limiter.available.notify_one();
+    }
+}
+
+impl AdaptiveLimiter {
+    /// Creates a limiter starting (and capped) at `max` requests in flight.
+    pub(crate) fn new(max: usize) -> Self {
+        AdaptiveLimiter {
+            state: Mutex::new(LimiterState {
+                limit: max,
+                in_flight: 0,
+                successes: 0,
+            }),
+            available: Condvar::new(),
+            min: 1,
+            max,
+        }
+    }
+
+    /// Waits until a request can be sent.
+    pub(crate) fn acquire(&self) -> LimiterPermit<'_> {
+        let mut state = self.state.lock().unwrap();
+        while state.in_flight >= state.limit {
+            state = self.available.wait(state).unwrap();
+        }
+        state.in_flight += 1;
+        LimiterPermit { limiter: self }
+    }
+
+    /// Current number of requests allowed in flight.
+    pub(crate) fn limit(&self) -> usize {
+        self.state.lock().unwrap().limit
+    }
+
+    /// Starts again from `limit` requests in flight (kept between the bounds of the limiter), as
+    /// when resuming from a saved `RateLimitState`.
+    pub(crate) fn restore(&self, limit: usize) {
+        let mut state = self.state.lock().unwrap();
+        state.limit = limit.clamp(self.min, self.max);
+        state.successes = 0;
+        self.available.notify_all();
+    }
+
+    /// Adjusts the limit with the outcome of a request.
+    pub(crate) fn record(&self, throttled: bool, remaining: Option<f64>) {
+        let mut state = self.state.lock().unwrap();
+        if state.record(throttled, remaining, self.min, self.max) {
+            self.available.notify_all();
+        }
+    }
+}
+
+/// Applies `f` to every item on up to `workers` threads, returning the results in the order of
+/// `items`. Requests sent by `f` still go through the global limiter, which may keep fewer of
+/// them in flight than there are workers.
+pub(crate) fn parallel_map<T, R, F>(items: &[T], workers: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..items.len()).map(|_| None).collect());
+    let workers = workers.max(1).min(items.len());
+
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::SeqCst);
+                let Some(item) = items.get(index) else {
+                    break;
+                };
+                let result = f(item);
+                results.lock().unwrap()[index] = Some(result);
+            });
+        }
+    });
+
+    // Every index is taken by exactly one worker; a panic in `f` propagates out of the scope
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|result| result.expect("every item is processed"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_limit_shrinks_on_throttling_and_grows_back() {
+        let limiter = AdaptiveLimiter::new(20);
+        limiter.record(true, None);
+        assert_eq!(limiter.limit(), 10);
+        limiter.record(false, Some(30.0));
+        assert_eq!(limiter.limit(), 5);
+        limiter.record(false, Some(150.0));
+        assert_eq!(limiter.limit(), 4);
+        for _ in 0..10 {
+            limiter.record(true, None);
+        }
+        assert_eq!(limiter.limit(), 1);
+
+        // One more request allowed after `limit` healthy responses
+        limiter.record(false, Some(650.0));
+        assert_eq!(limiter.limit(), 2);
+        limiter.record(false, Some(650.0));
+        assert_eq!(limiter.limit(), 2);
+        limiter.record(false, None);
+        assert_eq!(limiter.limit(), 3);
+        for _ in 0..1000 {
+            limiter.record(false, Some(700.0));
+        }
+        assert_eq!(limiter.limit(), 20);
+
+        let _permit = limiter.acquire();
+        assert_eq!(limiter.state.lock().unwrap().in_flight, 1);
+    }
+
+    #[test]
+    fn test_rate_limit_state_survives_restarts() {
+        let path = std::env::temp_dir().join(format!("canvas_rate_limit_{}.json", std::process::id()));
+        let saved_at = Utc::now();
+        let state = RateLimitState {
+            limit: 6,
+            rate_limit_remaining: Some(120.0),
+            last_request_cost: Some(2.5),
+            saved_at,
+        };
+        state.save(&path).unwrap();
+        let loaded = RateLimitState::load(&path).unwrap().unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, state);
+        assert_eq!(RateLimitState::load(&path).unwrap(), None);
+
+        assert_eq!(loaded.resume_limit(saved_at + chrono::Duration::seconds(30)), Some(6));
+        assert_eq!(loaded.resume_limit(saved_at + chrono::Duration::hours(1)), None);
+        let drained = RateLimitState {
+            rate_limit_remaining: Some(10.0),
+            ..loaded
+        };
+        assert_eq!(drained.resume_limit(saved_at), Some(3));
+
+        let limiter = AdaptiveLimiter::new(20);
+        limiter.restore(3);
+        assert_eq!(limiter.limit(), 3);
+        limiter.restore(0);
+        assert_eq!(limiter.limit(), 1);
+    }
+
+    #[test]
+    fn test_parallel_map_keeps_order_and_bounds_workers() {
+        let running = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let items: Vec<u64> = (0..40).collect();
+        let results = parallel_map(&items, 3, |item| {
+            let now = running.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(2));
+            running.fetch_sub(1, Ordering::SeqCst);
+            item * 2
+        });
+        assert_eq!(results, items.iter().map(|item| item * 2).collect::<Vec<_>>());
+        assert!(peak.load(Ordering::SeqCst) <= 3);
+        assert!(parallel_map(&Vec::<u64>::new(), 0, |item| *item).is_empty());
+    }
+}
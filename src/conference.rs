@@ -0,0 +1,168 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_all_pages_with_key;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// A playback format of a recording (e.g. `presentation`, `video`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PlaybackFormat {
+    #[serde(rename = "type")]
+    pub format_type: String,
+    pub url: String,
+    /// Length in minutes.
+    pub length: Option<u64>,
+}
+
+/// Metadata of a conference recording.
+///
+/// Fields:
+/// - `recording_id`: ID of the recording in the conference provider.
+/// - `title`: Title of the recording.
+/// - `duration_minutes`: Length of the recording.
+/// - `created_at`: When the recording was made, as a Unix timestamp in milliseconds.
+/// - `playback_url`: Default playback link.
+/// - `playback_formats`: Every available playback format.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ConferenceRecording {
+    pub recording_id: String,
+    pub title: Option<String>,
+    pub duration_minutes: Option<u64>,
+    pub created_at: Option<i64>,
+    pub playback_url: Option<String>,
+    #[serde(default)]
+    pub playback_formats: Vec<PlaybackFormat>,
+}
+
+/// A web conference of a course.
+///
+/// Fields:
+/// - `id`: ID of the conference.
+/// - `title` / `description`: What participants see.
+/// - `conference_type`: Provider, e.g. `BigBlueButton`.
+/// - `duration`: Planned length in minutes (`None` for no limit).
+/// - `started_at` / `ended_at`: When the conference ran.
+/// - `join_url`: Link to join the conference.
+/// - `recordings`: Recordings of the conference, when the provider supports them.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Conference {
+    pub id: u64,
+    pub title: String,
+    pub description: Option<String>,
+    pub conference_type: String,
+    pub duration: Option<f64>,
+    pub started_at: Option<String>,
+    pub ended_at: Option<String>,
+    pub join_url: Option<String>,
+    #[serde(default)]
+    pub recordings: Vec<ConferenceRecording>,
+}
+
+impl Conference {
+    /// Returns `true` once the conference has ended.
+    pub fn has_ended(&self) -> bool {
+        self.ended_at.is_some()
+    }
+}
+
+// Builds the body that creates a conference.
+fn new_conference_body(title: &str, description: Option<&str>, duration: Option<u64>, conference_type: &str) -> Value {
+    let mut conference = json!({
+        "title": title,
+        "conference_type": conference_type,
+        "settings": { "record": true },
+    });
+    if let Some(description) = description {
+        conference["description"] = json!(description);
+    }
+    if let Some(duration) = duration {
+        conference["duration"] = json!(duration);
+    }
+    json!({ "web_conference": conference })
+}
+
+/// Retrieves the conferences of a course, with the metadata of their recordings.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+///
+/// Returns:
+/// - `Result<Vec<Conference>, Box<dyn Error>>`: The conferences or an error.
+pub fn fetch_conferences(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<Vec<Conference>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/conferences", canvas_info.url_canvas, course_id);
+    Ok(fetch_all_pages_with_key(client, canvas_info, &url, Vec::new(), Some("conferences"))?
+        .into_iter()
+        .filter_map(|json| serde_json::from_value(json).ok())
+        .collect())
+}
+
+/// Creates a conference in a course. Recording is enabled so the session can be archived.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `title`: Title of the conference.
+/// - `description`: Optional description.
+/// - `duration`: Planned length in minutes, or `None` for no limit.
+///
+/// Returns:
+/// - `Result<Conference, Box<dyn Error>>`: The created conference or an error.
+///
+/// Example:
+/// ```
+/// let conference = create_conference(&client, &canvas_info, course.info.id, "Aula 5", None, Some(100))?;
+/// ```
+pub fn create_conference(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    title: &str,
+    description: Option<&str>,
+    duration: Option<u64>,
+) -> Result<Conference, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/conferences", canvas_info.url_canvas, course_id);
+    let body = new_conference_body(title, description, duration, "BigBlueButton");
+    let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to create conference with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conference_with_recordings() {
+        let body = new_conference_body("Aula 5", None, Some(100), "BigBlueButton");
+        assert_eq!(body["web_conference"]["duration"], json!(100));
+        assert!(body["web_conference"].get("description").is_none());
+
+        let conference: Conference = serde_json::from_value(json!({
+            "id": 3,
+            "title": "Aula 5",
+            "conference_type": "BigBlueButton",
+            "ended_at": "2024-03-10T12:00:00Z",
+            "recordings": [{
+                "recording_id": "abc-123",
+                "duration_minutes": 95,
+                "created_at": 1710068400000i64,
+                "playback_formats": [{"type": "presentation", "url": "https://bbb.example/p/abc", "length": 95}]
+            }]
+        }))
+        .unwrap();
+        assert!(conference.has_ended());
+        assert_eq!(conference.recordings[0].playback_formats[0].format_type, "presentation");
+    }
+}
@@ -9,18 +9,21 @@ use crate::CanvasCredentials;
 // with complex initializations at runtime by default.
 use lazy_static::lazy_static;
 
-// Import of `Semaphore` from the `std_semaphore` crate.
-// A semaphore is a synchronization primitive that can be used to control access
-// to a common resource by multiple threads.
-use std_semaphore::Semaphore;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::concurrency::{AdaptiveLimiter, RateLimitState};
+use crate::{audit, cassette, metrics, response_cache, usage, CanvasError};
 
 /// The maximum number of simultaneous HTTP requests allowed.
 ///
 /// This constant is crucial for controlling the load on the server and preventing
-/// overloading the network with too many concurrent requests. It's used in conjunction
-/// with a semaphore to limit the number of active HTTP requests at any given time.
-/// Adjusting this value should be based on server capabilities and application requirements.
-const SIMULTANEOUS_REQUESTS_LIMIT: isize = 20;
+/// overloading the network with too many concurrent requests. The effective limit starts
+/// here and adapts to the throttling feedback of Canvas: it shrinks when
+/// `X-Rate-Limit-Remaining` runs low or throttled responses (429, or 403 marked as throttling)
+/// appear, and grows back gradually up to this value. See `concurrency_limit`.
+const SIMULTANEOUS_REQUESTS_LIMIT: usize = 20;
 
 // The number of attempts to make for an HTTP request before giving up.
 //
@@ -47,23 +50,128 @@ pub enum HttpMethod {
     Delete,
 }
 
+impl HttpMethod {
+    /// Returns the HTTP verb as an uppercase string (e.g. "GET").
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HttpMethod::Get => "GET",
+            HttpMethod::Put(_) => "PUT",
+            HttpMethod::Post(_) => "POST",
+            HttpMethod::Delete => "DELETE",
+        }
+    }
+
+    /// Returns `true` when sending the request twice has the same effect as sending it once
+    /// (GET, PUT, DELETE). A repeated POST may create a second comment, message or enrollment.
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, HttpMethod::Post(_))
+    }
+}
+
+// Per-call options of `send_http_request_with_options`.
+//
+// - `read_cache`: Answer a GET from the response cache when possible.
+// - `retry_writes`: Retry a POST after a server error or a lost answer like any other request,
+//   for endpoints known to be idempotent (cross-listing a section, accepting an enrollment...).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RequestOptions {
+    pub read_cache: bool,
+    pub retry_writes: bool,
+}
+
+impl Default for RequestOptions {
+    fn default() -> Self {
+        RequestOptions {
+            read_cache: true,
+            retry_writes: false,
+        }
+    }
+}
+
 // Type alias for HTTP request results.
 // This alias simplifies the type signatures throughout the code and encapsulates
 // the result of an HTTP request, which is either a successful `reqwest::blocking::Response`
-// or an error represented by a `u16` status code.
-pub type HttpRequestResult = Result<reqwest::blocking::Response, u16>;
+// or an error represented by a `u16` status code (0 without answer), the body of the answer and
+// the reason to retry it, if any.
+pub type HttpRequestResult = Result<reqwest::blocking::Response, (u16, String, Option<RetryReason>)>;
 
-// Global semaphore for managing simultaneous HTTP requests.
-//
-// This lazy_static declaration ensures that the semaphore is initialized once
-// and remains in memory for the duration of the program. The semaphore's count is
-// set to the `SIMULTANEOUS_REQUESTS_LIMIT`, thereby enforcing the limit on the number
-// of concurrent HTTP requests.
+// Global limiter for managing simultaneous HTTP requests.
 //
-// Note: The use of a global semaphore is a common pattern for controlling access
-// to a limited resource (like network bandwidth) in a multi-threaded environment.
+// This lazy_static declaration ensures that the limiter is initialized once
+// and remains in memory for the duration of the program. It starts at
+// `SIMULTANEOUS_REQUESTS_LIMIT` requests in flight and adapts to the rate limit
+// feedback of every response.
+lazy_static! {
+    static ref LIMITER: AdaptiveLimiter = AdaptiveLimiter::new(SIMULTANEOUS_REQUESTS_LIMIT);
+}
+
+/// Returns the number of requests currently allowed in flight, as adapted to the throttling
+/// feedback of Canvas (between 1 and `SIMULTANEOUS_REQUESTS_LIMIT`).
+pub fn concurrency_limit() -> usize {
+    LIMITER.limit()
+}
+
+// File where the throttling state is saved, with the time of the last save.
 lazy_static! {
-    static ref SEMAPHORE: Semaphore = Semaphore::new(SIMULTANEOUS_REQUESTS_LIMIT);
+    static ref RATE_LIMIT_STATE_FILE: Mutex<Option<(PathBuf, Option<Instant>)>> = Mutex::new(None);
+}
+
+// Minimum interval between two saves of the throttling state.
+const RATE_LIMIT_STATE_SAVE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Default file for `persist_rate_limit_state`, in the cache folder of the user
+/// (e.g. `~/.cache/canvas_lms_connector/rate_limit.json` on Linux).
+pub fn default_rate_limit_state_path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|dir| dir.join("canvas_lms_connector").join("rate_limit.json"))
+}
+
+/// Keeps the throttling state of the connector in the file at `path` (or stops keeping it with
+/// `None`).
+///
+/// A state saved by a previous run less than `RATE_LIMIT_STATE_MAX_AGE_SECS` ago is restored
+/// right away, so a batch job restarted after a failure starts at the pace it had slowed down to
+/// instead of hitting the rate limit again. Afterwards the state is saved at most once per
+/// second while responses arrive. An unreadable file is ignored with a warning.
+///
+/// Example:
+/// ```
+/// persist_rate_limit_state(default_rate_limit_state_path().as_deref());
+/// ```
+pub fn persist_rate_limit_state(path: Option<&Path>) {
+    if let Some(path) = path {
+        match RateLimitState::load(path) {
+            Ok(Some(state)) => {
+                if let Some(limit) = state.resume_limit(chrono::Utc::now()) {
+                    LIMITER.restore(limit);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => log::warn!("Ignoring the rate limit state in {}: {}", path.display(), e),
+        }
+    }
+    *RATE_LIMIT_STATE_FILE.lock().unwrap() = path.map(|path| (path.to_path_buf(), None));
+}
+
+// Saves the throttling state after a response, when persistence is enabled and the last save
+// is old enough.
+fn save_rate_limit_state(remaining: Option<f64>, cost: Option<f64>) {
+    let mut file = RATE_LIMIT_STATE_FILE.lock().unwrap();
+    let Some((path, last_save)) = file.as_mut() else {
+        return;
+    };
+    if last_save.is_some_and(|last| last.elapsed() < RATE_LIMIT_STATE_SAVE_INTERVAL) {
+        return;
+    }
+    *last_save = Some(Instant::now());
+    let state = RateLimitState {
+        limit: LIMITER.limit(),
+        rate_limit_remaining: remaining,
+        last_request_cost: cost,
+        saved_at: chrono::Utc::now(),
+    };
+    if let Err(e) = state.save(path) {
+        log::warn!("Failed to save the rate limit state in {}: {}", path.display(), e);
+    }
 }
 
 /// Sends an HTTP request with a single attempt.
@@ -116,7 +224,11 @@ fn send_http_request_single_attempt(
     url: &str,
     canvas_info: &CanvasCredentials,
     params: Vec<(String, String)>,
+    retry_writes: bool,
 ) -> HttpRequestResult {
+    // Limits the number of requests in flight when the connector is used from several threads
+    let _permit = LIMITER.acquire();
+
     // Construir a requisição com base no método HTTP
     let request_builder = match &method {
         HttpMethod::Get => client
@@ -138,27 +250,404 @@ fn send_http_request_single_attempt(
     };
 
     // Enviar a requisição e verificar a resposta
+    let started = Instant::now();
     let response = request_builder.send();
 
-    match response {
+    let header = |name: &str| {
+        response
+            .as_ref()
+            .ok()
+            .and_then(|r| r.headers().get(name))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<f64>().ok())
+    };
+    let remaining = header("X-Rate-Limit-Remaining");
+    let status = response.as_ref().map_or(0, |r| r.status().as_u16());
+
+    if let Some(metrics) = metrics::metrics() {
+        metrics.record_request(method.as_str(), url, status, started.elapsed());
+        if let Some(remaining) = remaining {
+            metrics.record_rate_limit_remaining(remaining);
+        }
+    }
+    let cost = header("X-Request-Cost");
+    if response.is_ok() {
+        usage::record_response(canvas_info, method.as_str(), url, cost, remaining);
+    }
+
+    let result = match response {
         Ok(response) if response.status().is_success() => Ok(response),
-        Ok(response) => Err(response.status().as_u16()),
-        Err(_) => Err(0), // Código de erro genérico para falhas na requisição
+        Ok(response) => {
+            // Canvas explains most errors in the body
+            let status = response.status().as_u16();
+            let body = match response_size_limit() {
+                Some(limit) => limit_response_size(response, url, limit).and_then(|response| Ok(response.text()?)),
+                None => response.text().map_err(Into::into),
+            };
+            let body = body.unwrap_or_default();
+            let reason = RetryReason::from_response(status, &body, remaining)
+                .filter(|reason| retry_writes || repeatable(&method, *reason, false));
+            Err((status, body, reason))
+        }
+        // Código de erro genérico para falhas na requisição
+        Err(e) => {
+            let reason = Some(RetryReason::Network)
+                .filter(|reason| retry_writes || repeatable(&method, *reason, e.is_connect()));
+            Err((0, e.to_string(), reason))
+        }
+    };
+
+    // Only throttling shrinks the limit: a 403 for missing permissions says nothing about the load
+    if status != 0 {
+        let throttled = matches!(&result, Err((_, _, Some(RetryReason::RateLimited))));
+        LIMITER.record(throttled, remaining);
+        save_rate_limit_state(remaining, cost);
+    }
+    result
+}
+
+// Whether a failed attempt of `method` may be sent again. A POST that reached Canvas may have
+// been carried out even if the answer was an error or was lost, so it is repeated only when it
+// certainly wasn't: the connection could not be established (`not_sent`) or it was throttled.
+fn repeatable(method: &HttpMethod, reason: RetryReason, not_sent: bool) -> bool {
+    method.is_idempotent() || reason == RetryReason::RateLimited || (reason == RetryReason::Network && not_sent)
+}
+
+/// Category of a failed request attempt, used to select the applicable retry rule.
+///
+/// - `ServerError`: 5xx responses, usually transient failures on the Canvas side.
+/// - `RateLimited`: 429 responses, and 403 responses Canvas marks as throttling
+///   (`403 Forbidden (Rate Limit Exceeded)`). Other 403 responses (missing permissions) are not
+///   retried.
+/// - `Network`: the request never produced a response (DNS, TLS, timeouts, resets).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RetryReason {
+    ServerError,
+    RateLimited,
+    Network,
+}
+
+impl RetryReason {
+    /// Classifies a status code as returned by `send_http_request_single_attempt`,
+    /// where `0` stands for a network error. Returns `None` for non-retryable statuses.
+    ///
+    /// A 403 alone is not retryable: use `from_response` to tell throttling from a permission
+    /// error.
+    pub fn from_status(status: u16) -> Option<RetryReason> {
+        match status {
+            0 => Some(RetryReason::Network),
+            429 => Some(RetryReason::RateLimited),
+            500..=599 => Some(RetryReason::ServerError),
+            _ => None,
+        }
+    }
+
+    /// Classifies a failed answer from its status, body and `X-Rate-Limit-Remaining` header. A
+    /// 403 is throttling when Canvas says so in the body ("Rate Limit Exceeded") or when the rate
+    /// limit bucket is empty.
+    pub fn from_response(status: u16, body: &str, rate_limit_remaining: Option<f64>) -> Option<RetryReason> {
+        let throttled = rate_limit_remaining.is_some_and(|remaining| remaining <= 0.0)
+            || body.to_lowercase().contains("rate limit exceeded");
+        match status {
+            403 if throttled => Some(RetryReason::RateLimited),
+            _ => RetryReason::from_status(status),
+        }
+    }
+}
+
+/// Exponential backoff rule for a single `RetryReason`.
+///
+/// The delay before retry `n` (starting at 0) is `base_delay * 2^n`, capped at `max_delay`,
+/// with "equal jitter" applied: half of the delay is fixed and the other half is random. This
+/// spreads out retries from concurrent threads so they don't hit Canvas in lockstep.
+///
+/// Fields:
+/// - `max_attempts`: Total number of attempts, including the first one.
+/// - `base_delay`: Delay before the first retry (before jitter).
+/// - `max_delay`: Upper bound for any single delay.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryRule {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryRule {
+    /// Computes the delay to wait before retry number `attempt` (0-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.min(31));
+        let capped = self
+            .base_delay
+            .checked_mul(factor)
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay);
+        let half = capped / 2;
+        half + half.mul_f64(jitter_fraction())
+    }
+}
+
+/// Retry rules applied by `send_http_request`, one per `RetryReason`.
+///
+/// Example:
+/// ```
+/// let mut policy = RetryPolicy::default();
+/// policy.server_error.max_attempts = 3;
+/// set_retry_policy(policy);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub server_error: RetryRule,
+    pub rate_limited: RetryRule,
+    pub network: RetryRule,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            server_error: RetryRule {
+                max_attempts: 5,
+                base_delay: Duration::from_millis(500),
+                max_delay: Duration::from_secs(8),
+            },
+            rate_limited: RetryRule {
+                max_attempts: 5,
+                base_delay: Duration::from_secs(1),
+                max_delay: Duration::from_secs(30),
+            },
+            network: RetryRule {
+                max_attempts: SYNC_ATTEMPT,
+                base_delay: Duration::from_millis(100),
+                max_delay: Duration::from_secs(5),
+            },
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns the rule that applies to the given reason.
+    pub fn rule_for(&self, reason: RetryReason) -> &RetryRule {
+        match reason {
+            RetryReason::ServerError => &self.server_error,
+            RetryReason::RateLimited => &self.rate_limited,
+            RetryReason::Network => &self.network,
+        }
+    }
+
+    /// Returns the delay to wait before retrying a request that failed with `status` on the
+    /// given 0-based `attempt`, or `None` if the request should not be retried.
+    pub fn retry_delay(&self, status: u16, attempt: u32) -> Option<Duration> {
+        self.delay_for(RetryReason::from_status(status), attempt)
+    }
+
+    /// Same as `retry_delay` for a failure already classified (see `RetryReason::from_response`).
+    pub fn delay_for(&self, reason: Option<RetryReason>, attempt: u32) -> Option<Duration> {
+        let rule = self.rule_for(reason?);
+        if attempt + 1 < rule.max_attempts {
+            Some(rule.delay(attempt))
+        } else {
+            None
+        }
+    }
+}
+
+// Global retry policy used by `send_http_request`.
+lazy_static! {
+    static ref RETRY_POLICY: RwLock<RetryPolicy> = RwLock::new(RetryPolicy::default());
+}
+
+/// Replaces the retry policy used for every subsequent HTTP request.
+pub fn set_retry_policy(policy: RetryPolicy) {
+    *RETRY_POLICY.write().unwrap() = policy;
+}
+
+/// Returns a copy of the retry policy currently in use.
+pub fn retry_policy() -> RetryPolicy {
+    *RETRY_POLICY.read().unwrap()
+}
+
+// Maximum size of the API responses, in bytes (no limit by default).
+lazy_static! {
+    static ref RESPONSE_SIZE_LIMIT: RwLock<Option<u64>> = RwLock::new(None);
+}
+
+/// Limits the size of the API responses to `limit` bytes (`None` removes the limit).
+///
+/// A bigger response fails with `CanvasError::ResponseTooLarge` instead of being loaded in
+/// memory, which protects long-running processes from pathological answers (e.g. a page of
+/// submissions with huge text entries). The body is read up to the limit only, whether or not
+/// Canvas announced its size. File downloads are not affected.
+///
+/// Example:
+/// ```
+/// set_response_size_limit(Some(50 * 1024 * 1024));
+/// ```
+pub fn set_response_size_limit(limit: Option<u64>) {
+    *RESPONSE_SIZE_LIMIT.write().unwrap() = limit;
+}
+
+/// Returns the maximum size of the API responses set with `set_response_size_limit`.
+pub fn response_size_limit() -> Option<u64> {
+    *RESPONSE_SIZE_LIMIT.read().unwrap()
+}
+
+// Loads the body of `response` in memory, failing as soon as it exceeds `limit` bytes. The
+// response is rebuilt with the same status and headers.
+fn limit_response_size(
+    response: reqwest::blocking::Response,
+    url: &str,
+    limit: u64,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    let too_large = || Box::new(CanvasError::ResponseTooLarge { url: url.to_string(), limit });
+    if response.content_length().is_some_and(|length| length > limit) {
+        return Err(too_large());
+    }
+    let mut builder = http::Response::builder().status(response.status());
+    for (name, value) in response.headers() {
+        builder = builder.header(name, value);
+    }
+    let mut body = Vec::new();
+    std::io::Read::read_to_end(&mut std::io::Read::take(response, limit + 1), &mut body)?;
+    if body.len() as u64 > limit {
+        return Err(too_large());
+    }
+    Ok(reqwest::blocking::Response::from(builder.body(body)?))
+}
+
+// Maximum length of the text of an unexpected answer kept in `UnexpectedContentType`.
+const UNEXPECTED_CONTENT_SNIPPET_LENGTH: usize = 200;
+
+// Fails with `UnexpectedContentType` when a successful answer is not JSON (an SSO login page
+// after a redirect, a maintenance page...), instead of letting `json()` fail with a serde error.
+// Answers without a content type or without a body are accepted.
+fn check_content_type(response: reqwest::blocking::Response) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    let content_type = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.split(';').next().unwrap_or_default().trim().to_lowercase())
+        .unwrap_or_default();
+    if content_type.is_empty() || content_type == "application/json" || content_type.ends_with("+json") {
+        return Ok(response);
+    }
+    let url = response.url().to_string();
+    let mut builder = http::Response::builder().status(response.status());
+    for (name, value) in response.headers() {
+        builder = builder.header(name, value);
+    }
+    // Only the beginning of the body matters: the page may be big
+    let mut body = Vec::new();
+    std::io::Read::read_to_end(&mut std::io::Read::take(response, 64 * 1024), &mut body)?;
+    let text = String::from_utf8_lossy(&body);
+    if text.trim().is_empty() {
+        return Ok(reqwest::blocking::Response::from(builder.body(body)?));
+    }
+    Err(Box::new(CanvasError::UnexpectedContentType {
+        url,
+        content_type,
+        snippet: content_snippet(&text),
+    }))
+}
+
+// Readable beginning of a page: without tags, scripts and styles, with whitespace collapsed.
+fn content_snippet(text: &str) -> String {
+    let hidden = regex::Regex::new(r"(?is)<(script|style)[^>]*>.*?</(script|style)>").unwrap();
+    let tags = regex::Regex::new(r"(?s)<[^>]*>").unwrap();
+    let visible = hidden.replace_all(text, " ");
+    let text = tags.replace_all(&visible, " ");
+    let words: Vec<&str> = text.split_whitespace().collect();
+    let mut snippet = words.join(" ");
+    if let Some((index, _)) = snippet.char_indices().nth(UNEXPECTED_CONTENT_SNIPPET_LENGTH) {
+        snippet.truncate(index);
+        snippet.truncate(snippet.trim_end().len());
+        snippet.push_str("...");
+    }
+    snippet
+}
+
+// Returns a pseudo-random value in [0, 1) used to jitter retry delays.
+// The sub-second clock is good enough here and avoids pulling in a RNG crate.
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 1000) as f64 / 1000.0
+}
+
+/// Runs `operation` until it succeeds, sleeping according to `rule` between attempts.
+///
+/// Used for operations that don't go through `send_http_request` (such as multipart uploads
+/// to the pre-signed upload URL) so they share the same backoff behavior.
+pub(crate) fn retry_with_backoff<T, E, F>(rule: &RetryRule, mut operation: F) -> Result<T, E>
+where
+    F: FnMut() -> Result<T, E>,
+{
+    let mut attempt = 0;
+    loop {
+        match operation() {
+            Ok(value) => return Ok(value),
+            Err(e) if attempt + 1 >= rule.max_attempts => return Err(e),
+            Err(_) => {
+                std::thread::sleep(rule.delay(attempt));
+                attempt += 1;
+            }
+        }
     }
 }
 
+/// Sends a multipart upload to the storage URL handed out by Canvas (step 2 of the file upload
+/// protocol). The storage is not the Canvas API, so the request doesn't go through
+/// `send_http_request`, but it is recorded in the audit log like any other write: as a POST whose
+/// payload is `summary` (name and size of the file), to the URL without its pre-signed parameters.
+pub(crate) fn send_multipart_upload(
+    client: &reqwest::blocking::Client,
+    canvas_info: &CanvasCredentials,
+    upload_url: &str,
+    form: reqwest::blocking::multipart::Form,
+    summary: serde_json::Value,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    let method = HttpMethod::Post(summary);
+    let audited_url = upload_url.split('?').next().unwrap_or(upload_url);
+    let result = client.post(upload_url).multipart(form).send();
+    match &result {
+        Ok(response) if response.status().is_success() => {
+            audit::record_request(&method, audited_url, canvas_info, Some(response.status().as_u16()), None)
+        }
+        Ok(response) => audit::record_request(
+            &method,
+            audited_url,
+            canvas_info,
+            Some(response.status().as_u16()),
+            Some(format!("Upload failed with status: {}", response.status())),
+        ),
+        Err(e) => audit::record_request(&method, audited_url, canvas_info, None, Some(e.to_string())),
+    }
+    Ok(result?)
+}
 
 /// Sends an HTTP request with retry logic.
 ///
-/// This function attempts to send an HTTP request multiple times (up to `max_attempts`)
-/// in case of failure. It's particularly useful for handling transient network issues
-/// or temporary server-side errors. A delay is introduced between retries for 403 errors,
-/// which often represent rate limiting or similar temporary restrictions.
+/// This function attempts to send an HTTP request multiple times in case of failure, following
+/// the global `RetryPolicy`: server errors (5xx), rate limiting (429, and 403 marked as
+/// throttling) and network errors each have their own attempt limit and exponential backoff with
+/// jitter. Other statuses (e.g. 400, 404, a 403 for missing permissions) fail immediately, since
+/// retrying them cannot succeed. POST requests are not idempotent, so they are retried only when
+/// throttled or when the connection to Canvas could not be established: after a server error or a
+/// lost answer Canvas may already have carried them out.
 ///
 /// Note: This retry mechanism is a common pattern in network programming, especially
 /// when interacting with external APIs that may have rate limits or occasional downtime.
-use std::io;
-
+///
+/// Responses bigger than the limit set with `set_response_size_limit` fail with
+/// `CanvasError::ResponseTooLarge`, and successful responses that are not JSON (an HTML login
+/// page after an SSO redirect, for instance) with `CanvasError::UnexpectedContentType`.
+///
+/// Mutating requests (PUT, POST, DELETE) are recorded in the audit log once the final outcome is
+/// known, if one is registered with `set_audit_log`. When a cassette is inserted (see
+/// `insert_cassette`), requests are recorded to it or answered from it.
+///
+/// Otherwise, GET requests are answered from the response cache while their answer is fresh (see
+/// `set_response_cache`); successful writes drop the cached answers they may change.
 pub fn send_http_request(
     client: &reqwest::blocking::Client,
     method: HttpMethod,
@@ -166,32 +655,310 @@ pub fn send_http_request(
     canvas_info: &CanvasCredentials,
     params: Vec<(String, String)>,
 ) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
-    let mut attempts = 0;
-    let max_attempts = 5;
+    send_http_request_with_options(client, method, url, canvas_info, params, RequestOptions::default())
+}
+
+/// Same as `send_http_request`, but a GET is always sent to Canvas instead of being answered from
+/// the response cache (its fresh answer still replaces the cached one). Used for reads that decide
+/// whether a write happens, which must not see a stale state.
+pub(crate) fn send_http_request_uncached(
+    client: &reqwest::blocking::Client,
+    method: HttpMethod,
+    url: &str,
+    canvas_info: &CanvasCredentials,
+    params: Vec<(String, String)>,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    let options = RequestOptions {
+        read_cache: false,
+        ..Default::default()
+    };
+    send_http_request_with_options(client, method, url, canvas_info, params, options)
+}
+
+/// Same as `send_http_request`, with the cache and retry behavior set by `options`.
+pub(crate) fn send_http_request_with_options(
+    client: &reqwest::blocking::Client,
+    method: HttpMethod,
+    url: &str,
+    canvas_info: &CanvasCredentials,
+    params: Vec<(String, String)>,
+    options: RequestOptions,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    if let Some(replayed) = cassette::replay(&method, url, &params, canvas_info) {
+        return replayed;
+    }
+    // Cassettes must see every request, so the response cache is bypassed while one is in use
+    let cached = !cassette::is_inserted();
+    if cached && options.read_cache {
+        if let Some(response) = response_cache::lookup(&method, url, &params, canvas_info) {
+            return Ok(response);
+        }
+    }
+    let mut result =
+        send_http_request_with_retries(client, method.clone(), url, canvas_info, params.clone(), options.retry_writes)
+            .and_then(check_content_type);
+    if let Some(limit) = response_size_limit() {
+        result = result.and_then(|response| limit_response_size(response, url, limit));
+    }
+    let mut result = cassette::record(&method, url, &params, canvas_info, result);
+    if cached {
+        result = response_cache::store(&method, url, &params, canvas_info, result);
+    }
+    match &result {
+        Ok(response) => audit::record_request(
+            &method,
+            url,
+            canvas_info,
+            Some(response.status().as_u16()),
+            None,
+        ),
+        Err(e) => audit::record_request(&method, url, canvas_info, None, Some(e.to_string())),
+    }
+    result
+}
+
+// Retry loop behind `send_http_request`.
+fn send_http_request_with_retries(
+    client: &reqwest::blocking::Client,
+    method: HttpMethod,
+    url: &str,
+    canvas_info: &CanvasCredentials,
+    params: Vec<(String, String)>,
+    retry_writes: bool,
+) -> Result<reqwest::blocking::Response, Box<dyn std::error::Error>> {
+    let policy = retry_policy();
+    let mut attempt = 0;
 
     // Retry loop.
-    while attempts < max_attempts {
-        match send_http_request_single_attempt(client, method.clone(), url, canvas_info, params.clone()) {
+    loop {
+        match send_http_request_single_attempt(client, method.clone(), url, canvas_info, params.clone(), retry_writes) {
             Ok(response) => return Ok(response),
-            Err(status) if status == 403 && attempts < max_attempts - 1 => {
-                // Retry for 403 status codes.
-                attempts += 1;
-                std::thread::sleep(std::time::Duration::from_millis(1000)); // Wait before retrying.
+            Err((status, body, reason)) => match policy.delay_for(reason, attempt) {
+                Some(delay) => {
+                    attempt += 1;
+                    std::thread::sleep(delay); // Wait before retrying.
+                }
+                None if status == 0 && attempt == 0 => {
+                    // Not retried (a POST whose answer was lost): report the cause
+                    return Err(Box::new(CanvasError::Network(format!("Failed to reach {}: {}", url, body))));
+                }
+                None if status == 0 => {
+                    // Return an error after all attempts fail.
+                    return Err(Box::new(CanvasError::Network(format!(
+                        "All {} attempts to reach {} failed",
+                        attempt + 1,
+                        url
+                    ))));
+                }
+                None => {
+                    // Convert the status code and the messages of Canvas to a proper error type.
+                    return Err(Box::new(CanvasError::from_response_body(status, &body)));
+                }
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_size_limit() {
+        let response = |body: &str| {
+            reqwest::blocking::Response::from(
+                http::Response::builder().status(200).header("Link", "<next>; rel=\"next\"").body(body.to_string()).unwrap(),
+            )
+        };
+        let limited = limit_response_size(response(r#"{"id":1}"#), "https://canvas.example/x", 8).unwrap();
+        assert_eq!(limited.headers()["Link"], "<next>; rel=\"next\"");
+        assert_eq!(limited.text().unwrap(), r#"{"id":1}"#);
+
+        let error = limit_response_size(response(r#"{"id":10}"#), "https://canvas.example/x", 8).unwrap_err();
+        assert_eq!(
+            CanvasError::from(error),
+            CanvasError::ResponseTooLarge {
+                url: "https://canvas.example/x".to_string(),
+                limit: 8
             }
-            Err(status) => {
-                // Convert the status code to a proper error type.
-                return Err(Box::new(io::Error::new(
-                    io::ErrorKind::Other,
-                    format!("HTTP request failed with status code: {}", status),
-                )));
+        );
+    }
+
+    #[test]
+    fn test_html_answers_are_rejected() {
+        let response = |content_type: &str, body: &str| {
+            reqwest::blocking::Response::from(
+                http::Response::builder().status(200).header("Content-Type", content_type).body(body.to_string()).unwrap(),
+            )
+        };
+        assert!(check_content_type(response("application/json; charset=utf-8", "[]")).is_ok());
+        assert_eq!(check_content_type(response("text/html", "  ")).unwrap().status(), 200);
+
+        let page = "<html><head><title>Log In</title><style>body { color: red }</style></head>\n\
+                    <body><h1>Sign in to  your account</h1></body></html>";
+        match CanvasError::from(check_content_type(response("text/html; charset=utf-8", page)).unwrap_err()) {
+            CanvasError::UnexpectedContentType { content_type, snippet, .. } => {
+                assert_eq!(content_type, "text/html");
+                assert_eq!(snippet, "Log In Sign in to your account");
             }
+            other => panic!("unexpected error {:?}", other),
         }
+        assert!(content_snippet(&"a ".repeat(300)).ends_with("a..."));
     }
 
-    // Return an error after all attempts fail.
-    Err(Box::new(io::Error::new(
-        io::ErrorKind::PermissionDenied,
-        "All retry attempts failed with status 403",
-    )))
-}
+    #[test]
+    fn test_retry_reason_classification() {
+        assert_eq!(RetryReason::from_status(0), Some(RetryReason::Network));
+        assert_eq!(RetryReason::from_status(403), None);
+        assert_eq!(RetryReason::from_status(429), Some(RetryReason::RateLimited));
+        assert_eq!(RetryReason::from_status(502), Some(RetryReason::ServerError));
+        assert_eq!(RetryReason::from_status(400), None);
+        assert_eq!(RetryReason::from_status(404), None);
+
+        // A 403 is throttling only when Canvas says so; a permission error fails at once
+        let throttled = "403 Forbidden (Rate Limit Exceeded)";
+        assert_eq!(RetryReason::from_response(403, throttled, Some(12.0)), Some(RetryReason::RateLimited));
+        assert_eq!(RetryReason::from_response(403, "", Some(0.0)), Some(RetryReason::RateLimited));
+        let forbidden = r#"{"status":"unauthorized","errors":[{"message":"user not authorized to perform that action"}]}"#;
+        assert_eq!(RetryReason::from_response(403, forbidden, Some(650.0)), None);
+        assert_eq!(RetryReason::from_response(403, forbidden, None), None);
+        assert!(RetryPolicy::default().delay_for(RetryReason::from_response(403, forbidden, None), 0).is_none());
+        assert_eq!(RetryReason::from_response(503, "", None), Some(RetryReason::ServerError));
+    }
+
+    #[test]
+    fn test_delay_is_exponential_jittered_and_capped() {
+        let rule = RetryRule {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(100),
+            max_delay: Duration::from_millis(1000),
+        };
+        for attempt in 0..10 {
+            let expected = Duration::from_millis(100 * 2u64.pow(attempt)).min(rule.max_delay);
+            let delay = rule.delay(attempt);
+            assert!(delay >= expected / 2, "attempt {}: {:?}", attempt, delay);
+            assert!(delay <= expected, "attempt {}: {:?}", attempt, delay);
+        }
+        assert!(rule.delay(u32::MAX) <= rule.max_delay);
+    }
+
+    #[test]
+    fn test_posts_are_repeated_only_when_not_carried_out() {
+        let post = HttpMethod::Post(serde_json::json!({}));
+        assert!(!repeatable(&post, RetryReason::ServerError, false));
+        assert!(!repeatable(&post, RetryReason::Network, false));
+        assert!(repeatable(&post, RetryReason::Network, true));
+        assert!(repeatable(&post, RetryReason::RateLimited, false));
+        assert!(repeatable(&HttpMethod::Put(serde_json::json!({})), RetryReason::ServerError, false));
+        assert!(repeatable(&HttpMethod::Get, RetryReason::Network, false));
+
+        // A POST answered with a server error is sent once
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v1/conversations", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            listener.set_nonblocking(true).unwrap();
+            let mut requests = 0;
+            let deadline = Instant::now() + Duration::from_secs(3);
+            while Instant::now() < deadline {
+                match listener.accept() {
+                    Ok((mut stream, _)) => {
+                        stream.set_nonblocking(false).unwrap();
+                        let mut buffer = [0u8; 4096];
+                        let _ = std::io::Read::read(&mut stream, &mut buffer);
+                        let answer = "HTTP/1.1 503 Service Unavailable\r\nContent-Length: 0\r\nConnection: close\r\n\r\n";
+                        let _ = std::io::Write::write_all(&mut stream, answer.as_bytes());
+                        requests += 1;
+                    }
+                    Err(_) => std::thread::sleep(Duration::from_millis(10)),
+                }
+            }
+            requests
+        });
+        let canvas_info = CanvasCredentials {
+            url_canvas: url.clone(),
+            token_canvas: "token".to_string(),
+        };
+        let client = reqwest::blocking::Client::new();
+        let error = send_http_request_with_retries(&client, post, &url, &canvas_info, vec![], false).unwrap_err();
+        assert_eq!(CanvasError::from(error).status(), Some(503));
+        assert_eq!(server.join().unwrap(), 1);
+    }
+
+    #[test]
+    fn test_multipart_uploads_are_audited() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let upload_url = format!("http://{}/files_api?signature=secret", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            stream.set_read_timeout(Some(Duration::from_millis(200))).unwrap();
+            let mut buffer = [0u8; 4096];
+            while std::io::Read::read(&mut stream, &mut buffer).is_ok_and(|read| read > 0) {}
+            let body = r#"{"id": 77}"#;
+            let answer = format!(
+                "HTTP/1.1 201 Created\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            std::io::Write::write_all(&mut stream, answer.as_bytes()).unwrap();
+        });
+
+        let entries = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let recorded = std::sync::Arc::clone(&entries);
+        let prefix = upload_url.split('?').next().unwrap().to_string();
+        audit::set_audit_log(Some(audit::AuditLog::with_callback(move |entry| {
+            if entry.url == prefix {
+                recorded.lock().unwrap().push(entry.clone());
+            }
+        })));
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: "token".to_string(),
+        };
+        let form = reqwest::blocking::multipart::Form::new().text("key", "value");
+        let summary = serde_json::json!({"name": "relatorio.pdf", "size": 5});
+        let response = send_multipart_upload(&reqwest::blocking::Client::new(), &canvas_info, &upload_url, form, summary);
+        audit::set_audit_log(None);
+        server.join().unwrap();
+
+        assert_eq!(response.unwrap().status(), 201);
+        let entries = entries.lock().unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].method, "POST");
+        assert!(!entries[0].url.contains("secret"));
+        assert_eq!(entries[0].status, Some(201));
+        assert!(entries[0].payload_summary.as_deref().is_some_and(|summary| summary.contains("relatorio.pdf")));
+    }
+
+    #[test]
+    fn test_policy_stops_after_max_attempts() {
+        let policy = RetryPolicy::default();
+        let limit = policy.server_error.max_attempts;
+        assert!(policy.retry_delay(503, 0).is_some());
+        assert!(policy.retry_delay(503, limit - 2).is_some());
+        assert!(policy.retry_delay(503, limit - 1).is_none());
+        assert!(policy.retry_delay(404, 0).is_none());
+    }
 
+    #[test]
+    fn test_retry_with_backoff_retries_until_success() {
+        let rule = RetryRule {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(2),
+        };
+        let mut calls = 0;
+        let result: Result<u32, &str> = retry_with_backoff(&rule, || {
+            calls += 1;
+            if calls < 3 { Err("fail") } else { Ok(calls) }
+        });
+        assert_eq!(result, Ok(3));
+
+        let mut calls = 0;
+        let result: Result<u32, &str> = retry_with_backoff(&rule, || {
+            calls += 1;
+            Err("fail")
+        });
+        assert_eq!(result, Err("fail"));
+        assert_eq!(calls, 3);
+    }
+}
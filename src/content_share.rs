@@ -0,0 +1,167 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_all_pages_as;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// Kind of content that can be sent to another instructor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShareableContent {
+    Assignment,
+    DiscussionTopic,
+    Page,
+    Quiz,
+    Module,
+    ModuleItem,
+}
+
+impl ShareableContent {
+    /// Value of the `content_type` parameter expected by Canvas.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ShareableContent::Assignment => "assignment",
+            ShareableContent::DiscussionTopic => "discussion_topic",
+            ShareableContent::Page => "page",
+            ShareableContent::Quiz => "quiz",
+            ShareableContent::Module => "module",
+            ShareableContent::ModuleItem => "module_item",
+        }
+    }
+}
+
+/// Sender or receiver of a content share.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ShareUser {
+    pub id: u64,
+    pub display_name: Option<String>,
+}
+
+/// Course the shared content was taken from.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ShareSourceCourse {
+    pub id: u64,
+    pub name: String,
+}
+
+/// A piece of content sent from one instructor to others (Direct Share).
+///
+/// Fields:
+/// - `id`: ID of the share.
+/// - `name`: Name of the shared content.
+/// - `content_type`: `assignment`, `discussion_topic`, `page`, `quiz`, `module` or `module_item`.
+/// - `created_at`: When the content was sent.
+/// - `read_state`: `read` or `unread` (received shares only).
+/// - `sender`: Who sent the content (received shares only).
+/// - `receivers`: Who received the content (sent shares only).
+/// - `source_course`: Course the content comes from.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ContentShare {
+    pub id: u64,
+    pub name: String,
+    pub content_type: String,
+    pub created_at: Option<String>,
+    pub read_state: Option<String>,
+    pub sender: Option<ShareUser>,
+    #[serde(default)]
+    pub receivers: Vec<ShareUser>,
+    pub source_course: Option<ShareSourceCourse>,
+}
+
+impl ContentShare {
+    /// Returns `true` when the share was received and not opened yet.
+    pub fn is_unread(&self) -> bool {
+        self.read_state.as_deref() == Some("unread")
+    }
+}
+
+// Builds the body that sends content to other users.
+fn share_body(content_type: ShareableContent, content_id: u64, receiver_ids: &[u64]) -> Value {
+    json!({
+        "receiver_ids": receiver_ids,
+        "content_type": content_type.as_str(),
+        "content_id": content_id,
+    })
+}
+
+/// Sends a copy of some content to other instructors, who find it in their received shares.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `content_type`: Kind of the content.
+/// - `content_id`: ID of the content (assignment ID, page ID...).
+/// - `receiver_ids`: Users to send the content to.
+///
+/// Returns:
+/// - `Result<ContentShare, Box<dyn Error>>`: The created share or an error.
+///
+/// Example:
+/// ```
+/// share_content(&client, &canvas_info, ShareableContent::Quiz, quiz.info.id, &[colleague_id])?;
+/// ```
+pub fn share_content(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    content_type: ShareableContent,
+    content_id: u64,
+    receiver_ids: &[u64],
+) -> Result<ContentShare, Box<dyn Error>> {
+    let url = format!("{}/users/self/content_shares", canvas_info.url_canvas);
+    let body = share_body(content_type, content_id, receiver_ids);
+    let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to share content with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+/// Retrieves the content shared with the user.
+pub fn fetch_received_shares(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<ContentShare>, Box<dyn Error>> {
+    let url = format!("{}/users/self/content_shares/received", canvas_info.url_canvas);
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Retrieves the content the user shared with others.
+pub fn fetch_sent_shares(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<ContentShare>, Box<dyn Error>> {
+    let url = format!("{}/users/self/content_shares/sent", canvas_info.url_canvas);
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Marks a received share as read.
+pub fn mark_share_read(client: &Client, canvas_info: &CanvasCredentials, share_id: u64) -> Result<ContentShare, Box<dyn Error>> {
+    let url = format!("{}/users/self/content_shares/{}", canvas_info.url_canvas, share_id);
+    let body = json!({ "read_state": "read" });
+    let response = send_http_request(client, HttpMethod::Put(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update content share with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_share_body_and_received_share() {
+        let body = share_body(ShareableContent::DiscussionTopic, 12, &[3, 4]);
+        assert_eq!(body["content_type"], "discussion_topic");
+        assert_eq!(body["receiver_ids"], json!([3, 4]));
+
+        let share: ContentShare = serde_json::from_value(json!({
+            "id": 1,
+            "name": "Lista 3",
+            "content_type": "assignment",
+            "read_state": "unread",
+            "sender": {"id": 9, "display_name": "Prof. Lima"},
+            "receivers": [],
+            "source_course": {"id": 5, "name": "Física I"}
+        }))
+        .unwrap();
+        assert!(share.is_unread());
+        assert_eq!(share.source_course.unwrap().name, "Física I");
+    }
+}
@@ -1,12 +1,31 @@
 // Necessary imports from standard and external crates.
-use crate::assignment::Assignment;
-use crate::student::Student;
-use crate::{canvas, Canvas, CanvasCredentials, CanvasResultSingleCourse};
+use crate::academic_calendar::{self, AcademicCalendar, CourseTerm};
+use crate::appointment_group::{self, AppointmentGroup};
+use crate::assignment::{Assignment, AssignmentQuery};
+use crate::blueprint::{self, BlueprintStatus};
+use crate::conference::{self, Conference};
+use crate::course_file::{self, CourseFile};
+use crate::course_settings::{self, CourseSettings};
+use crate::dashboard;
+use crate::discussion::{self, DiscussionTopic};
+use crate::enrollment::{self, CsvEnrollmentResult, Enrollment};
+use crate::epub::{self, EpubExport};
+use crate::grading_standard::{self, GradingStandard};
+use crate::module::{self, Module, ModuleItemSequence, SequenceAssetType};
+use crate::page::{self, Page};
+use crate::quiz::{self, Quiz};
+use crate::section::{self, Section};
+use crate::snapshot::{CourseSnapshot, RosterDiff};
+use crate::student::{EnrollmentState, Student, StudentInfo};
+use crate::user::{EnrollmentRole, User};
+use crate::submission::{GradeWriteOptions, GradeWriteOutcome, SubmissionUpdate};
+use crate::{canvas, response_cache, Canvas, CanvasCredentials, CanvasResultSingleCourse};
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::Select;
 use regex::Regex;
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use std::error::Error;
 use std::process::exit;
 use std::sync::Arc;
@@ -23,19 +42,32 @@ use std::sync::Mutex;
 /// - `name`: Official name of the course.
 /// - `course_code`: Short identifier or code for the course.
 /// - `canvas_info`: Shared reference to Canvas credentials and API URL, enabling API interactions.
+/// - `access`: Whether the course content can actually be accessed (see `CourseAccess`).
+/// - `time_zone`: IANA time zone configured in the course settings (e.g. `America/Sao_Paulo`).
+/// - `students_cache` / `assignments_cache`: Deprecated. Rosters and assignment lists kept by
+///   `fetch_students` and `Course::fetch_assignments` while the response cache is disabled; enable
+///   it with `set_response_cache` instead.
 #[derive(Serialize, Deserialize, Debug, Default)]
 pub struct CourseInfo {
     pub id: u64,
     pub name: String,
     pub course_code: String,
+    #[serde(default)]
+    pub access: CourseAccess,
+    #[serde(default)]
+    pub time_zone: Option<String>,
     #[serde(skip)]
     pub canvas_info: Arc<CanvasCredentials>,
     #[serde(skip)]
     pub abbreviated_name: Option<CourseNameDetails>,
     #[serde(skip)]
+    #[deprecated(note = "use `set_response_cache` to cache course rosters")]
     pub students_cache: Mutex<Vec<Student>>,
     #[serde(skip)]
+    #[deprecated(note = "use `set_response_cache` to cache assignment lists")]
     pub assignments_cache: Mutex<Vec<Assignment>>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>, // Campos do Canvas não mapeados acima
 }
 
 /// High-level representation of a Canvas course.
@@ -51,17 +83,39 @@ pub struct Course {
     pub info: Arc<CourseInfo>,
 }
 
+/// Result of a single grade write in a batch, keyed by student ID in `update_assignment_scores`.
+pub type GradeWriteResult = Result<GradeWriteOutcome, Box<dyn Error>>;
+
+/// Access state of a course as reported by Canvas.
+///
+/// Courses outside their participation dates are returned by Canvas with only `id` and
+/// `access_restricted_by_date: true`, without a name or course code. Unpublished courses keep
+/// their details but cannot be used by students. This enum lets callers skip or report such
+/// courses instead of having them silently dropped.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CourseAccess {
+    #[default]
+    Available,
+    RestrictedByDate,
+    Unpublished,
+}
+
 // Implementando Clone manualmente
+#[allow(deprecated)]
 impl Clone for CourseInfo {
     fn clone(&self) -> Self {
         CourseInfo {
             id: self.id,
             name: self.name.clone(),
             course_code: self.course_code.clone(),
+            access: self.access.clone(),
+            time_zone: self.time_zone.clone(),
             canvas_info: Arc::clone(&self.canvas_info),
             abbreviated_name: self.abbreviated_name.clone(),
             students_cache: Mutex::new(self.students_cache.lock().unwrap().clone()),
             assignments_cache: Mutex::new(self.assignments_cache.lock().unwrap().clone()),
+            extra: self.extra.clone(),
         }
     }
 }
@@ -85,7 +139,12 @@ impl CourseInfo {
     ///     Err(e) => /* handle error */,
     /// }
     /// ```
+    #[allow(deprecated)]
     pub fn fetch_students(&self) -> Result<Vec<Student>, Box<dyn Error>> {
+        // Kept by the response cache when enabled (see `set_response_cache`)
+        if response_cache::response_cache().is_some() {
+            return canvas::fetch_students(self);
+        }
         {
             let students_cache = self.students_cache.lock().unwrap();
             if !students_cache.is_empty(){
@@ -102,11 +161,46 @@ impl CourseInfo {
         }
     }
 
+    /// Drops the cached answers of this course (roster, assignments...), so that the next calls
+    /// read them from Canvas again.
+    #[allow(deprecated)]
     pub fn clear_cache(&self){
-        let mut students_cache = self.students_cache.lock().unwrap();
-        students_cache.clear();
-        let mut assignments_cache = self.assignments_cache.lock().unwrap();
-        assignments_cache.clear();
+        self.students_cache.lock().unwrap().clear();
+        self.assignments_cache.lock().unwrap().clear();
+        response_cache::invalidate_course(&self.canvas_info.url_canvas, self.id);
+    }
+
+    /// Retrieves the students enrolled in a single section of this course.
+    ///
+    /// As for `fetch_students`, the answer is kept by the response cache when it is enabled (see
+    /// `set_response_cache`).
+    ///
+    /// Arguments:
+    /// - `section_id`: ID of the course section.
+    ///
+    /// Returns:
+    /// - `Result<Vec<Student>, Box<dyn std::error::Error>>`: The students of the section or an error.
+    pub fn fetch_students_in_section(&self, section_id: u64) -> Result<Vec<Student>, Box<dyn Error>> {
+        canvas::fetch_students_in_section(self, section_id)
+    }
+
+    /// Retrieves the students whose enrollment is in one of `states`.
+    pub fn fetch_students_with_states(
+        &self,
+        states: &[EnrollmentState],
+    ) -> Result<Vec<Student>, Box<dyn Error>> {
+        canvas::fetch_students_with_states(self, states)
+    }
+
+    /// Enrollment term of the course, when Canvas sent it with the course.
+    pub fn term(&self) -> Option<CourseTerm> {
+        academic_calendar::course_term(self)
+    }
+
+    /// `(year, semester)` of the course, taken from its enrollment term when Canvas knows it and
+    /// from today's date otherwise (see `CourseTerm::year_and_semester`).
+    pub fn year_and_semester(&self, calendar: &AcademicCalendar) -> (String, String) {
+        academic_calendar::course_year_and_semester(self, calendar)
     }
 }
 
@@ -138,10 +232,294 @@ impl Course {
         self.info.fetch_students()
     }
 
+    /// Retrieves the students enrolled in a single section of this course.
+    ///
+    /// Useful for TA tooling that only handles one section of a large course, where fetching the
+    /// whole roster is unnecessarily slow.
+    ///
+    /// Example:
+    /// ```
+    /// let students = course.fetch_students_in_section(4521)?;
+    /// ```
+    pub fn fetch_students_in_section(&self, section_id: u64) -> Result<Vec<Student>, Box<dyn Error>> {
+        self.info.fetch_students_in_section(section_id)
+    }
+
+    /// Retrieves the students of this course whose enrollment is in one of the given states.
+    ///
+    /// `fetch_students` only lists active and invited students. Include `Inactive` and
+    /// `Completed` to also get students who dropped the course or whose enrollment ended, e.g. to
+    /// grade the submissions they made before leaving. Passing these students to
+    /// `Assignment::fetch_submissions` makes their submissions match as well.
+    ///
+    /// Example:
+    /// ```
+    /// let students = course.fetch_students_with_states(&[
+    ///     EnrollmentState::Active,
+    ///     EnrollmentState::Inactive,
+    ///     EnrollmentState::Completed,
+    /// ])?;
+    /// ```
+    pub fn fetch_students_with_states(
+        &self,
+        states: &[EnrollmentState],
+    ) -> Result<Vec<Student>, Box<dyn Error>> {
+        self.info.fetch_students_with_states(states)
+    }
+
+    /// Retrieves the users enrolled in this course with the given role.
+    ///
+    /// Useful to list graders and co-teachers (for distributing grading duties), which
+    /// `fetch_students` doesn't return.
+    ///
+    /// Example:
+    /// ```
+    /// let graders = course.fetch_users(EnrollmentRole::Ta)?;
+    /// for ta in graders {
+    ///     println!("{} ({})", ta.name, ta.id);
+    /// }
+    /// ```
+    pub fn fetch_users(&self, role: EnrollmentRole) -> Result<Vec<User>, Box<dyn Error>> {
+        canvas::fetch_users(&self.info, role)
+    }
+
+    /// Searches the students of this course by partial name, email, login or SIS ID.
+    ///
+    /// The search runs on the Canvas server, so finding a student doesn't require downloading the
+    /// entire roster. Canvas rejects queries that are too short (usually fewer than 2 characters).
+    ///
+    /// Example:
+    /// ```
+    /// for student in course.search_users("silva")? {
+    ///     println!("{} <{:?}>", student.info.name, student.info.email);
+    /// }
+    /// ```
+    pub fn search_users(&self, query: &str) -> Result<Vec<Student>, Box<dyn Error>> {
+        canvas::search_users(&self.info, query)
+    }
+
+    /// Retrieves the grading standards (letter grade schemes) available in this course.
+    ///
+    /// Use them to show in reports the same letters students see in Canvas.
+    ///
+    /// Example:
+    /// ```
+    /// let standard = course
+    ///     .fetch_grading_standards(&client)?
+    ///     .into_iter()
+    ///     .next()
+    ///     .unwrap_or_else(GradingStandard::canvas_default);
+    /// println!("{:?}", standard.letter_for_score(8.7, 10.0));
+    /// ```
+    pub fn fetch_grading_standards(&self, client: &Client) -> Result<Vec<GradingStandard>, Box<dyn Error>> {
+        grading_standard::fetch_grading_standards(client, &self.info.canvas_info, self.info.id)
+    }
+
+    /// Retrieves the (classic) quizzes of the course.
+    ///
+    /// Example:
+    /// ```
+    /// for quiz in course.fetch_quizzes(&client)? {
+    ///     let answers = quiz.fetch_answers(&client)?;
+    ///     println!("{}: {} students answered", quiz.info.title, answers.len());
+    /// }
+    /// ```
+    pub fn fetch_quizzes(&self, client: &Client) -> Result<Vec<Quiz>, Box<dyn Error>> {
+        quiz::fetch_quizzes(client, &self.info)
+    }
+
+    /// Retrieves the enrollments of the course in the given states (active and invited when
+    /// `states` is empty).
+    pub fn fetch_enrollments(&self, client: &Client, states: &[EnrollmentState]) -> Result<Vec<Enrollment>, Box<dyn Error>> {
+        enrollment::fetch_enrollments(client, &self.info, states)
+    }
+
+    /// Enrolls the users of a roster CSV file (e.g. exported from the registrar system) and
+    /// sends them the course invitation.
+    ///
+    /// Users are identified by the `email` or `sis_user_id` (`sis_id`) column of the header, or by
+    /// the first column (e-mail or SIS ID) when the file has no header. `,` and `;` separators are
+    /// accepted. Looking users up by e-mail requires an admin token. A failing row doesn't stop
+    /// the others: the outcome of each one is returned.
+    ///
+    /// Example:
+    /// ```
+    /// let results = course.enroll_from_csv(&client, "matriculados.csv", EnrollmentRole::Student, None)?;
+    /// for failure in results.iter().filter(|result| !result.is_success()) {
+    ///     eprintln!("Line {} ({}): {}", failure.line, failure.identifier, failure.error.as_deref().unwrap_or(""));
+    /// }
+    /// ```
+    pub fn enroll_from_csv(
+        &self,
+        client: &Client,
+        file_path: &str,
+        role: EnrollmentRole,
+        section_id: Option<u64>,
+    ) -> Result<Vec<CsvEnrollmentResult>, Box<dyn Error>> {
+        let results = enrollment::enroll_from_csv(client, &self.info, file_path, role, section_id);
+        self.clear_cache();
+        results
+    }
+
+    /// Enrollment term of the course, when Canvas sent it with the course.
+    ///
+    /// Example:
+    /// ```
+    /// if let Some(term) = course.term() {
+    ///     println!("{} ({:?} - {:?})", term.name, term.start_at, term.end_at);
+    /// }
+    /// ```
+    pub fn term(&self) -> Option<CourseTerm> {
+        self.info.term()
+    }
+
+    /// `(year, semester)` of the course, from its enrollment term when Canvas knows it. Prefer it
+    /// to `get_current_year_and_semester`, which only looks at the current date.
+    pub fn year_and_semester(&self, calendar: &AcademicCalendar) -> (String, String) {
+        self.info.year_and_semester(calendar)
+    }
+
+    /// For blueprint courses, collects the changes waiting to be synced and the sync status of
+    /// every associated course. See `BlueprintStatus`.
+    pub fn fetch_blueprint_status(&self, client: &Client) -> Result<BlueprintStatus, Box<dyn Error>> {
+        blueprint::fetch_blueprint_status(client, &self.info.canvas_info, self.info.id)
+    }
+
+    /// Retrieves the appointment groups (Scheduler signup sheets) of the course, with their slots
+    /// and reservations.
+    pub fn fetch_appointment_groups(&self, client: &Client) -> Result<Vec<AppointmentGroup>, Box<dyn Error>> {
+        appointment_group::fetch_appointment_groups(
+            client,
+            &self.info.canvas_info,
+            &[format!("course_{}", self.info.id)],
+        )
+    }
+
+    /// Retrieves the web conferences of the course, with their recordings.
+    pub fn fetch_conferences(&self, client: &Client) -> Result<Vec<Conference>, Box<dyn Error>> {
+        conference::fetch_conferences(client, &self.info.canvas_info, self.info.id)
+    }
+
+    /// Exports the course as ePub and saves it to `file_path`, waiting for Canvas to generate it.
+    pub fn export_epub(&self, client: &Client, file_path: &str) -> Result<EpubExport, Box<dyn Error>> {
+        epub::export_epub_to_file(client, &self.info.canvas_info, self.info.id, file_path)
+    }
+
+    /// Retrieves the sections of the course.
+    pub fn fetch_sections(&self, client: &Client) -> Result<Vec<Section>, Box<dyn Error>> {
+        section::fetch_sections(client, &self.info)
+    }
+
+    /// Uploads a file (image, PDF...) to the files of the course, in `folder` when given. Use
+    /// `CourseFile::embed_html` to reference it in an assignment description or a page.
+    pub fn upload_file(&self, client: &Client, file_path: &str, folder: Option<&str>) -> Result<CourseFile, Box<dyn Error>> {
+        course_file::upload_course_file(client, &self.info.canvas_info, self.info.id, file_path, folder)
+    }
+
+    /// Retrieves the settings of the course. See `CourseSettings`.
+    pub fn fetch_settings(&self, client: &Client) -> Result<CourseSettings, Box<dyn Error>> {
+        course_settings::fetch_course_settings(client, &self.info.canvas_info, self.info.id)
+    }
+
+    /// Changes the settings of the course set to `Some` in `settings`, returning every setting
+    /// after the update.
+    pub fn update_settings(&self, client: &Client, settings: &CourseSettings) -> Result<CourseSettings, Box<dyn Error>> {
+        course_settings::update_course_settings(client, &self.info.canvas_info, self.info.id, settings)
+    }
+
+    /// Retrieves the nickname the authenticated user gave to this course, if any.
+    pub fn nickname(&self, client: &Client) -> Result<Option<String>, Box<dyn Error>> {
+        dashboard::fetch_course_nickname(client, &self.info.canvas_info, self.info.id)
+    }
+
+    /// Sets the nickname of this course for the authenticated user.
+    pub fn set_nickname(&self, client: &Client, nickname: &str) -> Result<(), Box<dyn Error>> {
+        dashboard::set_course_nickname(client, &self.info.canvas_info, self.info.id, nickname).map(|_| ())
+    }
+
+    /// Name shown to the user by Canvas: the nickname when there is one, the official name
+    /// otherwise.
+    ///
+    /// Example:
+    /// ```
+    /// println!("{}", course.display_name(&client)?);
+    /// ```
+    pub fn display_name(&self, client: &Client) -> Result<String, Box<dyn Error>> {
+        Ok(self.nickname(client)?.unwrap_or_else(|| self.info.name.clone()))
+    }
+
+    /// Retrieves the discussion topics of the course.
+    pub fn fetch_discussion_topics(&self, client: &Client) -> Result<Vec<DiscussionTopic>, Box<dyn Error>> {
+        discussion::fetch_discussion_topics(client, &self.info)
+    }
+
+    /// Retrieves one discussion topic of the course.
+    pub fn fetch_discussion_topic(&self, client: &Client, topic_id: u64) -> Result<DiscussionTopic, Box<dyn Error>> {
+        discussion::fetch_discussion_topic(client, &self.info, topic_id)
+    }
+
+    /// Retrieves the wiki pages of the course (without their bodies).
+    pub fn fetch_pages(&self, client: &Client) -> Result<Vec<Page>, Box<dyn Error>> {
+        page::fetch_pages(client, &self.info)
+    }
+
+    /// Retrieves one wiki page of the course by its slug, including its body.
+    ///
+    /// Example:
+    /// ```
+    /// let page = course.fetch_page(&client, "instrucoes-do-projeto")?;
+    /// let revisions = page.fetch_revisions(&client)?;
+    /// page.revert_to(&client, revisions[1].revision_id)?;
+    /// ```
+    pub fn fetch_page(&self, client: &Client, page_url: &str) -> Result<Page, Box<dyn Error>> {
+        page::fetch_page(client, &self.info, page_url)
+    }
+
+    /// Retrieves the modules of the course, in course order.
+    pub fn fetch_modules(&self, client: &Client) -> Result<Vec<Module>, Box<dyn Error>> {
+        module::fetch_modules(client, &self.info)
+    }
+
+    /// Finds the module items before and after a content item (see `ModuleItemSequence`).
+    pub fn fetch_module_item_sequence(
+        &self,
+        client: &Client,
+        asset_type: SequenceAssetType,
+        asset_id: u64,
+    ) -> Result<ModuleItemSequence, Box<dyn Error>> {
+        module::fetch_module_item_sequence(client, &self.info.canvas_info, self.info.id, asset_type, asset_id)
+    }
+
+    /// Publishes every module of the course and every unpublished item inside them, so a course
+    /// built by a script can go live at term start in one call.
+    ///
+    /// Returns:
+    /// - `Result<usize, Box<dyn Error>>`: Number of modules and items that were changed, or the
+    ///   first error found.
+    pub fn publish_all_modules(&self, client: &Client) -> Result<usize, Box<dyn Error>> {
+        let mut changed = 0;
+        for module in self.fetch_modules(client)? {
+            let module = if module.info.published {
+                module
+            } else {
+                changed += 1;
+                module.publish(client)?
+            };
+            changed += module.publish_items(client)?;
+        }
+        Ok(changed)
+    }
+
     pub fn clear_cache(&self){
         self.info.clear_cache();
     }
 
+    /// Returns `true` when the course content can be accessed, i.e. it is neither
+    /// restricted by date nor unpublished.
+    pub fn is_accessible(&self) -> bool {
+        self.info.access == CourseAccess::Available
+    }
+
     /// Retrieves assignments for this course.
     ///
     /// Queries the Canvas API to fetch all assignments related to the course. Uses course ID and
@@ -159,7 +537,12 @@ impl Course {
     ///     Err(e) => /* handle error */,
     /// }
     /// ```
+    #[allow(deprecated)]
     pub fn fetch_assignments(&self) -> Result<Vec<Assignment>, Box<dyn Error>> {
+        // Kept by the response cache when enabled (see `set_response_cache`)
+        if response_cache::response_cache().is_some() {
+            return canvas::fetch_assignments(self);
+        }
         {
             let assignments_cache = self.info.assignments_cache.lock().unwrap();
             if !assignments_cache.is_empty(){
@@ -176,6 +559,27 @@ impl Course {
         }
     }
 
+    /// Retrieves the assignments of this course matching `query`.
+    ///
+    /// Lets pickers such as `choose_assignment` show only relevant assignments (e.g. upcoming or
+    /// overdue ones, or those whose name contains a term).
+    ///
+    /// Example:
+    /// ```
+    /// let query = AssignmentQuery::default().bucket(AssignmentBucket::Upcoming);
+    /// let upcoming = course.fetch_assignments_with_query(&query)?;
+    /// course.choose_assignment(Some("Choose an upcoming assignment:"), Some(upcoming));
+    /// ```
+    pub fn fetch_assignments_with_query(
+        &self,
+        query: &AssignmentQuery,
+    ) -> Result<Vec<Assignment>, Box<dyn Error>> {
+        if query.is_empty() {
+            return self.fetch_assignments();
+        }
+        canvas::fetch_assignments_with_query(self, query)
+    }
+
     pub fn choose_assignment(
         &self,
         text: Option<&str>,
@@ -277,6 +681,97 @@ impl Course {
         result
     }
 
+    /// Updates the score of a student's submission, skipping redundant or conflicting writes.
+    ///
+    /// See `GradeWriteOptions` for the available rules. Useful in bulk grading scripts that may be
+    /// re-run, or that must not overwrite regrades made manually after the data was read.
+    ///
+    /// Example:
+    /// ```
+    /// let options = GradeWriteOptions { skip_if_unchanged: true, baseline: Some(read_at) };
+    /// match course.update_assignment_score_with_options(&client, assignment_id, student_id, Some(9.5), &options) {
+    ///     Ok(GradeWriteOutcome::Written) => /* grade posted */,
+    ///     Ok(skipped) => /* nothing written */,
+    ///     Err(e) => /* handle error */,
+    /// }
+    /// ```
+    pub fn update_assignment_score_with_options(
+        &self,
+        client: &Client,
+        assignment_id: u64,
+        student_id: u64,
+        new_score: Option<f64>,
+        options: &GradeWriteOptions,
+    ) -> Result<GradeWriteOutcome, Box<dyn Error>> {
+        let result = canvas::update_assignment_score_with_options(
+            client,
+            &self.info.canvas_info,
+            self.info.id,
+            assignment_id,
+            student_id,
+            new_score,
+            options,
+        );
+        if let Ok(GradeWriteOutcome::Written) = result {
+            self.clear_cache();
+        }
+        result
+    }
+
+    /// Applies several changes (grade, rubric assessment, comment...) to a student's submission in
+    /// a single request, so the student receives one notification. See `SubmissionUpdate`.
+    ///
+    /// Returns:
+    /// - `Result<Value, Box<dyn Error>>`: The updated submission as returned by Canvas, or an error.
+    pub fn update_submission(
+        &self,
+        client: &Client,
+        assignment_id: u64,
+        student_id: u64,
+        update: &SubmissionUpdate,
+    ) -> Result<Value, Box<dyn Error>> {
+        let result = canvas::update_submission(
+            client,
+            &self.info.canvas_info,
+            self.info.id,
+            assignment_id,
+            student_id,
+            update,
+        );
+        if result.is_ok() {
+            self.clear_cache();
+        }
+        result
+    }
+
+    /// Updates the scores of several students for an assignment, applying `options` to each write.
+    ///
+    /// Returns one result per input entry, in the same order, so a failure for one student does
+    /// not abort the rest of the batch.
+    pub fn update_assignment_scores(
+        &self,
+        client: &Client,
+        assignment_id: u64,
+        scores: &[(u64, Option<f64>)],
+        options: &GradeWriteOptions,
+    ) -> Vec<(u64, GradeWriteResult)> {
+        scores
+            .iter()
+            .map(|(student_id, score)| {
+                (
+                    *student_id,
+                    self.update_assignment_score_with_options(
+                        client,
+                        assignment_id,
+                        *student_id,
+                        *score,
+                        options,
+                    ),
+                )
+            })
+            .collect()
+    }
+
     /// Adds a file comment to a student's assignment submission.
     ///
     /// This function first uploads a file to the Canvas LMS and then attaches it as a comment
@@ -412,6 +907,7 @@ impl Course {
     /// This function returns errors in the following cases:
     /// - Failed connection to Canvas LMS.
     /// - Invalid credentials for the Canvas API.
+    /// - The course is outside its participation dates (access restricted by date).
     ///
     /// The function uses `Canvas::fetch_single_course_with_credentials` to make the API call
     /// and handle authentication, transforming the received JSON data into a `Course` object.
@@ -430,9 +926,43 @@ impl Course {
                 eprintln!("Erro de credenciais: {}", msg);
                 Err(format!("Erro de credenciais: {}", msg).into())
             }
+            CanvasResultSingleCourse::ErrAccessRestricted(course_id) => {
+                eprintln!("Curso {} com acesso restrito por data", course_id);
+                Err(format!("Curso {} com acesso restrito por data", course_id).into())
+            }
         }
     }
 
+    /// Takes an offline snapshot of the course.
+    ///
+    /// Collects students, assignments, submissions (scores, files and comments) and rubrics into
+    /// a single versioned `CourseSnapshot` that can be saved as JSON for archival or analysis.
+    ///
+    /// Example:
+    /// ```
+    /// let snapshot = course.snapshot()?;
+    /// snapshot.save_to_file("course-snapshot.json")?;
+    /// ```
+    pub fn snapshot(&self) -> Result<CourseSnapshot, Box<dyn Error>> {
+        CourseSnapshot::take(self)
+    }
+
+    /// Compares the current roster with the one of a previous snapshot, returning the students
+    /// added and removed since then.
+    ///
+    /// Example:
+    /// ```
+    /// let previous = CourseSnapshot::load_from_file("last-sync.json")?;
+    /// let diff = course.roster_diff(&previous)?;
+    /// for student in &diff.removed {
+    ///     println!("Dropped: {}", student.name);
+    /// }
+    /// ```
+    pub fn roster_diff(&self, previous: &CourseSnapshot) -> Result<RosterDiff, Box<dyn Error>> {
+        let current: Vec<StudentInfo> = self.fetch_students()?.iter().map(|s| (*s.info).clone()).collect();
+        Ok(RosterDiff::between(&previous.students, &current))
+    }
+
     // Retrieves a specific assignment from the course based on the assignment ID.
     ///
     /// This method makes an API call to fetch the details of a particular assignment in the course
@@ -0,0 +1,193 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::connection::{send_http_request, send_multipart_upload, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::multipart::Form;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::path::Path;
+
+/// A file stored in the files area of a course.
+///
+/// Fields:
+/// - `id`: ID of the file.
+/// - `course_id`: Course the file belongs to.
+/// - `display_name`: Name shown in Canvas.
+/// - `filename`: Name of the uploaded file.
+/// - `content_type`: MIME type of the file.
+/// - `size`: Size in bytes.
+/// - `folder_id`: Folder holding the file.
+/// - `url`: Download URL given by Canvas.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CourseFile {
+    pub id: u64,
+    #[serde(skip_deserializing)]
+    pub course_id: u64,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub filename: String,
+    #[serde(default, rename = "content-type")]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub folder_id: Option<u64>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl CourseFile {
+    /// Page of the file in the Canvas web interface.
+    pub fn html_url(&self, canvas_info: &CanvasCredentials) -> String {
+        format!("{}/courses/{}/files/{}", canvas_info.web_url(), self.course_id, self.id)
+    }
+
+    /// Returns `true` for images, which are embedded inline rather than linked.
+    pub fn is_image(&self) -> bool {
+        self.content_type.as_deref().is_some_and(|content_type| content_type.starts_with("image/"))
+    }
+
+    /// HTML snippet embedding the file in an assignment description or a page, the way the rich
+    /// content editor of Canvas does: an `<img>` for images, a file link (with inline preview)
+    /// for other files.
+    ///
+    /// Example:
+    /// ```
+    /// let figure = course.upload_file(&client, "figures/circuit.png", Some("assignments"))?;
+    /// let description = format!("<p>Analyze the circuit below.</p>{}", figure.embed_html(&canvas_info));
+    /// ```
+    pub fn embed_html(&self, canvas_info: &CanvasCredentials) -> String {
+        let name = escape_html(&self.display_name);
+        let url = self.html_url(canvas_info);
+        if self.is_image() {
+            format!(r#"<img src="{}/preview" alt="{}" data-api-returntype="File">"#, url, name)
+        } else {
+            format!(
+                r#"<a class="instructure_file_link instructure_scribd_file" title="{}" href="{}/download?wrap=1" data-api-returntype="File">{}</a>"#,
+                name, url, name
+            )
+        }
+    }
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Uploads a file to the files area of a course.
+///
+/// Follows the three steps of the Canvas upload protocol: announces the file, sends its content
+/// to the storage URL given by Canvas, then reads the created file. A file with the same name in
+/// the folder is kept, and the new one renamed.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `file_path`: Local file to upload.
+/// - `folder`: Folder path in the course files (e.g. `"assignments/figures"`), created if needed.
+///   The root folder is used when `None`.
+///
+/// Returns:
+/// - `Result<CourseFile, Box<dyn Error>>`: The uploaded file or an error.
+pub fn upload_course_file(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    file_path: &str,
+    folder: Option<&str>,
+) -> Result<CourseFile, Box<dyn Error>> {
+    let mut body = json!({"on_duplicate": "rename"});
+    if let Some(folder) = folder {
+        body["parent_folder_path"] = json!(folder);
+    }
+    let url = format!("{}/courses/{}/files", canvas_info.url_canvas, course_id);
+    let json = upload_file_to(client, canvas_info, &url, file_path, body)?;
+    let mut file: CourseFile = serde_json::from_value(json)?;
+    file.course_id = course_id;
+    Ok(file)
+}
+
+/// Sends a file with the three steps of the Canvas upload protocol to the upload endpoint `url`
+/// (course files, submission files...). `body` holds the options of the endpoint; the name and
+/// size of the file are added to it.
+///
+/// Returns:
+/// - `Result<Value, Box<dyn Error>>`: The created file, as sent by Canvas, or an error.
+pub(crate) fn upload_file_to(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    file_path: &str,
+    mut body: Value,
+) -> Result<Value, Box<dyn Error>> {
+    let file_name = Path::new(file_path)
+        .file_name()
+        .and_then(std::ffi::OsStr::to_str)
+        .ok_or("Invalid file name")?;
+    let file_size = std::fs::metadata(file_path)?.len();
+    body["name"] = json!(file_name);
+    body["size"] = json!(file_size);
+
+    // Step 1: announce the file
+    let response = send_http_request(client, HttpMethod::Post(body), url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to request file upload with status: {}", response.status()).into());
+    }
+    let token: Value = response.json()?;
+    let upload_url = token["upload_url"].as_str().ok_or("Missing upload_url")?;
+
+    // Step 2: send the content to the storage
+    let mut form = Form::new();
+    for (key, value) in token["upload_params"].as_object().ok_or("Missing upload_params")? {
+        form = form.text(key.clone(), value.as_str().ok_or("Invalid param value")?.to_string());
+    }
+    form = form.file("file", file_path)?;
+    let summary = json!({ "name": file_name, "size": file_size });
+    let response = send_multipart_upload(client, canvas_info, upload_url, form, summary)?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to upload file with status: {}", response.status()).into());
+    }
+    let uploaded: Value = response.json()?;
+
+    // Step 3: the storage answers with the file, or with where to confirm it
+    match uploaded["location"].as_str() {
+        Some(location) if uploaded["id"].is_null() => fetch_json(client, canvas_info, location),
+        _ => Ok(uploaded),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_embed_html_for_images_and_documents() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: String::new(),
+        };
+        let mut file: CourseFile = serde_json::from_value(json!({
+            "id": 900,
+            "display_name": "circuit \"A\".png",
+            "filename": "circuit.png",
+            "content-type": "image/png",
+            "size": 1024
+        }))
+        .unwrap();
+        file.course_id = 7;
+        assert_eq!(
+            file.embed_html(&canvas_info),
+            r#"<img src="https://canvas.example/courses/7/files/900/preview" alt="circuit &quot;A&quot;.png" data-api-returntype="File">"#
+        );
+
+        file.content_type = Some("application/pdf".to_string());
+        file.display_name = "enunciado.pdf".to_string();
+        assert!(file
+            .embed_html(&canvas_info)
+            .contains(r#"href="https://canvas.example/courses/7/files/900/download?wrap=1""#));
+    }
+}
@@ -0,0 +1,164 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+/// Settings of a course (`/courses/:id/settings`), as shown in the "Course Details" page.
+///
+/// Every field is optional: when fetched, Canvas fills the ones it knows; when updating, only the
+/// fields set to `Some` are sent, so the other settings of the course are left untouched.
+///
+/// Fields:
+/// - `allow_student_discussion_topics`: Students can create discussion topics.
+/// - `allow_student_forum_attachments`: Students can attach files to discussions.
+/// - `allow_student_discussion_editing`: Students can edit or delete their own discussion replies.
+/// - `allow_student_discussion_reporting`: Students can report discussion replies.
+/// - `allow_anonymous_discussions`: Students can create anonymous discussions.
+/// - `allow_student_organized_groups`: Students can create their own groups.
+/// - `allow_final_grade_override`: Instructors can override the final grade of students in the
+///   gradebook (see `Enrollment::set_final_grade_override`).
+/// - `filter_speed_grader_by_student_group`: SpeedGrader only shows the students of one group at a time.
+/// - `grading_standard_enabled` / `grading_standard_id`: Letter grade scheme of the course.
+/// - `hide_final_grades`: Hides the totals in the student grades summary.
+/// - `hide_distribution_graphs`: Hides the grade distribution graphs from students.
+/// - `hide_sections_on_course_users_page`: Hides the sections column of the People page.
+/// - `lock_all_announcements`: Students cannot comment on announcements.
+/// - `usage_rights_required`: Copyright and license information must be set before publishing files.
+/// - `restrict_student_past_view` / `restrict_student_future_view`: Students cannot see the course
+///   after its end date / before its start date.
+/// - `show_announcements_on_home_page` / `home_page_announcement_limit`: Recent announcements
+///   shown on the home page, and how many.
+/// - `syllabus_course_summary`: Shows the course summary in the syllabus.
+/// - `default_due_time`: Due time given to new assignments, as `HH:MM:SS`.
+///
+/// Example:
+/// ```
+/// let settings = CourseSettings {
+///     hide_distribution_graphs: Some(true),
+///     allow_student_discussion_topics: Some(false),
+///     ..Default::default()
+/// };
+/// for course in &courses {
+///     course.update_settings(&client, &settings)?;
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CourseSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_student_discussion_topics: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_student_forum_attachments: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_student_discussion_editing: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_student_discussion_reporting: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_anonymous_discussions: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_student_organized_groups: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow_final_grade_override: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub filter_speed_grader_by_student_group: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_standard_enabled: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub grading_standard_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_final_grades: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_distribution_graphs: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hide_sections_on_course_users_page: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_all_announcements: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage_rights_required: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restrict_student_past_view: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub restrict_student_future_view: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_announcements_on_home_page: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home_page_announcement_limit: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub syllabus_course_summary: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_due_time: Option<String>,
+}
+
+fn settings_url(canvas_info: &CanvasCredentials, course_id: u64) -> String {
+    format!("{}/courses/{}/settings", canvas_info.url_canvas, course_id)
+}
+
+/// Retrieves the settings of a course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+///
+/// Returns:
+/// - `Result<CourseSettings, Box<dyn Error>>`: The settings or an error.
+pub fn fetch_course_settings(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<CourseSettings, Box<dyn Error>> {
+    fetch_json(client, canvas_info, &settings_url(canvas_info, course_id))
+}
+
+/// Changes the settings of a course. Only the fields of `settings` set to `Some` are changed.
+///
+/// Returns:
+/// - `Result<CourseSettings, Box<dyn Error>>`: Every setting of the course after the update, or
+///   an error.
+pub fn update_course_settings(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    settings: &CourseSettings,
+) -> Result<CourseSettings, Box<dyn Error>> {
+    let body = serde_json::to_value(settings)?;
+    let url = settings_url(canvas_info, course_id);
+    let response = send_http_request(client, HttpMethod::Put(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update course settings with status: {}", response.status()).into());
+    }
+    let json: Value = response.json()?;
+    Ok(serde_json::from_value(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_only_set_fields_are_sent() {
+        let settings = CourseSettings {
+            hide_distribution_graphs: Some(true),
+            filter_speed_grader_by_student_group: Some(false),
+            ..Default::default()
+        };
+        assert_eq!(
+            serde_json::to_value(&settings).unwrap(),
+            json!({"hide_distribution_graphs": true, "filter_speed_grader_by_student_group": false})
+        );
+
+        let fetched: CourseSettings = serde_json::from_value(json!({
+            "allow_student_discussion_topics": true,
+            "home_page_announcement_limit": 3,
+            "some_new_setting": "kept by Canvas"
+        }))
+        .unwrap();
+        assert_eq!(fetched.allow_student_discussion_topics, Some(true));
+        assert_eq!(fetched.home_page_announcement_limit, Some(3));
+        assert_eq!(fetched.hide_final_grades, None);
+    }
+}
@@ -1,7 +1,12 @@
 // Import necessary crates and modules
+#[cfg(feature = "keyring")]
 use keyring::Entry;
+use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
 use std::process::exit;
+use std::sync::RwLock;
 
 /// Structure to hold Canvas API credentials.
 ///
@@ -24,6 +29,188 @@ pub struct CanvasCredentials {
     pub token_canvas: String,
 }
 
+/// Canvas environment targeted by a set of credentials.
+///
+/// Instructure provides two mirrors of every production instance: `beta` (refreshed weekly,
+/// used to preview upcoming releases) and `test` (refreshed every few weeks). Both accept the
+/// same tokens as production at the time of the refresh and are the right place to run
+/// destructive scripts before pointing them at real courses.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum CanvasEnvironment {
+    Production,
+    Beta,
+    Test,
+}
+
+impl CanvasEnvironment {
+    // Host label used by the environment's mirror, if any.
+    fn host_label(&self) -> Option<&'static str> {
+        match self {
+            CanvasEnvironment::Production => None,
+            CanvasEnvironment::Beta => Some("beta"),
+            CanvasEnvironment::Test => Some("test"),
+        }
+    }
+}
+
+/// Identity of the user owning a token, as returned by `CanvasCredentials::validate`.
+///
+/// Fields:
+/// - `id`: Canvas ID of the user.
+/// - `name`: Full name.
+/// - `short_name`: Display name.
+/// - `sortable_name`: Name in "Last, First" form.
+/// - `avatar_url`: URL of the profile picture.
+/// - `locale`: Language chosen by the user, if any.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UserIdentity {
+    pub id: u64,
+    pub name: String,
+    pub short_name: Option<String>,
+    pub sortable_name: Option<String>,
+    pub avatar_url: Option<String>,
+    pub locale: Option<String>,
+}
+
+/// Reason why a set of credentials could not be validated.
+///
+/// - `InvalidToken`: The token is unknown, expired or revoked (HTTP 401).
+/// - `Forbidden`: The token is valid but not allowed to read its own user (HTTP 403).
+/// - `NotFound`: The URL does not point to a Canvas API (HTTP 404).
+/// - `Http`: Any other unsuccessful status.
+/// - `Network`: No answer was received (DNS, TLS, timeout...).
+/// - `InvalidResponse`: The answer is not a Canvas user (e.g. the URL lacks `/api/v1`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialError {
+    InvalidToken,
+    Forbidden,
+    NotFound,
+    Http(u16),
+    Network(String),
+    InvalidResponse(String),
+}
+
+impl CredentialError {
+    /// Classifies an unsuccessful HTTP status.
+    pub fn from_status(status: u16) -> CredentialError {
+        match status {
+            401 => CredentialError::InvalidToken,
+            403 => CredentialError::Forbidden,
+            404 => CredentialError::NotFound,
+            _ => CredentialError::Http(status),
+        }
+    }
+
+    /// HTTP status behind the error, or `0` when no status was received.
+    pub fn status(&self) -> u16 {
+        match self {
+            CredentialError::InvalidToken => 401,
+            CredentialError::Forbidden => 403,
+            CredentialError::NotFound => 404,
+            CredentialError::Http(status) => *status,
+            CredentialError::Network(_) => 0,
+            CredentialError::InvalidResponse(_) => 200,
+        }
+    }
+}
+
+impl fmt::Display for CredentialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialError::InvalidToken => write!(f, "Invalid, expired or revoked Canvas token"),
+            CredentialError::Forbidden => write!(f, "Canvas token not allowed to read its user"),
+            CredentialError::NotFound => write!(f, "Canvas API not found at this URL"),
+            CredentialError::Http(status) => write!(f, "Canvas API answered with status {}", status),
+            CredentialError::Network(message) => write!(f, "Could not reach Canvas: {}", message),
+            CredentialError::InvalidResponse(message) => write!(f, "Unexpected answer from Canvas: {}", message),
+        }
+    }
+}
+
+impl Error for CredentialError {}
+
+// Service name of the keyring entries. `None` means the crate name.
+lazy_static! {
+    static ref KEYRING_SERVICE: RwLock<Option<String>> = RwLock::new(None);
+}
+
+/// Sets the service name under which the credentials are stored in the system keyring, or
+/// restores the default (the connector's crate name) with `None`.
+///
+/// Applications using this crate share the same keyring entries by default; set a name of your
+/// own before calling `CanvasCredentials::credentials` to keep their tokens apart.
+///
+/// Example:
+/// ```
+/// set_keyring_service(Some("br.pucpr.autograder"));
+/// let credentials = CanvasCredentials::credentials();
+/// ```
+pub fn set_keyring_service(service: Option<&str>) {
+    *KEYRING_SERVICE.write().unwrap() = service.map(str::to_string);
+}
+
+/// Returns the service name currently used for the keyring entries.
+pub fn keyring_service() -> String {
+    KEYRING_SERVICE
+        .read()
+        .unwrap()
+        .clone()
+        .unwrap_or_else(|| env!("CARGO_PKG_NAME").to_string())
+}
+
+/// Names of the environment variables holding the credentials (feature `use_env_credentials`).
+///
+/// Example:
+/// ```
+/// // Reads MYAPP_CANVAS_URL and MYAPP_CANVAS_TOKEN
+/// set_env_var_names(EnvVarNames::with_prefix("MYAPP"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct EnvVarNames {
+    pub url: String,
+    pub token: String,
+}
+
+impl Default for EnvVarNames {
+    fn default() -> Self {
+        EnvVarNames {
+            url: "CANVAS_URL".to_string(),
+            token: "CANVAS_TOKEN".to_string(),
+        }
+    }
+}
+
+impl EnvVarNames {
+    /// Default names preceded by `prefix` (an `_` separator is added when missing).
+    pub fn with_prefix(prefix: &str) -> Self {
+        let prefix = if prefix.is_empty() || prefix.ends_with('_') {
+            prefix.to_string()
+        } else {
+            format!("{}_", prefix)
+        };
+        let defaults = EnvVarNames::default();
+        EnvVarNames {
+            url: format!("{}{}", prefix, defaults.url),
+            token: format!("{}{}", prefix, defaults.token),
+        }
+    }
+}
+
+// Environment variable names used by `load_credentials_from_env`.
+lazy_static! {
+    static ref ENV_VAR_NAMES: RwLock<EnvVarNames> = RwLock::new(EnvVarNames::default());
+}
+
+/// Replaces the names of the environment variables read by `load_credentials_from_env`.
+pub fn set_env_var_names(names: EnvVarNames) {
+    *ENV_VAR_NAMES.write().unwrap() = names;
+}
+
+/// Returns the names of the environment variables currently used for the credentials.
+pub fn env_var_names() -> EnvVarNames {
+    ENV_VAR_NAMES.read().unwrap().clone()
+}
+
 // Enum to represent the source of Canvas credentials.
 enum CanvasCredentialType {
     None,                      // No credentials available
@@ -32,6 +219,114 @@ enum CanvasCredentialType {
 }
 
 impl CanvasCredentials {
+    /// Returns the environment the credentials point to, based on the URL host.
+    ///
+    /// Example:
+    /// ```
+    /// let credentials = CanvasCredentials {
+    ///     url_canvas: "https://school.beta.instructure.com/api/v1".to_string(),
+    ///     token_canvas: "token".to_string(),
+    /// };
+    /// assert_eq!(credentials.environment(), CanvasEnvironment::Beta);
+    /// ```
+    pub fn environment(&self) -> CanvasEnvironment {
+        let (_, host, _) = Self::split_url(&self.url_canvas);
+        let labels: Vec<&str> = host.split('.').collect();
+        if labels.iter().skip(1).any(|label| *label == "beta") {
+            CanvasEnvironment::Beta
+        } else if labels.iter().skip(1).any(|label| *label == "test") {
+            CanvasEnvironment::Test
+        } else {
+            CanvasEnvironment::Production
+        }
+    }
+
+    /// Returns a copy of the credentials pointing to the given environment.
+    ///
+    /// The environment label is inserted right after the first label of the host
+    /// (`school.instructure.com` becomes `school.beta.instructure.com`), replacing any
+    /// existing `beta`/`test` label. The token is kept unchanged.
+    pub fn to_environment(&self, environment: CanvasEnvironment) -> CanvasCredentials {
+        let (scheme, host, path) = Self::split_url(&self.url_canvas);
+        let mut labels: Vec<&str> = host
+            .split('.')
+            .enumerate()
+            .filter(|(i, label)| *i == 0 || (*label != "beta" && *label != "test"))
+            .map(|(_, label)| label)
+            .collect();
+        if let Some(label) = environment.host_label() {
+            if labels.len() > 1 {
+                labels.insert(1, label);
+            }
+        }
+        CanvasCredentials {
+            url_canvas: format!("{}{}{}", scheme, labels.join("."), path),
+            token_canvas: self.token_canvas.clone(),
+        }
+    }
+
+    /// Returns a copy of the credentials pointing to the beta mirror.
+    pub fn to_beta(&self) -> CanvasCredentials {
+        self.to_environment(CanvasEnvironment::Beta)
+    }
+
+    /// Returns a copy of the credentials pointing to the test mirror.
+    pub fn to_test(&self) -> CanvasCredentials {
+        self.to_environment(CanvasEnvironment::Test)
+    }
+
+    /// Returns a copy of the credentials pointing to production.
+    pub fn to_production(&self) -> CanvasCredentials {
+        self.to_environment(CanvasEnvironment::Production)
+    }
+
+    // Splits a URL into (scheme with "://", host, remaining path).
+    fn split_url(url: &str) -> (&str, &str, &str) {
+        let (scheme, rest) = match url.find("://") {
+            Some(index) => url.split_at(index + 3),
+            None => ("", url),
+        };
+        match rest.find('/') {
+            Some(index) => {
+                let (host, path) = rest.split_at(index);
+                (scheme, host, path)
+            }
+            None => (scheme, rest, ""),
+        }
+    }
+
+    /// Checks the credentials against the Canvas API without exiting the process.
+    ///
+    /// Performs a GET request to `/users/self`, so applications can handle expired tokens or
+    /// wrong URLs with their own UX.
+    ///
+    /// Returns:
+    /// - `Ok(UserIdentity)`: The user owning the token.
+    /// - `Err(CredentialError)`: Why the credentials are not usable.
+    ///
+    /// Example:
+    /// ```
+    /// match credentials.validate() {
+    ///     Ok(user) => println!("Connected as {}", user.name),
+    ///     Err(CredentialError::InvalidToken) => println!("Your token expired, please create a new one"),
+    ///     Err(e) => eprintln!("{}", e),
+    /// }
+    /// ```
+    pub fn validate(&self) -> Result<UserIdentity, CredentialError> {
+        let client = reqwest::blocking::Client::new();
+        let response = client
+            .get(format!("{}/users/self", self.url_canvas))
+            .header("Authorization", format!("Bearer {}", self.token_canvas))
+            .send()
+            .map_err(|e| CredentialError::Network(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(CredentialError::from_status(response.status().as_u16()));
+        }
+        response
+            .json()
+            .map_err(|e| CredentialError::InvalidResponse(e.to_string()))
+    }
+
     /// Tests the validity of Canvas API credentials.
     ///
     /// Performs a GET request to the Canvas API to verify if the provided credentials are valid.
@@ -44,26 +339,13 @@ impl CanvasCredentials {
     /// - `Ok(200)`: If credentials are valid.
     /// - `Err(u16)`: The HTTP status code if credentials are invalid or any network error (0 for generic errors).
     fn test_canvas_credentials(api_url: &str, access_token: &str) -> Result<u16, u16> {
-        // Create a blocking HTTP client
-        let client = reqwest::blocking::Client::new();
-        // Attempt to perform a GET request to the Canvas API
-        let res = client
-            .get(format!("{}/users/self", api_url))
-            .header("Authorization", format!("Bearer {}", access_token))
-            .send();
-
-        // Handle the response
-        match res {
-            Ok(response) => {
-                // Check if the response status is successful
-                if response.status().is_success() {
-                    Ok(200)
-                } else {
-                    Err(response.status().as_u16())
-                }
-            }
-            // Handle any network or client errors
-            Err(_) => Err(0),
+        let credentials = CanvasCredentials {
+            url_canvas: api_url.to_string(),
+            token_canvas: access_token.to_string(),
+        };
+        match credentials.validate() {
+            Ok(_) => Ok(200),
+            Err(e) => Err(e.status()),
         }
     }
 
@@ -78,9 +360,14 @@ impl CanvasCredentials {
 
         #[cfg(feature = "use_env_credentials")]
         {
+            // Variables of a `.env` file complete the environment (existing ones are kept)
+            #[cfg(feature = "dotenv")]
+            dotenvy::dotenv().ok();
+
             // Environment variables are used to store the credentials
-            match std::env::var("CANVAS_URL") {
-                Ok(url) => match std::env::var("CANVAS_TOKEN") {
+            let names = env_var_names();
+            match std::env::var(&names.url) {
+                Ok(url) => match std::env::var(&names.token) {
                     Ok(token) => {
                         println!("Credentials loaded from environment! -> {}", url);
                         Ok(CanvasCredentials {
@@ -103,28 +390,38 @@ impl CanvasCredentials {
     /// - `Ok(CanvasCredentials)`: Credentials if successfully retrieved.
     /// - `Err(String)`: Error message if issues occur accessing the keyring or retrieving credentials.
     pub fn load_credentials_from_system() -> Result<CanvasCredentials, String> {
-        let app_name = env!("CARGO_PKG_NAME");
-        // Retrieve the URL from the keyring
-        match Entry::new(app_name, "URL_CANVAS") {
-            Ok(entry) => {
-                match entry.get_password() {
-                    Ok(url) => {
-                        // Retrieve the token from the keyring
-                        match Entry::new(app_name, "TOKEN_CANVAS") {
-                            Ok(entry) => match entry.get_password() {
-                                Ok(token) => Ok(CanvasCredentials {
-                                    url_canvas: url,
-                                    token_canvas: token,
-                                }),
+        // Check if the keyring backend is compiled in
+        #[cfg(not(feature = "keyring"))]
+        {
+            Err("Feature not enabled".to_string())
+        }
+
+        #[cfg(feature = "keyring")]
+        {
+            let service = keyring_service();
+            let app_name = service.as_str();
+            // Retrieve the URL from the keyring
+            match Entry::new(app_name, "URL_CANVAS") {
+                Ok(entry) => {
+                    match entry.get_password() {
+                        Ok(url) => {
+                            // Retrieve the token from the keyring
+                            match Entry::new(app_name, "TOKEN_CANVAS") {
+                                Ok(entry) => match entry.get_password() {
+                                    Ok(token) => Ok(CanvasCredentials {
+                                        url_canvas: url,
+                                        token_canvas: token,
+                                    }),
+                                    Err(_) => Err("Error retrieving token from system".to_string()),
+                                },
                                 Err(_) => Err("Error retrieving token from system".to_string()),
-                            },
-                            Err(_) => Err("Error retrieving token from system".to_string()),
+                            }
                         }
+                        Err(_) => Err("Error retrieving URL from system".to_string()),
                     }
-                    Err(_) => Err("Error retrieving URL from system".to_string()),
                 }
+                Err(_) => Err("Error retrieving URL from system".to_string()),
             }
-            Err(_) => Err("Error retrieving URL from system".to_string()),
         }
     }
 
@@ -149,6 +446,13 @@ impl CanvasCredentials {
         }
     }
 
+    // Without the keyring backend there is nowhere to store credentials entered by the user.
+    #[cfg(not(feature = "keyring"))]
+    fn set_system_credentials() -> CanvasCredentialType {
+        eprintln!("Keyring support is not enabled: provide the credentials through the environment");
+        CanvasCredentialType::None
+    }
+
     /// Interactively sets and stores Canvas credentials in the system's keyring.
     ///
     /// This function prompts the user to manually enter the Canvas API credentials and stores
@@ -156,8 +460,10 @@ impl CanvasCredentials {
     ///
     /// Returns:
     /// - `CanvasCredentialType`: Enum variant indicating the stored credential type.
+    #[cfg(feature = "keyring")]
     fn set_system_credentials() -> CanvasCredentialType {
-        let app_name = env!("CARGO_PKG_NAME");
+        let service = keyring_service();
+        let app_name = service.as_str();
         loop {
             // Prompt user to enter credentials
             println!("Do you wish to register the credentials? (y/n)");
@@ -267,6 +573,60 @@ mod tests {
         assert_eq!(credentials.token_canvas, "secret-token");
     }
 
+    #[test]
+    fn test_keyring_service_override() {
+        assert_eq!(keyring_service(), "canvas_lms_connector");
+        set_keyring_service(Some("br.pucpr.autograder"));
+        assert_eq!(keyring_service(), "br.pucpr.autograder");
+        set_keyring_service(None);
+        assert_eq!(keyring_service(), "canvas_lms_connector");
+    }
+
+    #[test]
+    fn test_env_var_names_with_prefix() {
+        assert_eq!(EnvVarNames::default().url, "CANVAS_URL");
+        let names = EnvVarNames::with_prefix("MYAPP");
+        assert_eq!(names.url, "MYAPP_CANVAS_URL");
+        assert_eq!(names.token, "MYAPP_CANVAS_TOKEN");
+        assert_eq!(EnvVarNames::with_prefix("MYAPP_"), names);
+        assert_eq!(EnvVarNames::with_prefix(""), EnvVarNames::default());
+    }
+
+    #[test]
+    fn test_credential_error_classification() {
+        assert_eq!(CredentialError::from_status(401), CredentialError::InvalidToken);
+        assert_eq!(CredentialError::from_status(404), CredentialError::NotFound);
+        assert_eq!(CredentialError::from_status(500), CredentialError::Http(500));
+        assert_eq!(CredentialError::Network("timeout".to_string()).status(), 0);
+        assert_eq!(CredentialError::Forbidden.status(), 403);
+    }
+
+    #[test]
+    fn test_environment_switching() {
+        let production = CanvasCredentials {
+            url_canvas: "https://pucpr.instructure.com/api/v1".to_string(),
+            token_canvas: "secret-token".to_string(),
+        };
+        assert_eq!(production.environment(), CanvasEnvironment::Production);
+
+        let beta = production.to_beta();
+        assert_eq!(beta.url_canvas, "https://pucpr.beta.instructure.com/api/v1");
+        assert_eq!(beta.token_canvas, "secret-token");
+        assert_eq!(beta.environment(), CanvasEnvironment::Beta);
+
+        let test = beta.to_test();
+        assert_eq!(test.url_canvas, "https://pucpr.test.instructure.com/api/v1");
+        assert_eq!(test.environment(), CanvasEnvironment::Test);
+
+        assert_eq!(test.to_production(), production);
+
+        let custom = CanvasCredentials {
+            url_canvas: "https://canvas.university.edu/api/v1".to_string(),
+            token_canvas: String::new(),
+        };
+        assert_eq!(custom.to_beta().url_canvas, "https://canvas.beta.university.edu/api/v1");
+    }
+
     #[test]
     #[cfg(feature = "use_env_credentials")]
     fn test_load_credentials_from_env() {
@@ -0,0 +1,113 @@
+// Import necessary crates and modules
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::error::Error;
+
+// Builds the URL of a custom data scope of the authenticated user. The scope is a `/`-separated
+// path inside the namespace (e.g. `notes/course_7/student_42`); an empty scope is the whole namespace.
+fn custom_data_url(canvas_info: &CanvasCredentials, scope: &str) -> String {
+    let scope = scope.trim_matches('/');
+    if scope.is_empty() {
+        format!("{}/users/self/custom_data", canvas_info.url_canvas)
+    } else {
+        format!("{}/users/self/custom_data/{}", canvas_info.url_canvas, scope)
+    }
+}
+
+// Sends a custom data request and extracts the `data` member of the answer.
+fn send_custom_data_request(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    method: HttpMethod,
+    url: &str,
+    namespace: &str,
+) -> Result<Value, Box<dyn Error>> {
+    let params = vec![("ns".to_string(), namespace.to_string())];
+    let response = send_http_request(client, method, url, canvas_info, params)?;
+    if !response.status().is_success() {
+        return Err(format!("Custom data request failed with status: {}", response.status()).into());
+    }
+    let mut json: Value = response.json()?;
+    Ok(json["data"].take())
+}
+
+/// Reads the custom data stored by the authenticated user under a namespace and scope.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `namespace`: Namespace of the application, usually a reverse domain (`br.edu.example.grader`).
+/// - `scope`: Path of the data inside the namespace.
+///
+/// Returns:
+/// - `Result<T, Box<dyn Error>>`: The stored data, or an error (also when nothing is stored there).
+///
+/// Example:
+/// ```
+/// let notes: Vec<String> = fetch_custom_data(&client, &canvas_info, NS, "notes/student_42")?;
+/// ```
+pub fn fetch_custom_data<T: DeserializeOwned>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    namespace: &str,
+    scope: &str,
+) -> Result<T, Box<dyn Error>> {
+    let url = custom_data_url(canvas_info, scope);
+    let data = send_custom_data_request(client, canvas_info, HttpMethod::Get, &url, namespace)?;
+    Ok(serde_json::from_value(data)?)
+}
+
+/// Stores data under a namespace and scope, replacing what was there.
+///
+/// Returns:
+/// - `Result<Value, Box<dyn Error>>`: The data as stored by Canvas or an error.
+pub fn set_custom_data<T: Serialize>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    namespace: &str,
+    scope: &str,
+    data: &T,
+) -> Result<Value, Box<dyn Error>> {
+    let url = custom_data_url(canvas_info, scope);
+    let body = json!({ "ns": namespace, "data": data });
+    send_custom_data_request(client, canvas_info, HttpMethod::Put(body), &url, namespace)
+}
+
+/// Deletes the data stored under a namespace and scope (and every scope below it).
+///
+/// Returns:
+/// - `Result<Value, Box<dyn Error>>`: The deleted data or an error.
+pub fn delete_custom_data(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    namespace: &str,
+    scope: &str,
+) -> Result<Value, Box<dyn Error>> {
+    let url = custom_data_url(canvas_info, scope);
+    send_custom_data_request(client, canvas_info, HttpMethod::Delete, &url, namespace)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_data_url() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            ..Default::default()
+        };
+        assert_eq!(
+            custom_data_url(&canvas_info, "/notes/student_42/"),
+            "https://canvas.example/api/v1/users/self/custom_data/notes/student_42"
+        );
+        assert_eq!(
+            custom_data_url(&canvas_info, ""),
+            "https://canvas.example/api/v1/users/self/custom_data"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Personal label the authenticated user gave to a course.
+///
+/// Fields:
+/// - `course_id`: ID of the course.
+/// - `name`: Official name of the course.
+/// - `nickname`: Name shown to the user in the dashboard and course menus.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CourseNickname {
+    pub course_id: u64,
+    pub name: String,
+    pub nickname: Option<String>,
+}
+
+// Sends a request to the nicknames endpoints and checks the status.
+fn nickname_request(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    method: HttpMethod,
+    path: &str,
+    params: Vec<(String, String)>,
+) -> Result<Value, Box<dyn Error>> {
+    let url = format!("{}/users/self/course_nicknames{}", canvas_info.url_canvas, path);
+    let response = send_http_request(client, method, &url, canvas_info, params)?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update course nickname with status: {}", response.status()).into());
+    }
+    Ok(response.json().unwrap_or(Value::Null))
+}
+
+/// Retrieves every course nickname of the authenticated user.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+///
+/// Returns:
+/// - `Result<Vec<CourseNickname>, Box<dyn Error>>`: The nicknames or an error.
+pub fn fetch_course_nicknames(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+) -> Result<Vec<CourseNickname>, Box<dyn Error>> {
+    let url = format!("{}/users/self/course_nicknames", canvas_info.url_canvas);
+    fetch_json(client, canvas_info, &url)
+}
+
+/// Retrieves the nickname of one course. Returns `None` when the user didn't set one.
+pub fn fetch_course_nickname(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<Option<String>, Box<dyn Error>> {
+    let url = format!("{}/users/self/course_nicknames/{}", canvas_info.url_canvas, course_id);
+    let nickname: CourseNickname = fetch_json(client, canvas_info, &url)?;
+    Ok(nickname.nickname)
+}
+
+/// Sets the nickname of a course for the authenticated user.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `nickname`: New label (at most 59 characters, as enforced by Canvas).
+///
+/// Returns:
+/// - `Result<CourseNickname, Box<dyn Error>>`: The stored nickname or an error.
+pub fn set_course_nickname(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    nickname: &str,
+) -> Result<CourseNickname, Box<dyn Error>> {
+    let body = json!({ "nickname": nickname });
+    let json = nickname_request(client, canvas_info, HttpMethod::Put(body), &format!("/{}", course_id), vec![])?;
+    Ok(serde_json::from_value(json)?)
+}
+
+/// Removes the nickname of a course, restoring its official name.
+pub fn remove_course_nickname(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<(), Box<dyn Error>> {
+    nickname_request(client, canvas_info, HttpMethod::Delete, &format!("/{}", course_id), vec![])?;
+    Ok(())
+}
+
+/// Removes every course nickname of the authenticated user.
+pub fn clear_course_nicknames(client: &Client, canvas_info: &CanvasCredentials) -> Result<(), Box<dyn Error>> {
+    nickname_request(client, canvas_info, HttpMethod::Delete, "", vec![])?;
+    Ok(())
+}
+
+/// Retrieves the positions of the course cards in the dashboard of the authenticated user,
+/// keyed by course ID (lower positions come first).
+pub fn fetch_dashboard_positions(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+) -> Result<HashMap<u64, i64>, Box<dyn Error>> {
+    let url = format!("{}/users/self/dashboard_positions", canvas_info.url_canvas);
+    let json: Value = fetch_json(client, canvas_info, &url)?;
+    Ok(parse_dashboard_positions(&json["dashboard_positions"]))
+}
+
+/// Sets the positions of course cards in the dashboard of the authenticated user. Courses not
+/// listed keep their position.
+pub fn set_dashboard_positions(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    positions: &HashMap<u64, i64>,
+) -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/users/self/dashboard_positions", canvas_info.url_canvas);
+    let positions: Map<String, Value> = positions
+        .iter()
+        .map(|(course_id, position)| (format!("course_{}", course_id), json!(position)))
+        .collect();
+    let body = json!({ "dashboard_positions": positions });
+    let response = send_http_request(client, HttpMethod::Put(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update dashboard positions with status: {}", response.status()).into());
+    }
+    Ok(())
+}
+
+// Reads `{"course_123": 0, "group_9": 1}` keeping only courses.
+fn parse_dashboard_positions(json: &Value) -> HashMap<u64, i64> {
+    json.as_object()
+        .map(|positions| {
+            positions
+                .iter()
+                .filter_map(|(key, position)| {
+                    let course_id = key.strip_prefix("course_")?.parse().ok()?;
+                    Some((course_id, position.as_i64()?))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_dashboard_positions() {
+        let positions = parse_dashboard_positions(&json!({"course_12": 1, "course_7": 0, "group_3": 2}));
+        assert_eq!(positions.len(), 2);
+        assert_eq!(positions[&7], 0);
+        assert_eq!(positions[&12], 1);
+    }
+}
@@ -0,0 +1,473 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages, fetch_json};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CourseInfo;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Information about a discussion topic of a course.
+///
+/// Fields:
+/// - `id`: ID of the topic.
+/// - `title`: Title of the topic.
+/// - `message`: HTML of the opening post.
+/// - `assignment_id`: ID of the assignment, for graded discussions.
+/// - `read_state`: `read` or `unread`, for the authenticated user.
+/// - `unread_count`: Number of entries the authenticated user hasn't read.
+/// - `subscribed`: Whether the authenticated user is notified of new entries.
+/// - `group_category_id`: Group set of a group discussion (`None` for course-wide topics).
+/// - `group_topic_children`: Copies of a group discussion, one per group.
+/// - `course_info`: The course the topic belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct DiscussionTopicInfo {
+    pub id: u64,
+    pub title: String,
+    pub message: Option<String>,
+    pub assignment_id: Option<u64>,
+    pub read_state: Option<String>,
+    #[serde(default)]
+    pub unread_count: u64,
+    #[serde(default)]
+    pub subscribed: bool,
+    pub group_category_id: Option<u64>,
+    #[serde(default)]
+    pub group_topic_children: Vec<GroupTopicChild>,
+    #[serde(skip)]
+    pub course_info: Arc<CourseInfo>,
+}
+
+/// The copy of a group discussion that belongs to one group. Students of the group post in this
+/// child topic, not in the course topic.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GroupTopicChild {
+    pub id: u64,
+    pub group_id: u64,
+}
+
+/// A discussion topic of a course. See `Course::fetch_discussion_topics`.
+///
+/// Read state and subscriptions belong to the user making the request. Methods that change them
+/// take an `as_user` argument: pass a student ID to act on their behalf (requires the
+/// "become other users" permission), or `None` for the authenticated user.
+#[derive(Debug, Clone)]
+pub struct DiscussionTopic {
+    pub info: Arc<DiscussionTopicInfo>,
+}
+
+/// A participant of a discussion, as listed by `DiscussionTopic::full_view`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DiscussionParticipant {
+    pub id: u64,
+    pub display_name: Option<String>,
+}
+
+/// A file attached to a discussion entry.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DiscussionAttachment {
+    pub id: u64,
+    pub display_name: Option<String>,
+    pub url: Option<String>,
+    #[serde(rename = "content-type", alias = "content_type")]
+    pub content_type: Option<String>,
+}
+
+/// An entry (post or reply) of a discussion, with its replies.
+///
+/// Fields:
+/// - `id`: ID of the entry.
+/// - `user_id`: Author (absent for deleted entries).
+/// - `parent_id`: Entry this one replies to (`None` for top-level entries).
+/// - `created_at` / `updated_at`: Timestamps (ISO 8601).
+/// - `message`: HTML of the entry.
+/// - `rating_count` / `rating_sum`: "Likes" received, when ratings are enabled.
+/// - `deleted`: Whether the entry was deleted (its replies are kept).
+/// - `attachments`: Attached files.
+/// - `replies`: Direct replies, in thread order.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DiscussionEntry {
+    pub id: u64,
+    pub user_id: Option<u64>,
+    pub parent_id: Option<u64>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub message: Option<String>,
+    pub rating_count: Option<u64>,
+    pub rating_sum: Option<u64>,
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub attachments: Vec<DiscussionAttachment>,
+    #[serde(default, skip_serializing)]
+    attachment: Option<DiscussionAttachment>,
+    #[serde(default)]
+    pub replies: Vec<DiscussionEntry>,
+}
+
+/// The complete entry tree of a discussion topic.
+///
+/// Fields:
+/// - `participants`: Everyone who posted, with their display names.
+/// - `unread_entries`: IDs of the entries the requesting user hasn't read.
+/// - `view`: Top-level entries, each with its nested replies.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DiscussionFullView {
+    #[serde(default)]
+    pub participants: Vec<DiscussionParticipant>,
+    #[serde(default)]
+    pub unread_entries: Vec<u64>,
+    #[serde(default)]
+    pub view: Vec<DiscussionEntry>,
+}
+
+/// One row of `DiscussionFullView::flatten`: an entry without its replies, plus its position in
+/// the thread.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct FlatDiscussionEntry {
+    pub id: u64,
+    pub parent_id: Option<u64>,
+    pub depth: usize,
+    pub user_id: Option<u64>,
+    pub author_name: Option<String>,
+    pub created_at: Option<String>,
+    pub message: Option<String>,
+    pub rating_sum: u64,
+    pub attachment_count: usize,
+    pub reply_count: usize,
+    pub deleted: bool,
+}
+
+impl DiscussionFullView {
+    // Moves the single `attachment` some Canvas versions send into `attachments`.
+    fn normalize(entries: &mut [DiscussionEntry]) {
+        for entry in entries {
+            if let Some(attachment) = entry.attachment.take() {
+                entry.attachments.push(attachment);
+            }
+            Self::normalize(&mut entry.replies);
+        }
+    }
+
+    /// Lists every entry of the tree in thread order (depth-first), one row per entry, with the
+    /// author names resolved from `participants`.
+    ///
+    /// Example:
+    /// ```
+    /// let view = topic.full_view(&client)?;
+    /// for row in view.flatten() {
+    ///     println!("{}{:?}: {} likes", "  ".repeat(row.depth), row.author_name, row.rating_sum);
+    /// }
+    /// ```
+    pub fn flatten(&self) -> Vec<FlatDiscussionEntry> {
+        let mut rows = Vec::new();
+        self.flatten_into(&self.view, 0, &mut rows);
+        rows
+    }
+
+    fn flatten_into(&self, entries: &[DiscussionEntry], depth: usize, rows: &mut Vec<FlatDiscussionEntry>) {
+        for entry in entries {
+            let author_name = entry.user_id.and_then(|user_id| {
+                self.participants
+                    .iter()
+                    .find(|participant| participant.id == user_id)
+                    .and_then(|participant| participant.display_name.clone())
+            });
+            rows.push(FlatDiscussionEntry {
+                id: entry.id,
+                parent_id: entry.parent_id,
+                depth,
+                user_id: entry.user_id,
+                author_name,
+                created_at: entry.created_at.clone(),
+                message: entry.message.clone(),
+                rating_sum: entry.rating_sum.unwrap_or(0),
+                attachment_count: entry.attachments.len(),
+                reply_count: entry.replies.len(),
+                deleted: entry.deleted,
+            });
+            self.flatten_into(&entry.replies, depth + 1, rows);
+        }
+    }
+}
+
+impl DiscussionTopic {
+    // Base URL of this topic in the API.
+    fn api_url(&self) -> String {
+        format!(
+            "{}/courses/{}/discussion_topics/{}",
+            self.info.course_info.canvas_info.url_canvas, self.info.course_info.id, self.info.id
+        )
+    }
+
+    // Sends PUT (on) or DELETE (off) to a flag endpoint of the topic, optionally masquerading.
+    fn set_flag(&self, client: &Client, path: &str, on: bool, as_user: Option<u64>) -> Result<(), Box<dyn Error>> {
+        let url = format!("{}/{}", self.api_url(), path);
+        let method = if on { HttpMethod::Put(json!({})) } else { HttpMethod::Delete };
+        let params = as_user_params(as_user);
+        let response = send_http_request(client, method, &url, &self.info.course_info.canvas_info, params)?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to update {} with status: {}", path, response.status()).into());
+        }
+        Ok(())
+    }
+
+    /// Marks the topic (the opening post) as read or unread.
+    pub fn mark_read(&self, client: &Client, read: bool, as_user: Option<u64>) -> Result<(), Box<dyn Error>> {
+        self.set_flag(client, "read", read, as_user)
+    }
+
+    /// Marks the topic and all its entries as read or unread.
+    ///
+    /// Example:
+    /// ```
+    /// // Reset the read state of a student before measuring participation
+    /// topic.mark_all_read(&client, false, Some(student.info.id))?;
+    /// ```
+    pub fn mark_all_read(&self, client: &Client, read: bool, as_user: Option<u64>) -> Result<(), Box<dyn Error>> {
+        self.set_flag(client, "read_all", read, as_user)
+    }
+
+    /// Marks one entry (or reply) of the topic as read or unread.
+    pub fn mark_entry_read(
+        &self,
+        client: &Client,
+        entry_id: u64,
+        read: bool,
+        as_user: Option<u64>,
+    ) -> Result<(), Box<dyn Error>> {
+        self.set_flag(client, &format!("entries/{}/read", entry_id), read, as_user)
+    }
+
+    /// Subscribes to (or unsubscribes from) notifications of new entries.
+    pub fn set_subscribed(&self, client: &Client, subscribed: bool, as_user: Option<u64>) -> Result<(), Box<dyn Error>> {
+        self.set_flag(client, "subscribed", subscribed, as_user)
+    }
+
+    /// Retrieves the complete entry tree of the topic (authors, timestamps, ratings and
+    /// attachments) for offline analysis. See `DiscussionFullView::flatten`.
+    pub fn full_view(&self, client: &Client) -> Result<DiscussionFullView, Box<dyn Error>> {
+        let url = format!("{}/view", self.api_url());
+        let mut view: DiscussionFullView = fetch_json(client, &self.info.course_info.canvas_info, &url)?;
+        DiscussionFullView::normalize(&mut view.view);
+        Ok(view)
+    }
+
+    /// Returns `true` when the topic is a group discussion (each group discusses separately).
+    pub fn is_group_discussion(&self) -> bool {
+        self.info.group_category_id.is_some() || !self.info.group_topic_children.is_empty()
+    }
+
+    /// Returns the child topic of a group, for group discussions.
+    pub fn group_topic(&self, group_id: u64) -> Option<&GroupTopicChild> {
+        self.info
+            .group_topic_children
+            .iter()
+            .find(|child| child.group_id == group_id)
+    }
+
+    // Base URL of the topic where entries of `group_id` live: the group child topic for group
+    // discussions, the course topic otherwise.
+    fn entries_base_url(&self, group_id: Option<u64>) -> Result<String, Box<dyn Error>> {
+        if !self.is_group_discussion() {
+            return Ok(self.api_url());
+        }
+        let group_id = group_id.ok_or_else(|| {
+            format!("Topic {} is a group discussion: a group ID is required", self.info.id)
+        })?;
+        let child = self
+            .group_topic(group_id)
+            .ok_or_else(|| format!("Group {} has no copy of topic {}", group_id, self.info.id))?;
+        Ok(format!(
+            "{}/groups/{}/discussion_topics/{}",
+            self.info.course_info.canvas_info.url_canvas, child.group_id, child.id
+        ))
+    }
+
+    /// Posts a top-level entry in the topic.
+    ///
+    /// For group discussions the entry is routed to the child topic of `group_id` (required);
+    /// for course-wide topics `group_id` is ignored. Use `as_user` to post on behalf of a student.
+    ///
+    /// Returns:
+    /// - `Result<DiscussionEntry, Box<dyn Error>>`: The created entry or an error.
+    pub fn post_entry(
+        &self,
+        client: &Client,
+        message: &str,
+        group_id: Option<u64>,
+        as_user: Option<u64>,
+    ) -> Result<DiscussionEntry, Box<dyn Error>> {
+        let url = format!("{}/entries", self.entries_base_url(group_id)?);
+        let body = json!({ "message": message });
+        let canvas_info = &self.info.course_info.canvas_info;
+        let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, as_user_params(as_user))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to post discussion entry with status: {}", response.status()).into());
+        }
+        Ok(response.json()?)
+    }
+
+    /// Retrieves the entry tree of the child topic of one group.
+    pub fn group_full_view(&self, client: &Client, group_id: u64) -> Result<DiscussionFullView, Box<dyn Error>> {
+        let url = format!("{}/view", self.entries_base_url(Some(group_id))?);
+        let mut view: DiscussionFullView = fetch_json(client, &self.info.course_info.canvas_info, &url)?;
+        DiscussionFullView::normalize(&mut view.view);
+        Ok(view)
+    }
+
+    /// Retrieves the entry trees of every group of a group discussion, keyed by group ID, so
+    /// participation can be graded per group. For course-wide topics, returns the course tree
+    /// under the key `0`.
+    pub fn full_views_by_group(&self, client: &Client) -> Result<Vec<(u64, DiscussionFullView)>, Box<dyn Error>> {
+        if !self.is_group_discussion() {
+            return Ok(vec![(0, self.full_view(client)?)]);
+        }
+        self.info
+            .group_topic_children
+            .iter()
+            .map(|child| Ok((child.group_id, self.group_full_view(client, child.group_id)?)))
+            .collect()
+    }
+
+    /// Returns the IDs of the entries the user hasn't read yet.
+    pub fn unread_entry_ids(&self, client: &Client, as_user: Option<u64>) -> Result<Vec<u64>, Box<dyn Error>> {
+        let url = format!("{}/view", self.api_url());
+        let canvas_info = &self.info.course_info.canvas_info;
+        let response = send_http_request(client, HttpMethod::Get, &url, canvas_info, as_user_params(as_user))?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch discussion view with status: {}", response.status()).into());
+        }
+        let view: Value = response.json()?;
+        Ok(view["unread_entries"]
+            .as_array()
+            .map(|ids| ids.iter().filter_map(|id| id.as_u64()).collect())
+            .unwrap_or_default())
+    }
+}
+
+// Query parameters to act on behalf of another user.
+fn as_user_params(as_user: Option<u64>) -> Vec<(String, String)> {
+    as_user
+        .map(|id| vec![("as_user_id".to_string(), id.to_string())])
+        .unwrap_or_default()
+}
+
+// Builds a `DiscussionTopic` bound to its course from the JSON returned by Canvas.
+fn topic_from_json(course_info: &Arc<CourseInfo>, json: Value) -> Option<DiscussionTopic> {
+    let mut info: DiscussionTopicInfo = serde_json::from_value(json).ok()?;
+    info.course_info = Arc::clone(course_info);
+    Some(DiscussionTopic { info: Arc::new(info) })
+}
+
+/// Retrieves the discussion topics of a course (announcements excluded).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course whose topics are listed.
+///
+/// Returns:
+/// - `Result<Vec<DiscussionTopic>, Box<dyn Error>>`: The topics or an error.
+pub fn fetch_discussion_topics(
+    client: &Client,
+    course_info: &Arc<CourseInfo>,
+) -> Result<Vec<DiscussionTopic>, Box<dyn Error>> {
+    let url = format!(
+        "{}/courses/{}/discussion_topics",
+        course_info.canvas_info.url_canvas, course_info.id
+    );
+    Ok(fetch_all_pages(client, &course_info.canvas_info, &url, Vec::new())?
+        .into_iter()
+        .filter_map(|json| topic_from_json(course_info, json))
+        .collect())
+}
+
+/// Retrieves one discussion topic of a course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course the topic belongs to.
+/// - `topic_id`: ID of the topic.
+///
+/// Returns:
+/// - `Result<DiscussionTopic, Box<dyn Error>>`: The topic or an error.
+pub fn fetch_discussion_topic(
+    client: &Client,
+    course_info: &Arc<CourseInfo>,
+    topic_id: u64,
+) -> Result<DiscussionTopic, Box<dyn Error>> {
+    let url = format!(
+        "{}/courses/{}/discussion_topics/{}",
+        course_info.canvas_info.url_canvas, course_info.id, topic_id
+    );
+    let json: Value = fetch_json(client, &course_info.canvas_info, &url)?;
+    topic_from_json(course_info, json).ok_or_else(|| "Invalid discussion topic returned by Canvas".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_view_flatten() {
+        let mut view: DiscussionFullView = serde_json::from_value(json!({
+            "unread_entries": [3],
+            "participants": [
+                {"id": 10, "display_name": "Ana"},
+                {"id": 11, "display_name": "Bruno"}
+            ],
+            "view": [
+                {"id": 1, "user_id": 10, "parent_id": null, "created_at": "2024-03-01T12:00:00Z",
+                 "message": "<p>Minha opinião</p>", "rating_sum": 2,
+                 "attachment": {"id": 99, "display_name": "dados.csv", "content-type": "text/csv"},
+                 "replies": [
+                    {"id": 2, "user_id": 11, "parent_id": 1, "message": "<p>Concordo</p>",
+                     "replies": [{"id": 3, "user_id": 10, "parent_id": 2, "message": "<p>Obrigada</p>"}]}
+                 ]},
+                {"id": 4, "deleted": true, "parent_id": null}
+            ],
+            "new_entries": []
+        }))
+        .unwrap();
+        DiscussionFullView::normalize(&mut view.view);
+
+        let rows = view.flatten();
+        assert_eq!(rows.iter().map(|row| row.id).collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(rows.iter().map(|row| row.depth).collect::<Vec<_>>(), vec![0, 1, 2, 0]);
+        assert_eq!(rows[0].author_name.as_deref(), Some("Ana"));
+        assert_eq!(rows[0].attachment_count, 1);
+        assert_eq!(rows[0].rating_sum, 2);
+        assert_eq!(rows[1].author_name.as_deref(), Some("Bruno"));
+        assert!(rows[3].deleted && rows[3].author_name.is_none());
+        assert_eq!(view.view[0].attachments[0].content_type.as_deref(), Some("text/csv"));
+    }
+
+    #[test]
+    fn test_group_topic_routing() {
+        let mut info: DiscussionTopicInfo = serde_json::from_value(json!({
+            "id": 50,
+            "title": "Debate em grupo",
+            "group_category_id": 8,
+            "group_topic_children": [{"id": 51, "group_id": 100}, {"id": 52, "group_id": 101}]
+        }))
+        .unwrap();
+        info.course_info = Arc::new(CourseInfo {
+            id: 7,
+            canvas_info: Arc::new(crate::CanvasCredentials {
+                url_canvas: "https://canvas.example/api/v1".to_string(),
+                token_canvas: String::new(),
+            }),
+            ..Default::default()
+        });
+        let topic = DiscussionTopic { info: Arc::new(info) };
+
+        assert!(topic.is_group_discussion());
+        assert_eq!(
+            topic.entries_base_url(Some(101)).unwrap(),
+            "https://canvas.example/api/v1/groups/101/discussion_topics/52"
+        );
+        assert!(topic.entries_base_url(None).is_err());
+        assert!(topic.entries_base_url(Some(999)).is_err());
+    }
+}
@@ -0,0 +1,514 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_all_pages;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::params::Params;
+use crate::student::{CourseGrade, EnrollmentState};
+use crate::user::EnrollmentRole;
+use crate::{CanvasCredentials, CanvasError, CourseInfo};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::error::Error;
+use std::sync::Arc;
+
+/// Information about an enrollment (a user's membership in a course).
+///
+/// Fields:
+/// - `id`: ID of the enrollment.
+/// - `user_id`: ID of the enrolled user.
+/// - `course_section_id`: Section of the enrollment.
+/// - `enrollment_type`: Canvas type (`StudentEnrollment`, `TeacherEnrollment`...).
+/// - `enrollment_state`: `active`, `invited`, `inactive`, `completed`, `creation_pending`...
+/// - `user_name`: Name of the user, when Canvas includes it.
+/// - `grades`: Course grade of student enrollments, with the final grade override if any.
+/// - `course_info`: The course of the enrollment.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct EnrollmentInfo {
+    pub id: u64,
+    pub user_id: u64,
+    pub course_section_id: Option<u64>,
+    #[serde(rename = "type")]
+    pub enrollment_type: String,
+    pub enrollment_state: String,
+    #[serde(skip_deserializing)]
+    pub user_name: Option<String>,
+    #[serde(default)]
+    pub grades: Option<CourseGrade>,
+    #[serde(skip)]
+    pub course_info: Arc<CourseInfo>,
+}
+
+/// An enrollment of a course. See `Course::fetch_enrollments`.
+#[derive(Debug, Clone)]
+pub struct Enrollment {
+    pub info: Arc<EnrollmentInfo>,
+}
+
+impl Enrollment {
+    /// Returns `true` while the invitation has not been accepted.
+    pub fn is_invited(&self) -> bool {
+        self.info.enrollment_state == "invited"
+    }
+
+    // Sends a request to an action endpoint of this enrollment.
+    fn action(&self, client: &Client, method: HttpMethod, action: &str) -> Result<Value, Box<dyn Error>> {
+        let course_info = &self.info.course_info;
+        let url = format!(
+            "{}/courses/{}/enrollments/{}/{}",
+            course_info.canvas_info.url_canvas, course_info.id, self.info.id, action
+        );
+        let response = send_http_request(client, method, &url, &course_info.canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to {} enrollment with status: {}", action, response.status()).into());
+        }
+        Ok(response.json().unwrap_or(Value::Null))
+    }
+
+    /// Sends the course invitation e-mail again, to nudge users stuck in the invited state.
+    pub fn resend_invitation(&self, client: &Client) -> Result<(), Box<dyn Error>> {
+        self.action(client, HttpMethod::Post(json!({})), "resend_invitation")?;
+        Ok(())
+    }
+
+    /// Accepts the invitation. Canvas only allows the invited user to do it, so the credentials
+    /// must belong to that user.
+    pub fn accept(&self, client: &Client) -> Result<(), Box<dyn Error>> {
+        self.action(client, HttpMethod::Post(json!({})), "accept")?;
+        Ok(())
+    }
+
+    /// Rejects the invitation. Like `accept`, only the invited user can do it.
+    pub fn reject(&self, client: &Client) -> Result<(), Box<dyn Error>> {
+        self.action(client, HttpMethod::Post(json!({})), "reject")?;
+        Ok(())
+    }
+
+    /// Reactivates an inactive enrollment.
+    pub fn reactivate(&self, client: &Client) -> Result<(), Box<dyn Error>> {
+        self.action(client, HttpMethod::Put(json!({})), "reactivate")?;
+        Ok(())
+    }
+
+    /// Overrides the final grade of this enrollment (or removes the override with `None`).
+    /// See `set_final_grade_override`.
+    pub fn set_final_grade_override(&self, client: &Client, score: Option<f64>) -> Result<Option<f64>, Box<dyn Error>> {
+        set_final_grade_override(client, &self.info.course_info.canvas_info, self.info.id, score)
+    }
+}
+
+// The REST API has no endpoint to write overrides, so the GraphQL mutation used by the gradebook
+// is sent instead.
+fn override_score_mutation(enrollment_id: u64, score: Option<f64>) -> Value {
+    json!({
+        "query": "mutation SetOverrideScore($enrollmentId: ID!, $overrideScore: Float) { \
+                  setOverrideScore(input: {enrollmentId: $enrollmentId, overrideScore: $overrideScore}) { \
+                  grades { overrideScore } errors { message } } }",
+        "variables": { "enrollmentId": enrollment_id.to_string(), "overrideScore": score },
+    })
+}
+
+/// Sets the final grade override of a student enrollment, replacing the grade computed by Canvas
+/// in the gradebook and in grade exports. The course must allow overrides
+/// (`CourseSettings::allow_final_grade_override`).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `enrollment_id`: ID of the student enrollment.
+/// - `score`: Overriding score in percent, or `None` to remove the override.
+///
+/// Returns:
+/// - `Result<Option<f64>, Box<dyn Error>>`: The override stored by Canvas, or an error.
+///
+/// Example:
+/// ```
+/// for enrollment in course.fetch_enrollments(&client, &[])? {
+///     let grades = enrollment.info.grades.clone().unwrap_or_default();
+///     if grades.final_score.is_some_and(|score| (59.5..60.0).contains(&score)) {
+///         enrollment.set_final_grade_override(&client, Some(60.0))?;
+///     }
+/// }
+/// ```
+pub fn set_final_grade_override(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    enrollment_id: u64,
+    score: Option<f64>,
+) -> Result<Option<f64>, Box<dyn Error>> {
+    let url = format!("{}/api/graphql", canvas_info.web_url());
+    let body = override_score_mutation(enrollment_id, score);
+    let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to set final grade override with status: {}", response.status()).into());
+    }
+    let json: Value = response.json()?;
+    let result = &json["data"]["setOverrideScore"];
+    let message = json["errors"][0]["message"].as_str().or(result["errors"][0]["message"].as_str());
+    if let Some(message) = message {
+        return Err(format!("Failed to set final grade override: {}", message).into());
+    }
+    Ok(result["grades"]["overrideScore"].as_f64())
+}
+
+/// Retrieves the enrollments of a course, optionally restricted to some states.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course whose enrollments are listed.
+/// - `states`: States to include (Canvas defaults to active and invited when empty).
+///
+/// Returns:
+/// - `Result<Vec<Enrollment>, Box<dyn Error>>`: The enrollments or an error.
+///
+/// Example:
+/// ```
+/// let pending = course.fetch_enrollments(&client, &[EnrollmentState::Invited])?;
+/// for enrollment in pending {
+///     enrollment.resend_invitation(&client)?;
+/// }
+/// ```
+pub fn fetch_enrollments(
+    client: &Client,
+    course_info: &Arc<CourseInfo>,
+    states: &[EnrollmentState],
+) -> Result<Vec<Enrollment>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/enrollments", course_info.canvas_info.url_canvas, course_info.id);
+    let params = Params::new().array("state", states.iter().map(EnrollmentState::as_str));
+    Ok(fetch_all_pages(client, &course_info.canvas_info, &url, params.into())?
+        .into_iter()
+        .filter_map(|json| enrollment_from_json(course_info, &json))
+        .collect())
+}
+
+// Builds an `Enrollment` bound to its course from the JSON returned by Canvas.
+fn enrollment_from_json(course_info: &Arc<CourseInfo>, json: &Value) -> Option<Enrollment> {
+    let mut info: EnrollmentInfo = serde_json::from_value(json.clone()).ok()?;
+    info.user_name = json["user"]["name"].as_str().map(String::from);
+    info.course_info = Arc::clone(course_info);
+    Some(Enrollment { info: Arc::new(info) })
+}
+
+/// Outcome of one row of the roster given to `Course::enroll_from_csv`.
+///
+/// Fields:
+/// - `line`: Line of the row in the file (starting at 1).
+/// - `identifier`: E-mail or SIS ID read from the row.
+/// - `user_id`: Canvas user the identifier resolved to, if any.
+/// - `enrollment_id`: The enrollment created, when the row succeeded.
+/// - `error`: Why the row failed (unknown user, duplicate row, request refused...).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CsvEnrollmentResult {
+    pub line: usize,
+    pub identifier: String,
+    pub user_id: Option<u64>,
+    pub enrollment_id: Option<u64>,
+    pub error: Option<String>,
+}
+
+impl CsvEnrollmentResult {
+    /// Returns `true` when the user was enrolled.
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+// User of a roster row: its e-mail or its SIS ID (registrar ID).
+#[derive(Debug, Clone, PartialEq)]
+enum RosterIdentifier {
+    Email(String),
+    SisId(String),
+}
+
+impl RosterIdentifier {
+    // Reads a value of a column without header: e-mails are recognized by the `@`.
+    fn guess(value: &str) -> RosterIdentifier {
+        if value.contains('@') {
+            RosterIdentifier::Email(value.to_string())
+        } else {
+            RosterIdentifier::SisId(value.to_string())
+        }
+    }
+
+    fn as_str(&self) -> &str {
+        match self {
+            RosterIdentifier::Email(value) | RosterIdentifier::SisId(value) => value,
+        }
+    }
+}
+
+// Splits CSV text into rows of fields. The separator is `;` when the first line has more of them
+// than commas (spreadsheets exported with a comma as decimal separator); quoted fields may hold
+// separators, doubled quotes and line breaks. Each row comes with its line number.
+fn parse_csv(text: &str) -> Vec<(usize, Vec<String>)> {
+    let text = text.trim_start_matches('\u{feff}');
+    let first_line = text.lines().next().unwrap_or_default();
+    let separator = if first_line.matches(';').count() > first_line.matches(',').count() { ';' } else { ',' };
+
+    let mut rows = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            '\n' if !quoted => {
+                fields.push(std::mem::take(&mut field));
+                rows.push((row_line, std::mem::take(&mut fields)));
+                line += 1;
+                row_line = line;
+            }
+            '\r' if !quoted => {}
+            c if c == separator && !quoted => fields.push(std::mem::take(&mut field)),
+            c => {
+                if c == '\n' {
+                    line += 1;
+                }
+                field.push(c);
+            }
+        }
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        rows.push((row_line, fields));
+    }
+    rows.into_iter()
+        .map(|(line, fields)| (line, fields.into_iter().map(|field| field.trim().to_string()).collect::<Vec<_>>()))
+        .filter(|(_, fields)| fields.iter().any(|field| !field.is_empty()))
+        .collect()
+}
+
+// Reads the users of a roster. With a header, the `email` and `sis_user_id` (or `sis_id`)
+// columns are used, the SIS ID first when a row has both; without one, the first column holds
+// e-mails or SIS IDs.
+fn roster_identifiers(rows: &[(usize, Vec<String>)]) -> Vec<(usize, Option<RosterIdentifier>)> {
+    let Some((_, header)) = rows.first() else {
+        return Vec::new();
+    };
+    let column = |names: &[&str]| {
+        header.iter().position(|name| {
+            let name = name.to_lowercase().replace([' ', '-'], "_");
+            names.contains(&name.as_str())
+        })
+    };
+    let email = column(&["email", "e_mail"]);
+    let sis_id = column(&["sis_user_id", "sis_id"]);
+    if email.is_none() && sis_id.is_none() {
+        return rows
+            .iter()
+            .map(|(line, fields)| (*line, Some(RosterIdentifier::guess(&fields[0])).filter(|id| !id.as_str().is_empty())))
+            .collect();
+    }
+    let value = |fields: &[String], index: Option<usize>| {
+        index.and_then(|index| fields.get(index)).filter(|value| !value.is_empty()).cloned()
+    };
+    rows[1..]
+        .iter()
+        .map(|(line, fields)| {
+            let identifier = value(fields, sis_id)
+                .map(RosterIdentifier::SisId)
+                .or_else(|| value(fields, email).map(RosterIdentifier::Email));
+            (*line, identifier)
+        })
+        .collect()
+}
+
+// Body of the request enrolling a user, who receives the invitation e-mail of the course.
+fn enrollment_body(user_id: u64, role: EnrollmentRole, section_id: Option<u64>) -> Value {
+    let mut enrollment = json!({
+        "user_id": user_id,
+        "type": role.enrollment_type(),
+        "enrollment_state": "invited",
+        "notify": true,
+    });
+    if let Some(section_id) = section_id {
+        enrollment["course_section_id"] = json!(section_id);
+    }
+    json!({ "enrollment": enrollment })
+}
+
+// Finds the Canvas user of a roster identifier. SIS IDs are resolved by Canvas itself
+// (`sis_user_id:` prefix); e-mails are searched in the root account, which requires an admin
+// token.
+fn resolve_user(client: &Client, canvas_info: &CanvasCredentials, identifier: &RosterIdentifier) -> Result<u64, Box<dyn Error>> {
+    let (url, params) = match identifier {
+        RosterIdentifier::SisId(sis_id) => (
+            format!("{}/users/sis_user_id:{}", canvas_info.url_canvas, urlencoding::encode(sis_id)),
+            Params::new(),
+        ),
+        RosterIdentifier::Email(email) => (
+            format!("{}/accounts/self/users", canvas_info.url_canvas),
+            Params::new().param("search_term", email).include(["email"]),
+        ),
+    };
+    let users = match identifier {
+        RosterIdentifier::SisId(_) => {
+            let response = send_http_request(client, HttpMethod::Get, &url, canvas_info, params.into()).map_err(|e| {
+                let e = CanvasError::from(e);
+                if e.status() == Some(404) {
+                    format!("No user with SIS ID {}", identifier.as_str()).into()
+                } else {
+                    Box::new(e) as Box<dyn Error>
+                }
+            })?;
+            vec![response.json::<Value>()?]
+        }
+        RosterIdentifier::Email(_) => fetch_all_pages(client, canvas_info, &url, params.into())?,
+    };
+    let matches: Vec<u64> = users
+        .iter()
+        .filter(|user| match identifier {
+            RosterIdentifier::SisId(_) => true,
+            RosterIdentifier::Email(email) => ["email", "login_id"]
+                .iter()
+                .any(|key| user[*key].as_str().is_some_and(|value| value.eq_ignore_ascii_case(email))),
+        })
+        .filter_map(|user| user["id"].as_u64())
+        .collect();
+    match matches.as_slice() {
+        [user_id] => Ok(*user_id),
+        [] => Err(format!("No user with e-mail {}", identifier.as_str()).into()),
+        _ => Err(format!("Several users with e-mail {}", identifier.as_str()).into()),
+    }
+}
+
+/// Enrolls the users listed in a roster CSV file in a course, with the given role, in a section
+/// or in the default one. See `Course::enroll_from_csv`.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course where users are enrolled.
+/// - `file_path`: Path of the CSV file.
+/// - `role`: Role of the new enrollments.
+/// - `section_id`: Section of the new enrollments (`None` for the default section).
+///
+/// Returns:
+/// - `Result<Vec<CsvEnrollmentResult>, Box<dyn Error>>`: The outcome of each row, or an error
+///   when the file could not be read.
+pub fn enroll_from_csv(
+    client: &Client,
+    course_info: &Arc<CourseInfo>,
+    file_path: &str,
+    role: EnrollmentRole,
+    section_id: Option<u64>,
+) -> Result<Vec<CsvEnrollmentResult>, Box<dyn Error>> {
+    let canvas_info = &course_info.canvas_info;
+    let rows = parse_csv(&std::fs::read_to_string(file_path)?);
+    let url = format!("{}/courses/{}/enrollments", canvas_info.url_canvas, course_info.id);
+    let mut seen = HashSet::new();
+    let mut results = Vec::new();
+    for (line, identifier) in roster_identifiers(&rows) {
+        let mut result = CsvEnrollmentResult {
+            line,
+            identifier: identifier.as_ref().map(|id| id.as_str().to_string()).unwrap_or_default(),
+            ..Default::default()
+        };
+        let outcome = match &identifier {
+            None => Err("No e-mail or SIS ID in the row".into()),
+            Some(identifier) if !seen.insert(identifier.as_str().to_lowercase()) => {
+                Err(format!("{} is repeated in an earlier row", identifier.as_str()).into())
+            }
+            Some(identifier) => resolve_user(client, canvas_info, identifier).and_then(|user_id| {
+                result.user_id = Some(user_id);
+                let body = enrollment_body(user_id, role, section_id);
+                let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+                let json: Value = response.json()?;
+                json["id"].as_u64().ok_or_else(|| "Missing id in enrollment response".into())
+            }),
+        };
+        match outcome {
+            Ok(enrollment_id) => result.enrollment_id = Some(enrollment_id),
+            Err(e) => result.error = Some(e.to_string()),
+        }
+        results.push(result);
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_enrollment_from_json() {
+        let enrollment = enrollment_from_json(
+            &Arc::new(CourseInfo::default()),
+            &json!({
+                "id": 5,
+                "user_id": 10,
+                "course_section_id": 3,
+                "type": "StudentEnrollment",
+                "enrollment_state": "invited",
+                "user": {"id": 10, "name": "Ana"}
+            }),
+        )
+        .unwrap();
+        assert!(enrollment.is_invited());
+        assert_eq!(enrollment.info.user_name.as_deref(), Some("Ana"));
+        assert_eq!(enrollment.info.enrollment_type, "StudentEnrollment");
+        assert!(enrollment.info.grades.is_none());
+    }
+
+    #[test]
+    fn test_final_grade_override() {
+        let enrollment = enrollment_from_json(
+            &Arc::new(CourseInfo::default()),
+            &json!({
+                "id": 6,
+                "user_id": 11,
+                "type": "StudentEnrollment",
+                "enrollment_state": "active",
+                "grades": {"current_score": 58.0, "final_score": 59.6, "override_score": 60.0}
+            }),
+        )
+        .unwrap();
+        let grades = enrollment.info.grades.clone().unwrap();
+        assert_eq!(grades.effective_final_score(), Some(60.0));
+
+        let body = override_score_mutation(6, Some(60.0));
+        assert_eq!(body["variables"], json!({"enrollmentId": "6", "overrideScore": 60.0}));
+        assert_eq!(override_score_mutation(6, None)["variables"]["overrideScore"], Value::Null);
+    }
+
+    #[test]
+    fn test_roster_csv_rows() {
+        let rows = parse_csv("\u{feff}Nome;E-mail;SIS ID\r\n\"Souza; Ana\";ana@pucpr.br;2024001\r\nBia;BIA@pucpr.br;\r\n;;\r\nCaio;;\n");
+        assert_eq!(rows[1], (2, vec!["Souza; Ana".to_string(), "ana@pucpr.br".to_string(), "2024001".to_string()]));
+        assert_eq!(
+            roster_identifiers(&rows),
+            vec![
+                (2, Some(RosterIdentifier::SisId("2024001".to_string()))),
+                (3, Some(RosterIdentifier::Email("BIA@pucpr.br".to_string()))),
+                (5, None),
+            ]
+        );
+
+        let rows = parse_csv("ana@pucpr.br\n2024002\n");
+        assert_eq!(
+            roster_identifiers(&rows),
+            vec![
+                (1, Some(RosterIdentifier::Email("ana@pucpr.br".to_string()))),
+                (2, Some(RosterIdentifier::SisId("2024002".to_string()))),
+            ]
+        );
+
+        assert_eq!(
+            enrollment_body(10, EnrollmentRole::Ta, Some(3)),
+            json!({"enrollment": {
+                "user_id": 10, "type": "TaEnrollment", "enrollment_state": "invited",
+                "notify": true, "course_section_id": 3
+            }})
+        );
+    }
+}
@@ -0,0 +1,137 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::progress::wait_for_progress;
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+/// Interval between two checks of a running ePub export.
+pub const EPUB_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Number of checks of a running ePub export before giving up (about 15 minutes).
+pub const EPUB_POLL_ATTEMPTS: u32 = 300;
+
+/// The generated ePub file.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EpubAttachment {
+    pub id: u64,
+    pub url: String,
+    pub filename: Option<String>,
+    pub size: Option<u64>,
+}
+
+/// An ePub export of a course, an offline and accessible copy of its content.
+///
+/// Fields:
+/// - `id`: ID of the export.
+/// - `created_at`: When the export was requested.
+/// - `workflow_state`: `created`, `exporting`, `exported`, `generating`, `generated` or `failed`.
+/// - `progress_url`: URL to poll while the export runs.
+/// - `attachment`: The file, once generated.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct EpubExport {
+    pub id: u64,
+    pub created_at: Option<String>,
+    pub workflow_state: String,
+    pub progress_url: Option<String>,
+    pub attachment: Option<EpubAttachment>,
+}
+
+// Extracts the export from `{"id": course_id, "name": ..., "epub_export": {...}}`.
+fn export_from_course_json(json: &Value) -> Result<EpubExport, Box<dyn Error>> {
+    Ok(serde_json::from_value(json["epub_export"].clone())?)
+}
+
+/// Starts the ePub export of a course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+///
+/// Returns:
+/// - `Result<EpubExport, Box<dyn Error>>`: The started export or an error.
+pub fn start_epub_export(client: &Client, canvas_info: &CanvasCredentials, course_id: u64) -> Result<EpubExport, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/epub_exports", canvas_info.url_canvas, course_id);
+    let response = send_http_request(client, HttpMethod::Post(json!({})), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to start ePub export with status: {}", response.status()).into());
+    }
+    export_from_course_json(&response.json()?)
+}
+
+/// Retrieves an ePub export of a course.
+pub fn fetch_epub_export(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    export_id: u64,
+) -> Result<EpubExport, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/epub_exports/{}", canvas_info.url_canvas, course_id, export_id);
+    let json: Value = fetch_json(client, canvas_info, &url)?;
+    export_from_course_json(&json)
+}
+
+/// Exports a course as ePub and saves the file to `file_path`: starts the export, waits for it
+/// (see `EPUB_POLL_INTERVAL` and `EPUB_POLL_ATTEMPTS`) and downloads the result.
+///
+/// Example:
+/// ```
+/// for course in account.fetch_courses(&client, &query)? {
+///     course.export_epub(&client, &format!("epub/{}.epub", course.info.id))?;
+/// }
+/// ```
+pub fn export_epub_to_file(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    file_path: &str,
+) -> Result<EpubExport, Box<dyn Error>> {
+    let export = start_epub_export(client, canvas_info, course_id)?;
+    if let Some(progress_url) = &export.progress_url {
+        wait_for_progress(client, canvas_info, progress_url, EPUB_POLL_INTERVAL, EPUB_POLL_ATTEMPTS)?;
+    }
+    let export = fetch_epub_export(client, canvas_info, course_id, export.id)?;
+    let attachment = export
+        .attachment
+        .as_ref()
+        .ok_or_else(|| format!("ePub export {} has no file (state: {})", export.id, export.workflow_state))?;
+
+    let response = client.get(&attachment.url).bearer_auth(&canvas_info.token_canvas).send()?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download ePub with status: {}", response.status()).into());
+    }
+    let mut file = File::create(file_path)?;
+    file.write_all(&response.bytes()?)?;
+    Ok(export)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_from_course_json() {
+        let export = export_from_course_json(&json!({
+            "id": 7,
+            "name": "Cálculo I",
+            "epub_export": {
+                "id": 15,
+                "created_at": "2024-05-01T10:00:00Z",
+                "progress_url": "https://canvas.example/api/v1/progress/99",
+                "user_id": 3,
+                "workflow_state": "generated",
+                "attachment": {"id": 40, "url": "https://canvas.example/files/40/download", "filename": "calculo.epub"}
+            }
+        }))
+        .unwrap();
+        assert_eq!(export.id, 15);
+        assert_eq!(export.attachment.unwrap().filename.as_deref(), Some("calculo.epub"));
+    }
+}
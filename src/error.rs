@@ -0,0 +1,165 @@
+// Import necessary crates and modules
+use serde_json::Value;
+use std::error::Error;
+use std::fmt;
+
+/// Error returned by `CanvasClient` and by the requests sent through the connector.
+///
+/// Functions returning `Box<dyn Error>` carry a `CanvasError` for failed requests, which can be
+/// recovered with `CanvasError::from` (or `downcast_ref`).
+///
+/// Variants:
+/// - `Http`: Canvas answered with an error status (after the retries of transient errors). The
+///   `message` holds the error messages Canvas put in the body, such as
+///   `"Invalid assignment id"`, when there are any.
+/// - `Network`: No answer was received from Canvas.
+/// - `ResponseTooLarge`: The answer was bigger than the limit set with `set_response_size_limit`.
+/// - `UnexpectedContentType`: The answer was not JSON, usually an HTML page: the login page of an
+///   SSO after a redirect (expired or invalid token), or an error page of a proxy. `url` is the
+///   address that answered (after redirects) and `snippet` the beginning of its text.
+/// - `InvalidResponse`: The answer could not be decoded.
+/// - `Other`: Any other failure (I/O, invalid arguments...).
+///
+/// Example:
+/// ```
+/// match client.raw_get("/courses/1/gradebook_history/days", Vec::new()) {
+///     Ok(days) => println!("{}", days),
+///     Err(CanvasError::Http { status: 404, .. }) => println!("Not found"),
+///     Err(e) => eprintln!("{}", e),
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum CanvasError {
+    Http { status: u16, message: Option<String> },
+    Network(String),
+    ResponseTooLarge { url: String, limit: u64 },
+    UnexpectedContentType { url: String, content_type: String, snippet: String },
+    InvalidResponse(String),
+    Other(String),
+}
+
+impl CanvasError {
+    /// HTTP status of the failed request, if Canvas answered.
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            CanvasError::Http { status, .. } => Some(*status),
+            _ => None,
+        }
+    }
+
+    /// Builds the `Http` error of a response with `status`, taking the message from its body.
+    pub(crate) fn from_response_body(status: u16, body: &str) -> CanvasError {
+        let message = serde_json::from_str::<Value>(body).ok().and_then(|json| error_message(&json));
+        CanvasError::Http { status, message }
+    }
+}
+
+// Extracts the messages of the error bodies of Canvas, which come in several shapes:
+// `{"errors": [{"message": ...}]}`, `{"errors": {"field": [{"message": ...}]}}` (validation
+// errors), `{"errors": "..."}`, `{"message": ...}` and `{"error": ...}`.
+fn error_message(json: &Value) -> Option<String> {
+    let text = |value: &Value| match value {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(object) => object.get("message").and_then(Value::as_str).map(String::from),
+        _ => None,
+    };
+    let messages: Vec<String> = match &json["errors"] {
+        Value::Array(errors) => errors.iter().filter_map(text).collect(),
+        Value::Object(fields) => fields
+            .iter()
+            .flat_map(|(field, errors)| {
+                let errors = errors.as_array().cloned().unwrap_or_else(|| vec![errors.clone()]);
+                errors.iter().filter_map(text).map(|message| format!("{}: {}", field, message)).collect::<Vec<_>>()
+            })
+            .collect(),
+        Value::String(message) => vec![message.clone()],
+        _ => ["message", "error"].iter().filter_map(|key| json[*key].as_str().map(String::from)).take(1).collect(),
+    };
+    if messages.is_empty() {
+        None
+    } else {
+        Some(messages.join("; "))
+    }
+}
+
+impl fmt::Display for CanvasError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CanvasError::Http { status, message: Some(message) } => {
+                write!(f, "HTTP request failed with status code: {} ({})", status, message)
+            }
+            CanvasError::Http { status, message: None } => write!(f, "HTTP request failed with status code: {}", status),
+            CanvasError::Network(e) => write!(f, "Network error: {}", e),
+            CanvasError::ResponseTooLarge { url, limit } => {
+                write!(f, "Response of {} exceeds the limit of {} bytes", url, limit)
+            }
+            CanvasError::UnexpectedContentType { url, content_type, snippet } => write!(
+                f,
+                "Expected JSON from Canvas but {} answered with {} (login page or error page?): {}",
+                url, content_type, snippet
+            ),
+            CanvasError::InvalidResponse(e) => write!(f, "Invalid response from Canvas: {}", e),
+            CanvasError::Other(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl Error for CanvasError {}
+
+impl From<Box<dyn Error>> for CanvasError {
+    fn from(error: Box<dyn Error>) -> Self {
+        if let Some(error) = error.downcast_ref::<CanvasError>() {
+            return error.clone();
+        }
+        match error.downcast_ref::<reqwest::Error>() {
+            Some(e) if e.is_decode() => CanvasError::InvalidResponse(e.to_string()),
+            Some(e) if e.is_connect() || e.is_timeout() => CanvasError::Network(e.to_string()),
+            _ => CanvasError::Other(error.to_string()),
+        }
+    }
+}
+
+impl From<serde_json::Error> for CanvasError {
+    fn from(error: serde_json::Error) -> Self {
+        CanvasError::InvalidResponse(error.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_canvas_error_survives_boxing() {
+        let boxed: Box<dyn Error> = Box::new(CanvasError::Http { status: 404, message: None });
+        assert_eq!(boxed.to_string(), "HTTP request failed with status code: 404");
+        assert_eq!(CanvasError::from(boxed).status(), Some(404));
+
+        let other: Box<dyn Error> = "Missing upload_url".into();
+        assert_eq!(CanvasError::from(other), CanvasError::Other("Missing upload_url".to_string()));
+    }
+
+    #[test]
+    fn test_error_messages_from_canvas_bodies() {
+        let message = |body: &str| match CanvasError::from_response_body(400, body) {
+            CanvasError::Http { message, .. } => message,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            message(r#"{"errors":[{"message":"Invalid assignment id"}]}"#).as_deref(),
+            Some("Invalid assignment id")
+        );
+        assert_eq!(
+            message(r#"{"errors":{"name":[{"attribute":"name","type":"too_long","message":"Name is too long"}]}}"#)
+                .as_deref(),
+            Some("name: Name is too long")
+        );
+        assert_eq!(message(r#"{"message":"Not found"}"#).as_deref(), Some("Not found"));
+        assert_eq!(message(r#"{"errors":"unauthorized"}"#).as_deref(), Some("unauthorized"));
+        assert_eq!(message("<html>Bad Gateway</html>"), None);
+        assert_eq!(
+            CanvasError::from_response_body(400, r#"{"errors":[{"message":"Invalid assignment id"}]}"#).to_string(),
+            "HTTP request failed with status code: 400 (Invalid assignment id)"
+        );
+    }
+}
@@ -0,0 +1,368 @@
+// Import necessary crates and modules
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Drop rules of an assignment group, as configured in Canvas (`rules` of the assignment group).
+///
+/// Fields:
+/// - `drop_lowest`: Number of lowest scores to drop.
+/// - `drop_highest`: Number of highest scores to drop.
+/// - `never_drop`: Assignments that are never dropped.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct DropRules {
+    #[serde(default)]
+    pub drop_lowest: usize,
+    #[serde(default)]
+    pub drop_highest: usize,
+    #[serde(default)]
+    pub never_drop: Vec<u64>,
+}
+
+/// An assignment of a group together with the student's score on it.
+///
+/// Fields:
+/// - `assignment_id`: ID of the assignment.
+/// - `points_possible`: Maximum score of the assignment.
+/// - `score`: Score of the student, or `None` if not graded yet.
+/// - `excused`: Whether the student is excused (the assignment is then ignored).
+/// - `omit_from_final_grade`: Whether the assignment doesn't count towards the course grade.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GradedItem {
+    pub assignment_id: u64,
+    pub points_possible: f64,
+    pub score: Option<f64>,
+    #[serde(default)]
+    pub excused: bool,
+    #[serde(default)]
+    pub omit_from_final_grade: bool,
+}
+
+/// An assignment group with its weight, drop rules and the student's items.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct AssignmentGroupGrades {
+    pub id: u64,
+    pub name: String,
+    pub group_weight: f64,
+    #[serde(default)]
+    pub rules: DropRules,
+    #[serde(default)]
+    pub items: Vec<GradedItem>,
+}
+
+/// Score of one assignment group after applying its drop rules.
+///
+/// Fields:
+/// - `id`: ID of the assignment group.
+/// - `score`: Points earned in the kept assignments.
+/// - `possible`: Points possible of the kept assignments.
+/// - `dropped`: Assignments dropped by the rules.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GroupScore {
+    pub id: u64,
+    pub score: f64,
+    pub possible: f64,
+    pub dropped: Vec<u64>,
+}
+
+/// Result of `calculate`.
+///
+/// `current_score` only considers graded assignments; `final_score` counts ungraded ones as zero.
+/// Scores are percentages (0-100), or `None` when nothing counts towards the grade.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GradeCalculation {
+    pub current_score: Option<f64>,
+    pub final_score: Option<f64>,
+    pub current_groups: Vec<GroupScore>,
+    pub final_groups: Vec<GroupScore>,
+}
+
+// Assignment considered by the drop algorithm.
+#[derive(Debug, Clone, Copy)]
+struct Scored {
+    assignment_id: u64,
+    score: f64,
+    total: f64,
+}
+
+/// Computes the current and final course scores of a student the same way Canvas does.
+///
+/// Excused and `omit_from_final_grade` assignments are ignored. Drop rules are applied per group,
+/// choosing the assignments to drop that maximize the group percentage (which is not always the
+/// lowest raw score when points possible differ). With `weighted_groups`, each group contributes
+/// its percentage times its weight, and weights of groups without points are redistributed;
+/// otherwise all kept points are summed.
+///
+/// Example:
+/// ```
+/// let result = grade_calc::calculate(&groups, true);
+/// println!("current {:?}, final {:?}", result.current_score, result.final_score);
+/// ```
+pub fn calculate(groups: &[AssignmentGroupGrades], weighted_groups: bool) -> GradeCalculation {
+    let current_groups: Vec<GroupScore> = groups.iter().map(|g| group_score(g, false)).collect();
+    let final_groups: Vec<GroupScore> = groups.iter().map(|g| group_score(g, true)).collect();
+
+    GradeCalculation {
+        current_score: course_score(groups, &current_groups, weighted_groups),
+        final_score: course_score(groups, &final_groups, weighted_groups),
+        current_groups,
+        final_groups,
+    }
+}
+
+/// Computes the score of a single assignment group. With `include_ungraded`, ungraded assignments
+/// count as zero (final score); otherwise they are ignored (current score).
+pub fn group_score(group: &AssignmentGroupGrades, include_ungraded: bool) -> GroupScore {
+    let items: Vec<Scored> = group
+        .items
+        .iter()
+        .filter(|item| !item.excused && !item.omit_from_final_grade)
+        .filter_map(|item| match item.score {
+            Some(score) => Some(Scored {
+                assignment_id: item.assignment_id,
+                score,
+                total: item.points_possible,
+            }),
+            None if include_ungraded => Some(Scored {
+                assignment_id: item.assignment_id,
+                score: 0.0,
+                total: item.points_possible,
+            }),
+            None => None,
+        })
+        .collect();
+
+    let kept = drop_assignments(&items, &group.rules);
+    let dropped = items
+        .iter()
+        .filter(|item| !kept.iter().any(|k| k.assignment_id == item.assignment_id))
+        .map(|item| item.assignment_id)
+        .collect();
+
+    GroupScore {
+        id: group.id,
+        score: kept.iter().map(|k| k.score).sum(),
+        possible: kept.iter().map(|k| k.total).sum(),
+        dropped,
+    }
+}
+
+fn course_score(
+    groups: &[AssignmentGroupGrades],
+    scores: &[GroupScore],
+    weighted_groups: bool,
+) -> Option<f64> {
+    if weighted_groups {
+        let relevant: Vec<(f64, &GroupScore)> = groups
+            .iter()
+            .zip(scores)
+            .filter(|(_, score)| score.possible > 0.0)
+            .map(|(group, score)| (group.group_weight, score))
+            .collect();
+        let full_weight: f64 = relevant.iter().map(|(weight, _)| weight).sum();
+        if full_weight == 0.0 {
+            return None;
+        }
+        let grade: f64 = relevant
+            .iter()
+            .map(|(weight, score)| score.score / score.possible * weight)
+            .sum();
+        // Pesos de grupos sem pontos são redistribuídos entre os demais
+        if full_weight < 100.0 {
+            Some(grade * 100.0 / full_weight)
+        } else {
+            Some(grade)
+        }
+    } else {
+        let score: f64 = scores.iter().map(|s| s.score).sum();
+        let possible: f64 = scores.iter().map(|s| s.possible).sum();
+        if possible > 0.0 {
+            Some(score / possible * 100.0)
+        } else {
+            None
+        }
+    }
+}
+
+// Applies the drop rules of a group and returns the kept assignments.
+fn drop_assignments(items: &[Scored], rules: &DropRules) -> Vec<Scored> {
+    if rules.drop_lowest == 0 && rules.drop_highest == 0 {
+        return items.to_vec();
+    }
+
+    let (cant_drop, droppable): (Vec<Scored>, Vec<Scored>) = items
+        .iter()
+        .partition(|item| rules.never_drop.contains(&item.assignment_id));
+    if droppable.is_empty() {
+        return items.to_vec();
+    }
+
+    // Canvas always keeps at least one droppable assignment
+    let drop_lowest = rules.drop_lowest.min(droppable.len() - 1);
+    let drop_highest = if drop_lowest > 0 {
+        rules.drop_highest.min(droppable.len() - 1 - drop_lowest)
+    } else {
+        rules.drop_highest.min(droppable.len() - 1)
+    };
+    let keep_highest = droppable.len() - drop_lowest;
+    let keep_lowest = keep_highest - drop_highest;
+
+    let kept = if droppable.iter().any(|item| item.total > 0.0) {
+        let kept = keep_helper(&droppable, &cant_drop, keep_highest, true);
+        keep_helper(&kept, &cant_drop, keep_lowest, false)
+    } else {
+        let mut sorted = droppable.clone();
+        sorted.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+        sorted.truncate(keep_highest);
+        sorted.sort_by(|a, b| a.score.partial_cmp(&b.score).unwrap_or(Ordering::Equal));
+        sorted.truncate(keep_lowest);
+        sorted
+    };
+
+    cant_drop.into_iter().chain(kept).collect()
+}
+
+// Selects `keep` assignments maximizing (or, with `highest == false`, minimizing) the group
+// percentage, by binary search on the target ratio q.
+fn keep_helper(items: &[Scored], cant_drop: &[Scored], keep: usize, highest: bool) -> Vec<Scored> {
+    let keep = keep.max(1);
+    if items.len() <= keep {
+        return items.to_vec();
+    }
+
+    let max_total = items
+        .iter()
+        .chain(cant_drop)
+        .map(|item| item.total)
+        .fold(0.0, f64::max);
+    let ratios: Vec<f64> = items
+        .iter()
+        .chain(cant_drop)
+        .filter(|item| item.total > 0.0)
+        .map(|item| item.score / item.total)
+        .collect();
+    let mut q_low = ratios.iter().copied().fold(f64::INFINITY, f64::min);
+    let mut q_high = ratios.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    if !q_low.is_finite() || !q_high.is_finite() {
+        q_low = 0.0;
+        q_high = 0.0;
+    }
+    let mut q_mid = (q_low + q_high) / 2.0;
+
+    let big_f = |q: f64| -> (f64, Vec<Scored>) {
+        let mut rated: Vec<(f64, Scored)> = items.iter().map(|item| (item.score - q * item.total, *item)).collect();
+        rated.sort_by(|a, b| {
+            let order = a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal);
+            if highest {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        rated.truncate(keep);
+        let mut total: f64 = rated.iter().map(|(value, _)| value).sum();
+        total += cant_drop.iter().map(|item| item.score - q * item.total).sum::<f64>();
+        (total, rated.into_iter().map(|(_, item)| item).collect())
+    };
+
+    let (mut x, mut kept) = big_f(q_mid);
+    let threshold = 1.0 / (2.0 * keep as f64 * max_total.powi(2));
+    while q_high - q_low >= threshold {
+        if x < 0.0 {
+            q_high = q_mid;
+        } else {
+            q_low = q_mid;
+        }
+        q_mid = (q_low + q_high) / 2.0;
+        if q_mid == q_high || q_mid == q_low {
+            break;
+        }
+        (x, kept) = big_f(q_mid);
+    }
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(assignment_id: u64, score: Option<f64>, points_possible: f64) -> GradedItem {
+        GradedItem {
+            assignment_id,
+            points_possible,
+            score,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_weighted_current_and_final_scores() {
+        let groups = vec![
+            AssignmentGroupGrades {
+                id: 1,
+                name: "Provas".to_string(),
+                group_weight: 60.0,
+                items: vec![item(10, Some(8.0), 10.0), item(11, None, 10.0)],
+                ..Default::default()
+            },
+            AssignmentGroupGrades {
+                id: 2,
+                name: "Listas".to_string(),
+                group_weight: 40.0,
+                items: vec![item(20, Some(5.0), 5.0)],
+                ..Default::default()
+            },
+        ];
+        let result = calculate(&groups, true);
+        // current: 0.8 * 60 + 1.0 * 40 = 88
+        assert!((result.current_score.unwrap() - 88.0).abs() < 1e-9);
+        // final: 0.4 * 60 + 1.0 * 40 = 64
+        assert!((result.final_score.unwrap() - 64.0).abs() < 1e-9);
+
+        // Without grades in the second group, its weight is redistributed
+        let mut groups = groups;
+        groups[1].items[0].score = None;
+        let result = calculate(&groups, true);
+        assert!((result.current_score.unwrap() - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_drop_lowest_maximizes_percentage() {
+        // Dropping the 2/10 (20%) keeps 60% better than dropping the 0/1 (0%) on a smaller item.
+        let group = AssignmentGroupGrades {
+            id: 1,
+            name: "Quizzes".to_string(),
+            group_weight: 100.0,
+            rules: DropRules {
+                drop_lowest: 1,
+                ..Default::default()
+            },
+            items: vec![item(1, Some(2.0), 10.0), item(2, Some(0.0), 1.0), item(3, Some(9.0), 10.0)],
+        };
+        let score = group_score(&group, false);
+        assert_eq!(score.dropped, vec![1]);
+        assert_eq!(score.score, 9.0);
+        assert_eq!(score.possible, 11.0);
+
+        // never_drop protects the assignment
+        let mut group = group;
+        group.rules.never_drop = vec![1];
+        let score = group_score(&group, false);
+        assert_eq!(score.dropped, vec![2]);
+    }
+
+    #[test]
+    fn test_unweighted_and_excused() {
+        let mut excused = item(3, None, 50.0);
+        excused.excused = true;
+        let groups = vec![AssignmentGroupGrades {
+            id: 1,
+            name: "Atividades".to_string(),
+            items: vec![item(1, Some(15.0), 20.0), item(2, Some(25.0), 30.0), excused],
+            ..Default::default()
+        }];
+        let result = calculate(&groups, false);
+        assert!((result.current_score.unwrap() - 80.0).abs() < 1e-9);
+        assert_eq!(result.current_score, result.final_score);
+        assert_eq!(calculate(&[], false).current_score, None);
+    }
+}
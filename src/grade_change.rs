@@ -0,0 +1,191 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_pages_following_links;
+use crate::CanvasCredentials;
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// Objects a grade change event refers to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GradeChangeLinks {
+    pub assignment: Option<u64>,
+    pub course: Option<u64>,
+    pub student: Option<u64>,
+    pub grader: Option<u64>,
+    pub page_view: Option<String>,
+}
+
+/// An entry of the grade change audit log.
+///
+/// Fields:
+/// - `id`: ID of the event.
+/// - `created_at`: When the grade was changed.
+/// - `event_type`: `grade_change`.
+/// - `grade_before` / `grade_after`: Grade shown before and after the change.
+/// - `excused_before` / `excused_after`: Excused status before and after the change.
+/// - `graded_anonymously`: Whether the grader couldn't see the student's name.
+/// - `version_number`: Version of the submission after the change.
+/// - `request_id`: ID of the request that made the change.
+/// - `links`: Assignment, course, student and grader of the change.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GradeChangeEvent {
+    pub id: String,
+    pub created_at: DateTime<Utc>,
+    pub event_type: String,
+    pub grade_before: Option<String>,
+    pub grade_after: Option<String>,
+    pub excused_before: Option<bool>,
+    pub excused_after: Option<bool>,
+    pub graded_anonymously: Option<bool>,
+    pub version_number: Option<u64>,
+    pub request_id: Option<String>,
+    #[serde(default)]
+    pub links: GradeChangeLinks,
+}
+
+/// Filters of a grade change audit log search. At least one of course, assignment, student or
+/// grader must be set.
+///
+/// Example:
+/// ```
+/// // Every change to one student's grades in a course during the exam week
+/// let query = GradeChangeQuery::default()
+///     .course_id(course.info.id)
+///     .student_id(student.info.id)
+///     .between(start, end);
+/// let events = fetch_grade_changes(&client, &canvas_info, &query)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GradeChangeQuery {
+    pub course_id: Option<u64>,
+    pub assignment_id: Option<u64>,
+    pub student_id: Option<u64>,
+    pub grader_id: Option<u64>,
+    pub start_time: Option<DateTime<Utc>>,
+    pub end_time: Option<DateTime<Utc>>,
+}
+
+impl GradeChangeQuery {
+    /// Keeps only changes in the given course.
+    pub fn course_id(mut self, course_id: u64) -> Self {
+        self.course_id = Some(course_id);
+        self
+    }
+
+    /// Keeps only changes to the given assignment.
+    pub fn assignment_id(mut self, assignment_id: u64) -> Self {
+        self.assignment_id = Some(assignment_id);
+        self
+    }
+
+    /// Keeps only changes to the grades of the given student.
+    pub fn student_id(mut self, student_id: u64) -> Self {
+        self.student_id = Some(student_id);
+        self
+    }
+
+    /// Keeps only changes made by the given grader.
+    pub fn grader_id(mut self, grader_id: u64) -> Self {
+        self.grader_id = Some(grader_id);
+        self
+    }
+
+    /// Keeps only changes made between `start` and `end`.
+    pub fn between(mut self, start: DateTime<Utc>, end: DateTime<Utc>) -> Self {
+        self.start_time = Some(start);
+        self.end_time = Some(end);
+        self
+    }
+
+    /// Converts the query into Canvas API query parameters.
+    pub fn to_params(&self) -> Vec<(String, String)> {
+        let mut params = Vec::new();
+        let ids = [
+            ("course_id", self.course_id),
+            ("assignment_id", self.assignment_id),
+            ("student_id", self.student_id),
+            ("grader_id", self.grader_id),
+        ];
+        for (name, id) in ids {
+            if let Some(id) = id {
+                params.push((name.to_string(), id.to_string()));
+            }
+        }
+        if let Some(start) = self.start_time {
+            params.push(("start_time".to_string(), start.to_rfc3339()));
+        }
+        if let Some(end) = self.end_time {
+            params.push(("end_time".to_string(), end.to_rfc3339()));
+        }
+        params
+    }
+}
+
+/// Searches the grade change audit log.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials (the user needs the "view grade audit trail" permission).
+/// - `query`: Filters of the search.
+///
+/// Returns:
+/// - `Result<Vec<GradeChangeEvent>, Box<dyn Error>>`: The events, newest first, or an error.
+pub fn fetch_grade_changes(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    query: &GradeChangeQuery,
+) -> Result<Vec<GradeChangeEvent>, Box<dyn Error>> {
+    if query.course_id.is_none()
+        && query.assignment_id.is_none()
+        && query.student_id.is_none()
+        && query.grader_id.is_none()
+    {
+        return Err("A grade change query needs a course, assignment, student or grader".into());
+    }
+    let url = format!("{}/audit/grade_change", canvas_info.url_canvas);
+    Ok(fetch_pages_following_links(client, canvas_info, &url, query.to_params())?
+        .iter()
+        .flat_map(|page| page["events"].as_array().cloned().unwrap_or_default())
+        .filter_map(|json| serde_json::from_value(json).ok())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_grade_change_query_and_event() {
+        let start = DateTime::parse_from_rfc3339("2024-06-01T00:00:00Z").unwrap().with_timezone(&Utc);
+        let end = DateTime::parse_from_rfc3339("2024-06-08T00:00:00Z").unwrap().with_timezone(&Utc);
+        let params = GradeChangeQuery::default().course_id(7).grader_id(3).between(start, end).to_params();
+        assert_eq!(
+            params,
+            vec![
+                ("course_id".to_string(), "7".to_string()),
+                ("grader_id".to_string(), "3".to_string()),
+                ("start_time".to_string(), "2024-06-01T00:00:00+00:00".to_string()),
+                ("end_time".to_string(), "2024-06-08T00:00:00+00:00".to_string()),
+            ]
+        );
+
+        let event: GradeChangeEvent = serde_json::from_value(json!({
+            "id": "e1b2",
+            "created_at": "2024-06-03T12:30:00Z",
+            "event_type": "grade_change",
+            "grade_before": "7",
+            "grade_after": "9.5",
+            "excused_before": false,
+            "excused_after": false,
+            "graded_anonymously": false,
+            "version_number": 3,
+            "request_id": "req-1",
+            "links": {"assignment": 50, "course": 7, "student": 100, "grader": 3, "page_view": null}
+        }))
+        .unwrap();
+        assert_eq!(event.grade_after.as_deref(), Some("9.5"));
+        assert_eq!(event.links.grader, Some(3));
+    }
+}
@@ -0,0 +1,193 @@
+// Import necessary crates and modules
+use crate::connection::{send_http_request, HttpMethod};
+use crate::params::Params;
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// One entry of a grading scheme: the letter `name` is given from `value` (a fraction between 0
+/// and 1) up to the value of the previous entry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GradingSchemeEntry {
+    pub name: String,
+    pub value: f64,
+}
+
+/// A grading standard (letter grade scheme) of a course or account.
+///
+/// Fields:
+/// - `id`: ID of the grading standard (`0` for the built-in Canvas default).
+/// - `title`: Name of the standard.
+/// - `grading_scheme`: Entries sorted from the highest to the lowest `value`.
+///
+/// Example:
+/// ```
+/// let standard = GradingStandard::canvas_default();
+/// assert_eq!(standard.letter_for(91.0), Some("A-"));
+/// assert_eq!(standard.percent_for("B"), Some(86.99));
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GradingStandard {
+    pub id: u64,
+    pub title: String,
+    pub grading_scheme: Vec<GradingSchemeEntry>,
+}
+
+impl GradingStandard {
+    /// Creates a grading standard, sorting the entries from the highest to the lowest value.
+    pub fn new(id: u64, title: &str, entries: &[(&str, f64)]) -> Self {
+        let mut grading_scheme: Vec<GradingSchemeEntry> = entries
+            .iter()
+            .map(|(name, value)| GradingSchemeEntry {
+                name: name.to_string(),
+                value: *value,
+            })
+            .collect();
+        grading_scheme.sort_by(|a, b| b.value.total_cmp(&a.value));
+        GradingStandard {
+            id,
+            title: title.to_string(),
+            grading_scheme,
+        }
+    }
+
+    /// The default scheme Canvas uses when a course enables letter grades without choosing one.
+    pub fn canvas_default() -> Self {
+        GradingStandard::new(
+            0,
+            "Default Grading Scheme",
+            &[
+                ("A", 0.94),
+                ("A-", 0.90),
+                ("B+", 0.87),
+                ("B", 0.84),
+                ("B-", 0.80),
+                ("C+", 0.77),
+                ("C", 0.74),
+                ("C-", 0.70),
+                ("D+", 0.67),
+                ("D", 0.64),
+                ("D-", 0.61),
+                ("F", 0.0),
+            ],
+        )
+    }
+
+    /// Converts a percentage (0-100) into the letter grade students see.
+    ///
+    /// As in Canvas, the percentage is rounded to two decimal places before the lookup, so 89.995
+    /// counts as 90. Returns `None` only if the scheme has no entry low enough.
+    pub fn letter_for(&self, percent: f64) -> Option<&str> {
+        let percent = (percent * 100.0).round() / 100.0;
+        self.grading_scheme
+            .iter()
+            .find(|entry| percent >= entry.value * 100.0 - 1e-9)
+            .map(|entry| entry.name.as_str())
+    }
+
+    /// Converts a letter grade back into a percentage (0-100).
+    ///
+    /// Like Canvas when a letter is typed in the gradebook, the highest percentage of the range is
+    /// used: 100 for the top entry, otherwise 0.01 below the lower bound of the next higher entry.
+    /// The comparison ignores case. Returns `None` for letters not in the scheme.
+    pub fn percent_for(&self, letter: &str) -> Option<f64> {
+        let index = self
+            .grading_scheme
+            .iter()
+            .position(|entry| entry.name.eq_ignore_ascii_case(letter.trim()))?;
+        if index == 0 {
+            Some(100.0)
+        } else {
+            let upper = self.grading_scheme[index - 1].value * 100.0 - 0.01;
+            Some((upper * 100.0).round() / 100.0)
+        }
+    }
+
+    /// Converts a score into a letter grade given the points possible of the assignment.
+    pub fn letter_for_score(&self, score: f64, points_possible: f64) -> Option<&str> {
+        if points_possible <= 0.0 {
+            return None;
+        }
+        self.letter_for(score / points_possible * 100.0)
+    }
+}
+
+/// Retrieves the grading standards available in a course (including those inherited from its
+/// account).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+///
+/// Returns:
+/// - `Result<Vec<GradingStandard>, Box<dyn Error>>`: The grading standards or an error.
+pub fn fetch_grading_standards(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+) -> Result<Vec<GradingStandard>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/grading_standards", canvas_info.url_canvas, course_id);
+    let params = Params::new().per_page(100);
+
+    let response = send_http_request(client, HttpMethod::Get, &url, canvas_info, params.into())?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch grading standards with status: {}",
+            response.status()
+        )
+        .into());
+    }
+    let mut standards: Vec<GradingStandard> = response.json()?;
+    for standard in standards.iter_mut() {
+        standard
+            .grading_scheme
+            .sort_by(|a, b| b.value.total_cmp(&a.value));
+    }
+    Ok(standards)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letter_and_percent_conversion() {
+        let standard = GradingStandard::canvas_default();
+        assert_eq!(standard.letter_for(100.0), Some("A"));
+        assert_eq!(standard.letter_for(94.0), Some("A"));
+        assert_eq!(standard.letter_for(93.994), Some("A-"));
+        assert_eq!(standard.letter_for(93.996), Some("A"));
+        assert_eq!(standard.letter_for(12.0), Some("F"));
+        assert_eq!(standard.letter_for_score(17.0, 20.0), Some("B"));
+
+        assert_eq!(standard.percent_for("A"), Some(100.0));
+        assert_eq!(standard.percent_for("b"), Some(86.99));
+        assert_eq!(standard.percent_for("Z"), None);
+        // Round trip: the percentage of a letter maps back to the same letter
+        for entry in &standard.grading_scheme {
+            let percent = standard.percent_for(&entry.name).unwrap();
+            assert_eq!(standard.letter_for(percent), Some(entry.name.as_str()));
+        }
+    }
+
+    #[test]
+    fn test_grading_standard_from_canvas_json() {
+        let json = r#"{"id": 5, "title": "Conceitos", "context_type": "Course",
+            "grading_scheme": [{"name": "C", "value": 0.5}, {"name": "A", "value": 0.9}, {"name": "B", "value": 0.7}]}"#;
+        let standard: GradingStandard = serde_json::from_str(json).unwrap();
+        let standard = GradingStandard::new(
+            standard.id,
+            &standard.title,
+            &standard
+                .grading_scheme
+                .iter()
+                .map(|e| (e.name.as_str(), e.value))
+                .collect::<Vec<_>>(),
+        );
+        assert_eq!(standard.grading_scheme[0].name, "A");
+        assert_eq!(standard.letter_for(75.0), Some("B"));
+        assert_eq!(standard.letter_for(10.0), None);
+    }
+}
@@ -0,0 +1,129 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages, fetch_all_pages_as};
+use crate::params::Params;
+use crate::{CanvasCredentials, Course, Student};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// A group of a group set (group category), as seen from one of its members.
+///
+/// Fields:
+/// - `id`: ID of the group.
+/// - `name`: Name of the group (e.g. "Grupo 5").
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct GroupRef {
+    pub id: u64,
+    #[serde(default)]
+    pub name: String,
+}
+
+/// A group with the user IDs of its members, as returned by `fetch_groups_with_members`.
+pub type GroupMembers = (GroupRef, Vec<u64>);
+
+/// A student with their group, as returned by `Course::fetch_students_with_groups`.
+pub type StudentWithGroup = (Student, Option<GroupRef>);
+
+/// Retrieves the groups of a group set with the IDs of their members.
+///
+/// The members come embedded in the group listing (`include[]=users`), so the whole group set
+/// takes a single paginated request; only groups listed without their members are fetched one by
+/// one.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `group_category_id`: ID of the group set.
+///
+/// Returns:
+/// - `Result<Vec<GroupMembers>, Box<dyn Error>>`: Each group with the user IDs of its
+///   members, or an error.
+pub fn fetch_groups_with_members(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    group_category_id: u64,
+) -> Result<Vec<GroupMembers>, Box<dyn Error>> {
+    let url = format!("{}/group_categories/{}/groups", canvas_info.url_canvas, group_category_id);
+    let params = Params::new().include(["users"]);
+    let user_ids = |users: &[Value]| users.iter().filter_map(|user| user["id"].as_u64()).collect::<Vec<_>>();
+    let mut groups = Vec::new();
+    for group in fetch_all_pages(client, canvas_info, &url, params.into())? {
+        let group_ref: GroupRef = serde_json::from_value(group.clone())?;
+        let members = match group["users"].as_array() {
+            Some(users) => user_ids(users),
+            None => {
+                let users_url = format!("{}/groups/{}/users", canvas_info.url_canvas, group_ref.id);
+                user_ids(&fetch_all_pages_as::<Value>(client, canvas_info, &users_url, Vec::new())?)
+            }
+        };
+        groups.push((group_ref, members));
+    }
+    Ok(groups)
+}
+
+// Pairs each student with the group holding them, if any.
+fn join_students_and_groups(students: Vec<Student>, groups: Vec<GroupMembers>) -> Vec<StudentWithGroup> {
+    let mut group_of: HashMap<u64, GroupRef> = HashMap::new();
+    for (group, members) in groups {
+        for member in members {
+            group_of.insert(member, group.clone());
+        }
+    }
+    students
+        .into_iter()
+        .map(|student| {
+            let group = group_of.get(&student.info.id).cloned();
+            (student, group)
+        })
+        .collect()
+}
+
+impl Course {
+    /// Retrieves the students of the course, each with their group in the group set
+    /// `group_category_id` (`None` for students outside every group).
+    ///
+    /// Takes the roster and one listing of the group set, instead of a request per group.
+    ///
+    /// Example:
+    /// ```
+    /// for (student, group) in course.fetch_students_with_groups(assignment.info.group_category_id.unwrap())? {
+    ///     let group = group.map_or("(sem grupo)".to_string(), |group| group.name);
+    ///     println!("{};{}", student.info.name, group);
+    /// }
+    /// ```
+    pub fn fetch_students_with_groups(
+        &self,
+        group_category_id: u64,
+    ) -> Result<Vec<StudentWithGroup>, Box<dyn Error>> {
+        let students = self.fetch_students()?;
+        let groups = fetch_groups_with_members(&Client::new(), &self.info.canvas_info, group_category_id)?;
+        Ok(join_students_and_groups(students, groups))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StudentInfo;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_students_joined_with_their_groups() {
+        let student = |id: u64| Student {
+            info: Arc::new(StudentInfo {
+                id,
+                ..Default::default()
+            }),
+        };
+        let group = GroupRef {
+            id: 5,
+            name: "Grupo 5".to_string(),
+        };
+        let joined = join_students_and_groups(vec![student(10), student(11)], vec![(group.clone(), vec![11, 99])]);
+        let groups: Vec<(u64, Option<GroupRef>)> =
+            joined.into_iter().map(|(student, group)| (student.info.id, group)).collect();
+        assert_eq!(groups, vec![(10, None), (11, Some(group))]);
+    }
+}
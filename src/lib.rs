@@ -51,25 +51,156 @@
 //!     CanvasResultSingleCourse::Ok(course) => println!("Course: {:?}", course),
 //!     CanvasResultSingleCourse::ErrConnection(err) => eprintln!("Connection error: {}", err),
 //!     CanvasResultSingleCourse::ErrCredentials(err) => eprintln!("Credentials error: {}", err),
+//!     CanvasResultSingleCourse::ErrAccessRestricted(id) => eprintln!("Course {} is restricted by date", id),
 //! }
 //! ```
+pub mod academic_calendar; // Academic calendar rules used to derive year and semester.
+pub mod account; // Canvas accounts and admin operations.
+pub mod activity_stream; // Activity stream (recent activity) of the authenticated user.
+pub mod appointment_group; // Scheduler appointment groups, slots and reservations.
 mod assignment; // Manages assignments within Canvas courses.
+pub mod audit; // Audit log of mutating requests.
+pub mod authentication_log; // Authentication (login/logout) audit log.
+pub mod blueprint; // Blueprint courses and sync status.
+pub mod bookmark; // Bookmarks of the authenticated user.
+pub mod calendar; // Upcoming events and calendar feed of the authenticated user.
 pub mod canvas;
+pub mod cassette; // Record-and-replay of HTTP interactions for deterministic tests.
+mod client; // Public client with raw access to any endpoint.
+mod concurrency; // Adaptive limit of simultaneous requests.
+pub mod conference; // Web conferences (BigBlueButton) and their recordings.
 mod connection; // Manages HTTP connections and requests to the Canvas API.
+pub mod content_share; // Content sent between instructors (Direct Share).
 pub mod course; // Contains functionalities related to Canvas courses.
+pub mod course_file; // Files of a course and uploads for embedding in content.
+pub mod course_settings; // Course-level settings (discussions, grade visibility...).
 pub mod credentials; // Handles the storage and retrieval of Canvas API credentials.
+pub mod custom_data; // Per-user custom data store (users/self/custom_data).
+pub mod dashboard; // Course nicknames and dashboard card positions.
+pub mod discussion; // Discussion topics, entries and read state.
+pub mod enrollment; // Enrollments and invitation handling.
+pub mod epub; // ePub exports of courses.
+mod error; // Error type of requests to Canvas.
+pub mod grade_calc; // Local weighted-grade calculator following the Canvas rules.
+pub mod grade_change; // Grade change audit log searches.
+pub mod grading_standard; // Letter grade schemes and score conversion.
+pub mod group; // Groups of a group set and their members.
+#[cfg(feature = "live_events")]
+pub mod live_events; // Parses Canvas Live Events (webhook / SQS payloads).
+pub mod login; // User logins (pseudonyms).
+#[cfg(feature = "markdown")]
+pub mod markdown; // Markdown to Canvas HTML conversion.
+pub mod metrics; // Optional metrics facade for HTTP traffic.
+pub mod module; // Course modules, module items and publish state.
+pub mod originality_report; // Similarity reports of the plagiarism platform.
+pub mod page; // Wiki pages and their revision history.
+mod params; // Builder of query string parameters.
+pub mod peer_evaluation; // Ratings of teammates collected through a survey assignment.
+pub mod permissions; // Permissions of the credentials in a course, checked before batches.
+pub mod poll; // Polls, choices, sessions and participation.
+pub mod progress; // Polling of asynchronous Canvas jobs.
+pub mod quiz; // Classic quizzes, student answers and reports.
+mod response_cache; // Read-through cache of GET answers, by endpoint class.
 pub mod rubric_downloaded;
+pub mod rubric_results; // Students × criteria export of rubric assessments.
 pub mod rubric_submission;
+pub mod section; // Course sections and cross-listing.
+pub mod snapshot; // Offline, versioned JSON snapshots of courses.
+#[cfg(feature = "sqlite")]
+pub mod sqlite_archive; // Course snapshots written as SQLite databases.
 mod student; // Deals with operations related to students in Canvas courses.
+pub mod student_mode; // Student-side access with the token of a student (own courses, grades, submitting).
 mod submission; // Handles submissions for assignments in Canvas.
+pub mod sync; // Reconciles a local gradebook with Canvas.
+pub mod template; // Personalized comments and messages from templates.
+#[cfg(feature = "test-server")]
+pub mod test_server; // Local mock Canvas server for offline integration tests.
+pub mod timezone; // Rendering of UTC timestamps in the time zone of a course.
+pub mod todo; // To-do list and needs-grading counts of the authenticated user.
+pub mod usage; // Request cost and rate limit consumption per credentials.
+mod user; // Canvas users that are not tied to a course roster (teachers, TAs...).
+mod web_url; // Links to the Canvas web interface (SpeedGrader...).
 
 // Exports key structures for external use.
-pub use assignment::{Assignment, AssignmentInfo, GetSubmissionFromSubmissionIdCache};
+pub use academic_calendar::{AcademicCalendar, CourseTerm};
+pub use account::{Account, AccountCourseQuery, AccountInfo, CourseState};
+pub use activity_stream::{ActivityItem, ActivityKind, ActivitySummary};
+pub use appointment_group::{
+    AppointmentGroup, AppointmentSlot, NewAppointmentGroup, Reservation, ReservationRecord, ReservationUser,
+};
+pub use assignment::{
+    Assignment, AssignmentBucket, AssignmentDate, AssignmentInfo, AssignmentOrder,
+    AssignmentOverride, AssignmentQuery, AssignmentStatistics, CommentRequest, CommentResult,
+    GetSubmissionFromSubmissionIdCache, GradingProgress, MessageCriterion, SectionNeedsGrading,
+};
+pub use audit::{set_audit_log, AuditEntry, AuditLog};
+pub use authentication_log::{AuthenticationEvent, AuthenticationLinks, AuthenticationLogTarget};
+pub use blueprint::{
+    AssociatedCourseStatus, BlueprintAssociatedCourse, BlueprintChange, BlueprintException, BlueprintMigration,
+    BlueprintStatus, BlueprintTemplate,
+};
+pub use bookmark::{Bookmark, BookmarkUpdate};
+pub use calendar::{CalendarAssignment, CalendarEvent};
 pub use canvas::{Canvas, CanvasResultCourses, CanvasResultSingleCourse};
-pub use course::{Course, CourseInfo};
-pub use credentials::CanvasCredentials;
-pub use student::{Student, StudentInfo};
-pub use submission::{Submission, SubmissionType};
+pub use cassette::{eject_cassette, insert_cassette, CassetteMode};
+pub use client::CanvasClient;
+pub use conference::{Conference, ConferenceRecording, PlaybackFormat};
+pub use concurrency::{RateLimitState, RATE_LIMIT_STATE_MAX_AGE_SECS};
+pub use connection::{
+    concurrency_limit, default_rate_limit_state_path, persist_rate_limit_state, response_size_limit, retry_policy,
+    set_response_size_limit, set_retry_policy, RetryPolicy, RetryReason, RetryRule,
+};
+pub use content_share::{ContentShare, ShareSourceCourse, ShareUser, ShareableContent};
+pub use course::{Course, CourseAccess, CourseInfo};
+pub use course_file::CourseFile;
+pub use course_settings::CourseSettings;
+pub use credentials::{
+    env_var_names, keyring_service, set_env_var_names, set_keyring_service, CanvasCredentials, CanvasEnvironment,
+    CredentialError, EnvVarNames, UserIdentity,
+};
+pub use dashboard::CourseNickname;
+pub use discussion::{
+    DiscussionAttachment, DiscussionEntry, DiscussionFullView, DiscussionParticipant, DiscussionTopic,
+    DiscussionTopicInfo, FlatDiscussionEntry, GroupTopicChild,
+};
+pub use enrollment::{CsvEnrollmentResult, Enrollment, EnrollmentInfo};
+pub use epub::{EpubAttachment, EpubExport};
+pub use error::CanvasError;
+pub use grade_change::{GradeChangeEvent, GradeChangeLinks, GradeChangeQuery};
+pub use grading_standard::{GradingSchemeEntry, GradingStandard};
+pub use group::{GroupMembers, GroupRef, StudentWithGroup};
+pub use login::{Login, LoginUpdate};
+pub use metrics::{set_metrics, InMemoryMetrics, Metrics, MetricsSnapshot};
+pub use module::{
+    Module, ModuleInfo, ModuleItem, ModuleItemSequence, ModuleItemSequenceEntry, SequenceAssetType,
+};
+pub use originality_report::{NewOriginalityReport, OriginalityReport};
+pub use page::{DiffLine, Page, PageEditor, PageInfo, PageRevision};
+pub use params::Params;
+pub use peer_evaluation::{PeerEvaluation, PeerEvaluationGroup, PeerEvaluationMember};
+pub use permissions::{CoursePermissions, COMMON_PERMISSIONS};
+pub use poll::{Poll, PollChoice, PollSession, PollSubmission};
+pub use progress::{wait_for_progress, Progress};
+pub use quiz::{Quiz, QuizAnswer, QuizInfo, QuizQuestion, StudentQuizAnswers};
+pub use response_cache::{
+    clear_response_cache, response_cache, set_response_cache, EndpointClass, ResponseCacheConfig,
+};
+pub use rubric_downloaded::{RubricAssociation, RubricDownloaded, RubricIdMapping};
+pub use rubric_results::RubricResults;
+pub use rubric_submission::RubricValidationError;
+pub use section::{Section, SectionInfo};
+pub use snapshot::{CourseSnapshot, RosterDiff};
+#[cfg(feature = "sqlite")]
+pub use sqlite_archive::SQLITE_SCHEMA_VERSION;
+pub use student::{CourseGrade, EnrollmentState, Student, StudentInfo};
+pub use student_mode::SubmissionContent;
+pub use submission::{
+    Comment, CommentAttachment, CriterionAssessment, GradeWriteOptions, GradeWriteOutcome, Submission, SubmissionType, SubmissionUpdate,
+};
+pub use template::Template;
+pub use todo::{TodoAssignment, TodoCounts, TodoItem};
+pub use usage::ApiUsage;
+pub use user::{merge_users, update_user, EnrollmentRole, User, UserUpdate};
 
 // #[cfg(test)]
 // mod tests {
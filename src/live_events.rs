@@ -0,0 +1,282 @@
+// Import necessary crates and modules
+use crate::submission::{Submission, SubmissionType};
+use crate::{AssignmentInfo, Student};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::sync::Arc;
+
+/// Metadata shared by every live event.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LiveEventMetadata {
+    pub event_name: String,
+    pub event_time: Option<DateTime<Utc>>,
+    pub context_type: Option<String>,
+    pub context_id: Option<u64>,
+    pub user_id: Option<u64>,
+}
+
+/// Body of `submission_created` and `submission_updated` events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubmissionEvent {
+    pub metadata: LiveEventMetadata,
+    pub submission_id: u64,
+    pub assignment_id: u64,
+    pub user_id: u64,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub submission_type: Option<SubmissionType>,
+    pub score: Option<f64>,
+    pub attempt: Option<u64>,
+}
+
+/// Body of `grade_change` events.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GradeChangeEvent {
+    pub metadata: LiveEventMetadata,
+    pub submission_id: u64,
+    pub assignment_id: u64,
+    pub student_id: u64,
+    pub grader_id: Option<u64>,
+    pub score: Option<f64>,
+    pub old_score: Option<f64>,
+    pub grade: Option<String>,
+    pub old_grade: Option<String>,
+}
+
+/// A parsed Canvas Live Event.
+///
+/// Canvas can publish Live Events (in the "Canvas" raw format) to an SQS queue or an HTTPS
+/// webhook. Parsing them with `parse_live_event` or `parse_sqs_message` lets event-driven graders
+/// react to new submissions and grade changes without polling `get_all_submissions`. Receiving the
+/// messages is left to the caller's SQS client or web framework. Events not modeled here are
+/// returned as `Other` with their raw body.
+///
+/// Example:
+/// ```
+/// match parse_sqs_message(&message.body)? {
+///     LiveEvent::SubmissionCreated(event) => grade_queue.push(event.submission_id),
+///     LiveEvent::GradeChange(event) => println!("{} -> {:?}", event.student_id, event.score),
+///     _ => {}
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum LiveEvent {
+    SubmissionCreated(SubmissionEvent),
+    SubmissionUpdated(SubmissionEvent),
+    GradeChange(GradeChangeEvent),
+    Other {
+        metadata: LiveEventMetadata,
+        body: Value,
+    },
+}
+
+impl LiveEvent {
+    /// Returns the metadata of the event.
+    pub fn metadata(&self) -> &LiveEventMetadata {
+        match self {
+            LiveEvent::SubmissionCreated(e) | LiveEvent::SubmissionUpdated(e) => &e.metadata,
+            LiveEvent::GradeChange(e) => &e.metadata,
+            LiveEvent::Other { metadata, .. } => metadata,
+        }
+    }
+}
+
+impl SubmissionEvent {
+    /// Builds a `Submission` from the event, attaching the given assignment and the matching
+    /// student from `students` (if present).
+    pub fn to_submission(&self, assignment_info: Arc<AssignmentInfo>, students: &[Student]) -> Submission {
+        Submission {
+            id: self.submission_id,
+            assignment_id: self.assignment_id,
+            score: self.score,
+            submitted_at: self.submitted_at,
+            submission_type: self.submission_type.clone(),
+            students_info: students
+                .iter()
+                .filter(|student| student.info.id == self.user_id)
+                .map(|student| student.info.clone())
+                .collect(),
+            assignment_info,
+            file_ids: Vec::new(),
+            comments: Vec::new(),
+            extra: Default::default(),
+        }
+    }
+}
+
+// Live events encode IDs as strings; accept numbers as well.
+fn id_of(value: &Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+// Scores may also come as strings.
+fn number_of(value: &Value) -> Option<f64> {
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|s| s.parse().ok()))
+}
+
+fn time_of(value: &Value) -> Option<DateTime<Utc>> {
+    value
+        .as_str()
+        .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+fn required_id(body: &Value, field: &str) -> Result<u64, Box<dyn Error>> {
+    id_of(&body[field]).ok_or_else(|| format!("Live event is missing '{}'", field).into())
+}
+
+/// Parses a live event in the Canvas format (`{"metadata": {...}, "body": {...}}`).
+pub fn parse_live_event(payload: &str) -> Result<LiveEvent, Box<dyn Error>> {
+    let event: Value = serde_json::from_str(payload)?;
+    parse_live_event_value(&event)
+}
+
+/// Parses an already-decoded live event.
+pub fn parse_live_event_value(event: &Value) -> Result<LiveEvent, Box<dyn Error>> {
+    let meta = &event["metadata"];
+    let body = &event["body"];
+    let metadata = LiveEventMetadata {
+        event_name: meta["event_name"]
+            .as_str()
+            .ok_or("Live event is missing 'metadata.event_name'")?
+            .to_string(),
+        event_time: time_of(&meta["event_time"]),
+        context_type: meta["context_type"].as_str().map(String::from),
+        context_id: id_of(&meta["context_id"]),
+        user_id: id_of(&meta["user_id"]),
+    };
+
+    let event = match metadata.event_name.as_str() {
+        "submission_created" | "submission_updated" => {
+            let submission = SubmissionEvent {
+                submission_id: required_id(body, "submission_id")?,
+                assignment_id: required_id(body, "assignment_id")?,
+                user_id: required_id(body, "user_id")?,
+                submitted_at: time_of(&body["submitted_at"]),
+                submission_type: body["submission_type"]
+                    .as_str()
+                    .map(SubmissionType::from),
+                score: number_of(&body["score"]),
+                attempt: id_of(&body["attempt"]),
+                metadata: metadata.clone(),
+            };
+            if metadata.event_name == "submission_created" {
+                LiveEvent::SubmissionCreated(submission)
+            } else {
+                LiveEvent::SubmissionUpdated(submission)
+            }
+        }
+        "grade_change" => LiveEvent::GradeChange(GradeChangeEvent {
+            submission_id: required_id(body, "submission_id")?,
+            assignment_id: required_id(body, "assignment_id")?,
+            student_id: id_of(&body["student_id"])
+                .or_else(|| id_of(&body["user_id"]))
+                .ok_or("Live event is missing 'student_id'")?,
+            grader_id: id_of(&body["grader_id"]),
+            score: number_of(&body["score"]),
+            old_score: number_of(&body["old_score"]),
+            grade: body["grade"].as_str().map(String::from),
+            old_grade: body["old_grade"].as_str().map(String::from),
+            metadata,
+        }),
+        _ => LiveEvent::Other {
+            metadata,
+            body: body.clone(),
+        },
+    };
+    Ok(event)
+}
+
+/// Parses the body of an SQS message carrying a live event.
+///
+/// Handles both raw delivery and messages wrapped in an SNS envelope (`{"Type": "Notification",
+/// "Message": "<event json>"}`).
+pub fn parse_sqs_message(message_body: &str) -> Result<LiveEvent, Box<dyn Error>> {
+    let value: Value = serde_json::from_str(message_body)?;
+    match value["Message"].as_str() {
+        Some(inner) => parse_live_event(inner),
+        None => parse_live_event_value(&value),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_parse_submission_created() {
+        let payload = json!({
+            "metadata": {
+                "event_name": "submission_created",
+                "event_time": "2024-04-02T10:00:00Z",
+                "context_type": "Course",
+                "context_id": "321",
+                "user_id": "55"
+            },
+            "body": {
+                "submission_id": "900",
+                "assignment_id": "77",
+                "user_id": "55",
+                "submitted_at": "2024-04-02T09:59:58Z",
+                "submission_type": "online_upload",
+                "attempt": "1"
+            }
+        })
+        .to_string();
+
+        match parse_live_event(&payload).unwrap() {
+            LiveEvent::SubmissionCreated(event) => {
+                assert_eq!(event.submission_id, 900);
+                assert_eq!(event.assignment_id, 77);
+                assert_eq!(event.metadata.context_id, Some(321));
+                assert_eq!(event.submission_type, Some(SubmissionType::OnlineUpload));
+                let submission = event.to_submission(Arc::new(AssignmentInfo::default()), &[]);
+                assert_eq!(submission.id, 900);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_parse_grade_change_from_sns_envelope() {
+        let event = json!({
+            "metadata": {"event_name": "grade_change", "context_id": "321"},
+            "body": {
+                "submission_id": "900",
+                "assignment_id": "77",
+                "student_id": "55",
+                "grader_id": "3",
+                "score": 8.5,
+                "old_score": null,
+                "grade": "8.5"
+            }
+        });
+        let envelope = json!({"Type": "Notification", "Message": event.to_string()}).to_string();
+
+        match parse_sqs_message(&envelope).unwrap() {
+            LiveEvent::GradeChange(event) => {
+                assert_eq!(event.student_id, 55);
+                assert_eq!(event.grader_id, Some(3));
+                assert_eq!(event.score, Some(8.5));
+                assert_eq!(event.old_score, None);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_unknown_events_are_kept() {
+        let payload = json!({"metadata": {"event_name": "wiki_page_updated"}, "body": {"wiki_page_id": "1"}});
+        let event = parse_live_event_value(&payload).unwrap();
+        assert_eq!(event.metadata().event_name, "wiki_page_updated");
+        assert!(matches!(event, LiveEvent::Other { .. }));
+
+        assert!(parse_live_event("{\"body\": {}}").is_err());
+    }
+}
@@ -0,0 +1,173 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_all_pages;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// A login (pseudonym) of a user: the credentials used to sign in to Canvas.
+///
+/// Fields:
+/// - `id`: ID of the login.
+/// - `user_id`: User the login belongs to.
+/// - `account_id`: Account the login belongs to.
+/// - `unique_id`: Username used to sign in.
+/// - `sis_user_id`: SIS identifier of the user (registrar ID).
+/// - `integration_id`: Secondary SIS identifier.
+/// - `authentication_provider_id` / `authentication_provider_type`: Provider handling the sign
+///   in (`None` for Canvas passwords).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Login {
+    pub id: u64,
+    pub user_id: u64,
+    pub account_id: u64,
+    pub unique_id: String,
+    pub sis_user_id: Option<String>,
+    pub integration_id: Option<String>,
+    pub authentication_provider_id: Option<u64>,
+    pub authentication_provider_type: Option<String>,
+}
+
+/// Fields to change in a login. Only the fields set to `Some` are sent.
+///
+/// Example:
+/// ```
+/// let update = LoginUpdate {
+///     sis_user_id: Some("2024001234".to_string()),
+///     ..Default::default()
+/// };
+/// account.update_login(&client, login.id, &update)?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct LoginUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unique_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sis_user_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub integration_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub authentication_provider_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+}
+
+/// Lists the logins of every user of an account.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `account_id`: ID of the account.
+///
+/// Returns:
+/// - `Result<Vec<Login>, Box<dyn Error>>`: The logins or an error.
+pub fn fetch_account_logins(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    account_id: u64,
+) -> Result<Vec<Login>, Box<dyn Error>> {
+    let url = format!("{}/accounts/{}/logins", canvas_info.url_canvas, account_id);
+    fetch_logins(client, canvas_info, &url)
+}
+
+/// Lists the logins of one user.
+pub fn fetch_user_logins(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    user_id: u64,
+) -> Result<Vec<Login>, Box<dyn Error>> {
+    let url = format!("{}/users/{}/logins", canvas_info.url_canvas, user_id);
+    fetch_logins(client, canvas_info, &url)
+}
+
+// Downloads and decodes every page of a logins listing.
+fn fetch_logins(client: &Client, canvas_info: &CanvasCredentials, url: &str) -> Result<Vec<Login>, Box<dyn Error>> {
+    Ok(fetch_all_pages(client, canvas_info, url, Vec::new())?
+        .into_iter()
+        .filter_map(|login| serde_json::from_value(login).ok())
+        .collect())
+}
+
+/// Changes a login.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `account_id`: Account of the login.
+/// - `login_id`: ID of the login.
+/// - `update`: Fields to change.
+///
+/// Returns:
+/// - `Result<Login, Box<dyn Error>>`: The updated login or an error.
+pub fn update_login(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    account_id: u64,
+    login_id: u64,
+    update: &LoginUpdate,
+) -> Result<Login, Box<dyn Error>> {
+    let url = format!("{}/accounts/{}/logins/{}", canvas_info.url_canvas, account_id, login_id);
+    let body = json!({ "login": update });
+    send_login_request(client, canvas_info, HttpMethod::Put(body), &url)
+}
+
+/// Adds a login to an existing user. `update.unique_id` is required by Canvas.
+pub fn create_login(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    account_id: u64,
+    user_id: u64,
+    update: &LoginUpdate,
+) -> Result<Login, Box<dyn Error>> {
+    if update.unique_id.is_none() {
+        return Err("A unique_id is required to create a login".into());
+    }
+    let url = format!("{}/accounts/{}/logins", canvas_info.url_canvas, account_id);
+    let body = json!({ "user": { "id": user_id }, "login": update });
+    send_login_request(client, canvas_info, HttpMethod::Post(body), &url)
+}
+
+/// Deletes a login of a user.
+pub fn delete_login(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    user_id: u64,
+    login_id: u64,
+) -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/users/{}/logins/{}", canvas_info.url_canvas, user_id, login_id);
+    send_login_request::<Value>(client, canvas_info, HttpMethod::Delete, &url)?;
+    Ok(())
+}
+
+// Sends a mutating request to a logins endpoint and decodes the answer.
+fn send_login_request<T: serde::de::DeserializeOwned>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    method: HttpMethod,
+    url: &str,
+) -> Result<T, Box<dyn Error>> {
+    let response = send_http_request(client, method, url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update login with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_login_update_sends_only_set_fields() {
+        let update = LoginUpdate {
+            sis_user_id: Some("2024001234".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            json!({ "login": update }),
+            json!({ "login": { "sis_user_id": "2024001234" } })
+        );
+    }
+}
@@ -0,0 +1,58 @@
+// Import necessary crates and modules
+use pulldown_cmark::{html, CowStr, Event, Options, Parser};
+
+// Builds the image Canvas uses to render an equation, as inserted by its equation editor.
+fn equation_image(latex: &str, display: bool) -> String {
+    // The equation service expects the LaTeX encoded twice
+    let encoded = urlencoding::encode(&urlencoding::encode(latex)).into_owned();
+    let escaped = latex.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;");
+    let style = if display { r#" style="display: block; margin: 0 auto;""# } else { "" };
+    format!(
+        r#"<img class="equation_image" title="{0}" src="/equation_images/{1}?scale=1" alt="LaTeX: {0}" data-equation-content="{0}"{2}>"#,
+        escaped, encoded, style
+    )
+}
+
+/// Converts Markdown into HTML accepted by Canvas, for assignment descriptions, pages,
+/// announcements and discussion messages.
+///
+/// Supported: the CommonMark syntax, tables, strikethrough, task lists, code fences (rendered as
+/// `<pre><code class="language-...">`), and LaTeX between `$...$` (inline) or `$$...$$` (on its
+/// own line), rendered as Canvas equation images. Raw HTML in the Markdown is kept.
+///
+/// Requires the `markdown` feature.
+///
+/// Example:
+/// ```
+/// let message = markdown_to_canvas_html(&std::fs::read_to_string("avisos/prova.md")?);
+/// course.create_announcement(&client, "Prova 1", &message)?;
+/// ```
+pub fn markdown_to_canvas_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_MATH;
+    let events = Parser::new_ext(markdown, options).map(|event| match event {
+        Event::InlineMath(latex) => Event::InlineHtml(CowStr::from(equation_image(&latex, false))),
+        Event::DisplayMath(latex) => Event::InlineHtml(CowStr::from(equation_image(&latex, true))),
+        other => other,
+    });
+    let mut output = String::new();
+    html::push_html(&mut output, events);
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markdown_with_code_and_latex() {
+        let html = markdown_to_canvas_html("# Lista\n\nSeja $x^2$.\n\n$$a<b$$\n\n```rust\nfn main() {}\n```\n");
+        assert!(html.contains("<h1>Lista</h1>"));
+        assert!(html.contains(r#"src="/equation_images/x%255E2?scale=1""#));
+        assert!(html.contains(r#"title="a&lt;b""#));
+        assert!(html.contains(r#"style="display: block; margin: 0 auto;""#));
+        assert!(html.contains(r#"<pre><code class="language-rust">fn main() {}"#));
+    }
+}
@@ -0,0 +1,145 @@
+// Import necessary crates and modules
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
+
+/// Upper bounds (in milliseconds) of the latency histogram buckets used by `InMemoryMetrics`.
+/// Requests slower than the last bound are counted under `u64::MAX`.
+pub const LATENCY_BUCKETS_MS: [u64; 8] = [50, 100, 250, 500, 1000, 2500, 5000, 10000];
+
+/// Facade for collecting metrics about the HTTP traffic sent to Canvas.
+///
+/// Implement this trait to forward measurements to the monitoring system of your choice
+/// (Prometheus, StatsD, logs...) and register it with `set_metrics`. Every single HTTP attempt
+/// made by the connector, including retries, is reported.
+///
+/// Example:
+/// ```
+/// let metrics = Arc::new(InMemoryMetrics::default());
+/// set_metrics(Some(metrics.clone()));
+/// // ... use the connector ...
+/// println!("{:?}", metrics.snapshot());
+/// ```
+pub trait Metrics: Send + Sync {
+    /// Records a completed HTTP attempt. `status` is `0` when no response was received.
+    fn record_request(&self, method: &str, url: &str, status: u16, latency: Duration);
+
+    /// Records the value of the `X-Rate-Limit-Remaining` header of the latest response.
+    fn record_rate_limit_remaining(&self, _remaining: f64) {}
+}
+
+/// Point-in-time copy of the values collected by `InMemoryMetrics`.
+///
+/// Fields:
+/// - `requests`: Total number of HTTP attempts.
+/// - `errors`: Attempts that failed (network error or non-success status).
+/// - `errors_by_status`: Failed attempts grouped by status code (`0` for network errors).
+/// - `latency_histogram`: Attempt count per latency bucket, keyed by the bucket's upper bound in ms.
+/// - `total_latency`: Sum of all attempt latencies, useful to derive the mean.
+/// - `rate_limit_remaining`: Last reported `X-Rate-Limit-Remaining` value, if any.
+#[derive(Serialize, Debug, Clone, Default)]
+pub struct MetricsSnapshot {
+    pub requests: u64,
+    pub errors: u64,
+    pub errors_by_status: HashMap<u16, u64>,
+    pub latency_histogram: BTreeMap<u64, u64>,
+    pub total_latency: Duration,
+    pub rate_limit_remaining: Option<f64>,
+}
+
+impl MetricsSnapshot {
+    /// Mean latency of all recorded attempts, or `None` if nothing was recorded.
+    pub fn mean_latency(&self) -> Option<Duration> {
+        if self.requests == 0 {
+            None
+        } else {
+            Some(self.total_latency / self.requests as u32)
+        }
+    }
+}
+
+/// Built-in `Metrics` implementation that keeps counters in memory.
+#[derive(Default)]
+pub struct InMemoryMetrics {
+    data: Mutex<MetricsSnapshot>,
+}
+
+impl InMemoryMetrics {
+    /// Returns a copy of the metrics collected so far.
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        self.data.lock().unwrap().clone()
+    }
+
+    /// Resets all counters.
+    pub fn reset(&self) {
+        *self.data.lock().unwrap() = MetricsSnapshot::default();
+    }
+}
+
+impl Metrics for InMemoryMetrics {
+    fn record_request(&self, _method: &str, _url: &str, status: u16, latency: Duration) {
+        let mut data = self.data.lock().unwrap();
+        data.requests += 1;
+        if !(200..300).contains(&status) {
+            data.errors += 1;
+            *data.errors_by_status.entry(status).or_insert(0) += 1;
+        }
+        let latency_ms = latency.as_millis() as u64;
+        let bucket = LATENCY_BUCKETS_MS
+            .iter()
+            .copied()
+            .find(|bound| latency_ms <= *bound)
+            .unwrap_or(u64::MAX);
+        *data.latency_histogram.entry(bucket).or_insert(0) += 1;
+        data.total_latency += latency;
+    }
+
+    fn record_rate_limit_remaining(&self, remaining: f64) {
+        self.data.lock().unwrap().rate_limit_remaining = Some(remaining);
+    }
+}
+
+// Global metrics sink. `None` means metrics are disabled (the default).
+lazy_static! {
+    static ref METRICS: RwLock<Option<Arc<dyn Metrics>>> = RwLock::new(None);
+}
+
+/// Registers the metrics sink used by the connector, or disables metrics with `None`.
+pub fn set_metrics(metrics: Option<Arc<dyn Metrics>>) {
+    *METRICS.write().unwrap() = metrics;
+}
+
+/// Returns the currently registered metrics sink, if any.
+pub(crate) fn metrics() -> Option<Arc<dyn Metrics>> {
+    METRICS.read().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_metrics_counts_and_histogram() {
+        let metrics = InMemoryMetrics::default();
+        metrics.record_request("GET", "/courses", 200, Duration::from_millis(40));
+        metrics.record_request("GET", "/courses", 503, Duration::from_millis(300));
+        metrics.record_request("PUT", "/submissions", 0, Duration::from_secs(20));
+        metrics.record_rate_limit_remaining(512.5);
+
+        let snapshot = metrics.snapshot();
+        assert_eq!(snapshot.requests, 3);
+        assert_eq!(snapshot.errors, 2);
+        assert_eq!(snapshot.errors_by_status.get(&503), Some(&1));
+        assert_eq!(snapshot.errors_by_status.get(&0), Some(&1));
+        assert_eq!(snapshot.latency_histogram.get(&50), Some(&1));
+        assert_eq!(snapshot.latency_histogram.get(&500), Some(&1));
+        assert_eq!(snapshot.latency_histogram.get(&u64::MAX), Some(&1));
+        assert_eq!(snapshot.rate_limit_remaining, Some(512.5));
+        assert!(snapshot.mean_latency().is_some());
+
+        metrics.reset();
+        assert_eq!(metrics.snapshot().requests, 0);
+    }
+}
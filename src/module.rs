@@ -0,0 +1,351 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_all_pages;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::{CanvasCredentials, CourseInfo};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Information about a module of a course.
+///
+/// Fields:
+/// - `id`: ID of the module.
+/// - `name`: Name of the module.
+/// - `position`: Position of the module in the course.
+/// - `published`: Whether the module is visible to students.
+/// - `items_count`: Number of items in the module.
+/// - `course_info`: The course the module belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ModuleInfo {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub position: u64,
+    #[serde(default)]
+    pub published: bool,
+    #[serde(default)]
+    pub items_count: u64,
+    #[serde(skip)]
+    pub course_info: Arc<CourseInfo>,
+}
+
+/// A module of a course. See `Course::fetch_modules`.
+#[derive(Debug, Clone)]
+pub struct Module {
+    pub info: Arc<ModuleInfo>,
+}
+
+/// An item (page, assignment, file...) inside a module.
+///
+/// Fields:
+/// - `id`: ID of the module item.
+/// - `module_id`: ID of the module holding the item.
+/// - `title`: Title shown in the module.
+/// - `item_type`: Canvas type (`Page`, `Assignment`, `Quiz`, `File`, `SubHeader`...).
+/// - `content_id`: ID of the underlying content (absent for headers and external URLs).
+/// - `position`: Position inside the module.
+/// - `published`: Whether the item is visible to students (absent for some item types).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModuleItem {
+    pub id: u64,
+    pub module_id: u64,
+    pub title: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    pub content_id: Option<u64>,
+    #[serde(default)]
+    pub position: u64,
+    pub published: Option<bool>,
+}
+
+/// Kind of content identified by the `asset_id` of a module item sequence lookup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SequenceAssetType {
+    ModuleItem,
+    File,
+    Page,
+    Discussion,
+    Assignment,
+    Quiz,
+    ExternalTool,
+}
+
+impl SequenceAssetType {
+    /// Value of the `asset_type` parameter expected by Canvas.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SequenceAssetType::ModuleItem => "ModuleItem",
+            SequenceAssetType::File => "File",
+            SequenceAssetType::Page => "Page",
+            SequenceAssetType::Discussion => "Discussion",
+            SequenceAssetType::Assignment => "Assignment",
+            SequenceAssetType::Quiz => "Quiz",
+            SequenceAssetType::ExternalTool => "ExternalTool",
+        }
+    }
+}
+
+/// Position of a content item inside one module: the item itself and its neighbours.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModuleItemSequenceEntry {
+    pub prev: Option<ModuleItem>,
+    pub current: Option<ModuleItem>,
+    pub next: Option<ModuleItem>,
+}
+
+/// Result of the module item sequence lookup. A content item may appear in several modules, so
+/// there is one entry per occurrence; Canvas shows the "previous/next" buttons only when there
+/// is exactly one.
+///
+/// Example:
+/// ```
+/// let sequence = course.fetch_module_item_sequence(&client, SequenceAssetType::Assignment, 42)?;
+/// if let Some(next) = sequence.next() {
+///     println!("Next activity: {}", next.title);
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ModuleItemSequence {
+    #[serde(default)]
+    pub items: Vec<ModuleItemSequenceEntry>,
+}
+
+impl ModuleItemSequence {
+    /// Previous item, following the Canvas rule (only when the content is in a single module).
+    pub fn previous(&self) -> Option<&ModuleItem> {
+        match self.items.as_slice() {
+            [entry] => entry.prev.as_ref(),
+            _ => None,
+        }
+    }
+
+    /// Next item, following the Canvas rule (only when the content is in a single module).
+    pub fn next(&self) -> Option<&ModuleItem> {
+        match self.items.as_slice() {
+            [entry] => entry.next.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+impl Module {
+    /// Makes the module visible to students.
+    pub fn publish(&self, client: &Client) -> Result<Module, Box<dyn Error>> {
+        self.set_published(client, true)
+    }
+
+    /// Hides the module from students.
+    pub fn unpublish(&self, client: &Client) -> Result<Module, Box<dyn Error>> {
+        self.set_published(client, false)
+    }
+
+    /// Publishes or unpublishes the module. Returns the module as updated by Canvas.
+    pub fn set_published(&self, client: &Client, published: bool) -> Result<Module, Box<dyn Error>> {
+        let course_info = &self.info.course_info;
+        let json = update_module(
+            client,
+            &course_info.canvas_info,
+            course_info.id,
+            self.info.id,
+            json!({ "module": { "published": published } }),
+        )?;
+        module_from_json(course_info, json).ok_or_else(|| "Invalid module returned by Canvas".into())
+    }
+
+    /// Retrieves the items of the module.
+    pub fn fetch_items(&self, client: &Client) -> Result<Vec<ModuleItem>, Box<dyn Error>> {
+        let course_info = &self.info.course_info;
+        fetch_module_items(client, &course_info.canvas_info, course_info.id, self.info.id)
+    }
+
+    /// Publishes or unpublishes one item of the module.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `item_id`: ID of the module item.
+    /// - `published`: New publish state.
+    ///
+    /// Returns:
+    /// - `Result<ModuleItem, Box<dyn Error>>`: The item as updated by Canvas or an error.
+    pub fn set_item_published(
+        &self,
+        client: &Client,
+        item_id: u64,
+        published: bool,
+    ) -> Result<ModuleItem, Box<dyn Error>> {
+        let course_info = &self.info.course_info;
+        let canvas_info = &course_info.canvas_info;
+        let url = format!(
+            "{}/courses/{}/modules/{}/items/{}",
+            canvas_info.url_canvas, course_info.id, self.info.id, item_id
+        );
+        let body = json!({ "module_item": { "published": published } });
+        let response = send_http_request(client, HttpMethod::Put(body), &url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to update module item with status: {}", response.status()).into());
+        }
+        Ok(response.json()?)
+    }
+
+    /// Publishes every item of the module that is not yet published. Returns how many items
+    /// were changed.
+    pub fn publish_items(&self, client: &Client) -> Result<usize, Box<dyn Error>> {
+        let mut changed = 0;
+        for item in self.fetch_items(client)? {
+            if item.published == Some(false) {
+                self.set_item_published(client, item.id, true)?;
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+// Builds a `Module` bound to its course from the JSON returned by Canvas.
+fn module_from_json(course_info: &Arc<CourseInfo>, json: Value) -> Option<Module> {
+    let mut info: ModuleInfo = serde_json::from_value(json).ok()?;
+    info.course_info = Arc::clone(course_info);
+    Some(Module { info: Arc::new(info) })
+}
+
+// Sends a module update (PUT /courses/:course_id/modules/:id).
+fn update_module(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    module_id: u64,
+    body: Value,
+) -> Result<Value, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/modules/{}", canvas_info.url_canvas, course_id, module_id);
+    let response = send_http_request(client, HttpMethod::Put(body), &url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to update module with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+/// Retrieves the modules of a course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course whose modules are listed.
+///
+/// Returns:
+/// - `Result<Vec<Module>, Box<dyn Error>>`: The modules, in course order, or an error.
+pub fn fetch_modules(client: &Client, course_info: &Arc<CourseInfo>) -> Result<Vec<Module>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/modules", course_info.canvas_info.url_canvas, course_info.id);
+    let mut modules: Vec<Module> = fetch_all_pages(client, &course_info.canvas_info, &url, Vec::new())?
+        .into_iter()
+        .filter_map(|json| module_from_json(course_info, json))
+        .collect();
+    modules.sort_by_key(|module| module.info.position);
+    Ok(modules)
+}
+
+/// Retrieves the items of a module.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `module_id`: ID of the module.
+///
+/// Returns:
+/// - `Result<Vec<ModuleItem>, Box<dyn Error>>`: The items, in module order, or an error.
+pub fn fetch_module_items(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    module_id: u64,
+) -> Result<Vec<ModuleItem>, Box<dyn Error>> {
+    let url = format!(
+        "{}/courses/{}/modules/{}/items",
+        canvas_info.url_canvas, course_id, module_id
+    );
+    let mut items: Vec<ModuleItem> = fetch_all_pages(client, canvas_info, &url, Vec::new())?
+        .into_iter()
+        .filter_map(|item| serde_json::from_value(item).ok())
+        .collect();
+    items.sort_by_key(|item| item.position);
+    Ok(items)
+}
+
+/// Finds the previous and next module items around a content item, as used by the navigation
+/// footer of Canvas.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `asset_type`: Kind of content identified by `asset_id`.
+/// - `asset_id`: ID of the content (or of the module item, for `SequenceAssetType::ModuleItem`).
+///
+/// Returns:
+/// - `Result<ModuleItemSequence, Box<dyn Error>>`: The sequence or an error.
+pub fn fetch_module_item_sequence(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    asset_type: SequenceAssetType,
+    asset_id: u64,
+) -> Result<ModuleItemSequence, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/module_item_sequence", canvas_info.url_canvas, course_id);
+    let params = vec![
+        ("asset_type".to_string(), asset_type.as_str().to_string()),
+        ("asset_id".to_string(), asset_id.to_string()),
+    ];
+    let response = send_http_request(client, HttpMethod::Get, &url, canvas_info, params)?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "Failed to fetch module item sequence with status: {}",
+            response.status()
+        )
+        .into());
+    }
+    Ok(response.json()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_module_item_from_canvas_json() {
+        let item: ModuleItem = serde_json::from_value(json!({
+            "id": 768,
+            "module_id": 123,
+            "position": 1,
+            "title": "Leitura da semana 1",
+            "indent": 0,
+            "type": "Page",
+            "page_url": "leitura-semana-1",
+            "published": false
+        }))
+        .unwrap();
+        assert_eq!(item.item_type, "Page");
+        assert_eq!(item.content_id, None);
+        assert_eq!(item.published, Some(false));
+    }
+
+    #[test]
+    fn test_module_item_sequence_navigation() {
+        let sequence: ModuleItemSequence = serde_json::from_value(json!({
+            "items": [{
+                "prev": null,
+                "current": {"id": 1, "module_id": 9, "title": "Tarefa 1", "type": "Assignment", "content_id": 42},
+                "next": {"id": 2, "module_id": 9, "title": "Quiz 1", "type": "Quiz", "content_id": 7},
+                "mastery_path": null
+            }],
+            "modules": [{"id": 9, "name": "Semana 1"}]
+        }))
+        .unwrap();
+        assert!(sequence.previous().is_none());
+        assert_eq!(sequence.next().map(|item| item.title.as_str()), Some("Quiz 1"));
+
+        let mut twice = sequence.clone();
+        twice.items.push(sequence.items[0].clone());
+        assert!(twice.next().is_none());
+    }
+}
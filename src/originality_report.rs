@@ -0,0 +1,221 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::{CanvasCredentials, Submission};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// LTI tool setting linked to a report, opened by SpeedGrader instead of `originality_report_url`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct ReportToolSetting {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_type_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_url: Option<String>,
+}
+
+/// Similarity report of a submission (or of one of its files), as shown in SpeedGrader.
+///
+/// Fields:
+/// - `id`: ID of the report.
+/// - `file_id`: File checked, or `None` for text entry submissions.
+/// - `originality_score`: Similarity percentage (0 to 100).
+/// - `originality_report_url`: Page with the details of the report.
+/// - `originality_report_file_id`: File with the report, if uploaded to Canvas.
+/// - `workflow_state`: `pending`, `scored` or `error`.
+/// - `error_message`: Why the check failed, in the `error` state.
+/// - `submission_time`: Time of the submission attempt that was checked.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct OriginalityReport {
+    pub id: u64,
+    pub file_id: Option<u64>,
+    pub originality_score: Option<f64>,
+    pub originality_report_url: Option<String>,
+    pub originality_report_file_id: Option<u64>,
+    pub workflow_state: Option<String>,
+    pub error_message: Option<String>,
+    pub submission_time: Option<DateTime<Utc>>,
+    pub tool_setting: Option<ReportToolSetting>,
+}
+
+/// Fields of a report to create or change. Only the fields set to `Some` are sent.
+///
+/// Example:
+/// ```
+/// let report = NewOriginalityReport {
+///     file_id: Some(attachment_id),
+///     originality_score: Some(12.5),
+///     originality_report_url: Some(format!("https://checker.example/reports/{}", job_id)),
+///     ..Default::default()
+/// };
+/// create_originality_report(&client, &canvas_info, assignment_id, submission_id, &report)?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct NewOriginalityReport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub originality_score: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub originality_report_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub originality_report_file_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workflow_state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_setting: Option<ReportToolSetting>,
+}
+
+impl NewOriginalityReport {
+    // Builds the request body, rejecting scores Canvas would refuse.
+    fn to_json(&self) -> Result<Value, Box<dyn Error>> {
+        if let Some(score) = self.originality_score {
+            if !(0.0..=100.0).contains(&score) {
+                return Err(format!("Originality score must be between 0 and 100, got {}", score).into());
+            }
+        }
+        Ok(json!({ "originality_report": self }))
+    }
+}
+
+// The plagiarism platform lives under `/api/lti` rather than `/api/v1`.
+fn lti_url(canvas_info: &CanvasCredentials, path: &str) -> String {
+    let api = canvas_info.url_canvas.trim_end_matches('/');
+    format!("{}/lti{}", api.strip_suffix("/v1").unwrap_or(api), path)
+}
+
+fn reports_url(canvas_info: &CanvasCredentials, assignment_id: u64, submission_id: u64) -> String {
+    lti_url(
+        canvas_info,
+        &format!("/assignments/{}/submissions/{}/originality_report", assignment_id, submission_id),
+    )
+}
+
+/// Creates the originality report of a submission (or of one of its files, with `file_id`).
+///
+/// The plagiarism platform endpoints require a token issued to the LTI 2 tool registered as the
+/// assignment's similarity checker.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `assignment_id`, `submission_id`: The submission checked.
+/// - `report`: Score and links of the report.
+///
+/// Returns:
+/// - `Result<OriginalityReport, Box<dyn Error>>`: The created report or an error.
+pub fn create_originality_report(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    assignment_id: u64,
+    submission_id: u64,
+    report: &NewOriginalityReport,
+) -> Result<OriginalityReport, Box<dyn Error>> {
+    let url = reports_url(canvas_info, assignment_id, submission_id);
+    send_report_request(client, canvas_info, HttpMethod::Post(report.to_json()?), &url)
+}
+
+/// Updates an originality report, e.g. to set the score once the check is finished.
+pub fn update_originality_report(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    assignment_id: u64,
+    submission_id: u64,
+    report_id: u64,
+    report: &NewOriginalityReport,
+) -> Result<OriginalityReport, Box<dyn Error>> {
+    let url = format!("{}/{}", reports_url(canvas_info, assignment_id, submission_id), report_id);
+    send_report_request(client, canvas_info, HttpMethod::Put(report.to_json()?), &url)
+}
+
+/// Retrieves an originality report of a submission.
+pub fn fetch_originality_report(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    assignment_id: u64,
+    submission_id: u64,
+    report_id: u64,
+) -> Result<OriginalityReport, Box<dyn Error>> {
+    let url = format!("{}/{}", reports_url(canvas_info, assignment_id, submission_id), report_id);
+    fetch_json(client, canvas_info, &url)
+}
+
+/// Retrieves the originality report of a submitted file.
+pub fn fetch_file_originality_report(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    assignment_id: u64,
+    file_id: u64,
+) -> Result<OriginalityReport, Box<dyn Error>> {
+    let url = lti_url(canvas_info, &format!("/assignments/{}/files/{}/originality_report", assignment_id, file_id));
+    fetch_json(client, canvas_info, &url)
+}
+
+// Sends a POST or PUT and decodes the report.
+fn send_report_request(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    method: HttpMethod,
+    url: &str,
+) -> Result<OriginalityReport, Box<dyn Error>> {
+    let response = send_http_request(client, method, url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to save originality report with status: {}", response.status()).into());
+    }
+    Ok(response.json()?)
+}
+
+impl Submission {
+    /// Creates the originality report of this submission. See `create_originality_report`.
+    pub fn create_originality_report(
+        &self,
+        client: &Client,
+        report: &NewOriginalityReport,
+    ) -> Result<OriginalityReport, Box<dyn Error>> {
+        create_originality_report(
+            client,
+            &self.assignment_info.course_info.canvas_info,
+            self.assignment_id,
+            self.id,
+            report,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_body_and_lti_url() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: String::new(),
+        };
+        assert_eq!(
+            reports_url(&canvas_info, 10, 20),
+            "https://canvas.example/api/lti/assignments/10/submissions/20/originality_report"
+        );
+
+        let report = NewOriginalityReport {
+            file_id: Some(5000),
+            originality_score: Some(12.5),
+            workflow_state: Some("scored".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            report.to_json().unwrap(),
+            json!({"originality_report": {"file_id": 5000, "originality_score": 12.5, "workflow_state": "scored"}})
+        );
+        let invalid = NewOriginalityReport {
+            originality_score: Some(130.0),
+            ..Default::default()
+        };
+        assert!(invalid.to_json().is_err());
+    }
+}
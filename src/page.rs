@@ -0,0 +1,242 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages, fetch_json};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CourseInfo;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Information about a wiki page of a course.
+///
+/// Fields:
+/// - `page_id`: Numeric ID of the page.
+/// - `url`: Slug identifying the page in the API and in the course URLs.
+/// - `title`: Title of the page.
+/// - `body`: HTML content (only present when a single page is fetched).
+/// - `updated_at`: Date of the last edit (ISO 8601).
+/// - `published`: Whether the page is visible to students.
+/// - `course_info`: The course the page belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PageInfo {
+    pub page_id: u64,
+    pub url: String,
+    pub title: String,
+    pub body: Option<String>,
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub published: bool,
+    #[serde(skip)]
+    pub course_info: Arc<CourseInfo>,
+}
+
+/// A wiki page of a course. See `Course::fetch_pages`.
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub info: Arc<PageInfo>,
+}
+
+/// A saved version of a wiki page.
+///
+/// Fields:
+/// - `revision_id`: Sequential number of the revision.
+/// - `updated_at`: When the revision was saved.
+/// - `latest`: Whether this is the current version of the page.
+/// - `edited_by`: ID and name of the user who saved it, when known.
+/// - `title` / `body`: Content of the page at that revision (only filled by
+///   `Page::fetch_revision`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PageRevision {
+    pub revision_id: u64,
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub latest: bool,
+    pub edited_by: Option<PageEditor>,
+    pub title: Option<String>,
+    pub body: Option<String>,
+}
+
+/// Author of a page revision.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PageEditor {
+    pub id: u64,
+    pub display_name: Option<String>,
+}
+
+/// One line of the comparison produced by `diff_lines`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    Unchanged(String),
+    Removed(String),
+    Added(String),
+}
+
+impl Page {
+    // Base URL of this page in the API.
+    fn api_url(&self) -> String {
+        format!(
+            "{}/courses/{}/pages/{}",
+            self.info.course_info.canvas_info.url_canvas,
+            self.info.course_info.id,
+            urlencoding::encode(&self.info.url)
+        )
+    }
+
+    /// Lists the revisions of the page, newest first (without their content).
+    pub fn fetch_revisions(&self, client: &Client) -> Result<Vec<PageRevision>, Box<dyn Error>> {
+        let url = format!("{}/revisions", self.api_url());
+        Ok(fetch_all_pages(client, &self.info.course_info.canvas_info, &url, Vec::new())?
+            .into_iter()
+            .filter_map(|revision| serde_json::from_value(revision).ok())
+            .collect())
+    }
+
+    /// Retrieves one revision of the page, including its title and body.
+    pub fn fetch_revision(&self, client: &Client, revision_id: u64) -> Result<PageRevision, Box<dyn Error>> {
+        let url = format!("{}/revisions/{}", self.api_url(), revision_id);
+        fetch_json(client, &self.info.course_info.canvas_info, &url)
+    }
+
+    /// Retrieves the current version of the page, including its title and body.
+    pub fn fetch_latest_revision(&self, client: &Client) -> Result<PageRevision, Box<dyn Error>> {
+        let url = format!("{}/revisions/latest", self.api_url());
+        fetch_json(client, &self.info.course_info.canvas_info, &url)
+    }
+
+    /// Compares the bodies of two revisions line by line.
+    ///
+    /// Example:
+    /// ```
+    /// for line in page.diff_revisions(&client, 3, 4)? {
+    ///     match line {
+    ///         DiffLine::Removed(text) => println!("- {}", text),
+    ///         DiffLine::Added(text) => println!("+ {}", text),
+    ///         DiffLine::Unchanged(_) => {}
+    ///     }
+    /// }
+    /// ```
+    pub fn diff_revisions(&self, client: &Client, old_id: u64, new_id: u64) -> Result<Vec<DiffLine>, Box<dyn Error>> {
+        let old = self.fetch_revision(client, old_id)?;
+        let new = self.fetch_revision(client, new_id)?;
+        Ok(diff_lines(
+            old.body.as_deref().unwrap_or_default(),
+            new.body.as_deref().unwrap_or_default(),
+        ))
+    }
+
+    /// Restores the page to a previous revision. Canvas saves the restored content as a new
+    /// revision, so nothing is lost. Returns the new current revision.
+    pub fn revert_to(&self, client: &Client, revision_id: u64) -> Result<PageRevision, Box<dyn Error>> {
+        let canvas_info = &self.info.course_info.canvas_info;
+        let url = format!("{}/revisions/{}", self.api_url(), revision_id);
+        let response = send_http_request(client, HttpMethod::Post(json!({})), &url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to revert page with status: {}", response.status()).into());
+        }
+        Ok(response.json()?)
+    }
+}
+
+/// Compares two texts line by line (longest common subsequence), returning the lines of both in
+/// order, tagged as unchanged, removed from `old` or added in `new`.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    // lcs[i][j] = length of the common subsequence of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            result.push(DiffLine::Unchanged(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[i + 1][j] >= lcs[i][j + 1] {
+            result.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            result.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    result.extend(old[i..].iter().map(|line| DiffLine::Removed(line.to_string())));
+    result.extend(new[j..].iter().map(|line| DiffLine::Added(line.to_string())));
+    result
+}
+
+// Builds a `Page` bound to its course from the JSON returned by Canvas.
+fn page_from_json(course_info: &Arc<CourseInfo>, json: Value) -> Option<Page> {
+    let mut info: PageInfo = serde_json::from_value(json).ok()?;
+    info.course_info = Arc::clone(course_info);
+    Some(Page { info: Arc::new(info) })
+}
+
+/// Retrieves the wiki pages of a course (without their bodies).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course whose pages are listed.
+///
+/// Returns:
+/// - `Result<Vec<Page>, Box<dyn Error>>`: The pages or an error.
+pub fn fetch_pages(client: &Client, course_info: &Arc<CourseInfo>) -> Result<Vec<Page>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/pages", course_info.canvas_info.url_canvas, course_info.id);
+    Ok(fetch_all_pages(client, &course_info.canvas_info, &url, Vec::new())?
+        .into_iter()
+        .filter_map(|json| page_from_json(course_info, json))
+        .collect())
+}
+
+/// Retrieves one wiki page of a course, including its body.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course the page belongs to.
+/// - `page_url`: Slug of the page (`PageInfo::url`) or `page_id:<id>`.
+///
+/// Returns:
+/// - `Result<Page, Box<dyn Error>>`: The page or an error.
+pub fn fetch_page(client: &Client, course_info: &Arc<CourseInfo>, page_url: &str) -> Result<Page, Box<dyn Error>> {
+    let url = format!(
+        "{}/courses/{}/pages/{}",
+        course_info.canvas_info.url_canvas,
+        course_info.id,
+        urlencoding::encode(page_url)
+    );
+    let json: Value = fetch_json(client, &course_info.canvas_info, &url)?;
+    page_from_json(course_info, json).ok_or_else(|| "Invalid page returned by Canvas".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_lines() {
+        let old = "<h1>Instruções</h1>\n<p>Entrega até sexta.</p>\n<p>Use o modelo.</p>";
+        let new = "<h1>Instruções</h1>\n<p>Entrega até segunda.</p>\n<p>Use o modelo.</p>\n<p>Boa sorte!</p>";
+        assert_eq!(
+            diff_lines(old, new),
+            vec![
+                DiffLine::Unchanged("<h1>Instruções</h1>".to_string()),
+                DiffLine::Removed("<p>Entrega até sexta.</p>".to_string()),
+                DiffLine::Added("<p>Entrega até segunda.</p>".to_string()),
+                DiffLine::Unchanged("<p>Use o modelo.</p>".to_string()),
+                DiffLine::Added("<p>Boa sorte!</p>".to_string()),
+            ]
+        );
+        assert!(diff_lines(old, old).iter().all(|line| matches!(line, DiffLine::Unchanged(_))));
+    }
+}
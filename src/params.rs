@@ -0,0 +1,115 @@
+// Import necessary crates and modules
+use std::fmt::Display;
+
+/// Query string parameters of a request, kept in insertion order.
+///
+/// Canvas repeats array parameters (`include[]=user&include[]=course`), so parameters are a list
+/// of pairs rather than a map. `Params` converts into the `Vec<(String, String)>` taken by the
+/// request functions of the crate and by `CanvasClient`.
+///
+/// Example:
+/// ```
+/// let params = Params::new()
+///     .include(["submission_comments", "user"])
+///     .array("student_ids", [10, 11])
+///     .per_page(100);
+/// let submissions = client.raw_get("/courses/1/students/submissions", params)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params(Vec<(String, String)>);
+
+impl Params {
+    /// Empty parameter list.
+    pub fn new() -> Self {
+        Params(Vec::new())
+    }
+
+    /// Adds `key=value`.
+    pub fn param(mut self, key: &str, value: impl Display) -> Self {
+        self.0.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds `key=value` when `value` is `Some`.
+    pub fn param_opt(self, key: &str, value: Option<impl Display>) -> Self {
+        match value {
+            Some(value) => self.param(key, value),
+            None => self,
+        }
+    }
+
+    /// Adds the array parameter `key[]` once per value.
+    pub fn array<T: Display>(mut self, key: &str, values: impl IntoIterator<Item = T>) -> Self {
+        let key = format!("{}[]", key);
+        self.0.extend(values.into_iter().map(|value| (key.clone(), value.to_string())));
+        self
+    }
+
+    /// Adds `include[]` values (associated data to embed in the answer).
+    pub fn include<T: Display>(self, values: impl IntoIterator<Item = T>) -> Self {
+        self.array("include", values)
+    }
+
+    /// Sets the size of the pages of a listing.
+    pub fn per_page(self, per_page: u32) -> Self {
+        self.param("per_page", per_page)
+    }
+
+    /// Selects the page of a listing (numbered pages only).
+    pub fn page(self, page: u32) -> Self {
+        self.param("page", page)
+    }
+
+    /// Returns `true` when no parameter was added.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The parameters as `(key, value)` pairs.
+    pub fn into_vec(self) -> Vec<(String, String)> {
+        self.0
+    }
+}
+
+impl From<Params> for Vec<(String, String)> {
+    fn from(params: Params) -> Self {
+        params.0
+    }
+}
+
+impl From<Vec<(String, String)>> for Params {
+    fn from(params: Vec<(String, String)>) -> Self {
+        Params(params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_params_keep_order_and_repeat_arrays() {
+        let params = Params::new()
+            .page(2)
+            .per_page(100)
+            .include(["user", "course"])
+            .array("student_ids", [10, 11])
+            .param_opt("grouped", Some(true))
+            .param_opt("search_term", None::<&str>);
+        let pairs: Vec<(String, String)> = params.into();
+        let expected = [
+            ("page", "2"),
+            ("per_page", "100"),
+            ("include[]", "user"),
+            ("include[]", "course"),
+            ("student_ids[]", "10"),
+            ("student_ids[]", "11"),
+            ("grouped", "true"),
+        ];
+        assert_eq!(
+            pairs,
+            expected.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect::<Vec<_>>()
+        );
+        assert!(Params::new().is_empty());
+    }
+}
@@ -0,0 +1,292 @@
+// Import necessary crates and modules
+use crate::assignment::CommentResult;
+use crate::canvas::convert_json_to_assignment;
+use crate::connection::{send_http_request, HttpMethod};
+use crate::group::{GroupRef, StudentWithGroup};
+use crate::{Assignment, Course, Student, Submission};
+use regex::Regex;
+use reqwest::blocking::Client;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+/// Evaluation of the members of a group by their teammates, collected through a survey
+/// assignment.
+///
+/// Each student receives, as a comment on the survey, the numbered list of their teammates, and
+/// answers with a text entry repeating the lines with a rating at the end (`1) Ana Souza: 4`).
+/// `collect` reads the answers back and aggregates the ratings received by each student.
+///
+/// Fields:
+/// - `assignment`: Survey assignment holding the answers.
+/// - `group_category_id`: Group set whose groups are evaluated.
+/// - `max_rating`: Highest rating (the scale starts at 0).
+///
+/// Example:
+/// ```
+/// let evaluation = PeerEvaluation::create(&client, &course, "Avaliação por pares - Projeto 1", group_set_id, 5)?;
+/// evaluation.send_forms(&client)?;
+/// // ... after the deadline
+/// for group in evaluation.collect()? {
+///     for member in &group.members {
+///         println!("{};{};{:?}", group.group.name, member.name, member.average_received);
+///     }
+/// }
+/// ```
+#[derive(Clone)]
+pub struct PeerEvaluation {
+    pub assignment: Assignment,
+    pub group_category_id: u64,
+    pub max_rating: u32,
+}
+
+/// Ratings received by one student, as aggregated by `PeerEvaluation::collect`.
+///
+/// Fields:
+/// - `student_id` / `name`: The evaluated student.
+/// - `ratings`: Rating given by each teammate, by teammate ID.
+/// - `average_received`: Mean of `ratings`, `None` when no teammate answered.
+/// - `answered`: Whether the student rated their teammates.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeerEvaluationMember {
+    pub student_id: u64,
+    pub name: String,
+    pub ratings: BTreeMap<u64, f64>,
+    pub average_received: Option<f64>,
+    pub answered: bool,
+}
+
+/// Results of one group.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct PeerEvaluationGroup {
+    pub group: GroupRef,
+    pub members: Vec<PeerEvaluationMember>,
+}
+
+// Teammates of `student_id` in the order of their form: by name, without the student.
+fn teammates<'a>(members: &[&'a Student], student_id: u64) -> Vec<&'a Student> {
+    let mut teammates: Vec<&Student> = members.iter().copied().filter(|member| member.info.id != student_id).collect();
+    teammates.sort_by(|a, b| a.info.name.cmp(&b.info.name).then(a.info.id.cmp(&b.info.id)));
+    teammates
+}
+
+// Comment sent to a student with the teammates to rate.
+fn form_text(teammates: &[&Student], max_rating: u32) -> String {
+    let mut text = format!(
+        "Rate each teammate from 0 to {}: submit this survey as a text entry with the lines below, \
+         adding your rating at the end of each one.\n",
+        max_rating
+    );
+    for (index, teammate) in teammates.iter().enumerate() {
+        text.push_str(&format!("\n{}) {}: ", index + 1, teammate.info.name));
+    }
+    text
+}
+
+// Reads the answer of a student: the rating of each line, by the 0-based position of the
+// teammate in the form. Lines without a rating or outside the scale are ignored.
+fn parse_ratings(answer: &str, max_rating: u32) -> HashMap<usize, f64> {
+    let breaks = Regex::new(r"(?i)<br\s*/?>|</p>|</div>|</li>").unwrap();
+    let tags = Regex::new(r"<[^>]+>").unwrap();
+    let line = Regex::new(r"^\s*(\d+)\s*[).:-].*?(\d+(?:[.,]\d+)?)\s*$").unwrap();
+    let text = breaks.replace_all(answer, "\n");
+    let text = tags.replace_all(&text, "").replace("&nbsp;", " ");
+    text.lines()
+        .filter_map(|text| {
+            let captures = line.captures(text)?;
+            let position = captures[1].parse::<usize>().ok()?.checked_sub(1)?;
+            let rating = captures[2].replace(',', ".").parse::<f64>().ok()?;
+            (rating <= max_rating as f64).then_some((position, rating))
+        })
+        .collect()
+}
+
+// Groups the students of each group, in the order of the group IDs.
+fn members_by_group(students: &[StudentWithGroup]) -> BTreeMap<u64, (GroupRef, Vec<&Student>)> {
+    let mut groups: BTreeMap<u64, (GroupRef, Vec<&Student>)> = BTreeMap::new();
+    for (student, group) in students {
+        if let Some(group) = group {
+            groups.entry(group.id).or_insert_with(|| (group.clone(), Vec::new())).1.push(student);
+        }
+    }
+    groups
+}
+
+// Aggregates the answers (text entry of each student, by student ID).
+fn aggregate(students: &[StudentWithGroup], answers: &HashMap<u64, String>, max_rating: u32) -> Vec<PeerEvaluationGroup> {
+    members_by_group(students)
+        .into_values()
+        .map(|(group, members)| {
+            let mut results: Vec<PeerEvaluationMember> = members
+                .iter()
+                .map(|member| PeerEvaluationMember {
+                    student_id: member.info.id,
+                    name: member.info.name.clone(),
+                    ratings: BTreeMap::new(),
+                    average_received: None,
+                    answered: answers.contains_key(&member.info.id),
+                })
+                .collect();
+            for rater in &members {
+                let Some(answer) = answers.get(&rater.info.id) else {
+                    continue;
+                };
+                let teammates = teammates(&members, rater.info.id);
+                for (position, rating) in parse_ratings(answer, max_rating) {
+                    let Some(ratee) = teammates.get(position) else {
+                        continue;
+                    };
+                    if let Some(result) = results.iter_mut().find(|result| result.student_id == ratee.info.id) {
+                        result.ratings.insert(rater.info.id, rating);
+                    }
+                }
+            }
+            for result in &mut results {
+                if !result.ratings.is_empty() {
+                    result.average_received = Some(result.ratings.values().sum::<f64>() / result.ratings.len() as f64);
+                }
+            }
+            PeerEvaluationGroup { group, members: results }
+        })
+        .collect()
+}
+
+impl PeerEvaluation {
+    /// Creates the survey assignment in `course`: published, not graded, answered with a text
+    /// entry.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `course`: Course of the groups.
+    /// - `name`: Name of the survey assignment.
+    /// - `group_category_id`: Group set whose groups are evaluated.
+    /// - `max_rating`: Highest rating (the scale starts at 0).
+    pub fn create(
+        client: &Client,
+        course: &Course,
+        name: &str,
+        group_category_id: u64,
+        max_rating: u32,
+    ) -> Result<PeerEvaluation, Box<dyn Error>> {
+        let canvas_info = &course.info.canvas_info;
+        let url = format!("{}/courses/{}/assignments", canvas_info.url_canvas, course.info.id);
+        let body = json!({
+            "assignment": {
+                "name": name,
+                "description": format!(
+                    "<p>Rate each of your teammates from 0 to {}. Your list of teammates was sent as a \
+                     comment on this assignment: submit it as a text entry with your rating at the end of \
+                     each line.</p>",
+                    max_rating
+                ),
+                "submission_types": ["online_text_entry"],
+                "grading_type": "not_graded",
+                "published": true,
+            }
+        });
+        let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to create peer evaluation with status: {}", response.status()).into());
+        }
+        let json: Value = response.json()?;
+        let assignment = convert_json_to_assignment(&course.info, &json).ok_or("Invalid assignment in the answer")?;
+        course.clear_cache();
+        Ok(PeerEvaluation::from_assignment(assignment, group_category_id, max_rating))
+    }
+
+    /// Uses an existing assignment (created in Canvas, or a previous survey) as survey.
+    pub fn from_assignment(assignment: Assignment, group_category_id: u64, max_rating: u32) -> PeerEvaluation {
+        PeerEvaluation {
+            assignment,
+            group_category_id,
+            max_rating,
+        }
+    }
+
+    fn course(&self) -> Course {
+        Course {
+            info: self.assignment.info.course_info.clone(),
+        }
+    }
+
+    /// Sends each student in a group the numbered list of their teammates, as a comment on the
+    /// survey.
+    ///
+    /// Returns:
+    /// - `Result<Vec<(u64, CommentResult)>, Box<dyn Error>>`: The outcome for each student, or an
+    ///   error when the roster or the groups could not be read.
+    pub fn send_forms(&self, client: &Client) -> Result<Vec<(u64, CommentResult)>, Box<dyn Error>> {
+        let students = self.course().fetch_students_with_groups(self.group_category_id)?;
+        let mut comments = Vec::new();
+        for (_, members) in members_by_group(&students).into_values() {
+            for member in &members {
+                let text = form_text(&teammates(&members, member.info.id), self.max_rating);
+                comments.push((member.info.id, text, None));
+            }
+        }
+        Ok(self.assignment.comment_many(client, comments))
+    }
+
+    /// Reads the answers and aggregates the ratings received by each student, per group.
+    pub fn collect(&self) -> Result<Vec<PeerEvaluationGroup>, Box<dyn Error>> {
+        let students = self.course().fetch_students_with_groups(self.group_category_id)?;
+        let roster: Vec<Student> = students.iter().map(|(student, _)| student.clone()).collect();
+        let answers: HashMap<u64, String> = self
+            .assignment
+            .fetch_submissions(&roster)?
+            .iter()
+            .filter_map(|submission: &Submission| {
+                let body = submission.extra.get("body")?.as_str()?;
+                Some((submission.students_info.first()?.id, body.to_string()))
+            })
+            .collect();
+        Ok(aggregate(&students, &answers, self.max_rating))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StudentInfo;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_peer_ratings_are_aggregated_per_group() {
+        let student = |id: u64, name: &str| Student {
+            info: Arc::new(StudentInfo {
+                id,
+                name: name.to_string(),
+                ..Default::default()
+            }),
+        };
+        let group = GroupRef {
+            id: 5,
+            name: "Grupo 5".to_string(),
+        };
+        let students = vec![
+            (student(10, "Carla"), Some(group.clone())),
+            (student(11, "Ana"), Some(group.clone())),
+            (student(12, "Bia"), Some(group.clone())),
+            (student(13, "Davi"), None),
+        ];
+
+        let members = members_by_group(&students);
+        let form = form_text(&teammates(&members[&5].1, 10), 5);
+        assert!(form.ends_with("\n1) Ana: \n2) Bia: "));
+
+        // Carla rates Ana and Bia; Ana rates only Bia (her line for Carla is empty, and 9 is off the scale)
+        let answers = HashMap::from([
+            (10, "<p>1) Ana: 4</p><p>2) Bia: 3,5</p>".to_string()),
+            (11, "1) Bia: 5\n2) Carla:\n3) x: 9".to_string()),
+        ]);
+        let results = aggregate(&students, &answers, 5);
+        assert_eq!(results.len(), 1);
+        let member = |id: u64| results[0].members.iter().find(|member| member.student_id == id).unwrap();
+        assert_eq!(member(11).average_received, Some(4.0));
+        assert_eq!(member(12).average_received, Some(4.25));
+        assert_eq!(member(12).ratings, BTreeMap::from([(10, 3.5), (11, 5.0)]));
+        assert_eq!(member(10).average_received, None);
+        assert!(!member(12).answered);
+    }
+}
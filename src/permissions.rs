@@ -0,0 +1,146 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::params::Params;
+use crate::{CanvasCredentials, Course};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+
+/// Permissions checked by `Course::permissions`: the ones behind the helpers of
+/// `CoursePermissions`.
+pub const COMMON_PERMISSIONS: [&str; 8] = [
+    "manage_grades",
+    "view_all_grades",
+    "manage_assignments",
+    "read_roster",
+    "send_messages",
+    "manage_files",
+    "moderate_forum",
+    "manage_students",
+];
+
+/// Permissions of the owner of the credentials in a course, by Canvas permission name
+/// (`manage_grades`, `read_roster`...).
+///
+/// Checking them before a batch lets a tool stop with a clear message instead of failing
+/// midway, once part of the grades were already posted.
+///
+/// Example:
+/// ```
+/// let permissions = course.permissions(&client)?;
+/// if !permissions.can_grade() {
+///     eprintln!("Your token can't post grades in {}", course.info.name);
+///     return Ok(());
+/// }
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CoursePermissions {
+    pub permissions: BTreeMap<String, bool>,
+}
+
+impl CoursePermissions {
+    /// Returns `true` when the permission `name` is granted. Permissions that were not checked
+    /// count as not granted.
+    pub fn has(&self, name: &str) -> bool {
+        self.permissions.get(name).copied().unwrap_or(false)
+    }
+
+    /// Fails with a message naming `action` when the permission `name` is not granted.
+    ///
+    /// Example:
+    /// ```
+    /// course.permissions(&client)?.require("manage_grades", "post grades")?;
+    /// ```
+    pub fn require(&self, name: &str, action: &str) -> Result<(), Box<dyn Error>> {
+        if self.has(name) {
+            Ok(())
+        } else {
+            Err(format!("Your token can't {} in this course (missing permission {})", action, name).into())
+        }
+    }
+
+    /// Grades and comments on submissions can be posted.
+    pub fn can_grade(&self) -> bool {
+        self.has("manage_grades")
+    }
+
+    /// Grades of every student can be read.
+    pub fn can_view_all_grades(&self) -> bool {
+        self.has("view_all_grades") || self.has("manage_grades")
+    }
+
+    /// Assignments can be created, edited and deleted.
+    pub fn can_manage_assignments(&self) -> bool {
+        self.has("manage_assignments")
+    }
+
+    /// The list of students of the course can be read.
+    pub fn can_read_roster(&self) -> bool {
+        self.has("read_roster")
+    }
+
+    /// Conversation messages can be sent to the users of the course.
+    pub fn can_send_messages(&self) -> bool {
+        self.has("send_messages")
+    }
+}
+
+/// Checks permissions of the owner of the credentials in a course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `course_id`: ID of the course.
+/// - `names`: Canvas names of the permissions to check.
+///
+/// Returns:
+/// - `Result<CoursePermissions, Box<dyn Error>>`: Whether each permission is granted, or an error.
+pub fn fetch_course_permissions(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    course_id: u64,
+    names: &[&str],
+) -> Result<CoursePermissions, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/permissions", canvas_info.url_canvas, course_id);
+    let params: Vec<(String, String)> = Params::new().array("permissions", names).into();
+    let url = reqwest::Url::parse_with_params(&url, &params)?;
+    Ok(CoursePermissions {
+        permissions: fetch_json(client, canvas_info, url.as_str())?,
+    })
+}
+
+impl Course {
+    /// Permissions of the owner of the credentials in this course, for the permissions of
+    /// `COMMON_PERMISSIONS`. Use `fetch_course_permissions` for other ones.
+    pub fn permissions(&self, client: &Client) -> Result<CoursePermissions, Box<dyn Error>> {
+        fetch_course_permissions(client, &self.info.canvas_info, self.info.id, &COMMON_PERMISSIONS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_permission_helpers() {
+        let permissions = CoursePermissions {
+            permissions: serde_json::from_value(json!({
+                "manage_grades": false,
+                "view_all_grades": true,
+                "read_roster": true
+            }))
+            .unwrap(),
+        };
+        assert!(!permissions.can_grade());
+        assert!(permissions.can_view_all_grades());
+        assert!(permissions.can_read_roster());
+        assert!(!permissions.can_send_messages());
+        assert_eq!(
+            permissions.require("manage_grades", "post grades").unwrap_err().to_string(),
+            "Your token can't post grades in this course (missing permission manage_grades)"
+        );
+        assert!(permissions.require("read_roster", "list students").is_ok());
+    }
+}
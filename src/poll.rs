@@ -0,0 +1,172 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages_with_key, fetch_json};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// A poll (question) created by the user.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Poll {
+    pub id: u64,
+    pub question: String,
+    pub description: Option<String>,
+    pub created_at: Option<String>,
+}
+
+/// A possible answer of a poll.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PollChoice {
+    pub id: u64,
+    pub text: String,
+    pub is_correct: Option<bool>,
+    pub position: Option<u64>,
+}
+
+/// An answer given by a student during a poll session.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PollSubmission {
+    pub id: u64,
+    pub poll_choice_id: u64,
+    pub user_id: u64,
+    pub created_at: Option<String>,
+}
+
+/// A run of a poll in a course (or section).
+///
+/// Fields:
+/// - `id`: ID of the session.
+/// - `course_id` / `course_section_id`: Where the poll was run.
+/// - `is_published`: Whether the session is open for answers.
+/// - `results`: Number of answers per choice ID.
+/// - `poll_submissions`: The individual answers (only returned when fetching a single session).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct PollSession {
+    pub id: u64,
+    pub poll_id: Option<u64>,
+    pub course_id: u64,
+    pub course_section_id: Option<u64>,
+    #[serde(default)]
+    pub is_published: bool,
+    #[serde(default)]
+    pub results: HashMap<String, u64>,
+    #[serde(default)]
+    pub poll_submissions: Vec<PollSubmission>,
+}
+
+/// Counts, per user ID, the sessions in which the user answered, e.g. for participation points.
+///
+/// Example:
+/// ```
+/// let mut sessions = Vec::new();
+/// for session in fetch_poll_sessions(&client, &canvas_info, poll.id)? {
+///     sessions.push(fetch_poll_session(&client, &canvas_info, poll.id, session.id)?);
+/// }
+/// let participation = participation_counts(&sessions);
+/// ```
+pub fn participation_counts(sessions: &[PollSession]) -> HashMap<u64, usize> {
+    let mut counts = HashMap::new();
+    for session in sessions {
+        let mut users: Vec<u64> = session.poll_submissions.iter().map(|s| s.user_id).collect();
+        users.sort_unstable();
+        users.dedup();
+        for user_id in users {
+            *counts.entry(user_id).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+// Lists the items of a Polls endpoint, which wraps them in an object keyed by `key`.
+fn fetch_keyed<T: DeserializeOwned>(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    url: &str,
+    key: &str,
+) -> Result<Vec<T>, Box<dyn Error>> {
+    Ok(fetch_all_pages_with_key(client, canvas_info, url, Vec::new(), Some(key))?
+        .into_iter()
+        .filter_map(|json| serde_json::from_value(json).ok())
+        .collect())
+}
+
+/// Retrieves the polls created by the user.
+pub fn fetch_polls(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<Poll>, Box<dyn Error>> {
+    let url = format!("{}/polls", canvas_info.url_canvas);
+    fetch_keyed(client, canvas_info, &url, "polls")
+}
+
+/// Retrieves the choices of a poll.
+pub fn fetch_poll_choices(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    poll_id: u64,
+) -> Result<Vec<PollChoice>, Box<dyn Error>> {
+    let url = format!("{}/polls/{}/poll_choices", canvas_info.url_canvas, poll_id);
+    fetch_keyed(client, canvas_info, &url, "poll_choices")
+}
+
+/// Retrieves the sessions of a poll (without the individual answers).
+pub fn fetch_poll_sessions(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    poll_id: u64,
+) -> Result<Vec<PollSession>, Box<dyn Error>> {
+    let url = format!("{}/polls/{}/poll_sessions", canvas_info.url_canvas, poll_id);
+    fetch_keyed(client, canvas_info, &url, "poll_sessions")
+}
+
+/// Retrieves one session of a poll with its individual answers.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `poll_id`: ID of the poll.
+/// - `session_id`: ID of the session.
+///
+/// Returns:
+/// - `Result<PollSession, Box<dyn Error>>`: The session or an error.
+pub fn fetch_poll_session(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    poll_id: u64,
+    session_id: u64,
+) -> Result<PollSession, Box<dyn Error>> {
+    let url = format!(
+        "{}/polls/{}/poll_sessions/{}",
+        canvas_info.url_canvas, poll_id, session_id
+    );
+    let json: Value = fetch_json(client, canvas_info, &url)?;
+    let session = json["poll_sessions"]
+        .get(0)
+        .cloned()
+        .ok_or_else(|| format!("Poll session {} not found", session_id))?;
+    Ok(serde_json::from_value(session)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_participation_counts() {
+        let sessions: Vec<PollSession> = serde_json::from_value(json!([
+            {"id": 1, "course_id": 7, "results": {"10": 2},
+             "poll_submissions": [
+                 {"id": 1, "poll_choice_id": 10, "user_id": 100},
+                 {"id": 2, "poll_choice_id": 10, "user_id": 101}
+             ]},
+            {"id": 2, "course_id": 7,
+             "poll_submissions": [{"id": 3, "poll_choice_id": 11, "user_id": 100}]}
+        ]))
+        .unwrap();
+        let counts = participation_counts(&sessions);
+        assert_eq!(counts[&100], 2);
+        assert_eq!(counts[&101], 1);
+        assert_eq!(sessions[0].results["10"], 2);
+    }
+}
@@ -0,0 +1,86 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_json;
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// State of an asynchronous Canvas job (exports, bulk updates...), as returned by `/progress`.
+///
+/// Fields:
+/// - `id`: ID of the progress.
+/// - `workflow_state`: `queued`, `running`, `completed` or `failed`.
+/// - `completion`: Percentage done (0-100), when known.
+/// - `message`: Status or error message.
+/// - `url`: API URL of this progress.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct Progress {
+    pub id: u64,
+    pub workflow_state: String,
+    pub completion: Option<f64>,
+    pub message: Option<String>,
+    pub url: Option<String>,
+}
+
+impl Progress {
+    /// Returns `true` once the job has finished, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.workflow_state == "completed" || self.workflow_state == "failed"
+    }
+
+    /// Returns `true` when the job failed.
+    pub fn is_failed(&self) -> bool {
+        self.workflow_state == "failed"
+    }
+}
+
+/// Retrieves a progress by ID.
+pub fn fetch_progress(client: &Client, canvas_info: &CanvasCredentials, progress_id: u64) -> Result<Progress, Box<dyn Error>> {
+    let url = format!("{}/progress/{}", canvas_info.url_canvas, progress_id);
+    fetch_json(client, canvas_info, &url)
+}
+
+/// Polls a progress URL until the job finishes.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `progress_url`: URL returned by Canvas when the job was started.
+/// - `interval`: Time between two checks.
+/// - `max_attempts`: Number of checks before giving up.
+///
+/// Returns:
+/// - `Result<Progress, Box<dyn Error>>`: The completed progress, or an error when the job failed
+///   or didn't finish in time.
+///
+/// Example:
+/// ```
+/// let progress = wait_for_progress(&client, &canvas_info, &url, Duration::from_secs(2), 150)?;
+/// ```
+pub fn wait_for_progress(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    progress_url: &str,
+    interval: Duration,
+    max_attempts: u32,
+) -> Result<Progress, Box<dyn Error>> {
+    for attempt in 0..max_attempts {
+        if attempt > 0 {
+            sleep(interval);
+        }
+        let progress: Progress = fetch_json(client, canvas_info, progress_url)?;
+        if progress.is_failed() {
+            return Err(format!(
+                "Canvas job failed: {}",
+                progress.message.unwrap_or_default()
+            )
+            .into());
+        }
+        if progress.is_finished() {
+            return Ok(progress);
+        }
+    }
+    Err(format!("Timed out waiting for {}", progress_url).into())
+}
@@ -0,0 +1,271 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages, fetch_all_pages_with_key};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::params::Params;
+use crate::{CanvasCredentials, CourseInfo};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+use std::thread::sleep;
+use std::time::Duration;
+
+/// Number of times the status of a quiz report is checked before giving up.
+pub const REPORT_POLL_ATTEMPTS: u32 = 60;
+
+/// Interval between two checks of the status of a quiz report.
+pub const REPORT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Information about a (classic) quiz of a course.
+///
+/// Fields:
+/// - `id`: ID of the quiz.
+/// - `title`: Title of the quiz.
+/// - `assignment_id`: ID of the assignment backing the quiz (graded quizzes only).
+/// - `question_count`: Number of questions.
+/// - `points_possible`: Maximum score.
+/// - `course_info`: The course the quiz belongs to.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct QuizInfo {
+    pub id: u64,
+    pub title: String,
+    pub assignment_id: Option<u64>,
+    #[serde(default)]
+    pub question_count: u64,
+    pub points_possible: Option<f64>,
+    #[serde(skip)]
+    pub course_info: Arc<CourseInfo>,
+}
+
+/// A quiz of a course. See `Course::fetch_quizzes`.
+#[derive(Debug, Clone)]
+pub struct Quiz {
+    pub info: Arc<QuizInfo>,
+}
+
+/// A question of a quiz, as configured by the teacher.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct QuizQuestion {
+    pub id: u64,
+    pub question_name: Option<String>,
+    pub question_text: Option<String>,
+    pub question_type: Option<String>,
+    pub points_possible: Option<f64>,
+}
+
+/// The answer given by a student to one question.
+///
+/// Fields:
+/// - `question_id`: ID of the question.
+/// - `text`: Answer text (HTML for essay questions; the chosen option text otherwise).
+/// - `answer_id`: ID of the chosen option, for multiple choice questions.
+/// - `correct`: Whether the answer was automatically marked correct (`None` when it needs manual
+///   grading or is partially correct).
+/// - `points`: Points awarded.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct QuizAnswer {
+    pub question_id: u64,
+    pub text: Option<String>,
+    pub answer_id: Option<u64>,
+    pub correct: Option<bool>,
+    pub points: Option<f64>,
+}
+
+/// Answers of one student to the latest attempt of a quiz.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct StudentQuizAnswers {
+    pub user_id: u64,
+    pub attempt: Option<u64>,
+    pub answers: Vec<QuizAnswer>,
+}
+
+impl StudentQuizAnswers {
+    /// Extracts the answers of the latest attempt from a submission fetched with
+    /// `include[]=submission_history`. Returns `None` when the student has no answers.
+    pub fn from_submission_json(submission: &Value) -> Option<StudentQuizAnswers> {
+        let user_id = submission["user_id"].as_u64()?;
+        let latest = submission["submission_history"]
+            .as_array()?
+            .iter()
+            .filter(|attempt| attempt["submission_data"].is_array())
+            .max_by_key(|attempt| attempt["attempt"].as_u64().unwrap_or(0))?;
+
+        let answers = latest["submission_data"]
+            .as_array()?
+            .iter()
+            .filter_map(|answer| {
+                Some(QuizAnswer {
+                    question_id: answer["question_id"].as_u64()?,
+                    text: answer["text"].as_str().map(String::from),
+                    answer_id: answer["answer_id"].as_u64(),
+                    correct: answer["correct"].as_bool(),
+                    points: answer["points"].as_f64(),
+                })
+            })
+            .collect();
+
+        Some(StudentQuizAnswers {
+            user_id,
+            attempt: latest["attempt"].as_u64(),
+            answers,
+        })
+    }
+}
+
+impl Quiz {
+    /// Retrieves the questions of this quiz.
+    pub fn fetch_questions(&self, client: &Client) -> Result<Vec<QuizQuestion>, Box<dyn Error>> {
+        let canvas_info = &self.info.course_info.canvas_info;
+        let url = format!(
+            "{}/courses/{}/quizzes/{}/questions",
+            canvas_info.url_canvas, self.info.course_info.id, self.info.id
+        );
+        Ok(fetch_all_pages(client, canvas_info, &url, Vec::new())?
+            .into_iter()
+            .filter_map(|question| serde_json::from_value(question).ok())
+            .collect())
+    }
+
+    /// Retrieves the answers of every student to the latest attempt of this quiz.
+    ///
+    /// Free-response answers come as HTML in `QuizAnswer::text`, ready to be fed into external
+    /// grading or plagiarism tooling. Only graded quizzes (backed by an assignment) are supported.
+    ///
+    /// Example:
+    /// ```
+    /// for student in quiz.fetch_answers(&client)? {
+    ///     for answer in student.answers {
+    ///         println!("{} / {}: {:?}", student.user_id, answer.question_id, answer.text);
+    ///     }
+    /// }
+    /// ```
+    pub fn fetch_answers(&self, client: &Client) -> Result<Vec<StudentQuizAnswers>, Box<dyn Error>> {
+        let assignment_id = self
+            .info
+            .assignment_id
+            .ok_or_else(|| format!("Quiz {} is not graded (no assignment)", self.info.id))?;
+        let canvas_info = &self.info.course_info.canvas_info;
+        let url = format!(
+            "{}/courses/{}/assignments/{}/submissions",
+            canvas_info.url_canvas, self.info.course_info.id, assignment_id
+        );
+        let params = Params::new().include(["submission_history"]);
+        Ok(fetch_all_pages(client, canvas_info, &url, params.into())?
+            .iter()
+            .filter_map(StudentQuizAnswers::from_submission_json)
+            .collect())
+    }
+
+    /// Retrieves the raw quiz submissions (one per student, with score and attempt data).
+    pub fn fetch_quiz_submissions(&self, client: &Client) -> Result<Vec<Value>, Box<dyn Error>> {
+        let canvas_info = &self.info.course_info.canvas_info;
+        let url = format!(
+            "{}/courses/{}/quizzes/{}/submissions",
+            canvas_info.url_canvas, self.info.course_info.id, self.info.id
+        );
+        fetch_all_pages_with_key(client, canvas_info, &url, Vec::new(), Some("quiz_submissions"))
+    }
+
+    /// Generates the "Student Analysis" CSV report of this quiz and returns its content.
+    ///
+    /// Canvas builds the report asynchronously; this method waits for it (checking every
+    /// `REPORT_POLL_INTERVAL`, at most `REPORT_POLL_ATTEMPTS` times) and then downloads the file.
+    pub fn student_analysis_csv(&self, client: &Client) -> Result<Vec<u8>, Box<dyn Error>> {
+        let canvas_info = &self.info.course_info.canvas_info;
+        let reports_url = format!(
+            "{}/courses/{}/quizzes/{}/reports",
+            canvas_info.url_canvas, self.info.course_info.id, self.info.id
+        );
+        let body = json!({
+            "quiz_report": {
+                "report_type": "student_analysis",
+                "includes_all_versions": false
+            },
+            "include": ["file"]
+        });
+        let response = send_http_request(client, HttpMethod::Post(body), &reports_url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to create quiz report with status: {}", response.status()).into());
+        }
+        let report: Value = response.json()?;
+        let report_id = report["id"].as_u64().ok_or("Quiz report without id")?;
+
+        let report_url = format!("{}/{}", reports_url, report_id);
+        let mut file_url = report["file"]["url"].as_str().map(String::from);
+        let mut attempts = 0;
+        while file_url.is_none() {
+            if attempts >= REPORT_POLL_ATTEMPTS {
+                return Err("Timed out waiting for the quiz report".into());
+            }
+            attempts += 1;
+            sleep(REPORT_POLL_INTERVAL);
+            let params = Params::new().include(["file"]);
+            let report: Value = send_http_request(client, HttpMethod::Get, &report_url, canvas_info, params.into())?.json()?;
+            file_url = report["file"]["url"].as_str().map(String::from);
+        }
+
+        download_report(client, canvas_info, &file_url.unwrap_or_default())
+    }
+}
+
+// Downloads a generated report file.
+fn download_report(client: &Client, canvas_info: &CanvasCredentials, url: &str) -> Result<Vec<u8>, Box<dyn Error>> {
+    let response = client.get(url).bearer_auth(&canvas_info.token_canvas).send()?;
+    if !response.status().is_success() {
+        return Err(format!("Failed to download quiz report with status: {}", response.status()).into());
+    }
+    Ok(response.bytes()?.to_vec())
+}
+
+/// Retrieves the quizzes of a course.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course whose quizzes are listed.
+///
+/// Returns:
+/// - `Result<Vec<Quiz>, Box<dyn Error>>`: The quizzes or an error.
+pub fn fetch_quizzes(client: &Client, course_info: &Arc<CourseInfo>) -> Result<Vec<Quiz>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/quizzes", course_info.canvas_info.url_canvas, course_info.id);
+    Ok(fetch_all_pages(client, &course_info.canvas_info, &url, Vec::new())?
+        .into_iter()
+        .filter_map(|quiz| serde_json::from_value::<QuizInfo>(quiz).ok())
+        .map(|mut info| {
+            info.course_info = Arc::clone(course_info);
+            Quiz { info: Arc::new(info) }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answers_from_latest_attempt() {
+        let submission = json!({
+            "user_id": 42,
+            "submission_history": [
+                {"attempt": 1, "submission_data": [
+                    {"question_id": 1, "text": "old", "correct": false, "points": 0.0}
+                ]},
+                {"attempt": 2, "submission_data": [
+                    {"question_id": 1, "text": "<p>Resposta dissertativa</p>", "correct": "undefined", "points": 0.0},
+                    {"question_id": 2, "text": "B", "answer_id": 5512, "correct": true, "points": 1.0}
+                ]}
+            ]
+        });
+
+        let answers = StudentQuizAnswers::from_submission_json(&submission).unwrap();
+        assert_eq!(answers.user_id, 42);
+        assert_eq!(answers.attempt, Some(2));
+        assert_eq!(answers.answers.len(), 2);
+        assert_eq!(answers.answers[0].text.as_deref(), Some("<p>Resposta dissertativa</p>"));
+        assert_eq!(answers.answers[0].correct, None);
+        assert_eq!(answers.answers[1].answer_id, Some(5512));
+        assert_eq!(answers.answers[1].correct, Some(true));
+
+        assert!(StudentQuizAnswers::from_submission_json(&json!({"user_id": 1, "submission_history": [{"attempt": 1}]})).is_none());
+    }
+}
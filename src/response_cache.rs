@@ -0,0 +1,329 @@
+// Import necessary crates and modules
+use crate::connection::HttpMethod;
+use crate::CanvasCredentials;
+use lazy_static::lazy_static;
+use reqwest::blocking::Response;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Kind of endpoint a GET request reads, which selects how long its answer is cached.
+///
+/// - `Roster`: Users, students, enrollments and sections.
+/// - `Assignments`: Assignments and assignment groups.
+/// - `Submissions`: Submissions (and their comments, rubric assessments...).
+/// - `Other`: Everything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EndpointClass {
+    Roster,
+    Assignments,
+    Submissions,
+    Other,
+}
+
+impl EndpointClass {
+    /// Classifies an API URL by its last named path segment (`/courses/1/students/submissions`
+    /// is `Submissions`, `/courses/1/assignments/10` is `Assignments`).
+    pub fn of_url(url: &str) -> EndpointClass {
+        let path = reqwest::Url::parse(url).map_or_else(|_| url.to_string(), |url| url.path().to_string());
+        let segment = path
+            .split('/')
+            .rev()
+            .find(|segment| !segment.is_empty() && segment.parse::<u64>().is_err() && *segment != "self")
+            .unwrap_or("");
+        match segment {
+            "users" | "students" | "enrollments" | "sections" => EndpointClass::Roster,
+            "assignments" | "assignment_groups" => EndpointClass::Assignments,
+            "submissions" => EndpointClass::Submissions,
+            _ => EndpointClass::Other,
+        }
+    }
+}
+
+/// How long the answers of each `EndpointClass` are kept by the response cache. Classes without a
+/// duration are not cached.
+///
+/// `ResponseCacheConfig::default()` keeps rosters and assignments for an hour and submissions for
+/// a minute.
+///
+/// Example:
+/// ```
+/// set_response_cache(Some(ResponseCacheConfig::default().ttl(EndpointClass::Other, Duration::from_secs(300))));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseCacheConfig {
+    pub ttls: BTreeMap<EndpointClass, Duration>,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        ResponseCacheConfig::none()
+            .ttl(EndpointClass::Roster, Duration::from_secs(3600))
+            .ttl(EndpointClass::Assignments, Duration::from_secs(3600))
+            .ttl(EndpointClass::Submissions, Duration::from_secs(60))
+    }
+}
+
+impl ResponseCacheConfig {
+    /// Configuration caching nothing, to enable classes one by one with `ttl`.
+    pub fn none() -> Self {
+        ResponseCacheConfig { ttls: BTreeMap::new() }
+    }
+
+    /// Configuration keeping course rosters and assignment lists for an hour, so that the many
+    /// calls to `Course::fetch_students` and `Course::fetch_assignments` made by the connector
+    /// don't download them again.
+    pub fn rosters_and_assignments() -> Self {
+        ResponseCacheConfig::none()
+            .ttl(EndpointClass::Roster, Duration::from_secs(3600))
+            .ttl(EndpointClass::Assignments, Duration::from_secs(3600))
+    }
+
+    /// Sets how long the answers of `class` are kept.
+    pub fn ttl(mut self, class: EndpointClass, ttl: Duration) -> Self {
+        self.ttls.insert(class, ttl);
+        self
+    }
+}
+
+// A successful answer kept in memory.
+struct CachedResponse {
+    status: u16,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+    expires_at: Instant,
+}
+
+struct ResponseCache {
+    config: Option<ResponseCacheConfig>,
+    entries: HashMap<String, CachedResponse>,
+}
+
+lazy_static! {
+    static ref RESPONSE_CACHE: Mutex<ResponseCache> = Mutex::new(ResponseCache {
+        config: None,
+        entries: HashMap::new(),
+    });
+}
+
+/// Sets how long GET answers are kept in memory, by endpoint class (`None` disables the cache).
+/// Entries already cached are dropped. The cache is disabled until this is called.
+///
+/// Cached answers are shared by every thread and keyed by the normalized URL (query parameters
+/// in any order) and the credentials, so two tokens never see each other's data. A successful
+/// PUT, POST or DELETE under `/courses/:id` drops the cached answers of that course, including
+/// those read through a section (`/sections/:id/...`); other ones drop the whole cache. Changes made outside the connector (in the web interface) show up when
+/// the entries expire, or after `clear_response_cache`.
+///
+/// Example:
+/// ```
+/// // Grading loop reading submissions many times: keep them for a minute
+/// set_response_cache(Some(ResponseCacheConfig::default()));
+///
+/// // Only avoid downloading rosters and assignment lists again
+/// set_response_cache(Some(ResponseCacheConfig::rosters_and_assignments()));
+/// ```
+pub fn set_response_cache(config: Option<ResponseCacheConfig>) {
+    let mut cache = RESPONSE_CACHE.lock().unwrap();
+    cache.config = config;
+    cache.entries.clear();
+}
+
+/// Returns the configuration of the response cache set with `set_response_cache` (`None` while
+/// disabled).
+pub fn response_cache() -> Option<ResponseCacheConfig> {
+    RESPONSE_CACHE.lock().unwrap().config.clone()
+}
+
+/// Drops every cached answer.
+pub fn clear_response_cache() {
+    RESPONSE_CACHE.lock().unwrap().entries.clear();
+}
+
+// Drops the cached answers of a course (URLs under `/courses/:id/` or equal to `/courses/:id`),
+// and those read through its sections (`/sections/:id/...`): the course of a section can't be
+// told from its URL, so every section-scoped answer goes.
+pub(crate) fn invalidate_course(url_canvas: &str, course_id: u64) {
+    let url_canvas = url_canvas.trim_end_matches('/');
+    let prefix = format!("{}/courses/{}", url_canvas, course_id);
+    let sections = format!("{}/sections/", url_canvas);
+    RESPONSE_CACHE.lock().unwrap().entries.retain(|key, _| {
+        !url_of_key(key).is_some_and(|url| is_under(url, &prefix) || url.starts_with(&sections))
+    });
+}
+
+fn is_under(url: &str, prefix: &str) -> bool {
+    url.strip_prefix(prefix).is_some_and(|rest| rest.is_empty() || rest.starts_with('/') || rest.starts_with('?'))
+}
+
+// Key of a request: the token fingerprint, then the URL with its query parameters sorted.
+fn cache_key(url: &str, params: &[(String, String)], canvas_info: &CanvasCredentials) -> String {
+    let mut hasher = DefaultHasher::new();
+    canvas_info.token_canvas.hash(&mut hasher);
+    let mut pairs: Vec<(String, String)> = params.to_vec();
+    let base = match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            pairs.extend(parsed.query_pairs().map(|(name, value)| (name.into_owned(), value.into_owned())));
+            parsed.set_query(None);
+            parsed.set_fragment(None);
+            parsed.to_string()
+        }
+        Err(_) => url.to_string(),
+    };
+    // The order of repeated parameters (`include[]`) doesn't change the answer either
+    pairs.sort();
+    let query: Vec<String> = pairs
+        .iter()
+        .map(|(name, value)| format!("{}={}", urlencoding::encode(name), urlencoding::encode(value)))
+        .collect();
+    format!("{:016x} {}?{}", hasher.finish(), base, query.join("&"))
+}
+
+fn url_of_key(key: &str) -> Option<&str> {
+    key.split_once(' ').map(|(_, url)| url)
+}
+
+fn to_response(cached: &CachedResponse) -> Result<Response, Box<dyn Error>> {
+    let mut builder = http::Response::builder().status(cached.status);
+    for (name, value) in &cached.headers {
+        builder = builder.header(name, value);
+    }
+    Ok(Response::from(builder.body(cached.body.clone())?))
+}
+
+/// Answers a GET request from the cache. Returns `None` when it must really be sent.
+pub(crate) fn lookup(
+    method: &HttpMethod,
+    url: &str,
+    params: &[(String, String)],
+    canvas_info: &CanvasCredentials,
+) -> Option<Response> {
+    if !matches!(method, HttpMethod::Get) {
+        return None;
+    }
+    let cache = RESPONSE_CACHE.lock().unwrap();
+    cache.config.as_ref()?;
+    let cached = cache.entries.get(&cache_key(url, params, canvas_info))?;
+    if cached.expires_at <= Instant::now() {
+        return None;
+    }
+    to_response(cached).ok()
+}
+
+/// Keeps the successful answer of a GET request when its class is cached, and drops the answers a
+/// successful write may have changed. The answer is handed back to the caller (rebuilt from the
+/// cached copy when kept).
+pub(crate) fn store(
+    method: &HttpMethod,
+    url: &str,
+    params: &[(String, String)],
+    canvas_info: &CanvasCredentials,
+    result: Result<Response, Box<dyn Error>>,
+) -> Result<Response, Box<dyn Error>> {
+    let response = match result {
+        Ok(response) if response.status().is_success() => response,
+        other => return other,
+    };
+    if !matches!(method, HttpMethod::Get) {
+        let course_id = reqwest::Url::parse(url).ok().and_then(|parsed| {
+            let mut segments = parsed.path_segments()?;
+            segments.find(|segment| *segment == "courses")?;
+            segments.next()?.parse::<u64>().ok()
+        });
+        match course_id {
+            Some(course_id) => invalidate_course(&canvas_info.url_canvas, course_id),
+            None => clear_response_cache(),
+        }
+        return Ok(response);
+    }
+    let ttl = {
+        let cache = RESPONSE_CACHE.lock().unwrap();
+        let ttl = cache.config.as_ref().and_then(|config| config.ttls.get(&EndpointClass::of_url(url)).copied());
+        match ttl {
+            Some(ttl) => ttl,
+            None => return Ok(response),
+        }
+    };
+    let cached = CachedResponse {
+        status: response.status().as_u16(),
+        headers: response
+            .headers()
+            .iter()
+            .filter(|(name, _)| name.as_str() != "set-cookie")
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect(),
+        body: response.bytes()?.to_vec(),
+        expires_at: Instant::now() + ttl,
+    };
+    let response = to_response(&cached);
+    let now = Instant::now();
+    let mut cache = RESPONSE_CACHE.lock().unwrap();
+    cache.entries.retain(|_, entry| entry.expires_at > now);
+    cache.entries.insert(cache_key(url, params, canvas_info), cached);
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_classes_and_course_invalidation() {
+        let credentials = |token: &str| CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: token.to_string(),
+        };
+        let params = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect()
+        };
+        let url = "https://canvas.example/api/v1/courses/7/users?enrollment_type[]=student";
+        let key = cache_key(url, &params(&[("include[]", "email"), ("per_page", "100")]), &credentials("a"));
+        assert_eq!(
+            key,
+            cache_key(
+                "https://canvas.example/api/v1/courses/7/users",
+                &params(&[("per_page", "100"), ("enrollment_type[]", "student"), ("include[]", "email")]),
+                &credentials("a")
+            )
+        );
+        assert_ne!(key, cache_key(url, &params(&[("include[]", "email"), ("per_page", "100")]), &credentials("b")));
+
+        assert_eq!(EndpointClass::of_url(url), EndpointClass::Roster);
+        assert_eq!(EndpointClass::of_url("https://x/api/v1/courses/7/students/submissions"), EndpointClass::Submissions);
+        assert_eq!(EndpointClass::of_url("https://x/api/v1/courses/7/assignments/10"), EndpointClass::Assignments);
+        assert_eq!(EndpointClass::of_url("https://x/api/v1/users/self"), EndpointClass::Roster);
+        assert_eq!(EndpointClass::of_url("https://x/api/v1/courses/7/rubrics/3"), EndpointClass::Other);
+
+        let prefix = "https://canvas.example/api/v1/courses/7";
+        assert!(is_under(url_of_key(&key).unwrap(), prefix));
+        assert!(!is_under("https://canvas.example/api/v1/courses/70/users?", prefix));
+    }
+
+    #[test]
+    fn test_course_write_drops_section_answers() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: "token".to_string(),
+        };
+        let answer = |body: &str| -> Result<Response, Box<dyn Error>> {
+            Ok(Response::from(http::Response::builder().status(200).body(body.to_string()).unwrap()))
+        };
+        let section_url = "https://canvas.example/api/v1/sections/31/assignments/10/submissions";
+        let other_url = "https://canvas.example/api/v1/users/self/profile";
+        set_response_cache(Some(ResponseCacheConfig::default().ttl(EndpointClass::Other, Duration::from_secs(60))));
+        store(&HttpMethod::Get, section_url, &[], &canvas_info, answer("[]")).unwrap();
+        store(&HttpMethod::Get, other_url, &[], &canvas_info, answer("{}")).unwrap();
+        assert!(lookup(&HttpMethod::Get, section_url, &[], &canvas_info).is_some());
+
+        let grade_url = "https://canvas.example/api/v1/courses/7/assignments/10/submissions/5";
+        store(&HttpMethod::Put(serde_json::json!({})), grade_url, &[], &canvas_info, answer("{}")).unwrap();
+        let section_answer = lookup(&HttpMethod::Get, section_url, &[], &canvas_info);
+        let other_answer = lookup(&HttpMethod::Get, other_url, &[], &canvas_info);
+        set_response_cache(None);
+        assert!(section_answer.is_none());
+        assert!(other_answer.is_some());
+    }
+}
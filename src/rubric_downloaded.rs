@@ -1,6 +1,15 @@
-use serde::{Deserialize, Serialize};
+use crate::rubric_submission::{
+    CanvasRubricSubmission, CriterionSubmission, RatingSubmission, RubricAssociationSubmission,
+    RubricSubmissionDetails,
+};
+use crate::{canvas, Course};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::error::Error;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RubricDownloaded {
     pub context_id: u64,
     pub context_type: String,
@@ -14,9 +23,11 @@ pub struct RubricDownloaded {
     pub rating_order: Option<String>,                // Optional field
     pub read_only: Option<bool>,                     // Optional field
     pub reusable: Option<bool>,                      // Optional field
+    #[serde(default)]
+    pub associations: Vec<RubricAssociation>,        // Present with include[]=associations
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Criterion {
     pub criterion_use_range: Option<bool>,
     pub description: String,
@@ -24,13 +35,346 @@ pub struct Criterion {
     pub long_description: Option<String>,
     pub points: f64,
     pub ratings: Vec<Rating>,
+    pub ignore_for_scoring: Option<bool>,            // Criterion doesn't count towards the total
+    pub learning_outcome_id: Option<String>,         // Set when the criterion comes from an outcome
+    pub mastery_points: Option<f64>,                 // Outcome criteria only
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Rating {
+    #[serde(default)]
     pub criterion_id: String,
     pub description: String,
     pub id: String,
+    #[serde(default, deserialize_with = "null_as_empty")]
     pub long_description: String,
     pub points: f64,
 }
+
+/// Where a rubric is used (an assignment, or the course itself).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RubricAssociation {
+    pub id: u64,
+    pub rubric_id: u64,
+    pub association_id: u64,
+    pub association_type: String,                    // "Assignment", "Course" or "Account"
+    #[serde(default)]
+    pub use_for_grading: bool,
+    pub purpose: Option<String>,                     // "grading" or "bookmark"
+    pub hide_score_total: Option<bool>,
+}
+
+// Canvas sends `null` for empty long descriptions.
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+impl Criterion {
+    /// Converts the criterion into its submission form, keeping the order of the ratings
+    /// ("1", "2", ...). Long descriptions are not part of the submission format and are dropped.
+    pub fn to_submission(&self) -> CriterionSubmission {
+        CriterionSubmission {
+            description: self.description.clone(),
+            criterion_use_range: self.criterion_use_range,
+            ratings: self
+                .ratings
+                .iter()
+                .enumerate()
+                .map(|(index, rating)| {
+                    (
+                        (index + 1).to_string(),
+                        RatingSubmission {
+                            description: rating.description.clone(),
+                            points: rating.points,
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+impl RubricDownloaded {
+    /// Association of the rubric with an assignment, if any.
+    pub fn assignment_association(&self) -> Option<&RubricAssociation> {
+        self.associations
+            .iter()
+            .find(|association| association.association_type == "Assignment")
+    }
+
+    /// Converts the downloaded rubric into a `CanvasRubricSubmission` that can be posted with
+    /// `canvas::create_rubric`, e.g. to copy it into another course.
+    ///
+    /// Criteria keep their order through the numerical keys ("1", "2", ...).
+    ///
+    /// Arguments:
+    /// - `rubric_association`: Where the new rubric will be associated.
+    ///
+    /// Example:
+    /// ```
+    /// let rubric = assignment.download_rubric().unwrap();
+    /// let submission = rubric.to_submission(RubricAssociationSubmission {
+    ///     association_type: "Course".to_string(),
+    ///     association_id: other_course.info.id,
+    ///     use_for_grading: false,
+    /// });
+    /// ```
+    pub fn to_submission(&self, rubric_association: RubricAssociationSubmission) -> CanvasRubricSubmission {
+        let criteria: HashMap<String, CriterionSubmission> = self
+            .data
+            .iter()
+            .enumerate()
+            .map(|(index, criterion)| ((index + 1).to_string(), criterion.to_submission()))
+            .collect();
+        CanvasRubricSubmission::new(
+            RubricSubmissionDetails {
+                title: self.title.clone(),
+                criteria,
+            },
+            rubric_association,
+        )
+    }
+}
+
+impl RubricDownloaded {
+    /// Re-creates this rubric in another course, preserving the criterion order, the rating
+    /// points and the point ranges.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `course`: Course that receives the copy.
+    /// - `assignment_id`: Assignment of `course` to attach the copy to (used for grading), or
+    ///   `None` to only add it to the course rubrics.
+    ///
+    /// Returns:
+    /// - `Result<u64, Box<dyn Error>>`: ID of the new rubric or an error.
+    ///
+    /// Example:
+    /// ```
+    /// let rubric = assignment.download_rubric().unwrap();
+    /// let new_id = rubric.copy_to(&client, &next_term_course, None)?;
+    /// ```
+    pub fn copy_to(&self, client: &Client, course: &Course, assignment_id: Option<u64>) -> Result<u64, Box<dyn Error>> {
+        let association = match assignment_id {
+            Some(assignment_id) => RubricAssociationSubmission {
+                association_type: "Assignment".to_string(),
+                association_id: assignment_id,
+                use_for_grading: true,
+            },
+            None => RubricAssociationSubmission {
+                association_type: "Course".to_string(),
+                association_id: course.info.id,
+                use_for_grading: false,
+            },
+        };
+        let response = canvas::create_rubric_with_response(
+            client,
+            &course.info.canvas_info,
+            course.info.id,
+            &self.to_submission(association),
+        )?;
+        response["rubric"]["id"]
+            .as_u64()
+            .ok_or_else(|| "Canvas did not return the ID of the new rubric".into())
+    }
+}
+
+/// Correspondence between the criterion and rating IDs of two versions of a rubric.
+///
+/// Canvas gives new IDs to every criterion when a rubric is re-created (copied to another course,
+/// or rebuilt after edits), so assessments made with the old version no longer point to the
+/// criteria of the new one. Criteria are matched by description, ratings by description inside
+/// matched criteria.
+///
+/// Fields:
+/// - `criteria`: New criterion ID by old criterion ID.
+/// - `ratings`: New rating ID by old rating ID.
+/// - `unmatched`: IDs of the old criteria without a counterpart in the new version.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RubricIdMapping {
+    pub criteria: HashMap<String, String>,
+    pub ratings: HashMap<String, String>,
+    pub unmatched: Vec<String>,
+}
+
+// Description used to match criteria and ratings: case, repeated spaces and surrounding
+// whitespace are not significant.
+fn match_key(description: &str) -> String {
+    description.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase()
+}
+
+impl RubricIdMapping {
+    /// Translates a rubric assessment of the old version (`{"_101": {"points": 3, "rating_id": ...}}`)
+    /// to the IDs of the new one. Entries of unmatched criteria are dropped.
+    pub fn map_assessment(&self, assessment: &Value) -> Value {
+        let Some(entries) = assessment.as_object() else {
+            return assessment.clone();
+        };
+        let mut mapped = Map::new();
+        for (criterion_id, entry) in entries {
+            let Some(new_id) = self.criteria.get(criterion_id) else {
+                continue;
+            };
+            let mut entry = entry.clone();
+            if let Some(rating_id) = entry["rating_id"].as_str().and_then(|id| self.ratings.get(id)) {
+                entry["rating_id"] = Value::String(rating_id.clone());
+            }
+            mapped.insert(new_id.clone(), entry);
+        }
+        Value::Object(mapped)
+    }
+
+    /// Translates the `rubric_assessment` of submissions listed with
+    /// `include[]=rubric_assessment`, e.g. before `RubricResults::build_with_mapping`.
+    pub fn map_submissions(&self, submissions: &[Value]) -> Vec<Value> {
+        submissions
+            .iter()
+            .map(|submission| {
+                let mut submission = submission.clone();
+                if submission["rubric_assessment"].is_object() {
+                    submission["rubric_assessment"] = self.map_assessment(&submission["rubric_assessment"]);
+                }
+                submission
+            })
+            .collect()
+    }
+}
+
+impl RubricDownloaded {
+    /// Matches the criteria of this rubric with the ones of `newer`, another version of it.
+    /// Criteria sharing a description are paired in order.
+    ///
+    /// Example:
+    /// ```
+    /// let mapping = old_rubric.id_mapping_to(&assignment.download_rubric().unwrap());
+    /// for id in &mapping.unmatched {
+    ///     eprintln!("Criterion {} no longer exists", id);
+    /// }
+    /// ```
+    pub fn id_mapping_to(&self, newer: &RubricDownloaded) -> RubricIdMapping {
+        let mut mapping = RubricIdMapping::default();
+        let mut used = vec![false; newer.data.len()];
+        for criterion in &self.data {
+            let key = match_key(&criterion.description);
+            let found = newer
+                .data
+                .iter()
+                .enumerate()
+                .position(|(index, candidate)| !used[index] && match_key(&candidate.description) == key);
+            let Some(index) = found else {
+                mapping.unmatched.push(criterion.id.clone());
+                continue;
+            };
+            used[index] = true;
+            let counterpart = &newer.data[index];
+            mapping.criteria.insert(criterion.id.clone(), counterpart.id.clone());
+            for rating in &criterion.ratings {
+                let key = match_key(&rating.description);
+                if let Some(new_rating) = counterpart.ratings.iter().find(|new| match_key(&new.description) == key) {
+                    mapping.ratings.insert(rating.id.clone(), new_rating.id.clone());
+                }
+            }
+        }
+        mapping
+    }
+
+    /// Same as `copy_to`, also returning how the criteria of this rubric map to the ones of the
+    /// copy, to carry assessments over.
+    pub fn copy_to_with_mapping(
+        &self,
+        client: &Client,
+        course: &Course,
+        assignment_id: Option<u64>,
+    ) -> Result<(u64, RubricIdMapping), Box<dyn Error>> {
+        let new_id = self.copy_to(client, course, assignment_id)?;
+        let copy: RubricDownloaded = serde_json::from_value(canvas::download_rubric(
+            client,
+            &course.info.canvas_info,
+            course.info.id,
+            new_id,
+        )?)?;
+        Ok((new_id, self.id_mapping_to(&copy)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_rubric_and_conversion() {
+        let rubric: RubricDownloaded = serde_json::from_value(json!({
+            "id": 12,
+            "context_id": 1,
+            "context_type": "Course",
+            "title": "Apresentação",
+            "points_possible": 8.0,
+            "data": [
+                {"id": "_101", "description": "Conteúdo", "long_description": null, "points": 5.0,
+                 "criterion_use_range": true, "ratings": [
+                    {"id": "r1", "criterion_id": "_101", "description": "Completo", "long_description": null, "points": 5.0},
+                    {"id": "r2", "criterion_id": "_101", "description": "Parcial", "long_description": "", "points": 2.0}
+                ]},
+                {"id": "_102", "description": "Postura", "points": 3.0, "ratings": [
+                    {"id": "r3", "description": "Boa", "long_description": "", "points": 3.0}
+                ]}
+            ],
+            "associations": [
+                {"id": 7, "rubric_id": 12, "association_id": 55, "association_type": "Assignment",
+                 "use_for_grading": true, "purpose": "grading"}
+            ]
+        }))
+        .unwrap();
+        assert_eq!(rubric.assignment_association().map(|a| a.association_id), Some(55));
+
+        let submission = rubric.to_submission(RubricAssociationSubmission {
+            association_type: "Course".to_string(),
+            association_id: 2,
+            use_for_grading: false,
+        });
+        let first = &submission.rubric.criteria["1"];
+        assert_eq!(first.description, "Conteúdo");
+        assert_eq!(first.criterion_use_range, Some(true));
+        assert_eq!(first.ratings["2"].points, 2.0);
+        assert_eq!(submission.rubric.criteria["2"].description, "Postura");
+        assert_eq!(submission.total_points(), 8.0);
+    }
+
+    #[test]
+    fn test_criteria_are_matched_across_versions() {
+        let version = |criteria: Value| -> RubricDownloaded {
+            serde_json::from_value(json!({
+                "id": 1, "context_id": 1, "context_type": "Course",
+                "title": "Apresentação", "points_possible": 8.0, "data": criteria
+            }))
+            .unwrap()
+        };
+        let old = version(json!([
+            {"id": "_101", "description": "Conteúdo", "points": 5.0, "ratings": [
+                {"id": "r1", "description": "Completo", "points": 5.0},
+                {"id": "r2", "description": "Parcial", "points": 2.0}
+            ]},
+            {"id": "_102", "description": "Postura", "points": 3.0, "ratings": []}
+        ]));
+        let new = version(json!([
+            {"id": "_9", "description": "  conteúdo ", "points": 5.0, "ratings": [
+                {"id": "r8", "description": "completo", "points": 5.0}
+            ]}
+        ]));
+        let mapping = old.id_mapping_to(&new);
+        assert_eq!(mapping.criteria, HashMap::from([("_101".to_string(), "_9".to_string())]));
+        assert_eq!(mapping.ratings, HashMap::from([("r1".to_string(), "r8".to_string())]));
+        assert_eq!(mapping.unmatched, vec!["_102".to_string()]);
+
+        let submissions = mapping.map_submissions(&[json!({
+            "user_id": 7,
+            "rubric_assessment": {
+                "_101": {"points": 5.0, "rating_id": "r1"},
+                "_102": {"points": 3.0}
+            }
+        })]);
+        assert_eq!(submissions[0]["rubric_assessment"], json!({"_9": {"points": 5.0, "rating_id": "r8"}}));
+        assert_eq!(submissions[0]["user_id"], 7);
+    }
+}
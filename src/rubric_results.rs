@@ -0,0 +1,237 @@
+// Import necessary crates and modules
+use crate::rubric_downloaded::{RubricDownloaded, RubricIdMapping};
+use crate::Student;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+use std::fs::File;
+use std::io::Write;
+
+/// A criterion (column) of a `RubricResults` table.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RubricResultCriterion {
+    pub id: String,
+    pub description: String,
+    pub points_possible: f64,
+}
+
+/// Assessment of one criterion for one student.
+///
+/// Fields:
+/// - `points`: Points given, if any.
+/// - `rating_id`: ID of the selected rating, if any.
+/// - `comments`: Free-form comment of the grader (empty when none).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct RubricResultCell {
+    pub points: Option<f64>,
+    pub rating_id: Option<String>,
+    pub comments: String,
+}
+
+/// A student (row) of a `RubricResults` table. `cells` follow the order of
+/// `RubricResults::criteria`; `None` means the criterion wasn't assessed.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RubricResultRow {
+    pub student_id: u64,
+    pub student_name: String,
+    pub cells: Vec<Option<RubricResultCell>>,
+}
+
+impl RubricResultRow {
+    /// Sum of the points of the assessed criteria.
+    pub fn total(&self) -> f64 {
+        self.cells
+            .iter()
+            .flatten()
+            .filter_map(|cell| cell.points)
+            .fold(0.0, |total, points| total + points)
+    }
+}
+
+/// Students × criteria matrix with the points and comments of the rubric assessments of an
+/// assignment, as requested by accreditation reviews.
+///
+/// Example:
+/// ```
+/// let results = assignment.export_rubric_results(&students)?;
+/// results.save_csv("rubric_results.csv")?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RubricResults {
+    pub assignment_id: u64,
+    pub criteria: Vec<RubricResultCriterion>,
+    pub rows: Vec<RubricResultRow>,
+}
+
+impl RubricResults {
+    /// Builds the table from a rubric, the submissions listed with `include[]=rubric_assessment`
+    /// and the students to report (one row per student, in the given order).
+    pub fn build(
+        assignment_id: u64,
+        rubric: &RubricDownloaded,
+        submissions: &[Value],
+        students: &[Student],
+    ) -> RubricResults {
+        let criteria: Vec<RubricResultCriterion> = rubric
+            .data
+            .iter()
+            .map(|criterion| RubricResultCriterion {
+                id: criterion.id.clone(),
+                description: criterion.description.clone(),
+                points_possible: criterion.points,
+            })
+            .collect();
+
+        let rows = students
+            .iter()
+            .map(|student| {
+                let assessment = submissions
+                    .iter()
+                    .find(|submission| submission["user_id"].as_u64() == Some(student.info.id))
+                    .map(|submission| &submission["rubric_assessment"]);
+                let cells = criteria
+                    .iter()
+                    .map(|criterion| {
+                        let entry = assessment.map(|a| &a[criterion.id.as_str()])?;
+                        if !entry.is_object() {
+                            return None;
+                        }
+                        Some(RubricResultCell {
+                            points: entry["points"].as_f64(),
+                            rating_id: entry["rating_id"].as_str().map(String::from),
+                            comments: entry["comments"].as_str().unwrap_or_default().to_string(),
+                        })
+                    })
+                    .collect();
+                RubricResultRow {
+                    student_id: student.info.id,
+                    student_name: student.info.name.clone(),
+                    cells,
+                }
+            })
+            .collect();
+
+        RubricResults {
+            assignment_id,
+            criteria,
+            rows,
+        }
+    }
+
+    /// Same as `build` for assessments made with an older version of `rubric`, translated with
+    /// `mapping` (see `RubricDownloaded::id_mapping_to`).
+    pub fn build_with_mapping(
+        assignment_id: u64,
+        rubric: &RubricDownloaded,
+        submissions: &[Value],
+        students: &[Student],
+        mapping: &RubricIdMapping,
+    ) -> RubricResults {
+        RubricResults::build(assignment_id, rubric, &mapping.map_submissions(submissions), students)
+    }
+
+    /// Renders the table as CSV: student ID and name, then points and comments of each
+    /// criterion, then the total.
+    pub fn to_csv(&self) -> String {
+        let mut header = vec!["student_id".to_string(), "student_name".to_string()];
+        for criterion in &self.criteria {
+            header.push(format!("{} ({} pts)", criterion.description, criterion.points_possible));
+            header.push(format!("{} (comments)", criterion.description));
+        }
+        header.push("total".to_string());
+
+        let mut lines = vec![csv_line(&header)];
+        for row in &self.rows {
+            let mut fields = vec![row.student_id.to_string(), row.student_name.clone()];
+            for cell in &row.cells {
+                match cell {
+                    Some(cell) => {
+                        fields.push(cell.points.map(|p| p.to_string()).unwrap_or_default());
+                        fields.push(cell.comments.clone());
+                    }
+                    None => {
+                        fields.push(String::new());
+                        fields.push(String::new());
+                    }
+                }
+            }
+            fields.push(row.total().to_string());
+            lines.push(csv_line(&fields));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Writes the CSV rendering of the table to `file_path`.
+    pub fn save_csv(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        let mut file = File::create(file_path)?;
+        file.write_all(self.to_csv().as_bytes())?;
+        Ok(())
+    }
+}
+
+// Joins fields into a CSV line, quoting those that contain separators, quotes or line breaks.
+fn csv_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canvas::convert_json_to_student;
+    use crate::CourseInfo;
+    use serde_json::json;
+
+    #[test]
+    fn test_build_and_csv() {
+        let rubric: RubricDownloaded = serde_json::from_value(json!({
+            "context_id": 1,
+            "context_type": "Course",
+            "points_possible": 10.0,
+            "id": 3,
+            "title": "Relatório",
+            "data": [
+                {"id": "c1", "description": "Clareza", "points": 4.0, "ratings": []},
+                {"id": "c2", "description": "Método", "points": 6.0, "ratings": []}
+            ]
+        }))
+        .unwrap();
+        let students: Vec<Student> = [
+            json!({"id": 10, "name": "Ana"}),
+            json!({"id": 11, "name": "Bruno, Jr."}),
+        ]
+        .iter()
+        .filter_map(|j| convert_json_to_student(CourseInfo::default(), j))
+        .collect();
+        let submissions = vec![json!({
+            "user_id": 10,
+            "rubric_assessment": {
+                "c1": {"points": 3.0, "rating_id": "r1", "comments": "Bom, mas \"curto\""},
+                "c2": {"points": 6.0, "comments": ""}
+            }
+        })];
+
+        let results = RubricResults::build(7, &rubric, &submissions, &students);
+        assert_eq!(results.rows.len(), 2);
+        assert_eq!(results.rows[0].total(), 9.0);
+        assert!(results.rows[1].cells.iter().all(|c| c.is_none()));
+
+        let csv = results.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines[0],
+            "student_id,student_name,Clareza (4 pts),Clareza (comments),Método (6 pts),Método (comments),total"
+        );
+        assert_eq!(lines[1], "10,Ana,3,\"Bom, mas \"\"curto\"\"\",6,,9");
+        assert_eq!(lines[2], "11,\"Bruno, Jr.\",,,,,0");
+    }
+}
@@ -74,3 +74,177 @@ impl CanvasRubricSubmission {
         Ok(rubric)
     }
 }
+
+use crate::AssignmentInfo;
+use std::fmt;
+
+/// Problem found by `CanvasRubricSubmission::validate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RubricValidationError {
+    /// The maximum points of the criteria don't add up to the assignment's points possible.
+    TotalMismatch { rubric_total: f64, points_possible: f64 },
+    /// A criterion has no ratings.
+    NoRatings { criterion: String },
+    /// The rating points of a criterion go up and down (e.g. 5, 2, 4).
+    NonMonotonicRatings { criterion: String, points: Vec<f64> },
+}
+
+impl fmt::Display for RubricValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RubricValidationError::TotalMismatch { rubric_total, points_possible } => write!(
+                f,
+                "Rubric total ({}) differs from the assignment points possible ({})",
+                rubric_total, points_possible
+            ),
+            RubricValidationError::NoRatings { criterion } => {
+                write!(f, "Criterion '{}' has no ratings", criterion)
+            }
+            RubricValidationError::NonMonotonicRatings { criterion, points } => write!(
+                f,
+                "Ratings of criterion '{}' are not in order: {:?}",
+                criterion, points
+            ),
+        }
+    }
+}
+
+impl Error for RubricValidationError {}
+
+// Sorts map entries by their numerical string keys ("1", "2", ..., "10").
+fn sorted_by_key<T>(map: &HashMap<String, T>) -> Vec<(&String, &T)> {
+    let mut entries: Vec<(&String, &T)> = map.iter().collect();
+    entries.sort_by(|(a, _), (b, _)| {
+        match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(b),
+        }
+    });
+    entries
+}
+
+impl CriterionSubmission {
+    /// Highest points among the ratings of the criterion (0 when it has none).
+    pub fn max_points(&self) -> f64 {
+        self.ratings
+            .values()
+            .map(|rating| rating.points)
+            .fold(0.0, f64::max)
+    }
+}
+
+impl CanvasRubricSubmission {
+    /// Sum of the maximum points of every criterion.
+    pub fn total_points(&self) -> f64 {
+        self.rubric
+            .criteria
+            .values()
+            .map(|criterion| criterion.max_points())
+            .fold(0.0, |total, points| total + points)
+    }
+
+    /// Checks the rubric before it is sent to Canvas, which accepts inconsistent rubrics silently.
+    ///
+    /// Verifies that the maximum points of the criteria add up to the assignment's
+    /// `points_possible` (skipped when the assignment has none) and that the ratings of every
+    /// criterion, in key order, have monotonic points.
+    ///
+    /// Returns:
+    /// - `Result<(), Vec<RubricValidationError>>`: `Ok` or every problem found.
+    ///
+    /// Example:
+    /// ```
+    /// let rubric = CanvasRubricSubmission::load_from_json("rubric.json")?;
+    /// if let Err(errors) = rubric.validate(&assignment.info) {
+    ///     for error in errors {
+    ///         eprintln!("{}", error);
+    ///     }
+    /// }
+    /// ```
+    pub fn validate(&self, assignment: &AssignmentInfo) -> Result<(), Vec<RubricValidationError>> {
+        let mut errors = Vec::new();
+
+        for (key, criterion) in sorted_by_key(&self.rubric.criteria) {
+            let name = if criterion.description.is_empty() { key.clone() } else { criterion.description.clone() };
+            if criterion.ratings.is_empty() {
+                errors.push(RubricValidationError::NoRatings { criterion: name });
+                continue;
+            }
+            let points: Vec<f64> = sorted_by_key(&criterion.ratings)
+                .into_iter()
+                .map(|(_, rating)| rating.points)
+                .collect();
+            let descending = points.windows(2).all(|w| w[0] >= w[1]);
+            let ascending = points.windows(2).all(|w| w[0] <= w[1]);
+            if !descending && !ascending {
+                errors.push(RubricValidationError::NonMonotonicRatings { criterion: name, points });
+            }
+        }
+
+        if let Some(points_possible) = assignment.points_possible {
+            let rubric_total = self.total_points();
+            if (rubric_total - points_possible).abs() > 1e-6 {
+                errors.push(RubricValidationError::TotalMismatch {
+                    rubric_total,
+                    points_possible,
+                });
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_rubric() {
+        let rubric: CanvasRubricSubmission = serde_json::from_value(json!({
+            "rubric": {
+                "title": "Projeto",
+                "criteria": {
+                    "1": {"description": "Código", "criterion_use_range": false, "ratings": {
+                        "1": {"description": "Ótimo", "points": 6.0},
+                        "2": {"description": "Bom", "points": 3.0},
+                        "3": {"description": "Ruim", "points": 0.0}
+                    }},
+                    "2": {"description": "Relatório", "criterion_use_range": false, "ratings": {
+                        "1": {"description": "Ótimo", "points": 4.0},
+                        "2": {"description": "Bom", "points": 1.0},
+                        "3": {"description": "Regular", "points": 2.0}
+                    }}
+                }
+            },
+            "rubric_association": {"association_type": "Course", "association_id": 1, "use_for_grading": true}
+        }))
+        .unwrap();
+
+        let assignment = AssignmentInfo {
+            points_possible: Some(10.0),
+            ..Default::default()
+        };
+        assert_eq!(rubric.total_points(), 10.0);
+        assert_eq!(
+            rubric.validate(&assignment),
+            Err(vec![RubricValidationError::NonMonotonicRatings {
+                criterion: "Relatório".to_string(),
+                points: vec![4.0, 1.0, 2.0],
+            }])
+        );
+
+        let assignment = AssignmentInfo {
+            points_possible: Some(20.0),
+            ..Default::default()
+        };
+        let errors = rubric.validate(&assignment).unwrap_err();
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(errors[1], RubricValidationError::TotalMismatch { .. }));
+    }
+}
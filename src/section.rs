@@ -0,0 +1,150 @@
+// Import necessary crates and modules
+use crate::canvas::fetch_all_pages;
+use crate::connection::{send_http_request_with_options, HttpMethod, RequestOptions};
+use crate::{CanvasCredentials, CourseInfo};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+use std::sync::Arc;
+
+/// Information about a section of a course.
+///
+/// Fields:
+/// - `id`: ID of the section.
+/// - `name`: Name of the section.
+/// - `course_id`: Course the section currently belongs to.
+/// - `nonxlist_course_id`: Original course of a cross-listed section (`None` when the section is
+///   not cross-listed).
+/// - `sis_section_id`: SIS identifier, when visible to the user.
+/// - `canvas_info`: Credentials used to reach the section.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SectionInfo {
+    pub id: u64,
+    pub name: String,
+    pub course_id: u64,
+    pub nonxlist_course_id: Option<u64>,
+    pub sis_section_id: Option<String>,
+    #[serde(skip)]
+    pub canvas_info: Arc<CanvasCredentials>,
+}
+
+/// A section of a course. See `Course::fetch_sections`.
+///
+/// Example:
+/// ```
+/// // Merge every lecture section into the first course of the term
+/// for section in other_course.fetch_sections(&client)? {
+///     section.cross_list_to(&client, main_course.info.id)?;
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Section {
+    pub info: Arc<SectionInfo>,
+}
+
+impl Section {
+    /// Returns `true` when the section was moved to another course by cross-listing.
+    pub fn is_cross_listed(&self) -> bool {
+        self.info.nonxlist_course_id.is_some()
+    }
+
+    /// Moves (cross-lists) the section into another course, with its enrollments.
+    ///
+    /// Returns:
+    /// - `Result<Section, Box<dyn Error>>`: The section as updated by Canvas or an error.
+    pub fn cross_list_to(&self, client: &Client, course_id: u64) -> Result<Section, Box<dyn Error>> {
+        self.send(client, HttpMethod::Post(json!({})), &self.crosslist_url(Some(course_id)))
+    }
+
+    /// Returns a cross-listed section to its original course.
+    pub fn uncross_list(&self, client: &Client) -> Result<Section, Box<dyn Error>> {
+        self.send(client, HttpMethod::Delete, &self.crosslist_url(None))
+    }
+
+    // Cross-listing endpoint of the section, towards `course_id` when moving it.
+    fn crosslist_url(&self, course_id: Option<u64>) -> String {
+        let url = format!("{}/sections/{}/crosslist", self.info.canvas_info.url_canvas, self.info.id);
+        match course_id {
+            Some(course_id) => format!("{}/{}", url, course_id),
+            None => url,
+        }
+    }
+
+    // Sends a cross-listing request and decodes the updated section. Cross-listing twice to the
+    // same course changes nothing, so the POST is retried like any other request.
+    fn send(&self, client: &Client, method: HttpMethod, url: &str) -> Result<Section, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let options = RequestOptions {
+            retry_writes: true,
+            ..Default::default()
+        };
+        let response = send_http_request_with_options(client, method, url, canvas_info, vec![], options)?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to cross-list section with status: {}", response.status()).into());
+        }
+        section_from_json(canvas_info, response.json()?).ok_or_else(|| "Invalid section returned by Canvas".into())
+    }
+}
+
+// Builds a `Section` bound to its credentials from the JSON returned by Canvas.
+fn section_from_json(canvas_info: &Arc<CanvasCredentials>, json: Value) -> Option<Section> {
+    let mut info: SectionInfo = serde_json::from_value(json).ok()?;
+    info.canvas_info = Arc::clone(canvas_info);
+    Some(Section { info: Arc::new(info) })
+}
+
+/// Retrieves the sections of a course (including sections cross-listed into it).
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `course_info`: Course whose sections are listed.
+///
+/// Returns:
+/// - `Result<Vec<Section>, Box<dyn Error>>`: The sections or an error.
+pub fn fetch_sections(client: &Client, course_info: &CourseInfo) -> Result<Vec<Section>, Box<dyn Error>> {
+    let url = format!("{}/courses/{}/sections", course_info.canvas_info.url_canvas, course_info.id);
+    Ok(fetch_all_pages(client, &course_info.canvas_info, &url, Vec::new())?
+        .into_iter()
+        .filter_map(|json| section_from_json(&course_info.canvas_info, json))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cross_listed_section_from_canvas_json() {
+        let canvas_info = Arc::new(CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: "token".to_string(),
+        });
+        let section = section_from_json(
+            &canvas_info,
+            json!({
+                "id": 31,
+                "name": "Turma B",
+                "course_id": 200,
+                "nonxlist_course_id": 100,
+                "sis_section_id": null,
+                "start_at": null,
+                "end_at": null,
+                "total_students": 38
+            }),
+        )
+        .unwrap();
+        assert!(section.is_cross_listed());
+        assert_eq!(section.info.nonxlist_course_id, Some(100));
+        assert_eq!(section.info.sis_section_id, None);
+        assert_eq!(
+            section.crosslist_url(Some(300)),
+            "https://canvas.example/api/v1/sections/31/crosslist/300"
+        );
+        assert_eq!(section.crosslist_url(None), "https://canvas.example/api/v1/sections/31/crosslist");
+
+        // Section still in its own course
+        let section = section_from_json(&canvas_info, json!({"id": 32, "name": "Turma A", "course_id": 100})).unwrap();
+        assert!(!section.is_cross_listed());
+    }
+}
@@ -0,0 +1,290 @@
+// Import necessary crates and modules
+use crate::canvas;
+use crate::rubric_downloaded::RubricDownloaded;
+use crate::submission::{Comment, CriterionAssessment, Submission, SubmissionType};
+use crate::{AssignmentInfo, Course, CourseInfo, StudentInfo};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufReader, BufWriter};
+
+/// Version of the snapshot document format produced by `Course::snapshot`.
+///
+/// Bump this whenever a field is removed or changes meaning, so that readers of archived
+/// snapshots can detect documents they don't understand.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Offline copy of a course: roster, assignments, submissions and rubrics.
+///
+/// Fields:
+/// - `version`: Format version (`SNAPSHOT_VERSION` at the time of writing).
+/// - `taken_at`: When the snapshot was taken.
+/// - `course`: Course details.
+/// - `students`: Students enrolled in the course.
+/// - `assignments`: Assignments with their submissions and rubric.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CourseSnapshot {
+    pub version: u32,
+    pub taken_at: DateTime<Utc>,
+    pub course: CourseInfo,
+    pub students: Vec<StudentInfo>,
+    pub assignments: Vec<AssignmentSnapshot>,
+}
+
+/// An assignment inside a `CourseSnapshot`.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AssignmentSnapshot {
+    pub info: AssignmentInfo,
+    pub submissions: Vec<SubmissionSnapshot>,
+    pub rubric: Option<RubricDownloaded>,
+}
+
+/// A submission inside a `CourseSnapshot`.
+///
+/// Unlike `Submission`, students are referenced by ID (`user_ids`, with several entries for group
+/// submissions) so the document has no duplicated roster data. `rubric_assessment` holds the
+/// rubric scores by criterion ID (empty for assignments without a rubric, and in snapshots taken
+/// before it was recorded).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SubmissionSnapshot {
+    pub id: u64,
+    pub user_ids: Vec<u64>,
+    pub score: Option<f64>,
+    pub submitted_at: Option<DateTime<Utc>>,
+    pub submission_type: Option<SubmissionType>,
+    pub file_ids: Vec<u64>,
+    pub comments: Vec<Comment>,
+    #[serde(default)]
+    pub rubric_assessment: BTreeMap<String, CriterionAssessment>,
+}
+
+impl From<&Submission> for SubmissionSnapshot {
+    fn from(submission: &Submission) -> Self {
+        SubmissionSnapshot {
+            id: submission.id,
+            user_ids: submission.students_info.iter().map(|s| s.id).collect(),
+            score: submission.score,
+            submitted_at: submission.submitted_at,
+            submission_type: submission.submission_type.clone(),
+            file_ids: submission.file_ids.clone(),
+            comments: submission.comments.clone(),
+            rubric_assessment: BTreeMap::new(),
+        }
+    }
+}
+
+// Copies the `rubric_assessment` of the submissions listed by `canvas::get_rubric_assessments`
+// into the matching submission snapshots.
+fn add_rubric_assessments(submissions: &mut [SubmissionSnapshot], assessments: &[Value]) {
+    let by_id: HashMap<u64, &Value> = assessments
+        .iter()
+        .filter_map(|json| Some((json["id"].as_u64()?, &json["rubric_assessment"])))
+        .collect();
+    for submission in submissions {
+        if let Some(assessment) = by_id.get(&submission.id) {
+            submission.rubric_assessment = serde_json::from_value((*assessment).clone()).unwrap_or_default();
+        }
+    }
+}
+
+impl CourseSnapshot {
+    /// Downloads everything needed to build a snapshot of `course`.
+    ///
+    /// This performs one request for the roster, one for the assignment list, and then one
+    /// submissions listing and, for assignments with a rubric, one rubric download and one
+    /// listing of the rubric assessments.
+    pub fn take(course: &Course) -> Result<CourseSnapshot, Box<dyn Error>> {
+        let students = course.fetch_students()?;
+        let assignments = course.fetch_assignments()?;
+        let client = reqwest::blocking::Client::new();
+
+        let mut assignment_snapshots = Vec::new();
+        for assignment in &assignments {
+            let mut submissions: Vec<SubmissionSnapshot> =
+                assignment.fetch_submissions(&students)?.iter().map(SubmissionSnapshot::from).collect();
+            let rubric = if assignment.info.rubric_id.is_some() {
+                assignment.download_rubric()
+            } else {
+                None
+            };
+            if rubric.is_some() {
+                let assessments = canvas::get_rubric_assessments(
+                    &client,
+                    &course.info.canvas_info,
+                    course.info.id,
+                    assignment.info.id,
+                )?;
+                add_rubric_assessments(&mut submissions, &assessments);
+            }
+            assignment_snapshots.push(AssignmentSnapshot {
+                info: (*assignment.info).clone(),
+                submissions,
+                rubric,
+            });
+        }
+
+        Ok(CourseSnapshot {
+            version: SNAPSHOT_VERSION,
+            taken_at: Utc::now(),
+            course: (*course.info).clone(),
+            students: students.iter().map(|s| (*s.info).clone()).collect(),
+            assignments: assignment_snapshots,
+        })
+    }
+
+    /// Serializes the snapshot as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Writes the snapshot as JSON to `file_path`.
+    pub fn save_to_file(&self, file_path: &str) -> Result<(), Box<dyn Error>> {
+        let writer = BufWriter::new(File::create(file_path)?);
+        serde_json::to_writer_pretty(writer, self)?;
+        Ok(())
+    }
+
+    /// Parses a snapshot from JSON, rejecting documents written by a newer format version.
+    pub fn from_json(json: &str) -> Result<CourseSnapshot, Box<dyn Error>> {
+        let snapshot: CourseSnapshot = serde_json::from_str(json)?;
+        snapshot.check_version()?;
+        Ok(snapshot)
+    }
+
+    /// Loads a snapshot previously written by `save_to_file`.
+    pub fn load_from_file(file_path: &str) -> Result<CourseSnapshot, Box<dyn Error>> {
+        let reader = BufReader::new(File::open(file_path)?);
+        let snapshot: CourseSnapshot = serde_json::from_reader(reader)?;
+        snapshot.check_version()?;
+        Ok(snapshot)
+    }
+
+    fn check_version(&self) -> Result<(), Box<dyn Error>> {
+        if self.version > SNAPSHOT_VERSION {
+            return Err(format!(
+                "Snapshot version {} is newer than the supported version {}",
+                self.version, SNAPSHOT_VERSION
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+/// Students added to and removed from a course roster between two syncs.
+///
+/// Fields:
+/// - `added`: Students in the current roster but not in the previous one.
+/// - `removed`: Students of the previous roster no longer enrolled (dropped, or moved to an
+///   inactive state).
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct RosterDiff {
+    pub added: Vec<StudentInfo>,
+    pub removed: Vec<StudentInfo>,
+}
+
+impl RosterDiff {
+    /// Compares two rosters by student ID, keeping the order of each roster.
+    pub fn between(previous: &[StudentInfo], current: &[StudentInfo]) -> RosterDiff {
+        let previous_ids: HashSet<u64> = previous.iter().map(|student| student.id).collect();
+        let current_ids: HashSet<u64> = current.iter().map(|student| student.id).collect();
+        RosterDiff {
+            added: current.iter().filter(|student| !previous_ids.contains(&student.id)).cloned().collect(),
+            removed: previous.iter().filter(|student| !current_ids.contains(&student.id)).cloned().collect(),
+        }
+    }
+
+    /// Returns `true` when the roster didn't change.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_snapshot() -> CourseSnapshot {
+        CourseSnapshot {
+            version: SNAPSHOT_VERSION,
+            taken_at: Utc::now(),
+            course: CourseInfo {
+                id: 1,
+                name: "Algoritmos".to_string(),
+                course_code: "ALG".to_string(),
+                ..Default::default()
+            },
+            students: vec![StudentInfo {
+                id: 10,
+                name: "Ana".to_string(),
+                email: None,
+                ..Default::default()
+            }],
+            assignments: vec![AssignmentSnapshot {
+                info: AssignmentInfo {
+                    id: 100,
+                    name: "Lista 1".to_string(),
+                    ..Default::default()
+                },
+                submissions: vec![SubmissionSnapshot {
+                    id: 1000,
+                    user_ids: vec![10],
+                    score: Some(9.0),
+                    submitted_at: None,
+                    submission_type: Some(SubmissionType::OnlineUpload),
+                    file_ids: vec![5],
+                    comments: vec![Comment {
+                        id: 7,
+                        content: "Good".to_string(),
+                        ..Default::default()
+                    }],
+                    rubric_assessment: BTreeMap::from([(
+                        "_101".to_string(),
+                        CriterionAssessment {
+                            points: Some(4.0),
+                            ..Default::default()
+                        },
+                    )]),
+                }],
+                rubric: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_snapshot_json_roundtrip() {
+        let snapshot = sample_snapshot();
+        let json = snapshot.to_json().unwrap();
+        let parsed = CourseSnapshot::from_json(&json).unwrap();
+        assert_eq!(parsed.course.id, 1);
+        assert_eq!(parsed.students[0].name, "Ana");
+        assert_eq!(parsed.assignments[0].submissions, snapshot.assignments[0].submissions);
+    }
+
+    #[test]
+    fn test_snapshot_rejects_newer_versions() {
+        let mut snapshot = sample_snapshot();
+        snapshot.version = SNAPSHOT_VERSION + 1;
+        let json = snapshot.to_json().unwrap();
+        assert!(CourseSnapshot::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_roster_diff_between_syncs() {
+        let student = |id: u64, name: &str| StudentInfo {
+            id,
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let previous = sample_snapshot().students;
+        let current = vec![student(11, "Bia"), student(12, "Caio")];
+
+        let diff = RosterDiff::between(&previous, &current);
+        assert_eq!(diff.added.iter().map(|s| s.id).collect::<Vec<_>>(), vec![11, 12]);
+        assert_eq!(diff.removed.iter().map(|s| s.name.as_str()).collect::<Vec<_>>(), vec!["Ana"]);
+        assert!(RosterDiff::between(&current, &current).is_empty());
+    }
+}
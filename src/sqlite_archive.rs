@@ -0,0 +1,416 @@
+// Import necessary crates and modules
+use crate::snapshot::{AssignmentSnapshot, CourseSnapshot};
+use crate::Course;
+use rusqlite::{params, Connection};
+use serde_json::{Map, Value};
+use std::error::Error;
+use std::path::Path;
+
+/// Version of the schema of the SQLite archives written by `CourseSnapshot::save_to_sqlite`,
+/// stored in the `meta` table under `schema_version`.
+///
+/// Bump this whenever a table or column is removed or changes meaning; adding tables or columns
+/// keeps the version.
+pub const SQLITE_SCHEMA_VERSION: u32 = 1;
+
+// Tables of the archive. Timestamps are RFC 3339 text in UTC; `extra` columns hold, as JSON, the
+// Canvas fields not mapped to columns.
+const SCHEMA: &str = "
+CREATE TABLE meta (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+CREATE TABLE courses (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    course_code TEXT NOT NULL,
+    time_zone TEXT,
+    extra TEXT NOT NULL
+);
+CREATE TABLE students (
+    id INTEGER PRIMARY KEY,
+    course_id INTEGER NOT NULL REFERENCES courses(id),
+    name TEXT NOT NULL,
+    email TEXT,
+    extra TEXT NOT NULL
+);
+CREATE TABLE rubrics (
+    id INTEGER PRIMARY KEY,
+    title TEXT NOT NULL,
+    points_possible REAL NOT NULL
+);
+CREATE TABLE rubric_criteria (
+    rubric_id INTEGER NOT NULL REFERENCES rubrics(id),
+    id TEXT NOT NULL,
+    position INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    long_description TEXT,
+    points REAL NOT NULL,
+    PRIMARY KEY (rubric_id, id)
+);
+CREATE TABLE rubric_ratings (
+    rubric_id INTEGER NOT NULL,
+    criterion_id TEXT NOT NULL,
+    id TEXT NOT NULL,
+    description TEXT NOT NULL,
+    long_description TEXT NOT NULL,
+    points REAL NOT NULL,
+    PRIMARY KEY (rubric_id, criterion_id, id),
+    FOREIGN KEY (rubric_id, criterion_id) REFERENCES rubric_criteria(rubric_id, id)
+);
+CREATE TABLE assignments (
+    id INTEGER PRIMARY KEY,
+    course_id INTEGER NOT NULL REFERENCES courses(id),
+    name TEXT NOT NULL,
+    description TEXT,
+    due_at TEXT,
+    points_possible REAL,
+    submission_types TEXT NOT NULL,
+    group_category_id INTEGER,
+    rubric_id INTEGER REFERENCES rubrics(id),
+    extra TEXT NOT NULL
+);
+CREATE TABLE submissions (
+    id INTEGER PRIMARY KEY,
+    assignment_id INTEGER NOT NULL REFERENCES assignments(id),
+    score REAL,
+    submitted_at TEXT,
+    submission_type TEXT
+);
+CREATE TABLE submission_students (
+    submission_id INTEGER NOT NULL REFERENCES submissions(id),
+    student_id INTEGER NOT NULL,
+    PRIMARY KEY (submission_id, student_id)
+);
+CREATE TABLE submission_files (
+    submission_id INTEGER NOT NULL REFERENCES submissions(id),
+    file_id INTEGER NOT NULL,
+    PRIMARY KEY (submission_id, file_id)
+);
+CREATE TABLE comments (
+    submission_id INTEGER NOT NULL REFERENCES submissions(id),
+    id INTEGER NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (submission_id, id)
+);
+CREATE TABLE comment_attachments (
+    submission_id INTEGER NOT NULL,
+    comment_id INTEGER NOT NULL,
+    id INTEGER NOT NULL,
+    display_name TEXT NOT NULL,
+    filename TEXT NOT NULL,
+    content_type TEXT,
+    size INTEGER,
+    url TEXT,
+    PRIMARY KEY (submission_id, comment_id, id),
+    FOREIGN KEY (submission_id, comment_id) REFERENCES comments(submission_id, id)
+);
+CREATE TABLE rubric_scores (
+    submission_id INTEGER NOT NULL REFERENCES submissions(id),
+    criterion_id TEXT NOT NULL,
+    points REAL,
+    rating_id TEXT,
+    comments TEXT,
+    PRIMARY KEY (submission_id, criterion_id)
+);
+";
+
+// SQLite stores integers as i64; Canvas IDs fit in it.
+fn id(value: u64) -> i64 {
+    value as i64
+}
+
+fn extra_json(extra: &Map<String, Value>) -> String {
+    Value::Object(extra.clone()).to_string()
+}
+
+fn write_assignment(connection: &Connection, course_id: u64, assignment: &AssignmentSnapshot) -> rusqlite::Result<()> {
+    let info = &assignment.info;
+    if let Some(rubric) = &assignment.rubric {
+        // A rubric may be shared by several assignments
+        let inserted = connection.execute(
+            "INSERT OR IGNORE INTO rubrics (id, title, points_possible) VALUES (?1, ?2, ?3)",
+            params![id(rubric.id), rubric.title, rubric.points_possible],
+        )?;
+        if inserted > 0 {
+            for (position, criterion) in rubric.data.iter().enumerate() {
+                connection.execute(
+                    "INSERT INTO rubric_criteria (rubric_id, id, position, description, long_description, points)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    params![
+                        id(rubric.id),
+                        criterion.id,
+                        position as i64,
+                        criterion.description,
+                        criterion.long_description,
+                        criterion.points
+                    ],
+                )?;
+                for rating in &criterion.ratings {
+                    connection.execute(
+                        "INSERT INTO rubric_ratings (rubric_id, criterion_id, id, description, long_description, points)
+                         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                        params![
+                            id(rubric.id),
+                            criterion.id,
+                            rating.id,
+                            rating.description,
+                            rating.long_description,
+                            rating.points
+                        ],
+                    )?;
+                }
+            }
+        }
+    }
+
+    let submission_types: Vec<&str> = info.submission_types.iter().map(|t| t.as_str()).collect();
+    connection.execute(
+        "INSERT INTO assignments (id, course_id, name, description, due_at, points_possible, submission_types,
+                                  group_category_id, rubric_id, extra)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+        params![
+            id(info.id),
+            id(course_id),
+            info.name,
+            info.description,
+            info.due_at.map(|due_at| due_at.to_rfc3339()),
+            info.points_possible,
+            submission_types.join(","),
+            info.group_category_id.map(id),
+            assignment.rubric.as_ref().map(|rubric| id(rubric.id)),
+            extra_json(&info.extra)
+        ],
+    )?;
+
+    for submission in &assignment.submissions {
+        connection.execute(
+            "INSERT INTO submissions (id, assignment_id, score, submitted_at, submission_type)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                id(submission.id),
+                id(info.id),
+                submission.score,
+                submission.submitted_at.map(|submitted_at| submitted_at.to_rfc3339()),
+                submission.submission_type.as_ref().map(|t| t.as_str().to_string())
+            ],
+        )?;
+        for user_id in &submission.user_ids {
+            connection.execute(
+                "INSERT OR IGNORE INTO submission_students (submission_id, student_id) VALUES (?1, ?2)",
+                params![id(submission.id), id(*user_id)],
+            )?;
+        }
+        for file_id in &submission.file_ids {
+            connection.execute(
+                "INSERT OR IGNORE INTO submission_files (submission_id, file_id) VALUES (?1, ?2)",
+                params![id(submission.id), id(*file_id)],
+            )?;
+        }
+        for comment in &submission.comments {
+            connection.execute(
+                "INSERT OR IGNORE INTO comments (submission_id, id, content) VALUES (?1, ?2, ?3)",
+                params![id(submission.id), id(comment.id), comment.content],
+            )?;
+            for attachment in &comment.attachments {
+                connection.execute(
+                    "INSERT OR IGNORE INTO comment_attachments
+                         (submission_id, comment_id, id, display_name, filename, content_type, size, url)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        id(submission.id),
+                        id(comment.id),
+                        id(attachment.id),
+                        attachment.display_name,
+                        attachment.filename,
+                        attachment.content_type,
+                        attachment.size.map(id),
+                        attachment.url
+                    ],
+                )?;
+            }
+        }
+        for (criterion_id, assessment) in &submission.rubric_assessment {
+            connection.execute(
+                "INSERT INTO rubric_scores (submission_id, criterion_id, points, rating_id, comments)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    id(submission.id),
+                    criterion_id,
+                    assessment.points,
+                    assessment.rating_id,
+                    assessment.comments
+                ],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+// Creates the tables in an empty database and fills them, in a single transaction.
+fn write_snapshot(connection: &mut Connection, snapshot: &CourseSnapshot) -> rusqlite::Result<()> {
+    let transaction = connection.transaction()?;
+    transaction.execute_batch(SCHEMA)?;
+    for (key, value) in [
+        ("schema_version", SQLITE_SCHEMA_VERSION.to_string()),
+        ("snapshot_version", snapshot.version.to_string()),
+        ("taken_at", snapshot.taken_at.to_rfc3339()),
+    ] {
+        transaction.execute("INSERT INTO meta (key, value) VALUES (?1, ?2)", params![key, value])?;
+    }
+
+    let course = &snapshot.course;
+    transaction.execute(
+        "INSERT INTO courses (id, name, course_code, time_zone, extra) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![id(course.id), course.name, course.course_code, course.time_zone, extra_json(&course.extra)],
+    )?;
+    for student in &snapshot.students {
+        transaction.execute(
+            "INSERT OR IGNORE INTO students (id, course_id, name, email, extra) VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![id(student.id), id(course.id), student.name, student.email, extra_json(&student.extra)],
+        )?;
+    }
+    for assignment in &snapshot.assignments {
+        write_assignment(&transaction, course.id, assignment)?;
+    }
+    transaction.commit()
+}
+
+impl CourseSnapshot {
+    /// Writes the snapshot as a SQLite database at `file_path`, replacing any file already
+    /// there, for analytics in SQL (or pandas) without Canvas.
+    ///
+    /// Tables: `meta` (`schema_version`, `snapshot_version`, `taken_at`), `courses`, `students`,
+    /// `assignments`, `submissions`, `submission_students` (several rows for group submissions),
+    /// `submission_files`, `comments`, `comment_attachments`, `rubrics`, `rubric_criteria`,
+    /// `rubric_ratings` and `rubric_scores`. The schema only changes in a compatible way while
+    /// `SQLITE_SCHEMA_VERSION` stays the same.
+    ///
+    /// Requires the `sqlite` feature.
+    ///
+    /// Example:
+    /// ```
+    /// let snapshot = CourseSnapshot::take(&course)?;
+    /// snapshot.save_to_sqlite("arquivo/algoritmos-2024-1.sqlite")?;
+    /// // sqlite3 arquivo/algoritmos-2024-1.sqlite "SELECT name, AVG(score) FROM submissions ..."
+    /// ```
+    pub fn save_to_sqlite<P: AsRef<Path>>(&self, file_path: P) -> Result<(), Box<dyn Error>> {
+        let path = file_path.as_ref();
+        // Written aside and renamed, so an interrupted export never leaves a partial archive
+        let temp = path.with_extension("sqlite-tmp");
+        if temp.exists() {
+            std::fs::remove_file(&temp)?;
+        }
+        let mut connection = Connection::open(&temp)?;
+        write_snapshot(&mut connection, self)?;
+        connection.close().map_err(|(_, error)| error)?;
+        std::fs::rename(&temp, path)?;
+        Ok(())
+    }
+}
+
+impl Course {
+    /// Takes a snapshot of this course (see `CourseSnapshot::take`) and writes it as a SQLite
+    /// database at `file_path`. Requires the `sqlite` feature.
+    pub fn archive_to_sqlite<P: AsRef<Path>>(&self, file_path: P) -> Result<(), Box<dyn Error>> {
+        CourseSnapshot::take(self)?.save_to_sqlite(file_path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::snapshot::{SubmissionSnapshot, SNAPSHOT_VERSION};
+    use crate::{AssignmentInfo, Comment, CourseInfo, CriterionAssessment, RubricDownloaded, StudentInfo};
+    use chrono::Utc;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_snapshot_is_written_as_tables() {
+        let rubric: RubricDownloaded = serde_json::from_value(json!({
+            "id": 12, "context_id": 1, "context_type": "Course", "title": "Lista", "points_possible": 10.0,
+            "data": [{"id": "_101", "description": "Corretude", "points": 10.0, "ratings": [
+                {"id": "r1", "description": "Total", "points": 10.0},
+                {"id": "r2", "description": "Parcial", "points": 5.0}
+            ]}]
+        }))
+        .unwrap();
+        let student = |id: u64, name: &str| StudentInfo {
+            id,
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let submission = |id: u64, user_id: u64, score: f64| SubmissionSnapshot {
+            id,
+            user_ids: vec![user_id],
+            score: Some(score),
+            submitted_at: None,
+            submission_type: None,
+            file_ids: vec![],
+            comments: vec![],
+            rubric_assessment: BTreeMap::from([(
+                "_101".to_string(),
+                CriterionAssessment {
+                    points: Some(score),
+                    rating_id: Some(if score == 10.0 { "r1" } else { "r2" }.to_string()),
+                    ..Default::default()
+                },
+            )]),
+        };
+        let mut first = submission(1000, 10, 10.0);
+        first.comments.push(Comment {
+            id: 7,
+            content: "Ótimo".to_string(),
+            ..Default::default()
+        });
+        let snapshot = CourseSnapshot {
+            version: SNAPSHOT_VERSION,
+            taken_at: Utc::now(),
+            course: CourseInfo {
+                id: 1,
+                name: "Algoritmos".to_string(),
+                course_code: "ALG".to_string(),
+                ..Default::default()
+            },
+            students: vec![student(10, "Ana"), student(11, "Bia")],
+            assignments: vec![AssignmentSnapshot {
+                info: AssignmentInfo {
+                    id: 100,
+                    name: "Lista 1".to_string(),
+                    rubric_id: Some(12),
+                    ..Default::default()
+                },
+                submissions: vec![first, submission(1001, 11, 5.0)],
+                rubric: Some(rubric),
+            }],
+        };
+
+        let mut connection = Connection::open_in_memory().unwrap();
+        write_snapshot(&mut connection, &snapshot).unwrap();
+        let version: String = connection
+            .query_row("SELECT value FROM meta WHERE key = 'schema_version'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, SQLITE_SCHEMA_VERSION.to_string());
+        let (name, rating): (String, String) = connection
+            .query_row(
+                "SELECT s.name, r.description FROM rubric_scores sc
+                 JOIN submission_students ss ON ss.submission_id = sc.submission_id
+                 JOIN students s ON s.id = ss.student_id
+                 JOIN rubric_ratings r ON r.criterion_id = sc.criterion_id AND r.id = sc.rating_id
+                 WHERE sc.points < 10",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((name.as_str(), rating.as_str()), ("Bia", "Parcial"));
+        let average: f64 = connection
+            .query_row("SELECT AVG(score) FROM submissions WHERE assignment_id = 100", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(average, 7.5);
+        let comment: String = connection
+            .query_row("SELECT content FROM comments WHERE submission_id = 1000", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(comment, "Ótimo");
+    }
+}
@@ -1,7 +1,10 @@
 // Import necessary crates and modules
 use std::collections::HashMap;
+use std::error::Error;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
 use crate::CourseInfo;
 use crate::assignment::{Assignment, AssignmentInfo};
 use crate::submission::Submission;
@@ -16,7 +19,7 @@ use crate::canvas;
 /// Fields:
 /// - `id`: The unique identifier of the student in Canvas.
 /// - `name`: The student's full name.
-/// - `email`: The student's email address.
+/// - `email`: The student's email address, or `None` when its visibility is restricted in Canvas.
 /// - `course_info`: A thread-safe reference (`Arc`) to the course information and API credentials (`CourseInfo`).
 ///
 /// The struct is essential for various student-related operations in the Canvas API, such as retrieving student details,
@@ -25,9 +28,76 @@ use crate::canvas;
 pub struct StudentInfo {
     pub id: u64,
     pub name: String,
-    pub email: String,
+    pub email: Option<String>,
     #[serde(skip)]
     pub course_info: Arc<CourseInfo>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>, // Campos do Canvas não mapeados acima
+}
+
+/// Enrollment states that can be requested when listing the students of a course.
+///
+/// By default Canvas only lists `Active` and `Invited` enrollments, so students who dropped the
+/// course (`Inactive`) or whose enrollment ended (`Completed`) are missing from the roster.
+///
+/// - `Active`: Regular, active enrollment.
+/// - `Invited`: Invitation not yet accepted.
+/// - `Inactive`: Enrollment deactivated (e.g. the student dropped the course).
+/// - `Completed`: Enrollment concluded.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrollmentState {
+    Active,
+    Invited,
+    Inactive,
+    Completed,
+}
+
+impl EnrollmentState {
+    /// Value used by the Canvas API for this state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnrollmentState::Active => "active",
+            EnrollmentState::Invited => "invited",
+            EnrollmentState::Inactive => "inactive",
+            EnrollmentState::Completed => "completed",
+        }
+    }
+}
+
+/// Course grade of a student, as computed by Canvas.
+///
+/// `current_*` values only consider graded assignments, while `final_*` values count missing
+/// work as zero. The `unposted_*` variants include grades that were not posted to students yet.
+/// Letter grades (`*_grade`) are only present when the course uses a grading standard.
+/// `override_*` hold the final grade override set by the instructor, when the course allows it
+/// (see `CourseSettings::allow_final_grade_override`).
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CourseGrade {
+    pub current_score: Option<f64>,
+    pub final_score: Option<f64>,
+    pub current_grade: Option<String>,
+    pub final_grade: Option<String>,
+    #[serde(default)]
+    pub unposted_current_score: Option<f64>,
+    #[serde(default)]
+    pub unposted_final_score: Option<f64>,
+    #[serde(default)]
+    pub unposted_current_grade: Option<String>,
+    #[serde(default)]
+    pub unposted_final_grade: Option<String>,
+    #[serde(default)]
+    pub override_score: Option<f64>,
+    #[serde(default)]
+    pub override_grade: Option<String>,
+}
+
+impl CourseGrade {
+    /// Final score to report: the override set by the instructor when there is one, the
+    /// computed final score otherwise.
+    pub fn effective_final_score(&self) -> Option<f64> {
+        self.override_score.or(self.final_score)
+    }
 }
 
 /// High-level representation of a student in the Canvas Learning Management System.
@@ -139,4 +209,144 @@ impl Student {
         // println!("Z");
         Ok(association)
     }
+
+    /// Retrieves the current and final course grade of this student.
+    ///
+    /// Reads the grades computed by Canvas from the student's enrollment, so weighted totals don't
+    /// need to be recomputed locally (e.g. for progress emails).
+    ///
+    /// Arguments:
+    /// - `client`: The HTTP client used for making requests.
+    ///
+    /// Returns:
+    /// - `Result<CourseGrade, Box<dyn std::error::Error>>`: The course grade, or an error if the
+    ///   request failed or the student has no active enrollment in the course.
+    ///
+    /// Example:
+    /// ```
+    /// let grade = student.course_grade(&client)?;
+    /// println!("{}: {:?} ({:?})", student.info.name, grade.current_score, grade.current_grade);
+    /// ```
+    pub fn course_grade(
+        &self,
+        client: &reqwest::blocking::Client,
+    ) -> Result<CourseGrade, Box<dyn std::error::Error>> {
+        canvas::fetch_course_grade(
+            client,
+            self.info.course_info.canvas_info.as_ref(),
+            self.info.course_info.id,
+            self.info.id,
+        )
+    }
+}
+
+// Extension of a downloaded avatar, from its MIME type.
+fn avatar_extension(content_type: &str) -> &'static str {
+    match content_type.split(';').next().unwrap_or_default().trim() {
+        "image/png" => "png",
+        "image/gif" => "gif",
+        "image/svg+xml" => "svg",
+        "image/webp" => "webp",
+        _ => "jpg",
+    }
+}
+
+// Avatar of `student_id` already downloaded to `dir`, whatever its extension.
+fn cached_avatar(dir: &Path, student_id: u64) -> Option<PathBuf> {
+    std::fs::read_dir(dir).ok()?.filter_map(Result::ok).map(|entry| entry.path()).find(|path| {
+        path.is_file() && path.file_stem().and_then(|stem| stem.to_str()) == Some(student_id.to_string().as_str())
+    })
+}
+
+impl StudentInfo {
+    /// URL of the profile picture, as sent by Canvas with the roster. `None` when the student
+    /// kept the default picture.
+    pub fn avatar_url(&self) -> Option<&str> {
+        self.extra
+            .get("avatar_url")
+            .and_then(Value::as_str)
+            .filter(|url| !url.contains("/images/messages/avatar-"))
+    }
+
+    /// Downloads the profile picture to `dir`, as `<student id>.<extension>`. A picture already in
+    /// `dir` is reused without any request, so a roster can be regenerated cheaply.
+    ///
+    /// Returns:
+    /// - `Result<Option<PathBuf>, Box<dyn Error>>`: Path of the picture, `None` when the student
+    ///   has no picture, or an error.
+    pub fn download_avatar(&self, client: &reqwest::blocking::Client, dir: &str) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        let Some(url) = self.avatar_url() else {
+            return Ok(None);
+        };
+        let dir = Path::new(dir);
+        if let Some(path) = cached_avatar(dir, self.id) {
+            return Ok(Some(path));
+        }
+        let response = client.get(url).send()?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to download avatar of {} with status: {}", self.id, response.status()).into());
+        }
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let path = dir.join(format!("{}.{}", self.id, avatar_extension(content_type)));
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(&path, response.bytes()?)?;
+        Ok(Some(path))
+    }
+}
+
+impl Student {
+    /// URL of the profile picture of the student (see `StudentInfo::avatar_url`).
+    pub fn avatar_url(&self) -> Option<&str> {
+        self.info.avatar_url()
+    }
+
+    /// Downloads the profile picture of the student to `dir`, reusing a previous download.
+    ///
+    /// Example:
+    /// ```
+    /// for student in course.fetch_students()? {
+    ///     if let Some(photo) = student.download_avatar(&client, "roster/photos")? {
+    ///         println!("<img src=\"{}\"> {}", photo.display(), student.info.name);
+    ///     }
+    /// }
+    /// ```
+    pub fn download_avatar(&self, client: &reqwest::blocking::Client, dir: &str) -> Result<Option<PathBuf>, Box<dyn Error>> {
+        self.info.download_avatar(client, dir)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_avatar_url_and_cache() {
+        let mut student = StudentInfo {
+            id: 42,
+            ..Default::default()
+        };
+        student.extra.insert(
+            "avatar_url".to_string(),
+            json!("https://canvas.example/images/messages/avatar-50.png"),
+        );
+        assert_eq!(student.avatar_url(), None);
+        student.extra.insert("avatar_url".to_string(), json!("https://canvas.example/images/thumbnails/7/abc"));
+        assert_eq!(student.avatar_url(), Some("https://canvas.example/images/thumbnails/7/abc"));
+        assert_eq!(avatar_extension("image/png; charset=binary"), "png");
+        assert_eq!(avatar_extension("image/jpeg"), "jpg");
+
+        // A picture already downloaded is reused without reaching the URL
+        let dir = std::env::temp_dir().join(format!("canvas_avatars_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("42.gif"), b"GIF89a").unwrap();
+        let client = reqwest::blocking::Client::new();
+        let path = student.download_avatar(&client, dir.to_str().unwrap()).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(path, Some(dir.join("42.gif")));
+    }
 }
@@ -0,0 +1,190 @@
+// Import necessary crates and modules
+use crate::canvas::{self, fetch_all_pages, fetch_json};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::course_file::upload_file_to;
+use crate::params::Params;
+use crate::submission::SubmissionType;
+use crate::{Assignment, AssignmentInfo, Course, CourseGrade, Student, Submission};
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Work sent by a student to an assignment with `Assignment::submit`.
+///
+/// Variants:
+/// - `Text`: HTML text entered in Canvas (`online_text_entry`).
+/// - `Url`: Link to a website (`online_url`).
+/// - `Files`: Paths of local files, uploaded with the submission (`online_upload`).
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubmissionContent {
+    Text(String),
+    Url(String),
+    Files(Vec<String>),
+}
+
+impl SubmissionContent {
+    /// Submission type Canvas records for this content.
+    pub fn submission_type(&self) -> SubmissionType {
+        match self {
+            SubmissionContent::Text(_) => SubmissionType::OnlineTextEntry,
+            SubmissionContent::Url(_) => SubmissionType::OnlineUrl,
+            SubmissionContent::Files(_) => SubmissionType::OnlineUpload,
+        }
+    }
+}
+
+// Refuses content the assignment would reject, before anything is uploaded.
+fn check_content(assignment: &AssignmentInfo, content: &SubmissionContent) -> Result<(), Box<dyn Error>> {
+    let submission_type = content.submission_type();
+    if !assignment.accepts(&submission_type) {
+        return Err(format!(
+            "Assignment {} doesn't accept {} submissions",
+            assignment.id,
+            submission_type.as_str()
+        )
+        .into());
+    }
+    if let SubmissionContent::Files(paths) = content {
+        if paths.is_empty() {
+            return Err("No file to submit".into());
+        }
+        if let Some(path) = paths.iter().find(|path| !assignment.accepts_file(path)) {
+            return Err(format!("Assignment {} doesn't accept the file {}", assignment.id, path).into());
+        }
+    }
+    Ok(())
+}
+
+// Body of the submission request; `file_ids` are the uploaded files of `Files` content.
+fn submission_body(content: &SubmissionContent, file_ids: &[u64], comment: Option<&str>) -> Value {
+    let mut submission = json!({"submission_type": content.submission_type().as_str()});
+    match content {
+        SubmissionContent::Text(body) => submission["body"] = json!(body),
+        SubmissionContent::Url(url) => submission["url"] = json!(url),
+        SubmissionContent::Files(_) => submission["file_ids"] = json!(file_ids),
+    }
+    let mut body = json!({"submission": submission});
+    if let Some(comment) = comment {
+        body["comment"] = json!({"text_comment": comment});
+    }
+    body
+}
+
+impl Course {
+    /// The owner of the credentials, as a student of this course. For tools run with the token
+    /// of a student.
+    pub fn fetch_own_student(&self, client: &Client) -> Result<Student, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let me: Value = fetch_json(client, canvas_info, &format!("{}/users/self", canvas_info.url_canvas))?;
+        canvas::convert_json_to_student((*self.info).clone(), &me).ok_or_else(|| "Invalid user profile".into())
+    }
+
+    /// Submissions of the owner of the credentials to the assignments of this course, with their
+    /// comments. Unlike `Assignment::fetch_submissions`, it works with the token of a student.
+    ///
+    /// Example:
+    /// ```
+    /// for submission in course.fetch_own_submissions(&client)? {
+    ///     println!("{}: {:?}", submission.assignment_info.name, submission.score);
+    /// }
+    /// ```
+    pub fn fetch_own_submissions(&self, client: &Client) -> Result<Vec<Submission>, Box<dyn Error>> {
+        let canvas_info = &self.info.canvas_info;
+        let me = self.fetch_own_student(client)?;
+        let assignments: HashMap<u64, Assignment> = self
+            .fetch_assignments()?
+            .into_iter()
+            .map(|assignment| (assignment.info.id, assignment))
+            .collect();
+
+        // Without `student_ids`, Canvas answers with the submissions of the calling user
+        let url = format!("{}/courses/{}/students/submissions", canvas_info.url_canvas, self.info.id);
+        let params = Params::new().include(["submission_comments"]);
+        let students = vec![me];
+        Ok(fetch_all_pages(client, canvas_info, &url, params.into())?
+            .iter()
+            .filter_map(|json| {
+                let assignment = assignments.get(&json["assignment_id"].as_u64()?)?;
+                Assignment::convert_json_to_submission(&students, json, assignment.info.clone(), &None)
+            })
+            .collect())
+    }
+
+    /// Grades of the owner of the credentials in this course, as shown in their grades page.
+    pub fn fetch_own_grade(&self, client: &Client) -> Result<CourseGrade, Box<dyn Error>> {
+        canvas::fetch_course_grade_of(client, &self.info.canvas_info, self.info.id, "self")
+    }
+}
+
+impl Assignment {
+    /// Submits work to this assignment as the owner of the credentials (a student), with an
+    /// optional comment. Files are uploaded first.
+    ///
+    /// The content is checked against the submission types and file extensions the assignment
+    /// accepts before anything is sent.
+    ///
+    /// Example:
+    /// ```
+    /// let report = SubmissionContent::Files(vec!["report.pdf".to_string()]);
+    /// assignment.submit(&client, &report, Some("Second version, with the charts fixed"))?;
+    /// ```
+    pub fn submit(
+        &self,
+        client: &Client,
+        content: &SubmissionContent,
+        comment: Option<&str>,
+    ) -> Result<(), Box<dyn Error>> {
+        check_content(&self.info, content)?;
+        let canvas_info = &self.info.course_info.canvas_info;
+        let url = format!(
+            "{}/courses/{}/assignments/{}/submissions",
+            canvas_info.url_canvas, self.info.course_info.id, self.info.id
+        );
+        let mut file_ids = Vec::new();
+        if let SubmissionContent::Files(paths) = content {
+            for path in paths {
+                let file = upload_file_to(client, canvas_info, &format!("{}/self/files", url), path, json!({}))?;
+                file_ids.push(file["id"].as_u64().ok_or("Missing id in upload file response")?);
+            }
+        }
+        let body = submission_body(content, &file_ids, comment);
+        let response = send_http_request(client, HttpMethod::Post(body), &url, canvas_info, vec![])?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to submit assignment with status: {}", response.status()).into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_submission_content_is_checked_and_sent() {
+        let assignment = AssignmentInfo {
+            id: 100,
+            submission_types: vec![SubmissionType::OnlineUpload],
+            allowed_extensions: vec!["pdf".to_string()],
+            ..Default::default()
+        };
+        let report = SubmissionContent::Files(vec!["relatorio.PDF".to_string()]);
+        assert!(check_content(&assignment, &report).is_ok());
+        assert!(check_content(&assignment, &SubmissionContent::Files(vec!["main.rs".to_string()])).is_err());
+        assert!(check_content(&assignment, &SubmissionContent::Files(Vec::new())).is_err());
+        assert!(check_content(&assignment, &SubmissionContent::Text("<p>Oi</p>".to_string())).is_err());
+
+        assert_eq!(
+            submission_body(&report, &[900, 901], Some("Versão final")),
+            json!({
+                "submission": {"submission_type": "online_upload", "file_ids": [900, 901]},
+                "comment": {"text_comment": "Versão final"}
+            })
+        );
+        assert_eq!(
+            submission_body(&SubmissionContent::Url("https://github.com/ana/lista1".to_string()), &[], None),
+            json!({"submission": {"submission_type": "online_url", "url": "https://github.com/ana/lista1"}})
+        );
+    }
+}
@@ -1,21 +1,25 @@
 use std::error::Error;
 // Import necessary crates and modules
-use crate::{canvas, AssignmentInfo, Course, StudentInfo};
+use crate::connection::{send_http_request, HttpMethod};
+use crate::params::Params;
+use crate::{canvas, Assignment, AssignmentInfo, Canvas, CanvasCredentials, Course, CourseInfo, StudentInfo};
 use chrono::{DateTime, Duration, Utc};
 use reqwest::blocking::Client;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "snake_case")]
+/// Type of a submission. Types unknown to this version of the connector are kept in `Other`
+/// with the value sent by Canvas (e.g. `student_annotation`), so they are not lost.
+#[derive(Debug, Clone, PartialEq)]
 pub enum SubmissionType {
     OnlineUpload,
     OnlineTextEntry,
     OnlineUrl,
     MediaRecording,
     None,
-    #[serde(other)] // Tratamento para tipos desconhecidos
-    Other,
+    Other(String),
 }
 
 impl Default for SubmissionType {
@@ -32,11 +36,36 @@ impl SubmissionType {
             SubmissionType::OnlineUrl => "online_url",
             SubmissionType::MediaRecording => "media_recording",
             SubmissionType::None => "none",
-            SubmissionType::Other => "other",
+            SubmissionType::Other(raw) => raw,
         }
     }
 }
 
+impl From<&str> for SubmissionType {
+    fn from(value: &str) -> Self {
+        match value {
+            "online_upload" => SubmissionType::OnlineUpload,
+            "online_text_entry" => SubmissionType::OnlineTextEntry,
+            "online_url" => SubmissionType::OnlineUrl,
+            "media_recording" => SubmissionType::MediaRecording,
+            "none" => SubmissionType::None,
+            other => SubmissionType::Other(other.to_string()),
+        }
+    }
+}
+
+impl Serialize for SubmissionType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for SubmissionType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(SubmissionType::from(String::deserialize(deserializer)?.as_str()))
+    }
+}
+
 /// Structure representing a student's submission for an assignment in the Canvas Learning Management System.
 ///
 /// This struct provides a detailed view of a student's submission, capturing key aspects like the submission's ID,
@@ -72,10 +101,82 @@ impl SubmissionType {
 //     pub file_ids: Vec<u64>, // IDs dos arquivos associados
 // }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct Comment {
     pub id: u64,        // ID do comentário
     pub content: String, // Conteúdo do comentário
+    #[serde(default)]
+    pub attachments: Vec<CommentAttachment>, // Arquivos anexados ao comentário
+}
+
+/// File attached to a submission comment (e.g. a corrected version sent by the student).
+///
+/// Fields:
+/// - `id`: ID of the file, usable with `canvas::download_file`.
+/// - `display_name`: Name shown in Canvas.
+/// - `filename`: Name of the uploaded file.
+/// - `content_type`: MIME type of the file.
+/// - `size`: Size in bytes.
+/// - `url`: Download URL given by Canvas.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CommentAttachment {
+    pub id: u64,
+    #[serde(default)]
+    pub display_name: String,
+    #[serde(default)]
+    pub filename: String,
+    #[serde(default, rename = "content-type")]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub size: Option<u64>,
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+impl Comment {
+    // Builds a comment from an entry of `submission_comments`.
+    pub(crate) fn from_json(json: &Value) -> Option<Comment> {
+        let attachments = json["attachments"].as_array().map_or(Vec::new(), |attachments| {
+            attachments
+                .iter()
+                .filter_map(|attachment| serde_json::from_value(attachment.clone()).ok())
+                .collect()
+        });
+        Some(Comment {
+            id: json["id"].as_u64()?,
+            content: json["comment"].as_str()?.to_string(),
+            attachments,
+        })
+    }
+
+    /// Downloads the files attached to this comment into `output_dir`, using the same pipeline as
+    /// `Submission::download_submission_files`.
+    ///
+    /// Returns:
+    /// - `Result<Vec<String>, Box<dyn Error>>`: Paths of the saved files (empty when the comment has
+    ///   no attachment), or an error.
+    ///
+    /// Example:
+    /// ```
+    /// for comment in &submission.comments {
+    ///     comment.download_attachments(&client, &canvas_info, "replies")?;
+    /// }
+    /// ```
+    pub fn download_attachments(
+        &self,
+        client: &Client,
+        canvas_info: &CanvasCredentials,
+        output_dir: &str,
+    ) -> Result<Vec<String>, Box<dyn Error>> {
+        if self.attachments.is_empty() {
+            return Ok(Vec::new());
+        }
+        std::fs::create_dir_all(output_dir)?;
+        self.attachments
+            .iter()
+            .map(|attachment| canvas::download_file(client, canvas_info, attachment.id, output_dir))
+            .collect()
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -94,19 +195,190 @@ pub struct Submission {
     #[serde(skip)]
     pub file_ids: Vec<u64>, // IDs dos arquivos associados
     pub comments: Vec<Comment>,  // Lista de comentários, agora incluindo o ID do comentário
+    #[serde(flatten)]
+    pub extra: Map<String, Value>, // Campos do Canvas não mapeados acima
+}
+
+/// Options controlling how a grade write treats the score currently stored in Canvas.
+///
+/// Fields:
+/// - `skip_if_unchanged`: Read the current score first and skip the PUT when it already matches
+///   the new score, avoiding redundant writes (and the notifications they trigger).
+/// - `baseline`: Moment the caller's local data was read from Canvas. When set, the write is
+///   skipped if the submission was graded after this instant, so that manual regrades made in
+///   SpeedGrader in the meantime are not overwritten.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct GradeWriteOptions {
+    pub skip_if_unchanged: bool,
+    pub baseline: Option<DateTime<Utc>>,
+}
+
+impl GradeWriteOptions {
+    /// Returns `true` when the options require reading the current score before writing.
+    pub fn requires_read(&self) -> bool {
+        self.skip_if_unchanged || self.baseline.is_some()
+    }
+
+    /// Decides whether a write must be skipped given the remote state of the submission.
+    ///
+    /// Returns `Some(outcome)` describing why the write is skipped, or `None` if it should proceed.
+    pub fn check(
+        &self,
+        remote_score: Option<f64>,
+        remote_graded_at: Option<DateTime<Utc>>,
+        new_score: Option<f64>,
+    ) -> Option<GradeWriteOutcome> {
+        if let (Some(baseline), Some(graded_at)) = (self.baseline, remote_graded_at) {
+            if graded_at > baseline {
+                return Some(GradeWriteOutcome::SkippedRemoteNewer {
+                    remote_score,
+                    graded_at,
+                });
+            }
+        }
+        if self.skip_if_unchanged {
+            let unchanged = match (remote_score, new_score) {
+                (Some(remote), Some(new)) => (remote - new).abs() < 1e-9,
+                (None, None) => true,
+                _ => false,
+            };
+            if unchanged {
+                return Some(GradeWriteOutcome::SkippedUnchanged);
+            }
+        }
+        None
+    }
+}
+
+/// Result of a guarded grade write (see `GradeWriteOptions`).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum GradeWriteOutcome {
+    Written,
+    SkippedUnchanged,
+    SkippedRemoteNewer {
+        remote_score: Option<f64>,
+        graded_at: DateTime<Utc>,
+    },
+}
+
+/// Assessment of one rubric criterion, sent with a `SubmissionUpdate`.
+///
+/// Fields:
+/// - `points`: Points given for the criterion.
+/// - `rating_id`: ID of the selected rating, if any.
+/// - `comments`: Free-text comment for the criterion.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct CriterionAssessment {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub points: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rating_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<String>,
+}
+
+/// Changes applied to a submission in a single PUT, so the student gets one notification instead
+/// of one per change.
+///
+/// Fields:
+/// - `grade`: Grade to post, as accepted by Canvas (`"9.5"`, `"85%"`, `"A-"`, `"pass"`...). An
+///   empty string clears the grade.
+/// - `excuse`: Excuses the student from the assignment (or lifts the excuse with `false`).
+/// - `rubric_assessment`: Assessment per criterion ID.
+/// - `comment`: Text comment added to the submission.
+/// - `group_comment`: Sends the comment to the whole group, for group assignments.
+///
+/// Example:
+/// ```
+/// let update = SubmissionUpdate {
+///     grade: Some("8.5".to_string()),
+///     rubric_assessment: BTreeMap::from([("_1234".to_string(), CriterionAssessment { points: Some(8.5), ..Default::default() })]),
+///     comment: Some("Bom trabalho!".to_string()),
+///     ..Default::default()
+/// };
+/// submission.update(&client, &update)?;
+/// ```
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct SubmissionUpdate {
+    pub grade: Option<String>,
+    pub excuse: Option<bool>,
+    pub rubric_assessment: BTreeMap<String, CriterionAssessment>,
+    pub comment: Option<String>,
+    pub group_comment: bool,
+}
+
+impl SubmissionUpdate {
+    /// Returns `true` when there is nothing to send.
+    pub fn is_empty(&self) -> bool {
+        self.grade.is_none() && self.excuse.is_none() && self.rubric_assessment.is_empty() && self.comment.is_none()
+    }
+
+    /// Builds the body of the `PUT /courses/:course_id/assignments/:assignment_id/submissions/:user_id` request.
+    pub fn to_json(&self) -> Value {
+        let mut body = json!({});
+        if let Some(grade) = &self.grade {
+            body["submission"]["posted_grade"] = json!(grade);
+        }
+        if let Some(excuse) = self.excuse {
+            body["submission"]["excuse"] = json!(excuse);
+        }
+        if !self.rubric_assessment.is_empty() {
+            body["rubric_assessment"] = json!(self.rubric_assessment);
+        }
+        if let Some(comment) = &self.comment {
+            body["comment"]["text_comment"] = json!(comment);
+            if self.group_comment {
+                body["comment"]["group_comment"] = json!(true);
+            }
+        }
+        body
+    }
 }
 
 
 
 impl Submission {
-    /// Checks if the submission is late by comparing `submitted_at` with `due_at`.
+    /// Returns the due date that applies to this submission, accounting for overrides.
+    ///
+    /// Canvas sends the date it resolved for the student as `cached_due_date` (kept in `extra`),
+    /// which is used when present. Otherwise the overrides are resolved with
+    /// `AssignmentInfo::due_at_for_student`, using the sections listed in the student's
+    /// `enrollments` and the submission's `group` when Canvas included them.
+    pub fn effective_due_at(&self) -> Option<DateTime<Utc>> {
+        if let Some(cached) = self.extra.get("cached_due_date") {
+            return cached
+                .as_str()
+                .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+                .map(|date| date.with_timezone(&Utc));
+        }
+        let student = match self.students_info.first() {
+            Some(student) => student,
+            None => return self.assignment_info.due_at,
+        };
+        let section_ids: Vec<u64> = student
+            .extra
+            .get("enrollments")
+            .and_then(Value::as_array)
+            .map(|enrollments| {
+                enrollments
+                    .iter()
+                    .filter_map(|enrollment| enrollment["course_section_id"].as_u64())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let group_id = self.extra.get("group").and_then(|group| group["id"].as_u64());
+        self.assignment_info.due_at_for_student(student.id, &section_ids, group_id)
+    }
+
+    /// Checks if the submission is late by comparing `submitted_at` with the effective due date
+    /// (`effective_due_at`), so extensions granted to the student are respected.
     ///
     /// Returns:
     /// - `Some(Duration)` if the submission is late, indicating the time difference between `submitted_at` and `due_at`.
     /// - `None` if the submission is not late or if there is no submission or due date.
     pub fn is_late(&self) -> Option<Duration> {
         // Check if both submission and due dates are available
-        if let (Some(submitted_at), Some(due_at)) = (self.submitted_at, self.assignment_info.due_at)
+        if let (Some(submitted_at), Some(due_at)) = (self.submitted_at, self.effective_due_at())
         {
             // Compare the dates
             if submitted_at > due_at {
@@ -243,6 +515,221 @@ impl Submission {
         ret
     }
 
+    /// Updates the score of this submission, honoring the given `GradeWriteOptions`.
+    ///
+    /// The local `score` is only updated when the write actually happens.
+    ///
+    /// Returns:
+    /// - `Result<GradeWriteOutcome, Box<dyn Error>>`: Whether the grade was written or why it was skipped.
+    pub fn update_score_with_options(
+        &mut self,
+        client: &Client,
+        new_score: Option<f64>,
+        options: &GradeWriteOptions,
+    ) -> Result<GradeWriteOutcome, Box<dyn Error>> {
+        // Pega o primeiro estudante da lista
+        let student_info = match self.students_info.first() {
+            Some(student_info) => student_info,
+            None => return Err("No student info found".into()),
+        };
+
+        let course = Course {
+            info: student_info.course_info.clone(),
+        };
+
+        let outcome = course.update_assignment_score_with_options(
+            client,
+            self.assignment_id,
+            student_info.id,
+            new_score,
+            options,
+        )?;
+        if outcome == GradeWriteOutcome::Written {
+            self.score = new_score;
+        }
+        Ok(outcome)
+    }
+
+    /// Applies several changes (grade, rubric assessment, comment...) to this submission in a
+    /// single request. See `SubmissionUpdate`.
+    ///
+    /// The local `score` is refreshed with the one computed by Canvas.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `update`: Changes to apply.
+    ///
+    /// Returns:
+    /// - `Result<(), Box<dyn Error>>`: Success or an error detailing any issues encountered.
+    pub fn update(&mut self, client: &Client, update: &SubmissionUpdate) -> Result<(), Box<dyn Error>> {
+        // Pega o primeiro estudante da lista
+        let student_info = match self.students_info.first() {
+            Some(student_info) => student_info,
+            None => return Err("No student info found".into()),
+        };
+
+        // Só avisa: a nota pode ser lançada mesmo quando a entrega não confere com a atividade
+        if let Err(e) = self.assignment_info.check_submission(self) {
+            log::warn!("Grading submission {}: {}", self.id, e);
+        }
+
+        let course = Course {
+            info: student_info.course_info.clone(),
+        };
+
+        let updated = course.update_submission(client, self.assignment_id, student_info.id, update)?;
+        if update.grade.is_some() || update.excuse.is_some() || !update.rubric_assessment.is_empty() {
+            self.score = updated["score"].as_f64();
+        }
+        Ok(())
+    }
+
+    /// Names of the attached files whose extension the assignment doesn't allow (see
+    /// `AssignmentInfo::allowed_extensions`).
+    pub fn extension_violations(&self) -> Vec<String> {
+        self.extra
+            .get("attachments")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|attachment| attachment["filename"].as_str())
+            .filter(|file_name| !self.assignment_info.accepts_file(file_name))
+            .map(String::from)
+            .collect()
+    }
+
+    /// Member who turned the submission in. For group submissions, Canvas reports it in `user_id`;
+    /// the first member is returned when it isn't known.
+    pub fn submitter(&self) -> Option<&Arc<StudentInfo>> {
+        let user_id = self.extra.get("user_id").and_then(Value::as_u64);
+        self.students_info
+            .iter()
+            .find(|student| Some(student.id) == user_id)
+            .or_else(|| self.students_info.first())
+    }
+
+    /// Returns `true` for submissions shared by several members of a group.
+    pub fn is_group_submission(&self) -> bool {
+        self.students_info.len() > 1
+    }
+
+    /// Grades the members of a group submission, giving some of them a different grade or comment
+    /// (e.g. a penalty for a member who didn't contribute).
+    ///
+    /// `group_update` is applied to every member without an entry in `member_updates`. Comments
+    /// are sent to each member separately, so that an override comment is only seen by its member.
+    /// Overrides need the assignment to grade group members individually: otherwise Canvas copies
+    /// every grade to the whole group, and an error is returned before anything is sent.
+    ///
+    /// Arguments:
+    /// - `client`: HTTP client for executing requests.
+    /// - `group_update`: Grade, rubric and comment of the group.
+    /// - `member_updates`: Changes for specific members, by student ID.
+    ///
+    /// Returns:
+    /// - `Result<(), Box<dyn Error>>`: Success, or the first error (members before it are graded).
+    ///
+    /// Example:
+    /// ```
+    /// let group = SubmissionUpdate { grade: Some("9".to_string()), ..Default::default() };
+    /// let penalty = SubmissionUpdate {
+    ///     grade: Some("5".to_string()),
+    ///     comment: Some("Não participou do desenvolvimento.".to_string()),
+    ///     ..Default::default()
+    /// };
+    /// submission.update_members(&client, &group, &HashMap::from([(absent_student_id, penalty)]))?;
+    /// ```
+    pub fn update_members(
+        &mut self,
+        client: &Client,
+        group_update: &SubmissionUpdate,
+        member_updates: &HashMap<u64, SubmissionUpdate>,
+    ) -> Result<(), Box<dyn Error>> {
+        let plan = self.member_update_plan(group_update, member_updates)?;
+        let submitter_id = self.submitter().map(|student| student.id);
+        let course = Course {
+            info: self.assignment_info.course_info.clone(),
+        };
+        for (student_id, update) in plan {
+            let updated = course.update_submission(client, self.assignment_id, student_id, &update)?;
+            let graded = update.grade.is_some() || update.excuse.is_some() || !update.rubric_assessment.is_empty();
+            if graded && Some(student_id) == submitter_id {
+                self.score = updated["score"].as_f64();
+            }
+        }
+        Ok(())
+    }
+
+    // Decides which update goes to which member in `update_members`.
+    fn member_update_plan(
+        &self,
+        group_update: &SubmissionUpdate,
+        member_updates: &HashMap<u64, SubmissionUpdate>,
+    ) -> Result<Vec<(u64, SubmissionUpdate)>, Box<dyn Error>> {
+        if let Some(student_id) = member_updates.keys().find(|id| !self.students_info.iter().any(|s| s.id == **id)) {
+            return Err(format!("Student {} is not a member of submission {}", student_id, self.id).into());
+        }
+        let individually = self.assignment_info.extra.get("grade_group_students_individually").and_then(Value::as_bool);
+        if individually != Some(true) {
+            // Graded as a group: Canvas copies the grade of one member to the others
+            if !member_updates.is_empty() && self.is_group_submission() {
+                return Err("Per-member grades require the assignment to grade group members individually".into());
+            }
+            let mut update = member_updates.values().next().unwrap_or(group_update).clone();
+            update.group_comment = self.is_group_submission();
+            return Ok(self.submitter().map(|s| (s.id, update)).into_iter().collect());
+        }
+        Ok(self
+            .students_info
+            .iter()
+            .map(|student| {
+                let mut update = member_updates.get(&student.id).unwrap_or(group_update).clone();
+                update.group_comment = false;
+                (student.id, update)
+            })
+            .filter(|(_, update)| !update.is_empty())
+            .collect())
+    }
+
+    /// Retrieves a single submission, with its files and comments, without loading the roster or
+    /// the assignments of the course.
+    ///
+    /// The course, assignment and student of the returned submission are built from what Canvas
+    /// sends with the submission, so only three requests are made whatever the size of the course.
+    ///
+    /// Arguments:
+    /// - `credentials`: Canvas API credentials.
+    /// - `course_id`, `assignment_id`: The assignment submitted to.
+    /// - `user_id`: The student who submitted.
+    ///
+    /// Returns:
+    /// - `Result<Submission, Box<dyn Error>>`: The submission or an error.
+    ///
+    /// Example:
+    /// ```
+    /// let submission = Submission::fetch(&CanvasCredentials::credentials(), 1234, 5678, 42)?;
+    /// submission.download_submission_files(&client, "downloads")?;
+    /// ```
+    pub fn fetch(
+        credentials: &CanvasCredentials,
+        course_id: u64,
+        assignment_id: u64,
+        user_id: u64,
+    ) -> Result<Submission, Box<dyn Error>> {
+        let client = Client::new();
+        let course_url = format!("{}/courses/{}", credentials.url_canvas, course_id);
+        let assignment_json: Value = canvas::fetch_json(&client, credentials, &format!("{}/assignments/{}", course_url, assignment_id))?;
+        let url = format!("{}/assignments/{}/submissions/{}", course_url, assignment_id, user_id);
+        let params = Params::new().include(["submission_comments", "user", "course"]);
+        let response = send_http_request(&client, HttpMethod::Get, &url, credentials, params.into())?;
+        if !response.status().is_success() {
+            return Err(format!("Failed to fetch submission with status: {}", response.status()).into());
+        }
+        let submission_json: Value = response.json()?;
+        submission_from_json(&Arc::new(credentials.clone()), course_id, &assignment_json, &submission_json)
+            .ok_or_else(|| format!("Invalid submission of user {} to assignment {}", user_id, assignment_id).into())
+    }
+
     /// Downloads all files associated with this submission.
     ///
     /// This method iterates over the `file_ids` associated with the submission and
@@ -302,6 +789,17 @@ impl Submission {
         }
     }
 
+    /// Downloads the files attached to the comments of this submission into `output_dir`.
+    /// See `Comment::download_attachments`.
+    pub fn download_comment_attachments(&self, client: &Client, output_dir: &str) -> Result<Vec<String>, Box<dyn Error>> {
+        let canvas_info = &self.assignment_info.course_info.canvas_info;
+        let mut downloaded_files = Vec::new();
+        for comment in &self.comments {
+            downloaded_files.extend(comment.download_attachments(client, canvas_info, output_dir)?);
+        }
+        Ok(downloaded_files)
+    }
+
     // Deleta um comentário associado a esta submissão.
     ///
     /// Este método chama a função `delete_comment` definida em `canvas.rs` para
@@ -336,3 +834,221 @@ impl Submission {
         )
     }
 }
+
+// Builds a standalone submission from a single-submission answer (with `user` and `course`
+// included) and the JSON of its assignment.
+fn submission_from_json(
+    credentials: &Arc<CanvasCredentials>,
+    course_id: u64,
+    assignment_json: &Value,
+    submission_json: &Value,
+) -> Option<Submission> {
+    let course_info = Canvas::convert_json_to_course(credentials, &submission_json["course"])
+        .map(|course| course.info)
+        .unwrap_or_else(|| {
+            Arc::new(CourseInfo {
+                id: course_id,
+                canvas_info: Arc::clone(credentials),
+                ..Default::default()
+            })
+        });
+    let assignment = canvas::convert_json_to_assignment(&course_info, assignment_json)?;
+    let student = canvas::convert_json_to_student((*course_info).clone(), &submission_json["user"])?;
+    Assignment::convert_json_to_submission(&vec![student], submission_json, assignment.info, &None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_grade_write_options_skip_rules() {
+        let read_at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let before = Utc.with_ymd_and_hms(2024, 5, 1, 11, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 5, 1, 13, 0, 0).unwrap();
+
+        let always = GradeWriteOptions::default();
+        assert!(!always.requires_read());
+        assert_eq!(always.check(Some(7.0), Some(after), Some(7.0)), None);
+
+        let unchanged = GradeWriteOptions {
+            skip_if_unchanged: true,
+            baseline: None,
+        };
+        assert_eq!(
+            unchanged.check(Some(7.0), None, Some(7.0)),
+            Some(GradeWriteOutcome::SkippedUnchanged)
+        );
+        assert_eq!(unchanged.check(None, None, None), Some(GradeWriteOutcome::SkippedUnchanged));
+        assert_eq!(unchanged.check(Some(7.0), None, Some(8.0)), None);
+        assert_eq!(unchanged.check(None, None, Some(8.0)), None);
+
+        let guarded = GradeWriteOptions {
+            skip_if_unchanged: false,
+            baseline: Some(read_at),
+        };
+        assert_eq!(guarded.check(Some(5.0), Some(before), Some(9.0)), None);
+        assert_eq!(
+            guarded.check(Some(5.0), Some(after), Some(9.0)),
+            Some(GradeWriteOutcome::SkippedRemoteNewer {
+                remote_score: Some(5.0),
+                graded_at: after
+            })
+        );
+    }
+
+    #[test]
+    fn test_submission_update_single_body() {
+        let update = SubmissionUpdate {
+            grade: Some("8.5".to_string()),
+            rubric_assessment: BTreeMap::from([
+                ("_1234".to_string(), CriterionAssessment { points: Some(5.0), rating_id: Some("r1".to_string()), comments: None }),
+                ("_5678".to_string(), CriterionAssessment { points: Some(3.5), comments: Some("Faltou o gráfico".to_string()), ..Default::default() }),
+            ]),
+            comment: Some("Bom trabalho!".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            update.to_json(),
+            json!({
+                "submission": {"posted_grade": "8.5"},
+                "rubric_assessment": {
+                    "_1234": {"points": 5.0, "rating_id": "r1"},
+                    "_5678": {"points": 3.5, "comments": "Faltou o gráfico"}
+                },
+                "comment": {"text_comment": "Bom trabalho!"}
+            })
+        );
+        assert!(SubmissionUpdate::default().is_empty());
+        assert_eq!(SubmissionUpdate::default().to_json(), json!({}));
+    }
+
+    #[test]
+    fn test_unknown_submission_data_is_kept() {
+        let submission_type: SubmissionType = serde_json::from_value(json!("student_annotation")).unwrap();
+        assert_eq!(submission_type, SubmissionType::Other("student_annotation".to_string()));
+        assert_eq!(serde_json::to_value(&submission_type).unwrap(), json!("student_annotation"));
+        assert_eq!(SubmissionType::from("online_url"), SubmissionType::OnlineUrl);
+
+        let submission: Submission = serde_json::from_value(json!({
+            "id": 1, "assignment_id": 2, "score": 7.5, "submitted_at": null,
+            "submission_type": "basic_lti_launch", "comments": [],
+            "workflow_state": "graded", "sticker": "trophy"
+        }))
+        .unwrap();
+        assert_eq!(submission.submission_type.as_ref().unwrap().as_str(), "basic_lti_launch");
+        assert_eq!(submission.extra["sticker"], "trophy");
+        assert_eq!(serde_json::to_value(&submission).unwrap()["workflow_state"], "graded");
+
+        let student = crate::canvas::convert_json_to_student(
+            Default::default(),
+            &json!({"id": 10, "name": "Ana", "sortable_name": "Souza, Ana", "pronouns": "ela/dela"}),
+        )
+        .unwrap();
+        assert_eq!(student.info.extra["pronouns"], "ela/dela");
+        assert!(!student.info.extra.contains_key("name"));
+    }
+
+    #[test]
+    fn test_comment_attachments_from_json() {
+        let comment = Comment::from_json(&json!({
+            "id": 7,
+            "comment": "Fixed version attached",
+            "author_id": 10,
+            "attachments": [{
+                "id": 5001,
+                "display_name": "lista1-v2.pdf",
+                "filename": "lista1-v2.pdf",
+                "content-type": "application/pdf",
+                "size": 2048,
+                "url": "https://canvas.example/files/5001/download"
+            }]
+        }))
+        .unwrap();
+        assert_eq!(comment.content, "Fixed version attached");
+        assert_eq!(comment.attachments.len(), 1);
+        assert_eq!(comment.attachments[0].id, 5001);
+        assert_eq!(comment.attachments[0].content_type.as_deref(), Some("application/pdf"));
+        assert!(Comment::from_json(&json!({"id": 8})).is_none());
+    }
+
+    #[test]
+    fn test_standalone_submission_from_json() {
+        let credentials = Arc::new(CanvasCredentials::default());
+        let assignment = json!({"id": 100, "name": "Lista 1", "points_possible": 10.0});
+        let submission = json!({
+            "id": 1000,
+            "assignment_id": 100,
+            "user_id": 42,
+            "score": 8.5,
+            "submission_type": "online_upload",
+            "attachments": [{"id": 5000}],
+            "submission_comments": [{"id": 7, "comment": "Good"}],
+            "user": {"id": 42, "name": "Ana"},
+            "course": {"id": 7, "name": "Cálculo", "course_code": "CALC"}
+        });
+        let submission = submission_from_json(&credentials, 7, &assignment, &submission).unwrap();
+        assert_eq!(submission.students_info[0].name, "Ana");
+        assert_eq!(submission.assignment_info.name, "Lista 1");
+        assert_eq!(submission.assignment_info.course_info.course_code, "CALC");
+        assert_eq!(submission.file_ids, vec![5000]);
+        assert_eq!(submission.comments[0].content, "Good");
+
+        let without_course = json!({"id": 1001, "assignment_id": 100, "user_id": 43, "user": {"id": 43, "name": "Bia"}});
+        let submission = submission_from_json(&credentials, 7, &assignment, &without_course).unwrap();
+        assert_eq!(submission.assignment_info.course_info.id, 7);
+    }
+
+    #[test]
+    fn test_group_member_overrides() {
+        let member = |id: u64| {
+            Arc::new(StudentInfo {
+                id,
+                ..Default::default()
+            })
+        };
+        let mut assignment_info = AssignmentInfo::default();
+        assignment_info.extra.insert("grade_group_students_individually".to_string(), json!(true));
+        let mut submission = Submission {
+            id: 1,
+            assignment_id: 100,
+            score: None,
+            submitted_at: None,
+            submission_type: None,
+            students_info: vec![member(10), member(11), member(12)],
+            assignment_info: Arc::new(assignment_info),
+            file_ids: Vec::new(),
+            comments: Vec::new(),
+            extra: Map::new(),
+        };
+        submission.extra.insert("user_id".to_string(), json!(11));
+        assert_eq!(submission.submitter().unwrap().id, 11);
+        assert!(submission.is_group_submission());
+
+        let group = SubmissionUpdate {
+            grade: Some("9".to_string()),
+            comment: Some("Bom trabalho".to_string()),
+            group_comment: true,
+            ..Default::default()
+        };
+        let penalty = SubmissionUpdate {
+            grade: Some("5".to_string()),
+            ..Default::default()
+        };
+        let plan = submission.member_update_plan(&group, &HashMap::from([(12, penalty.clone())])).unwrap();
+        assert_eq!(plan.len(), 3);
+        assert_eq!(plan[0].1.grade.as_deref(), Some("9"));
+        assert!(!plan[0].1.group_comment);
+        assert_eq!(plan[2], (12, penalty.clone()));
+        assert!(submission.member_update_plan(&group, &HashMap::from([(99, penalty.clone())])).is_err());
+
+        // Graded as a group: a single write through the submitter, overrides refused
+        submission.assignment_info = Arc::new(AssignmentInfo::default());
+        let plan = submission.member_update_plan(&group, &HashMap::new()).unwrap();
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].0, 11);
+        assert!(plan[0].1.group_comment);
+        assert!(submission.member_update_plan(&group, &HashMap::from([(12, penalty)])).is_err());
+    }
+}
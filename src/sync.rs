@@ -0,0 +1,265 @@
+// Import necessary crates and modules
+use crate::submission::GradeWriteOptions;
+use crate::{response_cache, Course};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Scores indexed by student ID, then by assignment ID.
+pub type Gradebook = HashMap<u64, HashMap<u64, f64>>;
+
+/// Kind of difference between a local grade and the one stored in Canvas.
+///
+/// - `New`: Canvas has no score yet.
+/// - `Changed`: Canvas has a different score that was not modified since the last sync.
+/// - `Conflict`: Canvas has a different score that was also modified since the last sync
+///   (e.g. a manual regrade in SpeedGrader), so local and remote changes collide.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ChangeKind {
+    New,
+    Changed,
+    Conflict,
+}
+
+/// A single grade that differs between the local gradebook and Canvas.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GradeChange {
+    pub student_id: u64,
+    pub assignment_id: u64,
+    pub kind: ChangeKind,
+    pub local: f64,
+    pub remote: Option<f64>,
+    pub baseline: Option<f64>,
+}
+
+/// What to do with `ChangeKind::Conflict` entries when applying a sync.
+///
+/// - `KeepRemote`: Leave the Canvas score untouched and report the conflict as skipped.
+/// - `PreferLocal`: Overwrite the Canvas score with the local one.
+/// - `Abort`: Refuse to apply anything if at least one conflict exists.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum ConflictPolicy {
+    KeepRemote,
+    PreferLocal,
+    Abort,
+}
+
+/// Outcome of `apply`. In dry-run mode, `applied` lists what would have been written.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct SyncReport {
+    pub dry_run: bool,
+    pub applied: Vec<GradeChange>,
+    pub skipped: Vec<GradeChange>,
+    pub failed: Vec<(GradeChange, String)>,
+}
+
+// Scores are compared with a tolerance to ignore floating-point noise from the API.
+fn same_score(a: f64, b: f64) -> bool {
+    (a - b).abs() < 1e-9
+}
+
+fn lookup(gradebook: &Gradebook, student_id: u64, assignment_id: u64) -> Option<f64> {
+    gradebook
+        .get(&student_id)
+        .and_then(|scores| scores.get(&assignment_id))
+        .copied()
+}
+
+/// Computes the differences between a local gradebook and the Canvas one.
+///
+/// `baseline` is the gradebook as it was on Canvas at the previous sync; without it, every
+/// differing remote score is considered `Changed` rather than `Conflict`. Only grades present in
+/// `local` are considered. The result is sorted by assignment, then student.
+pub fn diff(local: &Gradebook, remote: &Gradebook, baseline: Option<&Gradebook>) -> Vec<GradeChange> {
+    let mut changes = Vec::new();
+    for (&student_id, scores) in local {
+        for (&assignment_id, &local_score) in scores {
+            let remote_score = lookup(remote, student_id, assignment_id);
+            let baseline_score = baseline.and_then(|b| lookup(b, student_id, assignment_id));
+
+            let kind = match remote_score {
+                None => ChangeKind::New,
+                Some(remote_score) if same_score(remote_score, local_score) => continue,
+                Some(remote_score) => {
+                    let remote_modified = match (baseline, baseline_score) {
+                        (None, _) => false,
+                        (Some(_), Some(base)) => !same_score(base, remote_score),
+                        (Some(_), None) => true,
+                    };
+                    if remote_modified {
+                        ChangeKind::Conflict
+                    } else {
+                        ChangeKind::Changed
+                    }
+                }
+            };
+
+            changes.push(GradeChange {
+                student_id,
+                assignment_id,
+                kind,
+                local: local_score,
+                remote: remote_score,
+                baseline: baseline_score,
+            });
+        }
+    }
+    changes.sort_by_key(|c| (c.assignment_id, c.student_id));
+    changes
+}
+
+/// Downloads the current Canvas scores of `course` for the given assignments.
+///
+/// Group submissions are expanded so that every member of the group gets the group's score. The
+/// scores are always read from Canvas, never from the response cache, since they decide which
+/// grades are written.
+pub fn fetch_remote_gradebook(
+    course: &Course,
+    assignment_ids: &[u64],
+) -> Result<Gradebook, Box<dyn Error>> {
+    response_cache::invalidate_course(&course.info.canvas_info.url_canvas, course.info.id);
+    let students = course.fetch_students()?;
+    let mut gradebook = Gradebook::new();
+    for &assignment_id in assignment_ids {
+        let assignment = course.get_assignment_from_assignment_id(assignment_id)?;
+        for submission in assignment.fetch_submissions(&students)? {
+            if let Some(score) = submission.score {
+                for student in &submission.students_info {
+                    gradebook
+                        .entry(student.id)
+                        .or_default()
+                        .insert(assignment_id, score);
+                }
+            }
+        }
+    }
+    Ok(gradebook)
+}
+
+/// Computes the sync plan for `course`: downloads the Canvas scores of every assignment
+/// referenced in `local` and diffs them (see `diff`).
+pub fn plan(
+    course: &Course,
+    local: &Gradebook,
+    baseline: Option<&Gradebook>,
+) -> Result<Vec<GradeChange>, Box<dyn Error>> {
+    let mut assignment_ids: Vec<u64> = local
+        .values()
+        .flat_map(|scores| scores.keys().copied())
+        .collect();
+    assignment_ids.sort_unstable();
+    assignment_ids.dedup();
+
+    let remote = fetch_remote_gradebook(course, &assignment_ids)?;
+    Ok(diff(local, &remote, baseline))
+}
+
+/// Applies a sync plan to Canvas.
+///
+/// Conflicts are handled according to `policy`. With `dry_run`, nothing is written and the report
+/// lists what would have been applied. Failed writes don't stop the batch; they are collected in
+/// `SyncReport::failed`.
+///
+/// Example:
+/// ```
+/// let changes = sync::plan(&course, &local_gradebook, Some(&last_synced))?;
+/// let report = sync::apply(&course, &client, &changes, ConflictPolicy::KeepRemote, true)?;
+/// println!("{} grades would be written", report.applied.len());
+/// ```
+pub fn apply(
+    course: &Course,
+    client: &Client,
+    changes: &[GradeChange],
+    policy: ConflictPolicy,
+    dry_run: bool,
+) -> Result<SyncReport, Box<dyn Error>> {
+    if policy == ConflictPolicy::Abort && changes.iter().any(|c| c.kind == ChangeKind::Conflict) {
+        return Err("Sync aborted: conflicting grades were modified in Canvas since the last sync".into());
+    }
+
+    let mut report = SyncReport {
+        dry_run,
+        ..Default::default()
+    };
+    for change in changes {
+        if change.kind == ChangeKind::Conflict && policy == ConflictPolicy::KeepRemote {
+            report.skipped.push(change.clone());
+            continue;
+        }
+        if dry_run {
+            report.applied.push(change.clone());
+            continue;
+        }
+        match course.update_assignment_score_with_options(
+            client,
+            change.assignment_id,
+            change.student_id,
+            Some(change.local),
+            &GradeWriteOptions::default(),
+        ) {
+            Ok(_) => report.applied.push(change.clone()),
+            Err(e) => report.failed.push((change.clone(), e.to_string())),
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CourseInfo;
+    use std::sync::Arc;
+
+    fn gradebook(entries: &[(u64, u64, f64)]) -> Gradebook {
+        let mut gradebook = Gradebook::new();
+        for &(student, assignment, score) in entries {
+            gradebook.entry(student).or_default().insert(assignment, score);
+        }
+        gradebook
+    }
+
+    #[test]
+    fn test_diff_classifies_changes() {
+        let local = gradebook(&[(1, 10, 8.0), (2, 10, 7.0), (3, 10, 6.0), (4, 10, 5.0)]);
+        let remote = gradebook(&[(2, 10, 7.0), (3, 10, 9.0), (4, 10, 4.0)]);
+        let baseline = gradebook(&[(3, 10, 9.0), (4, 10, 3.0)]);
+
+        let changes = diff(&local, &remote, Some(&baseline));
+        let kinds: Vec<(u64, ChangeKind)> = changes.iter().map(|c| (c.student_id, c.kind)).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (1, ChangeKind::New),
+                (3, ChangeKind::Changed),
+                (4, ChangeKind::Conflict),
+            ]
+        );
+
+        // Without a baseline nothing can be flagged as a conflict.
+        let changes = diff(&local, &remote, None);
+        assert!(changes.iter().all(|c| c.kind != ChangeKind::Conflict));
+    }
+
+    #[test]
+    fn test_apply_dry_run_and_policies() {
+        let course = Course {
+            info: Arc::new(CourseInfo::default()),
+        };
+        let client = Client::new();
+        let local = gradebook(&[(1, 10, 8.0), (4, 10, 5.0)]);
+        let remote = gradebook(&[(4, 10, 4.0)]);
+        let baseline = gradebook(&[(4, 10, 3.0)]);
+        let changes = diff(&local, &remote, Some(&baseline));
+
+        let report = apply(&course, &client, &changes, ConflictPolicy::KeepRemote, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.applied.len(), 1);
+        assert_eq!(report.skipped.len(), 1);
+
+        let report = apply(&course, &client, &changes, ConflictPolicy::PreferLocal, true).unwrap();
+        assert_eq!(report.applied.len(), 2);
+
+        assert!(apply(&course, &client, &changes, ConflictPolicy::Abort, true).is_err());
+    }
+}
@@ -0,0 +1,174 @@
+// Import necessary crates and modules
+use crate::assignment::{CommentRequest, CommentResult};
+use crate::{Assignment, StudentInfo, Submission};
+use reqwest::blocking::Client;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Text with `{{variable}}` placeholders, rendered once per student or group to personalize
+/// comments, messages and announcements.
+///
+/// Variables filled by `Template::vars_for_submission`:
+/// - `name`: Name of the student, or of every member of a group (`"Ana, Bia"`).
+/// - `first_name`: First name of the student (of each member for groups).
+/// - `group`: Name of the group, when Canvas sent it; the member names otherwise.
+/// - `score`: Score of the submission (`"8.5"`), empty when not graded.
+/// - `points_possible`: Maximum score of the assignment.
+/// - `assignment`: Name of the assignment.
+///
+/// Other variables can be added to the map before rendering. `{{{{` and `}}}}` write literal
+/// braces.
+///
+/// Example:
+/// ```
+/// let template = Template::new("Olá {{first_name}}, sua nota em {{assignment}} foi {{score}}/{{points_possible}}.");
+/// let submissions = assignment.fetch_submissions(&students)?;
+/// for (student_id, result) in assignment.comment_many_from_template(&client, &template, &submissions) {
+///     if let Err(e) = result {
+///         eprintln!("{}: {}", student_id, e);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    text: String,
+}
+
+impl Template {
+    /// Creates a template from its text.
+    pub fn new(text: &str) -> Self {
+        Template { text: text.to_string() }
+    }
+
+    /// Replaces the placeholders with the values of `vars`. Fails on a placeholder without a value
+    /// or not closed, so that no half-personalized text is sent.
+    pub fn render(&self, vars: &HashMap<String, String>) -> Result<String, Box<dyn Error>> {
+        let mut output = String::new();
+        let mut rest = self.text.as_str();
+        while let Some(start) = rest.find("{{") {
+            output.push_str(&rest[..start].replace("}}}}", "}}"));
+            let after = &rest[start + 2..];
+            if let Some(stripped) = after.strip_prefix("{{") {
+                output.push_str("{{");
+                rest = stripped;
+                continue;
+            }
+            let end = after.find("}}").ok_or("Unclosed placeholder in template")?;
+            let name = after[..end].trim();
+            let value = vars
+                .get(name)
+                .ok_or_else(|| format!("No value for the template variable {}", name))?;
+            output.push_str(value);
+            rest = &after[end + 2..];
+        }
+        output.push_str(&rest.replace("}}}}", "}}"));
+        Ok(output)
+    }
+
+    /// Variables describing a submission and its student(s). See the documentation of `Template`.
+    pub fn vars_for_submission(submission: &Submission) -> HashMap<String, String> {
+        let names = |f: fn(&StudentInfo) -> &str| {
+            submission.students_info.iter().map(|student| f(student)).collect::<Vec<_>>().join(", ")
+        };
+        let name = names(|student| &student.name);
+        let group = submission
+            .extra
+            .get("group")
+            .and_then(|group| group["name"].as_str())
+            .map_or_else(|| name.clone(), String::from);
+        let points_possible = submission.assignment_info.points_possible;
+        HashMap::from([
+            ("first_name".to_string(), names(|student| student.name.split_whitespace().next().unwrap_or(""))),
+            ("name".to_string(), name),
+            ("group".to_string(), group),
+            ("score".to_string(), submission.score.map(format_score).unwrap_or_default()),
+            ("points_possible".to_string(), points_possible.map(format_score).unwrap_or_default()),
+            ("assignment".to_string(), submission.assignment_info.name.clone()),
+        ])
+    }
+}
+
+// Writes scores without useless decimals: 8 instead of 8.0, 8.5 as is.
+fn format_score(score: f64) -> String {
+    format!("{}", (score * 100.0).round() / 100.0)
+}
+
+impl Assignment {
+    /// Posts a comment rendered from `template` on each of `submissions` (to the first member for
+    /// group submissions), through `comment_many`. Submissions whose rendering fails are reported
+    /// without being sent.
+    ///
+    /// Returns:
+    /// - `Vec<(u64, CommentResult)>`: The outcome for each student, in the order of `submissions`.
+    pub fn comment_many_from_template(
+        &self,
+        client: &Client,
+        template: &Template,
+        submissions: &[Submission],
+    ) -> Vec<(u64, CommentResult)> {
+        let mut failures = Vec::new();
+        let mut requests: Vec<CommentRequest> = Vec::new();
+        for submission in submissions {
+            let Some(student) = submission.students_info.first() else {
+                continue;
+            };
+            match template.render(&Template::vars_for_submission(submission)) {
+                Ok(text) => requests.push((student.id, text, None)),
+                Err(e) => failures.push((student.id, Err(e))),
+            }
+        }
+        let mut results = self.comment_many(client, requests);
+        results.extend(failures);
+        let order: Vec<u64> = submissions
+            .iter()
+            .filter_map(|submission| submission.students_info.first().map(|student| student.id))
+            .collect();
+        results.sort_by_key(|(student_id, _)| order.iter().position(|id| id == student_id));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AssignmentInfo;
+    use serde_json::{json, Map};
+    use std::sync::Arc;
+
+    #[test]
+    fn test_render_submission_template() {
+        let student = |id: u64, name: &str| {
+            Arc::new(StudentInfo {
+                id,
+                name: name.to_string(),
+                ..Default::default()
+            })
+        };
+        let mut submission = Submission {
+            id: 1,
+            assignment_id: 100,
+            score: Some(8.0),
+            submitted_at: None,
+            submission_type: None,
+            students_info: vec![student(10, "Ana Souza"), student(11, "Bia Lima")],
+            assignment_info: Arc::new(AssignmentInfo {
+                name: "Lista 1".to_string(),
+                points_possible: Some(10.0),
+                ..Default::default()
+            }),
+            file_ids: Vec::new(),
+            comments: Vec::new(),
+            extra: Map::new(),
+        };
+        submission.extra.insert("group".to_string(), json!({"id": 5, "name": "Grupo 5"}));
+
+        let vars = Template::vars_for_submission(&submission);
+        let template = Template::new("Olá {{ first_name }} ({{group}}): {{score}}/{{points_possible}} em {{assignment}} {{{{ok}}}}");
+        assert_eq!(
+            template.render(&vars).unwrap(),
+            "Olá Ana, Bia (Grupo 5): 8/10 em Lista 1 {{ok}}"
+        );
+        assert!(Template::new("{{nota}}").render(&vars).is_err());
+        assert!(Template::new("{{name").render(&vars).is_err());
+    }
+}
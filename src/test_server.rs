@@ -0,0 +1,260 @@
+// Import necessary crates and modules
+use crate::CanvasCredentials;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use tiny_http::{Header, Response, Server};
+
+/// Token accepted by the mock server (any token is accepted; this one is just a placeholder).
+pub const MOCK_TOKEN: &str = "mock-canvas-token";
+
+// Response served for a method and path.
+#[derive(Debug, Clone)]
+enum Fixture {
+    // Same response whatever the page (arrays are a single page followed by `[]`)
+    Single(u16, Value),
+    // One array per page, selected with the `page` query parameter
+    Pages(Vec<Vec<Value>>),
+}
+
+/// A request received by the mock server.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub body: String,
+}
+
+/// Local HTTP server answering like the Canvas API from in-memory fixtures, for integration tests
+/// without network access or real tokens. The server stops when dropped.
+///
+/// Paths are relative to the API root (`/courses/1/users`). Unknown routes answer `404` like
+/// Canvas. Paginated fixtures honour the `page` query parameter, return `[]` past the last page
+/// and send a `Link: rel="next"` header.
+///
+/// Example:
+/// ```
+/// let canvas = MockCanvas::with_sample_course()?;
+/// let course = match Canvas::fetch_single_course_with_credentials(&canvas.credentials(), 1) {
+///     CanvasResultSingleCourse::Ok(course) => course,
+///     _ => panic!("course not served"),
+/// };
+/// let students = course.fetch_students()?;
+/// canvas.mock("GET", "/courses/1/assignments/100/submissions/10", json!({"id": 1, "score": 9.5}));
+/// ```
+pub struct MockCanvas {
+    base_url: String,
+    fixtures: Arc<Mutex<HashMap<(String, String), Fixture>>>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+    server: Arc<Server>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl MockCanvas {
+    /// Starts an empty server on a free local port.
+    pub fn start() -> Result<MockCanvas, Box<dyn std::error::Error + Send + Sync>> {
+        let server = Arc::new(Server::http("127.0.0.1:0")?);
+        let port = server.server_addr().to_ip().map(|addr| addr.port()).ok_or("Server has no IP address")?;
+        let base_url = format!("http://127.0.0.1:{}", port);
+        let fixtures: Arc<Mutex<HashMap<(String, String), Fixture>>> = Arc::new(Mutex::new(HashMap::new()));
+        let requests = Arc::new(Mutex::new(Vec::new()));
+
+        let handle = {
+            let (server, fixtures, requests, base_url) =
+                (server.clone(), fixtures.clone(), requests.clone(), base_url.clone());
+            std::thread::spawn(move || {
+                for mut request in server.incoming_requests() {
+                    let (path, query) = split_url(request.url());
+                    let mut body = Vec::new();
+                    let _ = request.as_reader().read_to_end(&mut body);
+                    let method = request.method().as_str().to_uppercase();
+                    let fixture = fixtures.lock().unwrap().get(&(method.clone(), path.clone())).cloned();
+                    requests.lock().unwrap().push(RecordedRequest {
+                        method,
+                        path: path.clone(),
+                        query: query.clone(),
+                        body: String::from_utf8_lossy(&body).to_string(),
+                    });
+
+                    let page: usize = query
+                        .iter()
+                        .find(|(key, _)| key == "page")
+                        .and_then(|(_, value)| value.parse().ok())
+                        .unwrap_or(1);
+                    let (status, body, next) = respond(fixture.as_ref(), page);
+                    let mut response = Response::from_string(body.to_string())
+                        .with_status_code(status)
+                        .with_header(Header::from_bytes("Content-Type", "application/json").unwrap());
+                    if next {
+                        let link = format!("<{}{}?page={}&per_page=100>; rel=\"next\"", base_url, path, page + 1);
+                        response = response.with_header(Header::from_bytes("Link", link).unwrap());
+                    }
+                    let _ = request.respond(response);
+                }
+            })
+        };
+
+        Ok(MockCanvas {
+            base_url,
+            fixtures,
+            requests,
+            server,
+            handle: Some(handle),
+        })
+    }
+
+    /// Starts a server with a sample course: course 1 ("Algoritmos"), students 10 and 11,
+    /// assignment 100 with its submissions split in two pages, and the file upload handshake of
+    /// submission comments.
+    pub fn with_sample_course() -> Result<MockCanvas, Box<dyn std::error::Error + Send + Sync>> {
+        let canvas = MockCanvas::start()?;
+        let course = json!({"id": 1, "name": "Algoritmos - Turma A", "course_code": "ALG-A",
+                            "workflow_state": "available", "time_zone": "America/Sao_Paulo"});
+        canvas.mock("GET", "/courses", json!([course]));
+        canvas.mock("GET", "/courses/1", course);
+        canvas.mock(
+            "GET",
+            "/courses/1/users",
+            json!([
+                {"id": 10, "name": "Ana Souza", "email": "ana@example.edu"},
+                {"id": 11, "name": "Bruno Lima", "email": "bruno@example.edu"}
+            ]),
+        );
+        canvas.mock(
+            "GET",
+            "/courses/1/assignments",
+            json!([{"id": 100, "name": "Lista 1", "due_at": "2024-06-11T02:59:00Z", "points_possible": 10.0}]),
+        );
+        canvas.mock_pages(
+            "GET",
+            "/courses/1/assignments/100/submissions",
+            vec![
+                vec![json!({"id": 1000, "assignment_id": 100, "user_id": 10, "score": 8.5,
+                            "submitted_at": "2024-06-10T20:00:00Z", "submission_type": "online_upload",
+                            "attachments": [{"id": 5000, "display_name": "lista1.pdf"}],
+                            "submission_comments": []})],
+                vec![json!({"id": 1001, "assignment_id": 100, "user_id": 11, "score": null,
+                            "submitted_at": null, "submission_comments": []})],
+            ],
+        );
+        for user_id in [10, 11] {
+            canvas.mock(
+                "POST",
+                &format!("/courses/1/assignments/100/submissions/{}/comments/files", user_id),
+                json!({"upload_url": canvas.url("/files/upload"), "upload_params": {"filename": "upload"}}),
+            );
+            canvas.mock("PUT", &format!("/courses/1/assignments/100/submissions/{}", user_id), json!({"id": 1000}));
+        }
+        canvas.mock("POST", "/files/upload", json!({"id": 9000, "display_name": "upload"}));
+        Ok(canvas)
+    }
+
+    /// Credentials pointing at the server.
+    pub fn credentials(&self) -> CanvasCredentials {
+        CanvasCredentials {
+            url_canvas: self.url(""),
+            token_canvas: MOCK_TOKEN.to_string(),
+        }
+    }
+
+    /// Absolute URL of a path relative to the API root.
+    pub fn url(&self, path: &str) -> String {
+        format!("{}/api/v1{}", self.base_url, path)
+    }
+
+    /// Answers `200` with `body` to `method` on `path`.
+    pub fn mock(&self, method: &str, path: &str, body: Value) {
+        self.mock_status(method, path, 200, body);
+    }
+
+    /// Answers `status` with `body` to `method` on `path`, e.g. to simulate errors.
+    pub fn mock_status(&self, method: &str, path: &str, status: u16, body: Value) {
+        self.insert(method, path, Fixture::Single(status, body));
+    }
+
+    /// Serves a paginated listing, one array per page.
+    pub fn mock_pages(&self, method: &str, path: &str, pages: Vec<Vec<Value>>) {
+        self.insert(method, path, Fixture::Pages(pages));
+    }
+
+    /// Requests received so far, in order.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    fn insert(&self, method: &str, path: &str, fixture: Fixture) {
+        let key = (method.to_uppercase(), format!("/api/v1{}", path));
+        self.fixtures.lock().unwrap().insert(key, fixture);
+    }
+}
+
+impl Drop for MockCanvas {
+    fn drop(&mut self) {
+        self.server.unblock();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+// Splits a request URL into its path and decoded query parameters.
+fn split_url(url: &str) -> (String, Vec<(String, String)>) {
+    let (path, query) = url.split_once('?').unwrap_or((url, ""));
+    let query = query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| urlencoding::decode(s).map(|s| s.to_string()).unwrap_or_else(|_| s.to_string());
+            (decode(key), decode(value))
+        })
+        .collect();
+    (path.to_string(), query)
+}
+
+// Status, body and whether a next page exists for a fixture and page number.
+fn respond(fixture: Option<&Fixture>, page: usize) -> (u16, Value, bool) {
+    match fixture {
+        None => (404, json!({"errors": [{"message": "The specified resource does not exist."}]}), false),
+        Some(Fixture::Single(status, Value::Array(_))) if page > 1 => (*status, json!([]), false),
+        Some(Fixture::Single(status, body)) => (*status, body.clone(), false),
+        Some(Fixture::Pages(pages)) => {
+            let body = pages.get(page.wrapping_sub(1)).cloned().unwrap_or_default();
+            (200, Value::Array(body), page < pages.len())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Canvas, CanvasResultSingleCourse};
+
+    #[test]
+    fn test_sample_course_end_to_end() {
+        let canvas = MockCanvas::with_sample_course().unwrap();
+        let course = match Canvas::fetch_single_course_with_credentials(&canvas.credentials(), 1) {
+            CanvasResultSingleCourse::Ok(course) => course,
+            _ => panic!("course not served"),
+        };
+        assert_eq!(course.info.course_code, "ALG-A");
+
+        let students = course.fetch_students().unwrap();
+        assert_eq!(students.len(), 2);
+        let assignment = course.get_assignment_from_assignment_id(100).unwrap();
+        let submissions = assignment.fetch_submissions(&students).unwrap();
+        assert_eq!(submissions.len(), 2);
+        assert_eq!(submissions.iter().find(|s| s.id == 1000).unwrap().file_ids, vec![5000]);
+
+        assert!(matches!(
+            Canvas::fetch_single_course_with_credentials(&canvas.credentials(), 2),
+            CanvasResultSingleCourse::ErrConnection(_)
+        ));
+        assert!(canvas
+            .requests()
+            .iter()
+            .any(|r| r.path == "/api/v1/courses/1/assignments/100/submissions" && r.query.contains(&("page".to_string(), "2".to_string()))));
+    }
+}
@@ -0,0 +1,90 @@
+// Import necessary crates and modules
+use crate::course::CourseInfo;
+use crate::{AssignmentInfo, Submission};
+use chrono::{DateTime, Utc};
+pub use chrono_tz::Tz;
+
+/// Format used by the `*_local_str` helpers, e.g. `10/06/2024 23:59`.
+pub const DEFAULT_LOCAL_FORMAT: &str = "%d/%m/%Y %H:%M";
+
+/// Parses an IANA time zone name as returned by Canvas (`America/Sao_Paulo`).
+pub fn parse_time_zone(name: &str) -> Option<Tz> {
+    name.parse().ok()
+}
+
+/// Converts a UTC timestamp to the given time zone.
+pub fn to_time_zone(datetime: &DateTime<Utc>, time_zone: Tz) -> DateTime<Tz> {
+    datetime.with_timezone(&time_zone)
+}
+
+impl CourseInfo {
+    /// Time zone of the course settings, or UTC when it is unknown or not recognized.
+    pub fn tz(&self) -> Tz {
+        self.time_zone.as_deref().and_then(parse_time_zone).unwrap_or(Tz::UTC)
+    }
+
+    /// Converts a UTC timestamp to the time zone of the course.
+    pub fn to_local(&self, datetime: &DateTime<Utc>) -> DateTime<Tz> {
+        to_time_zone(datetime, self.tz())
+    }
+}
+
+impl AssignmentInfo {
+    /// Due date in the time zone of the course, so a "23:59" deadline is shown as such.
+    pub fn due_at_local(&self) -> Option<DateTime<Tz>> {
+        self.due_at.map(|due_at| self.course_info.to_local(&due_at))
+    }
+
+    /// Due date in the time zone of the course, formatted with `DEFAULT_LOCAL_FORMAT`.
+    pub fn due_at_local_str(&self) -> Option<String> {
+        self.due_at_local().map(|due_at| due_at.format(DEFAULT_LOCAL_FORMAT).to_string())
+    }
+}
+
+impl Submission {
+    /// Submission time in the time zone of the course.
+    pub fn submitted_at_local(&self) -> Option<DateTime<Tz>> {
+        self.submitted_at.map(|submitted_at| self.assignment_info.course_info.to_local(&submitted_at))
+    }
+
+    /// Submission time in the time zone of the course, formatted with `DEFAULT_LOCAL_FORMAT`.
+    pub fn submitted_at_local_str(&self) -> Option<String> {
+        self.submitted_at_local().map(|submitted_at| submitted_at.format(DEFAULT_LOCAL_FORMAT).to_string())
+    }
+
+    /// Effective due date of this submission (see `effective_due_at`) in the time zone of the course.
+    pub fn effective_due_at_local(&self) -> Option<DateTime<Tz>> {
+        self.effective_due_at().map(|due_at| self.assignment_info.course_info.to_local(&due_at))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_due_date_in_course_time_zone() {
+        let course_info = Arc::new(CourseInfo {
+            id: 7,
+            time_zone: Some("America/Sao_Paulo".to_string()),
+            ..Default::default()
+        });
+        let assignment = AssignmentInfo {
+            id: 1,
+            name: "Lista 1".to_string(),
+            due_at: Some(Utc.with_ymd_and_hms(2024, 6, 11, 2, 59, 0).unwrap()),
+            course_info: course_info.clone(),
+            ..Default::default()
+        };
+        assert_eq!(assignment.due_at_local_str().as_deref(), Some("10/06/2024 23:59"));
+
+        let unknown = CourseInfo {
+            time_zone: Some("Mars/Olympus_Mons".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(unknown.tz(), Tz::UTC);
+        assert_eq!(CourseInfo::default().tz(), Tz::UTC);
+    }
+}
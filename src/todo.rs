@@ -0,0 +1,119 @@
+// Import necessary crates and modules
+use crate::canvas::{fetch_all_pages_as, fetch_json};
+use crate::{Canvas, CanvasCredentials};
+use chrono::{DateTime, Utc};
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+
+/// Assignment of a to-do item.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TodoAssignment {
+    pub id: u64,
+    pub name: String,
+    pub course_id: Option<u64>,
+    pub due_at: Option<DateTime<Utc>>,
+    pub points_possible: Option<f64>,
+    pub needs_grading_count: Option<u64>,
+    pub html_url: Option<String>,
+}
+
+/// An entry of the to-do list of the authenticated user.
+///
+/// Fields:
+/// - `todo_type`: `grading` (teachers: submissions to grade) or `submitting` (students: work due).
+/// - `assignment`: The assignment concerned.
+/// - `needs_grading_count`: Submissions waiting for a grade (`grading` items).
+/// - `course_id`: Course of the item.
+/// - `html_url`: Where to do it (SpeedGrader for `grading` items).
+/// - `ignore` / `ignore_permanently`: URLs to dismiss the item.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TodoItem {
+    #[serde(rename = "type")]
+    pub todo_type: String,
+    pub assignment: Option<TodoAssignment>,
+    pub needs_grading_count: Option<u64>,
+    pub course_id: Option<u64>,
+    pub html_url: Option<String>,
+    pub ignore: Option<String>,
+    pub ignore_permanently: Option<String>,
+}
+
+impl TodoItem {
+    /// Returns `true` for submissions waiting to be graded.
+    pub fn is_grading(&self) -> bool {
+        self.todo_type == "grading"
+    }
+}
+
+/// Counters of the to-do list, as shown on the Canvas dashboard.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct TodoCounts {
+    #[serde(default)]
+    pub needs_grading_count: u64,
+    #[serde(default)]
+    pub assignments_needing_submitting: u64,
+}
+
+/// Keeps the `grading` items, the ones with most submissions to grade first.
+pub fn needs_grading(items: &[TodoItem]) -> Vec<TodoItem> {
+    let mut grading: Vec<TodoItem> = items.iter().filter(|item| item.is_grading()).cloned().collect();
+    grading.sort_by_key(|item| std::cmp::Reverse(item.needs_grading_count));
+    grading
+}
+
+/// Retrieves the to-do list of the authenticated user.
+pub fn fetch_todo(client: &Client, canvas_info: &CanvasCredentials) -> Result<Vec<TodoItem>, Box<dyn Error>> {
+    let url = format!("{}/users/self/todo", canvas_info.url_canvas);
+    fetch_all_pages_as(client, canvas_info, &url, Vec::new())
+}
+
+/// Retrieves the counters of the to-do list of the authenticated user.
+pub fn fetch_todo_counts(client: &Client, canvas_info: &CanvasCredentials) -> Result<TodoCounts, Box<dyn Error>> {
+    let url = format!("{}/users/self/todo_item_count", canvas_info.url_canvas);
+    fetch_json(client, canvas_info, &url)
+}
+
+impl Canvas {
+    /// Retrieves the to-do list of the authenticated user: submissions to grade (with their
+    /// `needs_grading_count`) and upcoming work.
+    ///
+    /// Example:
+    /// ```
+    /// let items = Canvas::todo(&credentials)?;
+    /// for item in needs_grading(&items) {
+    ///     let assignment = item.assignment.unwrap_or_default();
+    ///     println!("{} - {} to grade", assignment.name, item.needs_grading_count.unwrap_or(0));
+    /// }
+    /// ```
+    pub fn todo(info: &CanvasCredentials) -> Result<Vec<TodoItem>, Box<dyn Error>> {
+        fetch_todo(&Client::new(), info)
+    }
+
+    /// Retrieves the counters of the to-do list of the authenticated user.
+    pub fn todo_counts(info: &CanvasCredentials) -> Result<TodoCounts, Box<dyn Error>> {
+        fetch_todo_counts(&Client::new(), info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_needs_grading_order() {
+        let items: Vec<TodoItem> = serde_json::from_value(json!([
+            {"type": "grading", "needs_grading_count": 3, "course_id": 7,
+             "assignment": {"id": 1, "name": "Lista 1", "needs_grading_count": 3}},
+            {"type": "submitting", "course_id": 7, "assignment": {"id": 2, "name": "Quiz 2"}},
+            {"type": "grading", "needs_grading_count": 12, "course_id": 8,
+             "assignment": {"id": 3, "name": "Projeto", "due_at": "2024-06-10T02:59:00Z"}}
+        ]))
+        .unwrap();
+        let grading = needs_grading(&items);
+        assert_eq!(grading.len(), 2);
+        assert_eq!(grading[0].assignment.as_ref().unwrap().name, "Projeto");
+        assert_eq!(grading[1].needs_grading_count, Some(3));
+    }
+}
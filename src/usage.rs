@@ -0,0 +1,131 @@
+// Import necessary crates and modules
+use crate::CanvasCredentials;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+/// API consumption of one set of credentials, built from the `X-Request-Cost` and
+/// `X-Rate-Limit-Remaining` headers returned by Canvas.
+///
+/// Fields:
+/// - `requests`: Number of responses received (retries included).
+/// - `total_cost`: Sum of the `X-Request-Cost` of every response.
+/// - `rate_limit_remaining`: Last `X-Rate-Limit-Remaining` value, i.e. what is left in the bucket.
+/// - `requests_by_endpoint`: Responses per endpoint (`GET /courses/:id/assignments`).
+/// - `cost_by_endpoint`: Cost per endpoint.
+///
+/// Example:
+/// ```
+/// let usage = canvas_info.usage();
+/// if usage.rate_limit_remaining.is_some_and(|left| left < 100.0) {
+///     std::thread::sleep(Duration::from_secs(5));
+/// }
+/// println!("{} requests, cost {:.1}", usage.requests, usage.total_cost);
+/// ```
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct ApiUsage {
+    pub requests: u64,
+    pub total_cost: f64,
+    pub rate_limit_remaining: Option<f64>,
+    pub requests_by_endpoint: BTreeMap<String, u64>,
+    pub cost_by_endpoint: BTreeMap<String, f64>,
+}
+
+impl ApiUsage {
+    // Adds one response to the totals.
+    fn record(&mut self, endpoint: String, cost: Option<f64>, remaining: Option<f64>) {
+        self.requests += 1;
+        *self.requests_by_endpoint.entry(endpoint.clone()).or_insert(0) += 1;
+        if let Some(cost) = cost {
+            self.total_cost += cost;
+            *self.cost_by_endpoint.entry(endpoint).or_insert(0.0) += cost;
+        }
+        if remaining.is_some() {
+            self.rate_limit_remaining = remaining;
+        }
+    }
+}
+
+// Usage per API token.
+lazy_static! {
+    static ref USAGE: Mutex<HashMap<String, ApiUsage>> = Mutex::new(HashMap::new());
+}
+
+impl CanvasCredentials {
+    /// Returns the API consumption recorded for these credentials since the start of the
+    /// program (or the last `reset_usage`).
+    pub fn usage(&self) -> ApiUsage {
+        USAGE.lock().unwrap().get(&self.token_canvas).cloned().unwrap_or_default()
+    }
+
+    /// Clears the API consumption recorded for these credentials.
+    pub fn reset_usage(&self) {
+        USAGE.lock().unwrap().remove(&self.token_canvas);
+    }
+}
+
+// Groups URLs by endpoint: removes the host, the query and replaces numeric IDs by `:id`.
+fn endpoint_key(canvas_info: &CanvasCredentials, method: &str, url: &str) -> String {
+    let path = url.strip_prefix(canvas_info.url_canvas.as_str()).unwrap_or(url);
+    let path = path.split('?').next().unwrap_or_default();
+    let path: Vec<&str> = path
+        .split('/')
+        .map(|segment| {
+            if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+                ":id"
+            } else {
+                segment
+            }
+        })
+        .collect();
+    format!("{} {}", method, path.join("/"))
+}
+
+/// Records a response received with `canvas_info`.
+pub(crate) fn record_response(
+    canvas_info: &CanvasCredentials,
+    method: &str,
+    url: &str,
+    cost: Option<f64>,
+    remaining: Option<f64>,
+) {
+    let endpoint = endpoint_key(canvas_info, method, url);
+    USAGE
+        .lock()
+        .unwrap()
+        .entry(canvas_info.token_canvas.clone())
+        .or_default()
+        .record(endpoint, cost, remaining);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage_per_credentials_and_endpoint() {
+        let canvas_info = CanvasCredentials {
+            url_canvas: "https://canvas.example/api/v1".to_string(),
+            token_canvas: "usage-test-token".to_string(),
+        };
+        let other = CanvasCredentials {
+            token_canvas: "usage-test-other".to_string(),
+            ..canvas_info.clone()
+        };
+        record_response(&canvas_info, "GET", "https://canvas.example/api/v1/courses/7/assignments?page=2", Some(1.5), Some(600.0));
+        record_response(&canvas_info, "GET", "https://canvas.example/api/v1/courses/8/assignments", Some(0.5), None);
+        record_response(&canvas_info, "PUT", "https://canvas.example/api/v1/courses/7/assignments/3", None, Some(598.0));
+
+        let usage = canvas_info.usage();
+        assert_eq!(usage.requests, 3);
+        assert_eq!(usage.total_cost, 2.0);
+        assert_eq!(usage.rate_limit_remaining, Some(598.0));
+        assert_eq!(usage.requests_by_endpoint["GET /courses/:id/assignments"], 2);
+        assert_eq!(usage.cost_by_endpoint["GET /courses/:id/assignments"], 2.0);
+        assert_eq!(other.usage(), ApiUsage::default());
+
+        canvas_info.reset_usage();
+        assert_eq!(canvas_info.usage().requests, 0);
+    }
+}
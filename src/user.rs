@@ -0,0 +1,162 @@
+// Import necessary crates and modules
+use crate::connection::{send_http_request, HttpMethod};
+use crate::CanvasCredentials;
+use reqwest::blocking::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::error::Error;
+
+/// Role of an enrollment in a course, as used by the `enrollment_type[]` filter of the Canvas API.
+///
+/// - `Student`: Enrolled student.
+/// - `Teacher`: Teacher of the course.
+/// - `Ta`: Teaching assistant.
+/// - `Observer`: Observer (e.g. a parent or mentor).
+/// - `Designer`: Course designer.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum EnrollmentRole {
+    Student,
+    Teacher,
+    Ta,
+    Observer,
+    Designer,
+}
+
+impl EnrollmentRole {
+    /// Value used by the Canvas API for this role.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EnrollmentRole::Student => "student",
+            EnrollmentRole::Teacher => "teacher",
+            EnrollmentRole::Ta => "ta",
+            EnrollmentRole::Observer => "observer",
+            EnrollmentRole::Designer => "designer",
+        }
+    }
+
+    /// Enrollment type created by Canvas for this role (`StudentEnrollment`...).
+    pub fn enrollment_type(&self) -> &'static str {
+        match self {
+            EnrollmentRole::Student => "StudentEnrollment",
+            EnrollmentRole::Teacher => "TeacherEnrollment",
+            EnrollmentRole::Ta => "TaEnrollment",
+            EnrollmentRole::Observer => "ObserverEnrollment",
+            EnrollmentRole::Designer => "DesignerEnrollment",
+        }
+    }
+}
+
+/// A Canvas user, as returned by the user listing endpoints.
+///
+/// Unlike `StudentInfo`, a `User` is not bound to a course: it is used for teachers, TAs and other
+/// non-student accounts.
+///
+/// Fields:
+/// - `id`: Unique identifier of the user in Canvas.
+/// - `name`: Full name.
+/// - `sortable_name`: Name in "Last, First" form, if provided.
+/// - `email`: Email address, or `None` when its visibility is restricted.
+/// - `login_id`: Login used to sign in, if visible to the caller.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct User {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub sortable_name: Option<String>,
+    #[serde(default)]
+    pub email: Option<String>,
+    #[serde(default)]
+    pub login_id: Option<String>,
+}
+
+/// Profile fields to change in a user. Only the fields set to `Some` are sent.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+pub struct UserUpdate {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub short_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sortable_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_zone: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+}
+
+// Sends a mutating request to a users endpoint and decodes the returned user.
+pub(crate) fn send_user_request(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    method: HttpMethod,
+    url: &str,
+) -> Result<User, Box<dyn Error>> {
+    let response = send_http_request(client, method, url, canvas_info, vec![])?;
+    if !response.status().is_success() {
+        let status = response.status();
+        let body: Value = response.json().unwrap_or(Value::Null);
+        return Err(format!("User request failed with status: {} {}", status, body["errors"]).into());
+    }
+    Ok(response.json()?)
+}
+
+/// Changes the profile of a user.
+///
+/// Arguments:
+/// - `client`: HTTP client for executing requests.
+/// - `canvas_info`: Canvas API credentials.
+/// - `user_id`: ID of the user.
+/// - `update`: Fields to change.
+///
+/// Returns:
+/// - `Result<User, Box<dyn Error>>`: The updated user or an error.
+pub fn update_user(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    user_id: u64,
+    update: &UserUpdate,
+) -> Result<User, Box<dyn Error>> {
+    let url = format!("{}/users/{}", canvas_info.url_canvas, user_id);
+    send_user_request(client, canvas_info, HttpMethod::Put(json!({ "user": update })), &url)
+}
+
+/// Merges the user `from_user_id` into `into_user_id`: logins, enrollments and submissions move
+/// to the destination user and the source user is deleted. This cannot be undone through the API.
+///
+/// Returns:
+/// - `Result<User, Box<dyn Error>>`: The destination user or an error.
+pub fn merge_users(
+    client: &Client,
+    canvas_info: &CanvasCredentials,
+    from_user_id: u64,
+    into_user_id: u64,
+) -> Result<User, Box<dyn Error>> {
+    let url = format!(
+        "{}/users/{}/merge_into/{}",
+        canvas_info.url_canvas, from_user_id, into_user_id
+    );
+    send_user_request(client, canvas_info, HttpMethod::Put(json!({})), &url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_user_deserialization_with_missing_fields() {
+        let user: User = serde_json::from_value(json!({
+            "id": 42,
+            "name": "Maria Souza",
+            "sortable_name": "Souza, Maria"
+        }))
+        .unwrap();
+        assert_eq!(user.id, 42);
+        assert_eq!(user.sortable_name.as_deref(), Some("Souza, Maria"));
+        assert_eq!(user.email, None);
+        assert_eq!(EnrollmentRole::Ta.as_str(), "ta");
+    }
+}
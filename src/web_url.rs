@@ -0,0 +1,207 @@
+// Import necessary crates and modules
+use crate::{Assignment, AssignmentInfo, CanvasCredentials, Course, CourseInfo, Student, StudentInfo, Submission};
+use serde_json::{Map, Value};
+
+impl CanvasCredentials {
+    /// Base URL of the Canvas web interface, i.e. `url_canvas` without the `/api/v1` suffix.
+    pub fn web_url(&self) -> String {
+        let url = self.url_canvas.trim_end_matches('/');
+        url.strip_suffix("/api/v1").unwrap_or(url).to_string()
+    }
+
+    /// Page of a file in the Canvas web interface (e.g. a submission attachment).
+    pub fn file_html_url(&self, file_id: u64) -> String {
+        format!("{}/files/{}", self.web_url(), file_id)
+    }
+}
+
+// `html_url` sent by Canvas in the payload, when present.
+fn payload_html_url(extra: &Map<String, Value>) -> Option<String> {
+    extra.get("html_url").and_then(Value::as_str).map(str::to_string)
+}
+
+impl CourseInfo {
+    /// Home page of the course in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        payload_html_url(&self.extra).unwrap_or_else(|| format!("{}/courses/{}", self.canvas_info.web_url(), self.id))
+    }
+}
+
+impl Course {
+    /// Home page of the course in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        self.info.html_url()
+    }
+}
+
+impl AssignmentInfo {
+    /// Page of the assignment in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        payload_html_url(&self.extra).unwrap_or_else(|| {
+            format!("{}/assignments/{}", self.course_info.html_url(), self.id)
+        })
+    }
+}
+
+impl Assignment {
+    /// Page of the assignment in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        self.info.html_url()
+    }
+}
+
+impl StudentInfo {
+    /// Profile of the student in the course, in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        payload_html_url(&self.extra).unwrap_or_else(|| format!("{}/users/{}", self.course_info.html_url(), self.id))
+    }
+}
+
+impl Student {
+    /// Profile of the student in the course, in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        self.info.html_url()
+    }
+}
+
+// SpeedGrader reads the student from the query on recent Canvas versions and from the
+// `{"student_id":"..."}` anchor on older ones, so both are set.
+fn speedgrader_url(canvas_info: &CanvasCredentials, course_id: u64, assignment_id: u64, student_id: Option<u64>) -> String {
+    let mut url = format!(
+        "{}/courses/{}/gradebook/speed_grader?assignment_id={}",
+        canvas_info.web_url(),
+        course_id,
+        assignment_id
+    );
+    if let Some(student_id) = student_id {
+        url.push_str(&format!("&student_id={}#%7B%22student_id%22%3A%22{}%22%7D", student_id, student_id));
+    }
+    url
+}
+
+impl AssignmentInfo {
+    /// SpeedGrader page of the assignment, opened on its first student.
+    pub fn speedgrader_url(&self) -> String {
+        speedgrader_url(&self.course_info.canvas_info, self.course_info.id, self.id, None)
+    }
+}
+
+impl Assignment {
+    /// SpeedGrader page of the assignment, opened on `student`.
+    ///
+    /// Example:
+    /// ```
+    /// for submission in assignment.fetch_submissions(&students)?.iter().filter(|s| s.score.is_none()) {
+    ///     println!("Review manually: {}", assignment.speedgrader_url_for(&submission.students_info[0]));
+    /// }
+    /// ```
+    pub fn speedgrader_url_for(&self, student: &StudentInfo) -> String {
+        speedgrader_url(&self.info.course_info.canvas_info, self.info.course_info.id, self.info.id, Some(student.id))
+    }
+}
+
+impl Submission {
+    /// Page of the submission (as seen by the student) in the Canvas web interface.
+    pub fn html_url(&self) -> String {
+        let student_id = self.students_info.first().map_or(0, |student| student.id);
+        format!("{}/submissions/{}", self.assignment_info.html_url(), student_id)
+    }
+
+    /// Pages of the files attached to the submission, in the Canvas web interface.
+    pub fn file_html_urls(&self) -> Vec<String> {
+        let canvas_info = &self.assignment_info.course_info.canvas_info;
+        self.file_ids.iter().map(|file_id| canvas_info.file_html_url(*file_id)).collect()
+    }
+
+    /// SpeedGrader page of this submission (for group submissions, opened on its first member).
+    pub fn speedgrader_url(&self) -> String {
+        let course_info = &self.assignment_info.course_info;
+        let student_id = self.students_info.first().map(|student| student.id);
+        speedgrader_url(&course_info.canvas_info, course_info.id, self.assignment_id, student_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_speedgrader_urls() {
+        let course_info = Arc::new(CourseInfo {
+            id: 7,
+            canvas_info: Arc::new(CanvasCredentials {
+                url_canvas: "https://school.instructure.com/api/v1".to_string(),
+                token_canvas: String::new(),
+            }),
+            ..Default::default()
+        });
+        let assignment = Assignment {
+            info: Arc::new(AssignmentInfo {
+                id: 100,
+                course_info: course_info.clone(),
+                ..Default::default()
+            }),
+        };
+        let student = StudentInfo {
+            id: 42,
+            course_info,
+            ..Default::default()
+        };
+        assert_eq!(assignment.info.course_info.canvas_info.web_url(), "https://school.instructure.com");
+        assert_eq!(
+            assignment.info.speedgrader_url(),
+            "https://school.instructure.com/courses/7/gradebook/speed_grader?assignment_id=100"
+        );
+        assert_eq!(
+            assignment.speedgrader_url_for(&student),
+            "https://school.instructure.com/courses/7/gradebook/speed_grader?assignment_id=100&student_id=42#%7B%22student_id%22%3A%2242%22%7D"
+        );
+    }
+
+    #[test]
+    fn test_html_urls_from_payload_or_built() {
+        let canvas_info = Arc::new(CanvasCredentials {
+            url_canvas: "https://school.instructure.com/api/v1/".to_string(),
+            token_canvas: String::new(),
+        });
+        let course_info = Arc::new(CourseInfo {
+            id: 7,
+            canvas_info: canvas_info.clone(),
+            ..Default::default()
+        });
+        let mut extra = Map::new();
+        extra.insert("html_url".to_string(), Value::from("https://school.instructure.com/courses/7/assignments/100"));
+        let assignment_info = Arc::new(AssignmentInfo {
+            id: 100,
+            course_info: course_info.clone(),
+            extra,
+            ..Default::default()
+        });
+        let student = Arc::new(StudentInfo {
+            id: 42,
+            course_info: course_info.clone(),
+            ..Default::default()
+        });
+        let submission = Submission {
+            id: 1,
+            assignment_id: 100,
+            score: None,
+            submitted_at: None,
+            submission_type: None,
+            students_info: vec![student.clone()],
+            assignment_info: assignment_info.clone(),
+            file_ids: vec![5000],
+            comments: Vec::new(),
+            extra: Map::new(),
+        };
+
+        assert_eq!(course_info.html_url(), "https://school.instructure.com/courses/7");
+        assert_eq!(student.html_url(), "https://school.instructure.com/courses/7/users/42");
+        assert_eq!(
+            submission.html_url(),
+            "https://school.instructure.com/courses/7/assignments/100/submissions/42"
+        );
+        assert_eq!(submission.file_html_urls(), vec!["https://school.instructure.com/files/5000"]);
+    }
+}